@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Command-line argument parsing.
+//!
+//! See [`Args`].
+
+use crafty_novels::{
+    export::{ColorDepth, OutputFormat},
+    import::InputFormat,
+    syntax::transform::Builtin,
+};
+use std::{ops::Bound, path::PathBuf, str::FromStr};
+
+/// The help text printed alongside argument errors.
+pub const USAGE: &str = "\
+Usage: crafty_novels_cli [COMMAND] [OPTIONS]
+
+Without options, converts a hardcoded test book, printing tokens to stderr and HTML to stdout.
+
+Commands:
+  convert  Convert books from one format to another [default]
+  analyze  Report how heavily a book from stdin is formatted, overall and per page
+
+Options:
+  --stdin-format <FORMAT>  Convert input from stdin, where FORMAT is one of:
+                             book  a single book, written to stdout
+                             list  one JSON object per line, `{\"name\": ..., \"content\": ...}`,
+                                   each written to stdout as a JSON line with an `output` or
+                                   `error` field
+  --report <DIR>           With `--stdin-format list`, also write a summary of every conversion
+                           into DIR as `report.json` and `report.html`
+  --fail-fast              With `--stdin-format list`, stop at the first book that fails to
+                           convert and exit with an error
+  --from <FORMAT>          The format of the input [default: stendhal]
+  --to <FORMAT>            The format of the output [default: html]
+  --pages <RANGE>          With `--stdin-format book` or `analyze`, only use the pages in RANGE,
+                           numbered from 1 like in Minecraft: `3..10` is pages 3 through 10,
+                           `3..` is page 3 onward, `..10` is up to page 10, and `3` is page 3
+  --explain                With `--stdin-format book`, print a warning to stderr for each
+                           part of the book that the output format cannot represent
+  --dry-run                With `--stdin-format book`, read and check the book without
+                           writing any output
+  --entry <NAME>           With `--stdin-format book` or `analyze`, the entry to read from a zip
+                           archive on stdin [default: its first file]
+  --compress               Gzip-compress the output, not including a report
+  --colors <DEPTH>         With `--to cp437`, how many colors the terminal can show, where
+                           DEPTH is one of `16`, `256`, or `24bit` [default: detected from
+                           the `COLORTERM` and `TERM` environment variables]
+  --transform <NAMES>      Rewrite each book between reading and writing it, where NAMES is a
+                           comma-separated list of passes to run in order, any of:
+                             strip-colors          remove all colors
+                             normalize-whitespace  collapse repeated spaces and paragraph breaks
+                             collapse-empty-pages  remove pages without text or images
+                             normalize-metadata    clean up the title, author, and other fields
+                             typography            use the quotes and dashes of the language
+                             detect-title-page     move a title page into the metadata
+                           Can be given more than once to add more passes
+  --fragment               With `--stdin-format book` and `--to html`, write only the `<article>`
+                           holding the book, without the document around it, to embed in a page
+  -h, --help               Print this help text";
+
+/// How the input on stdin is structured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StdinFormat {
+    /// A single book.
+    Book,
+    /// Many books, one JSON object per line.
+    List,
+}
+
+impl FromStr for StdinFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "book" => Ok(Self::Book),
+            "list" => Ok(Self::List),
+            _ => Err(format!(
+                "unknown stdin format '{s}', expected 'book' or 'list'"
+            )),
+        }
+    }
+}
+
+/// The pages of a book to use, numbered from one, including both ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageRange {
+    /// The first page to use, or the first page of the book.
+    pub first: Option<usize>,
+    /// The last page to use, or the last page of the book.
+    pub last: Option<usize>,
+}
+
+impl PageRange {
+    /// Returns the bounds of the range as page indices, which are numbered from zero.
+    pub fn indices(self) -> (Bound<usize>, Bound<usize>) {
+        let index =
+            |page: Option<usize>| page.map_or(Bound::Unbounded, |page| Bound::Included(page - 1));
+
+        (index(self.first), index(self.last))
+    }
+}
+
+impl FromStr for PageRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let page = |page: &str| match page.parse::<usize>() {
+            Ok(0) => Err("pages are numbered from 1".to_owned()),
+            Ok(page) => Ok(page),
+            Err(e) => Err(format!("invalid page '{page}': {e}")),
+        };
+        let bound = |bound: &str| {
+            if bound.is_empty() {
+                Ok(None)
+            } else {
+                page(bound).map(Some)
+            }
+        };
+
+        let (first, last) = if let Some((first, last)) = s.split_once("..") {
+            (bound(first)?, bound(last)?)
+        } else {
+            let page = page(s)?;
+            (Some(page), Some(page))
+        };
+
+        if let (Some(first), Some(last)) = (first, last) {
+            if first > last {
+                return Err(format!("page range '{s}' ends before it starts"));
+            }
+        }
+
+        Ok(Self { first, last })
+    }
+}
+
+/// Parse a [`ColorDepth`] from the number of colors, or `24bit` or `truecolor`.
+///
+/// # Errors
+///
+/// - A description of the problem if `s` is not a known color depth
+pub fn parse_color_depth(s: &str) -> Result<ColorDepth, String> {
+    match s {
+        "16" => Ok(ColorDepth::Ansi16),
+        "256" => Ok(ColorDepth::Ansi256),
+        "24bit" | "truecolor" => Ok(ColorDepth::TrueColor),
+        _ => Err(format!(
+            "unknown color depth '{s}', expected '16', '256', or '24bit'"
+        )),
+    }
+}
+
+/// What to do with the input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Command {
+    /// Convert books from one format to another.
+    #[default]
+    Convert,
+    /// Report how heavily a book is formatted.
+    Analyze,
+}
+
+/// The parsed command-line arguments.
+#[allow(clippy::struct_excessive_bools)] // Each is an independent command-line flag
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Args {
+    /// What to do with the input.
+    pub command: Command,
+    /// How to read input from stdin, if at all.
+    pub stdin_format: Option<StdinFormat>,
+    /// The format of the input.
+    pub from: InputFormat,
+    /// The format of the output.
+    pub to: OutputFormat,
+    /// The directory to write a batch report into, if any.
+    pub report: Option<PathBuf>,
+    /// Whether to stop a batch run at the first failure.
+    pub fail_fast: bool,
+    /// The pages of the book to use, if not all of them.
+    pub pages: Option<PageRange>,
+    /// The entry to read from a zip archive, if not its first file.
+    pub entry: Option<Box<str>>,
+    /// Whether to gzip-compress the output.
+    pub compress: bool,
+    /// How many colors the terminal can show, if not detected from the environment.
+    pub colors: Option<ColorDepth>,
+    /// The passes to run on each book between reading and writing it, in order.
+    pub transforms: Vec<Builtin>,
+    /// Whether to write HTML as a fragment rather than a whole document.
+    pub fragment: bool,
+    /// Whether to warn about what the output format cannot represent.
+    pub explain: bool,
+    /// Whether to skip writing the output.
+    pub dry_run: bool,
+    /// Whether to print the help text and exit.
+    pub help: bool,
+}
+
+impl Args {
+    /// Parse arguments, not including the name of the program.
+    ///
+    /// # Errors
+    ///
+    /// - A description of the problem if an argument is unknown, missing a value, or has an
+    ///   invalid value
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.peekable();
+
+        match args.peek().map(String::as_str) {
+            Some("convert") => {
+                args.next();
+            }
+            Some("analyze") => {
+                parsed.command = Command::Analyze;
+                args.next();
+            }
+            _ => (),
+        }
+
+        while let Some(arg) = args.next() {
+            /// Get the value following the current argument, or return an error.
+            macro_rules! value {
+                () => {
+                    args.next()
+                        .ok_or_else(|| format!("expected a value after '{arg}'"))?
+                };
+            }
+
+            match arg.as_str() {
+                "--stdin-format" => parsed.stdin_format = Some(value!().parse()?),
+                "--from" => parsed.from = value!().parse().map_err(|e| format!("{e}"))?,
+                "--to" => parsed.to = value!().parse().map_err(|e| format!("{e}"))?,
+                "--report" => parsed.report = Some(value!().into()),
+                "--fail-fast" => parsed.fail_fast = true,
+                "--pages" => parsed.pages = Some(value!().parse()?),
+                "--entry" => parsed.entry = Some(value!().into()),
+                "--compress" => parsed.compress = true,
+                "--colors" => parsed.colors = Some(parse_color_depth(&value!())?),
+                "--transform" => {
+                    for name in value!().split(',') {
+                        parsed
+                            .transforms
+                            .push(name.trim().parse().map_err(|e| format!("{e}"))?);
+                    }
+                }
+                "--fragment" => parsed.fragment = true,
+                "--explain" => parsed.explain = true,
+                "--dry-run" => parsed.dry_run = true,
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(format!("unknown argument '{arg}'")),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            command: Command::Convert,
+            stdin_format: None,
+            from: InputFormat::Stendhal,
+            to: OutputFormat::Html,
+            report: None,
+            fail_fast: false,
+            pages: None,
+            entry: None,
+            compress: false,
+            colors: None,
+            transforms: vec![],
+            fragment: false,
+            explain: false,
+            dry_run: false,
+            help: false,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Streaming conversion of many books, one JSON object per line.
+//!
+//! See [`run`].
+
+use crafty_novels::{
+    export::OutputFormat, import::InputFormat, session::Session, syntax::transform::Pipeline,
+};
+use report::Entry;
+pub use report::{Failure, FailureKind, Report};
+use serde_json::{json, Value};
+use std::{
+    any::Any,
+    io::{BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+    time::Instant,
+};
+
+mod report;
+
+/// Convert every line of `input` and write one line to `output` for each of them.
+///
+/// Each line of `input` is a JSON object with a string `name`, which is copied into the output, and
+/// a string `content`, which holds the document to convert from `from` to `to`, running
+/// `transforms` on it in between. Blank lines are skipped.
+///
+/// Each line of `output` is a JSON object with the `name` and either the converted document in
+/// `output` or a description of what went wrong in `error`.
+///
+/// A line that isn't valid UTF-8, fails to parse, or fails to convert does not stop the run, unless
+/// `fail_fast` is set, in which case the run stops after the first failure. If `input` cannot be
+/// read, that is recorded as a failure and the run stops, as the rest of it is likely unreadable
+/// too.
+///
+/// Returns a [`Report`] of every conversion, including the failures.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+pub fn run(
+    mut input: impl BufRead,
+    output: &mut impl Write,
+    from: InputFormat,
+    to: OutputFormat,
+    transforms: &Pipeline,
+    fail_fast: bool,
+) -> std::io::Result<Report> {
+    let mut report = Report::default();
+    let mut buffer: Vec<u8> = vec![];
+
+    for number in 1.. {
+        let start = Instant::now();
+        let mut session = Session::new();
+
+        buffer.clear();
+        let (name, result) = match input.read_until(b'\n', &mut buffer) {
+            Ok(0) => break,
+            Ok(_) => {
+                let line = trim_line_ending(&buffer);
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+
+                convert_line(line, &mut session, from, to, transforms)
+            }
+            Err(e) => (
+                Value::Null,
+                Err(Failure::new(
+                    FailureKind::Read,
+                    format!("could not read input: {e}"),
+                )),
+            ),
+        };
+        let result = result.map_err(|failure| failure.on_line(number));
+
+        let response = match &result {
+            Ok(converted) => json!({ "name": name, "output": converted }),
+            Err(failure) => json!({ "name": name, "error": failure.message() }),
+        };
+
+        serde_json::to_writer(&mut *output, &response)?;
+        output.write_all(b"\n")?;
+        output.flush()?;
+
+        let stop = match &result {
+            Err(failure) => fail_fast || failure.kind() == FailureKind::Read,
+            Ok(_) => false,
+        };
+
+        report.entries.push(Entry {
+            name,
+            from,
+            to,
+            error: result.err(),
+            diagnostics: session.take_diagnostics(),
+            stats: session.stats(),
+            duration: start.elapsed(),
+        });
+
+        if stop {
+            break;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Convert a single line of input with `session`, running `transforms` on it, returning its `name`
+/// and either the converted document or what went wrong.
+///
+/// A panic during the conversion is caught and returned as a [`FailureKind::Conversion`], so that
+/// it only fails this line.
+fn convert_line(
+    line: &[u8],
+    session: &mut Session,
+    from: InputFormat,
+    to: OutputFormat,
+    transforms: &Pipeline,
+) -> (Value, Result<Box<str>, Failure>) {
+    let line = match std::str::from_utf8(line) {
+        Ok(line) => line,
+        Err(e) => {
+            let message = format!("not valid UTF-8: {e}");
+            return (
+                Value::Null,
+                Err(Failure::new(FailureKind::Encoding, message)),
+            );
+        }
+    };
+
+    let (name, content) = match parse_request(line) {
+        Ok(request) => request,
+        Err(e) => {
+            let message = format!("invalid request: {e}");
+            return (
+                Value::Null,
+                Err(Failure::new(FailureKind::Request, message)),
+            );
+        }
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        session
+            .import_str(from, &content)
+            .map(|tokens| session.export_to_string(&transforms.apply(tokens), to))
+            .map_err(|e| e.to_string())
+    }))
+    .unwrap_or_else(|payload| {
+        Err(format!(
+            "the converter panicked: {}",
+            panic_message(&*payload)
+        ))
+    })
+    .map_err(|message| Failure::new(FailureKind::Conversion, message));
+
+    (name, result)
+}
+
+/// Returns `line` without its trailing `"\n"` or `"\r\n"`, like [`BufRead::lines`].
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Returns the message a panic was started with, if it was a string.
+fn panic_message(payload: &dyn Any) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause")
+}
+
+/// Parse a line into its `name` and `content` fields.
+fn parse_request(line: &str) -> Result<(Value, String), String> {
+    let mut request: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+
+    /// Take a string field out of `request`, or return an error.
+    macro_rules! take_string {
+        ($field:expr) => {
+            match request.get_mut($field).map(Value::take) {
+                Some(Value::String(value)) => value,
+                _ => return Err(format!("expected a string field '{}'", $field)),
+            }
+        };
+    }
+
+    let name = take_string!("name");
+    let content = take_string!("content");
+
+    Ok((Value::String(name), content))
+}
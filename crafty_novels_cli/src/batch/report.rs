@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! A summary of a [batch run][`super::run`], for people and for other tools.
+//!
+//! See [`Report`].
+
+use crafty_novels::{
+    export::OutputFormat,
+    import::InputFormat,
+    session::{Diagnostic, Severity, Stats},
+};
+use serde_json::{json, Value};
+use std::{
+    fmt::{Display, Write as _},
+    fs,
+    path::Path,
+    time::Duration,
+};
+
+/// The stage of a batch run that a book failed at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureKind {
+    /// The input could not be read.
+    Read,
+    /// The line was not valid UTF-8.
+    Encoding,
+    /// The line was not a valid request.
+    Request,
+    /// The book could not be converted.
+    Conversion,
+}
+
+impl FailureKind {
+    /// Every kind of failure, in the order they can happen.
+    pub const ALL: [Self; 4] = [Self::Read, Self::Encoding, Self::Request, Self::Conversion];
+
+    /// Returns the name of the kind, as written in reports.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Encoding => "encoding",
+            Self::Request => "request",
+            Self::Conversion => "conversion",
+        }
+    }
+}
+
+impl Display for FailureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Why a book could not be converted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    /// The stage it failed at.
+    kind: FailureKind,
+    /// A description of what went wrong.
+    message: String,
+}
+
+impl Failure {
+    /// Creates a failure of `kind`, described by `message`.
+    pub const fn new(kind: FailureKind, message: String) -> Self {
+        Self { kind, message }
+    }
+
+    /// Prefixes the message with the line of input it happened on, numbered from one.
+    #[must_use]
+    pub fn on_line(self, number: usize) -> Self {
+        Self {
+            message: format!("line {number}: {}", self.message),
+            ..self
+        }
+    }
+
+    /// Returns the stage it failed at.
+    pub const fn kind(&self) -> FailureKind {
+        self.kind
+    }
+
+    /// Returns a description of what went wrong.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// The outcome of converting a single book.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    /// The `name` of the request, or `null` if the request could not be parsed.
+    pub name: Value,
+    /// The format of the input.
+    pub from: InputFormat,
+    /// The format of the output.
+    pub to: OutputFormat,
+    /// What went wrong, if the conversion failed.
+    pub error: Option<Failure>,
+    /// Everything the conversion reported, other than the error.
+    pub diagnostics: Vec<Diagnostic>,
+    /// The sizes of the input and output.
+    pub stats: Stats,
+    /// How long the conversion took.
+    pub duration: Duration,
+}
+
+impl Entry {
+    /// Returns the [`Severity::Warning`]s, formatted as messages.
+    fn warnings(&self) -> impl Iterator<Item = &str> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity() == Severity::Warning)
+            .map(Diagnostic::message)
+    }
+
+    /// Returns the name as it is written in reports, with strings unquoted.
+    fn display_name(&self) -> String {
+        match &self.name {
+            Value::String(name) => name.clone(),
+            Value::Null => "(invalid request)".into(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// A record of every book converted in a batch run, in the order they were converted.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    /// One entry per non-blank line of input.
+    pub entries: Vec<Entry>,
+}
+
+impl Report {
+    /// Returns the report as JSON, with a `summary` of the totals and one object per book in
+    /// `files`.
+    pub fn to_json(&self) -> Value {
+        let files: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "name": entry.name,
+                    "from": entry.from.name(),
+                    "to": entry.to.name(),
+                    "error": entry.error.as_ref().map(Failure::message),
+                    "error_kind": entry.error.as_ref().map(|error| error.kind().name()),
+                    "warnings": entry.warnings().collect::<Vec<_>>(),
+                    "stats": {
+                        "bytes_imported": entry.stats.bytes_imported(),
+                        "bytes_exported": entry.stats.bytes_exported(),
+                        "tokens_imported": entry.stats.tokens_imported(),
+                        "pages_imported": entry.stats.pages_imported(),
+                    },
+                    "duration_ms": duration_ms(entry.duration),
+                })
+            })
+            .collect();
+
+        json!({
+            "summary": {
+                "files": self.entries.len(),
+                "failed": self.failed(),
+                "failures": self.failures_by_kind(),
+                "warnings": self.entries.iter().map(|entry| entry.warnings().count()).sum::<usize>(),
+                "duration_ms": duration_ms(self.entries.iter().map(|entry| entry.duration).sum()),
+            },
+            "files": files,
+        })
+    }
+
+    /// Returns the report as a standalone HTML page, with a table of every book.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(concat!(
+            r#"<!DOCTYPE html><html lang="en" dir="ltr"><head><meta charset="utf-8" />"#,
+            "<title>Conversion report</title>",
+            r#"<meta name="viewport" content="width=device-width, initial-scale=1.0" />"#,
+            "</head><body><h1>Conversion report</h1>",
+        ));
+
+        let failures: Vec<String> = FailureKind::ALL
+            .into_iter()
+            .filter_map(|kind| match self.failed_with(kind) {
+                0 => None,
+                count => Some(format!("{count} {kind}")),
+            })
+            .collect();
+        let failures = if failures.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", failures.join(", "))
+        };
+
+        // Writing into a `String` is infallible
+        let _ = write!(
+            html,
+            "<p>{} file(s), {} failed{failures}</p><table><thead><tr>\
+             <th>Name</th><th>From</th><th>To</th><th>Result</th><th>Warnings</th>\
+             <th>Input bytes</th><th>Output bytes</th><th>Pages</th><th>Duration (ms)</th>\
+             </tr></thead><tbody>",
+            self.entries.len(),
+            self.failed(),
+        );
+
+        for entry in &self.entries {
+            let result = entry.error.as_ref().map_or_else(
+                || "ok".into(),
+                |error| format!("{} error: {}", error.kind(), escape(error.message())),
+            );
+            let warnings: Vec<String> = entry.warnings().map(escape).collect();
+
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{result}</td><td>{}</td>\
+                 <td>{}</td><td>{}</td><td>{}</td><td>{:.3}</td></tr>",
+                escape(&entry.display_name()),
+                entry.from,
+                entry.to,
+                warnings.join("<br />"),
+                entry.stats.bytes_imported(),
+                entry.stats.bytes_exported(),
+                entry.stats.pages_imported(),
+                duration_ms(entry.duration),
+            );
+        }
+
+        html.push_str("</tbody></table></body></html>");
+        html
+    }
+
+    /// Write the report into `directory` as `report.json` and `report.html`, creating the
+    /// directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot create the directory or write either file
+    pub fn write_to_directory(&self, directory: &Path) -> std::io::Result<()> {
+        fs::create_dir_all(directory)?;
+
+        let json = serde_json::to_string_pretty(&self.to_json())?;
+        fs::write(directory.join("report.json"), json)?;
+        fs::write(directory.join("report.html"), self.to_html())?;
+
+        Ok(())
+    }
+
+    /// Returns how many conversions failed.
+    pub fn failed(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.error.is_some())
+            .count()
+    }
+
+    /// Returns how many conversions failed with `kind`.
+    fn failed_with(&self, kind: FailureKind) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                entry
+                    .error
+                    .as_ref()
+                    .is_some_and(|error| error.kind() == kind)
+            })
+            .count()
+    }
+
+    /// Returns how many conversions failed with each kind, as a JSON object.
+    fn failures_by_kind(&self) -> Value {
+        FailureKind::ALL
+            .into_iter()
+            .map(|kind| (kind.name().to_owned(), self.failed_with(kind).into()))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+/// Returns `duration` in fractional milliseconds.
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Escape the characters of `text` that are special in HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(char),
+        }
+    }
+
+    escaped
+}
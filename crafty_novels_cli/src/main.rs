@@ -17,11 +17,275 @@
 
 #![warn(clippy::cargo, clippy::nursery, clippy::pedantic)]
 #![cfg_attr(debug_assertions, allow(clippy::missing_errors_doc))]
+// `serde_json` and `thiserror` depend on different major versions of `syn`
+#![allow(clippy::multiple_crate_versions)]
 
-use crafty_novels::{export::Html, import::Stendhal, Export, Tokenize};
+use args::{Args, Command, PageRange, StdinFormat, USAGE};
+use crafty_novels::{
+    export::{
+        check_compatibility, Brf, ColorDepth, Cp437, Cp437ExportOptions, DocumentKind, GzipWriter,
+        Html, HtmlExportOptions, OutputFormat, PlainText, PlainTextExportOptions, Ssml, Text,
+        TextDirection,
+    },
+    import::{decode, decompress, Ghostwriter, InputFormat, JsonText, Nbt, Stendhal, Tellraw},
+    syntax::{transform::Pipeline, TokenList},
+    Export, Tokenize,
+};
+use std::{
+    error::Error,
+    io::{stdin, stdout, Read, Write},
+    process::ExitCode,
+};
 
-fn main() {
-    test_string_parsing();
+mod args;
+mod batch;
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    if args.command == Command::Analyze {
+        return match analyze_stdin_book(&args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let result = match args.stdin_format {
+        None => {
+            test_string_parsing();
+            Ok(())
+        }
+        Some(StdinFormat::Book) => convert_stdin_book(&args),
+        Some(StdinFormat::List) => convert_stdin_list(&args),
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Convert a single book from stdin, or only the pages in `args`, writing the output to stdout.
+///
+/// The transforms in `args` are run on the selected pages, in order.
+///
+/// With `--explain`, first warns on stderr about each part of the book that the output format
+/// cannot represent. With `--dry-run`, nothing is written to stdout.
+///
+/// Gzip and zip input is decompressed first, reading the zip entry named by `--entry`, if any.
+/// With `--compress`, the output is gzip-compressed. With `--fragment`, HTML is written without the
+/// document around the book.
+fn convert_stdin_book(args: &Args) -> Result<(), Box<dyn Error>> {
+    let input = decompress(stdin().lock(), args.entry.as_deref())?;
+    let tokens = select_pages(tokenize_reader(input, args.from)?, args.pages)?;
+    let tokens = args
+        .transforms
+        .iter()
+        .copied()
+        .collect::<Pipeline>()
+        .apply(tokens);
+
+    if args.explain {
+        for warning in check_compatibility(&tokens, args.to) {
+            eprintln!("warning: {warning}");
+        }
+    }
+
+    if !args.dry_run {
+        let colors = args.colors.unwrap_or_else(detect_color_depth);
+        let document_kind = if args.fragment {
+            DocumentKind::Fragment
+        } else {
+            DocumentKind::Standalone
+        };
+
+        if args.compress {
+            let mut output = GzipWriter::new(stdout().lock());
+            export_to_writer(tokens, &mut output, args.to, colors, document_kind)?;
+            output.finish()?.flush()?;
+        } else {
+            export_to_writer(tokens, &mut stdout().lock(), args.to, colors, document_kind)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert many books from stdin, one JSON object per line, writing the output to stdout and the
+/// report to the directory in `args`, if any.
+///
+/// With `--fail-fast`, stops at the first failure and returns an error after writing the report.
+/// With `--compress`, the output is gzip-compressed, but not the report.
+fn convert_stdin_list(args: &Args) -> Result<(), Box<dyn Error>> {
+    if args.pages.is_some() {
+        return Err("`--pages` can only be used with a single book".into());
+    }
+    if args.entry.is_some() {
+        return Err("`--entry` can only be used with a single book".into());
+    }
+    if args.explain || args.dry_run {
+        return Err("`--explain` and `--dry-run` can only be used with a single book".into());
+    }
+
+    let transforms = args.transforms.iter().copied().collect::<Pipeline>();
+    let report = if args.compress {
+        let mut output = GzipWriter::new(stdout().lock());
+        let report = batch::run(
+            stdin().lock(),
+            &mut output,
+            args.from,
+            args.to,
+            &transforms,
+            args.fail_fast,
+        )?;
+        output.finish()?.flush()?;
+        report
+    } else {
+        batch::run(
+            stdin().lock(),
+            &mut stdout().lock(),
+            args.from,
+            args.to,
+            &transforms,
+            args.fail_fast,
+        )?
+    };
+
+    if let Some(directory) = &args.report {
+        report.write_to_directory(directory)?;
+    }
+
+    if args.fail_fast && report.failed() > 0 {
+        return Err("stopped at the first failed conversion".into());
+    }
+
+    Ok(())
+}
+
+/// Analyze the formatting of a single book from stdin, or only the pages in `args`, writing the
+/// report to stdout.
+///
+/// The transforms in `args` are run on the selected pages first, in order.
+fn analyze_stdin_book(args: &Args) -> Result<(), Box<dyn Error>> {
+    let input = decompress(stdin().lock(), args.entry.as_deref())?;
+    let tokens = select_pages(tokenize_reader(input, args.from)?, args.pages)?;
+    let tokens = args
+        .transforms
+        .iter()
+        .copied()
+        .collect::<Pipeline>()
+        .apply(tokens);
+    writeln!(stdout().lock(), "{}", tokens.analyze())?;
+
+    Ok(())
+}
+
+/// Tokenize a book from a reader in the given format.
+///
+/// Text inputs are decoded first, so UTF-16 and UTF-8 with a byte order mark are understood.
+///
+/// If a Stendhal document fails to tokenize, the error ends with a `help:` line for each
+/// suggested fix.
+fn tokenize_reader(input: impl Read, from: InputFormat) -> Result<TokenList, Box<dyn Error>> {
+    Ok(match from {
+        InputFormat::Stendhal => {
+            // Read it all up front, so that suggested fixes can be found in it
+            let mut string = String::new();
+            decode(input)?.read_to_string(&mut string)?;
+
+            Stendhal::tokenize_string(&string).map_err(|e| {
+                let mut message = e.to_string();
+                for suggestion in Stendhal::suggest_fixes(&string, &e) {
+                    message.push_str("\nhelp: ");
+                    message.push_str(&suggestion.to_string());
+                }
+                message
+            })?
+        }
+        InputFormat::Tellraw => Tellraw::tokenize_reader(decode(input)?)?,
+        InputFormat::Nbt => Nbt::tokenize_reader(input)?,
+        InputFormat::JsonText => JsonText::tokenize_reader(decode(input)?)?,
+        InputFormat::Ghostwriter => Ghostwriter::tokenize_reader(decode(input)?)?,
+    })
+}
+
+/// Cut `tokens` down to `pages`, if any.
+fn select_pages(tokens: TokenList, pages: Option<PageRange>) -> Result<TokenList, Box<dyn Error>> {
+    match pages {
+        // Report the page as numbered on the command line, rather than its index
+        Some(pages) => tokens.pages(pages.indices()).map_err(|e| {
+            format!(
+                "page {} is past the end of a book with {} pages",
+                e.index() + 1,
+                e.pages()
+            )
+            .into()
+        }),
+        None => Ok(tokens),
+    }
+}
+
+/// Guess how many colors the terminal can show from the `COLORTERM` and `TERM` environment
+/// variables, falling back to the sixteen colors that every color terminal supports.
+fn detect_color_depth() -> ColorDepth {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+
+    if matches!(var("COLORTERM").as_str(), "truecolor" | "24bit") {
+        ColorDepth::TrueColor
+    } else if var("TERM").contains("256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// Export a book into a writer in the given format, writing colors for a terminal that can show
+/// `colors` and HTML as a `document_kind`, where relevant.
+fn export_to_writer(
+    tokens: TokenList,
+    output: &mut impl Write,
+    to: OutputFormat,
+    colors: ColorDepth,
+    document_kind: DocumentKind,
+) -> std::io::Result<()> {
+    match to {
+        OutputFormat::Html => Html::export_token_vector_to_writer_with_options(
+            &tokens,
+            output,
+            &HtmlExportOptions::new()
+                .with_document_kind(document_kind)
+                .with_work_language(&tokens),
+        ),
+        OutputFormat::Text => Text::export_token_vector_to_writer(tokens, output),
+        OutputFormat::Ssml => Ssml::export_token_vector_to_writer(tokens, output),
+        OutputFormat::Brf => Brf::export_token_vector_to_writer(tokens, output),
+        OutputFormat::PlainText => PlainText::export_token_vector_to_writer_with_options(
+            &tokens,
+            output,
+            &PlainTextExportOptions::new().with_direction(TextDirection::for_work(&tokens)),
+        ),
+        OutputFormat::Cp437 => Cp437::export_token_vector_to_writer_with_options(
+            &tokens,
+            output,
+            &Cp437ExportOptions::new().with_color_depth(colors),
+        ),
+    }
 }
 
 fn test_string_parsing() {
@@ -35,7 +35,13 @@ impl UpperHex for Rgb {
     ///
     /// Ex. `(255, 255, 255)` -> `"FFFFFF"`.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:X}{:X}{:X}", self.red(), self.green(), self.blue())
+        write!(
+            f,
+            "{:02X}{:02X}{:02X}",
+            self.red(),
+            self.green(),
+            self.blue()
+        )
     }
 }
 
@@ -23,6 +23,8 @@
 #![allow(clippy::module_name_repetitions)]
 
 mod display;
+#[cfg(test)]
+mod test;
 
 /// Represents the possible text colors (foreground and background) in Minecraft: Java Edition.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
@@ -45,6 +47,69 @@ pub enum Color {
     White,
 }
 
+impl Color {
+    /// Every color, in the order of their format codes (`'0'` to `'f'`).
+    pub const ALL: [Self; 16] = [
+        Self::Black,
+        Self::DarkBlue,
+        Self::DarkGreen,
+        Self::DarkAqua,
+        Self::DarkRed,
+        Self::DarkPurple,
+        Self::Gold,
+        Self::Gray,
+        Self::DarkGray,
+        Self::Blue,
+        Self::Green,
+        Self::Aqua,
+        Self::Red,
+        Self::LightPurple,
+        Self::Yellow,
+        Self::White,
+    ];
+
+    /// Returns the color with the given name, as used by Minecraft's JSON text components, ex.
+    /// `"dark_blue"`.
+    ///
+    /// See [`ColorValue::name`].
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|color| ColorValue::from(*color).name() == name)
+    }
+
+    /// Returns the [`Rgb`] value of the color as drawn on `layer`.
+    ///
+    /// See [`ColorValue::rgb`].
+    #[must_use]
+    pub fn rgb(self, layer: ColorLayer) -> Rgb {
+        ColorValue::from(self).rgb(layer)
+    }
+
+    /// Returns the color whose foreground is closest to `rgb`.
+    #[must_use]
+    pub fn nearest(rgb: Rgb) -> Self {
+        let distance = |color: &Self| {
+            let fg = ColorValue::from(*color).fg();
+
+            [
+                (fg.red(), rgb.red()),
+                (fg.green(), rgb.green()),
+                (fg.blue(), rgb.blue()),
+            ]
+            .into_iter()
+            .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+            .sum::<u32>()
+        };
+
+        Self::ALL
+            .into_iter()
+            .min_by_key(distance)
+            .unwrap_or(Self::White)
+    }
+}
+
 impl From<Color> for ColorValue {
     /// Get the values associated with a given [`Color`] in Minecraft: Java Edition.
     fn from(color: Color) -> Self {
@@ -85,6 +150,28 @@ impl From<Color> for ColorValue {
     }
 }
 
+/// Which part of the text a [`Color`] is drawn as.
+///
+/// Minecraft draws colored text twice: once in the foreground value of its color as the text
+/// itself, and once in the darker background value as the shadow behind it.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels_minecraft::{Color, ColorLayer, Rgb};
+///
+/// assert_eq!(Color::Blue.rgb(ColorLayer::Foreground), Rgb::new(85, 85, 255));
+/// assert_eq!(Color::Blue.rgb(ColorLayer::Background), Rgb::new(21, 21, 63));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorLayer {
+    /// The text itself.
+    #[default]
+    Foreground,
+    /// The shadow behind the text.
+    Background,
+}
+
 /// Represents a [`Color`] as it is used for text formatting in Minecraft.
 ///
 /// To reprsent an arbitrary RGB color, see [`Rgb`].
@@ -92,7 +179,7 @@ impl From<Color> for ColorValue {
 /// # Examples
 ///
 /// ```rust
-/// use crafty_novels::syntax::minecraft::{ColorValue, Color, Rgb};
+/// use crafty_novels_minecraft::{ColorValue, Color, Rgb};
 ///
 /// // Has no constructor, as it is designed to represent values for the `Color` enum
 /// let blue = ColorValue::from(Color::Blue);
@@ -166,10 +253,19 @@ impl ColorValue {
     pub const fn bg(&self) -> Rgb {
         self.bg
     }
+
+    /// Returns the [`Rgb`] value of the color as drawn on `layer`.
+    #[must_use]
+    pub const fn rgb(&self, layer: ColorLayer) -> Rgb {
+        match layer {
+            ColorLayer::Foreground => self.fg,
+            ColorLayer::Background => self.bg,
+        }
+    }
 }
 
 /// Represents a 24-bit RGB color value.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Rgb {
     red: u8,
     green: u8,
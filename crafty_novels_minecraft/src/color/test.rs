@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for [`color`][`super`].
+
+use super::{Color, ColorValue, Rgb};
+
+#[test]
+fn rgb_hex_zero_padded() {
+    assert_eq!(Rgb::new(255, 170, 0).to_string(), "#FFAA00");
+    assert_eq!(format!("{:X}", Rgb::new(0, 5, 10)), "00050A");
+    assert_eq!(ColorValue::from(Color::Black).to_string(), "#000000");
+    assert_eq!(format!("{:X}", ColorValue::from(Color::Gold)), "FFAA00");
+}
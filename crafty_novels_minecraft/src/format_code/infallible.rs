@@ -42,6 +42,7 @@ impl From<Format> for FormatCode {
             ) => {
                 match $value {
                     Format::Color(color) => color.into(),
+                    Format::Unknown(code) => Self { code, format: $value },
                     $( Format::$variant => Self {
                             code: $format_code,
                             format: $value,
@@ -51,6 +51,47 @@ impl FormatCode {
         code.try_into()
     }
 
+    /// Creates a new [`FormatCode`], ignoring ASCII case.
+    ///
+    /// Minecraft: Java Edition only recognizes lowercase format codes, but some tools emit
+    /// uppercase ones like `"§L"`. The original [`char`] is kept, so the code is written back out
+    /// as it was read.
+    ///
+    /// # Errors
+    ///
+    /// - [`ConversionError::NoSuchFormatCode`] if the lowercase [`char`] does not correspond to a
+    ///   variant of [`Format`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels_minecraft::{Format, FormatCode};
+    ///
+    /// let code = FormatCode::new_lenient('L')?;
+    /// assert_eq!(code.format(), Format::Bold);
+    /// assert_eq!(code.to_string(), "§L");
+    ///
+    /// assert!(FormatCode::new('L').is_err());
+    /// # Ok::<(), crafty_novels_minecraft::ConversionError>(())
+    /// ```
+    pub fn new_lenient(code: char) -> Result<Self, ConversionError> {
+        Self::new(code.to_ascii_lowercase())
+            .map(|lowercase| Self { code, ..lowercase })
+            .map_err(|_| ConversionError::NoSuchFormatCode(code))
+    }
+
+    /// Creates a new [`FormatCode`], falling back to [`Format::Unknown`].
+    ///
+    /// Looks up the [`char`] against Minecraft: Java Edition's list of formatting codes. If it
+    /// does not correspond to a variant of [`Format`], it is preserved as [`Format::Unknown`].
+    #[must_use]
+    pub fn new_or_unknown(code: char) -> Self {
+        Self::new(code).unwrap_or(Self {
+            code,
+            format: Format::Unknown(code),
+        })
+    }
+
     /// Returns the inner [`char`].
     #[must_use]
     pub const fn code(self) -> char {
@@ -154,3 +154,49 @@ fn format_code_from_str() -> Result {
 
     Ok(())
 }
+
+#[test]
+fn format_code_new_or_unknown() {
+    assert_eq!(
+        FormatCode::new_or_unknown('l'),
+        FormatCode {
+            code: 'l',
+            format: Format::Bold,
+        }
+    );
+    assert_eq!(
+        FormatCode::new_or_unknown('z'),
+        FormatCode {
+            code: 'z',
+            format: Format::Unknown('z'),
+        }
+    );
+    assert_eq!(FormatCode::from(Format::Unknown('z')).code(), 'z');
+}
+
+#[test]
+fn format_code_new_lenient() -> Result {
+    for (code, format) in [
+        ('L', Format::Bold),
+        ('l', Format::Bold),
+        ('A', Format::Color(Color::Green)),
+        ('R', Format::Reset),
+        ('0', Format::Color(Color::Black)),
+    ] {
+        // Strict parsing only accepts lowercase
+        assert_eq!(
+            FormatCode::new(code).is_ok(),
+            !code.is_ascii_uppercase(),
+            "{code}"
+        );
+
+        let format_code = FormatCode::new_lenient(code)?;
+        assert_eq!(format_code, FormatCode { code, format });
+        assert_eq!(char::from(format_code), code);
+        assert_eq!(format_code.to_string(), format!("§{code}"));
+    }
+
+    assert!(FormatCode::new_lenient('Z').is_err());
+
+    Ok(())
+}
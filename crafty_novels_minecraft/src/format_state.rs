@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The resolved formatting of text at a point in a document.
+//!
+//! See [`FormatState`].
+
+#![allow(clippy::module_name_repetitions)]
+
+use super::{Color, Format, Style};
+
+/// The formatting that applies to text after a sequence of [`Format`]s.
+///
+/// [`Format`]s are stateful: each one adds to the formatting of all the text that follows it, until
+/// a [`Format::Reset`]. [`FormatState`] resolves that into the formatting of a single point in the
+/// document.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels_minecraft::{Color, Format, FormatState};
+///
+/// let mut state = FormatState::new();
+/// state.apply(Format::Bold);
+/// state.apply(Format::Color(Color::Gold));
+/// assert!(state.bold());
+/// assert_eq!(state.color(), Some(Color::Gold));
+///
+/// state.apply(Format::Reset);
+/// assert!(state.is_plain());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormatState {
+    color: Option<Color>,
+    style: Style,
+}
+
+impl FormatState {
+    /// Creates a new [`FormatState`] without any formatting.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            color: None,
+            style: Style::NONE,
+        }
+    }
+
+    /// Creates a new [`FormatState`] from its parts.
+    #[must_use]
+    pub const fn from_parts(color: Option<Color>, style: Style) -> Self {
+        Self { color, style }
+    }
+
+    /// Applies a [`Format`] to the state.
+    ///
+    /// A [`Format::Color`] replaces any previous color, [`Format::Reset`] removes all formatting,
+    /// and [`Format::Unknown`] is ignored.
+    pub const fn apply(&mut self, format: Format) {
+        match format {
+            Format::Color(color) => self.color = Some(color),
+            Format::Reset => *self = Self::new(),
+            Format::Unknown(_) => (),
+            decoration => {
+                if let Some(style) = Style::from_format(decoration) {
+                    self.style.insert(style);
+                }
+            }
+        }
+    }
+
+    /// Returns a copy of the state with a [`Format`] applied.
+    ///
+    /// See [`Self::apply`].
+    #[must_use]
+    pub const fn with(mut self, format: Format) -> Self {
+        self.apply(format);
+        self
+    }
+
+    /// Returns the [`Format`]s that, applied in order to an empty state, produce this state.
+    ///
+    /// Does not include [`Format::Reset`].
+    #[must_use]
+    pub fn formats(&self) -> Vec<Format> {
+        let mut formats = vec![];
+
+        if let Some(color) = self.color {
+            formats.push(Format::Color(color));
+        }
+        formats.extend(self.style.formats());
+
+        formats
+    }
+
+    /// Whether or not `other` can be reached from this state without a [`Format::Reset`].
+    ///
+    /// That is, whether `other` only adds formatting.
+    #[must_use]
+    pub const fn is_subset_of(&self, other: &Self) -> bool {
+        // A color can be replaced by another, but not removed
+        let keeps_color = self.color.is_none() || other.color.is_some();
+
+        keeps_color && other.style.contains(self.style)
+    }
+
+    /// Whether or not the state has no formatting at all.
+    #[must_use]
+    pub const fn is_plain(&self) -> bool {
+        self.color.is_none() && self.style.is_empty()
+    }
+
+    /// Returns the decorations of the text.
+    #[must_use]
+    pub const fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns the color of the text, if any.
+    #[must_use]
+    pub const fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// Whether or not the text is obfuscated.
+    #[must_use]
+    pub const fn obfuscated(&self) -> bool {
+        self.style.contains(Style::OBFUSCATED)
+    }
+
+    /// Whether or not the text is bold.
+    #[must_use]
+    pub const fn bold(&self) -> bool {
+        self.style.contains(Style::BOLD)
+    }
+
+    /// Whether or not the text is struck through.
+    #[must_use]
+    pub const fn strikethrough(&self) -> bool {
+        self.style.contains(Style::STRIKETHROUGH)
+    }
+
+    /// Whether or not the text is underlined.
+    #[must_use]
+    pub const fn underline(&self) -> bool {
+        self.style.contains(Style::UNDERLINE)
+    }
+
+    /// Whether or not the text is italic.
+    #[must_use]
+    pub const fn italic(&self) -> bool {
+        self.style.contains(Style::ITALIC)
+    }
+}
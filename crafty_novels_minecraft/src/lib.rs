@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Syntax definitions for Minecraft: Java Edition text, shared by
+//! [crafty_novels](https://github.com/RemasteredArch/crafty_novels).
+//!
+//! Holds the formatting that `'§'` format codes represent, see [`Format`] and [`FormatCode`], the
+//! colors they can set, see [`Color`], and how formatting adds up over a run of text, see
+//! [`FormatState`]. [`obfuscation`] makes stand-ins for obfuscated text, and [`metrics`] measures
+//! text in the default font.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels_minecraft::{Color, Format, FormatState};
+//!
+//! let state = FormatState::new()
+//!     .with("§6".parse::<Format>()?)
+//!     .with("§l".parse::<Format>()?);
+//!
+//! assert_eq!(state.color(), Some(Color::Gold));
+//! assert!(state.bold());
+//! # Ok::<(), crafty_novels_minecraft::ConversionError>(())
+//! ```
+//!
+//! # License
+//!
+//! This crate is in no way affiliated with Microsoft, Mojang, or Minecraft. All
+//! trademarks belong to their respective owners.
+//!
+//! This crate is licensed under the GNU Affero General Public License version 3, or
+//! (at your option) any later version. You should have received a copy of the GNU Affero General
+//! Public License along with this crate, found in `LICENSE`. If not, see
+//! <https://www.gnu.org/licenses/>.
+
+#![warn(clippy::cargo, clippy::nursery, clippy::pedantic)]
+#![cfg_attr(debug_assertions, allow(clippy::missing_errors_doc))]
+
+pub use color::{Color, ColorLayer, ColorValue, Rgb};
+pub use error::ConversionError;
+pub use format_code::FormatCode;
+pub use format_state::FormatState;
+pub use obfuscation::{ObfuscatedLength, Obfuscator};
+use std::str::FromStr;
+pub use style::Style;
+
+mod color;
+mod error;
+mod format_code;
+mod format_state;
+pub mod metrics;
+pub mod obfuscation;
+mod style;
+
+/// Represents the ways that Minecraft: Java Edition will format text.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+pub enum Format {
+    Color(Color),
+    /// AKA "Magical Text Source", characters should rapidly swap between a set of characters.
+    Obfuscated,
+    Bold,
+    Strikethrough,
+    Underline,
+    Italic,
+    Reset,
+    /// A format code that Minecraft: Java Edition does not recognize, such as one added by a
+    /// modded client. Holds the character following the `'§'`.
+    ///
+    /// Importers only produce this when explicitly configured to. Exporters decide for themselves
+    /// whether to drop or annotate it.
+    Unknown(char),
+}
+
+impl From<FormatCode> for Format {
+    /// Look up a [`char`] against Minecraft: Java Edition's list of formatting codes.
+    ///
+    /// # Errors
+    ///
+    /// - [`ConversionError::NoSuchFormatCode`] if the [`FormatCode`] does not correspond to a
+    ///   variant of [`Format`]
+    fn from(code: FormatCode) -> Self {
+        code.format()
+    }
+}
+
+impl TryFrom<char> for Format {
+    type Error = ConversionError;
+
+    /// Match a format code to a [`Format`] variant.
+    ///
+    /// # Errors
+    ///
+    /// - [`ConversionError::NoSuchFormatCode`] if the [`FormatCode`] does not correspond to a
+    ///   variant of [`Format`]
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(FormatCode::try_from(value)?.format())
+    }
+}
+
+impl FromStr for Format {
+    type Err = ConversionError;
+
+    /// Get the character following the `'§'` in a Minecraft format code.
+    ///
+    /// Expects a two byte string that starts with `'§'`.
+    ///
+    /// Ex. The `'0'` in `"§0"`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ConversionError::InvalidFormatCodeString`] if passed a string that is longer than two
+    ///   [`char`]s or does not start with `'§'`
+    /// - [`ConversionError::NoSuchFormatCode`] if the [`FormatCode`] does not correspond to a
+    ///   variant of [`Format`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = FormatCode::from_str(s)?;
+
+        Ok(Self::from(code))
+    }
+}
+
+impl From<Format> for char {
+    fn from(value: Format) -> Self {
+        Self::from(FormatCode::from(value))
+    }
+}
+
+/// Remove all of the format codes (`'§'` followed by another character) from `text`.
+///
+/// A trailing `'§'` without a following character is also removed.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels_minecraft::strip_format_codes;
+///
+/// assert_eq!(strip_format_codes("§6Golden §lChronicle"), "Golden Chronicle");
+/// ```
+#[must_use]
+pub fn strip_format_codes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut iter = text.chars();
+
+    while let Some(char) = iter.next() {
+        if char == '§' {
+            iter.next();
+        } else {
+            output.push(char);
+        }
+    }
+
+    output
+}
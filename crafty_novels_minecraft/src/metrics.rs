@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Font metrics for Minecraft: Java Edition's default font, as used by written books.
+//!
+//! See [`char_width`].
+
+/// The width of the text area of a page in a written book, in pixels.
+pub const PAGE_WIDTH: u32 = 114;
+
+/// Returns how far a character advances the cursor in Minecraft: Java Edition's default font, in
+/// pixels, including the one pixel of spacing that follows every glyph.
+///
+/// Bold text is one pixel wider per character. Characters outside of the ASCII range use the
+/// common width of six pixels, which is correct for most Latin letters but only approximates other
+/// scripts.
+#[must_use]
+pub const fn char_width(char: char, bold: bool) -> u32 {
+    let width = match char {
+        '!' | '\'' | ',' | '.' | ':' | ';' | 'i' | '|' => 2,
+        '`' | 'l' => 3,
+        ' ' | '"' | '(' | ')' | '*' | 'I' | '[' | ']' | 't' | '{' | '}' => 4,
+        '<' | '>' | 'f' | 'k' => 5,
+        '@' | '~' => 7,
+        _ => 6,
+    };
+
+    if bold {
+        width + 1
+    } else {
+        width
+    }
+}
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deterministic stand-ins for [obfuscated][`super::Format::Obfuscated`] text.
+//!
+//! See [`Obfuscator`].
+
+/// The glyphs used by [`Obfuscator::default`].
+pub const DEFAULT_GLYPHS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// How long the replacement for each word of obfuscated text is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ObfuscatedLength {
+    /// Replace every character with one glyph, keeping the length of the text.
+    Keep,
+    /// Replace every word with this many glyphs, hiding the length of the text.
+    Fixed(usize),
+}
+
+/// Replaces obfuscated text with scrambled glyphs.
+///
+/// In Minecraft: Java Edition, obfuscated text constantly cycles through random characters. Formats
+/// that cannot animate can instead replace it with a scramble of glyphs. [`Obfuscator`] makes that
+/// scramble deterministic: it is seeded by a hash of the text itself, so converting the same
+/// document twice produces the same output, and version-controlled outputs don't produce noisy
+/// diffs.
+///
+/// Whitespace is always preserved.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels_minecraft::{ObfuscatedLength, Obfuscator};
+///
+/// let obfuscator = Obfuscator::default();
+/// let scrambled = obfuscator.obfuscate("secret words");
+///
+/// // The same input is always scrambled in the same way
+/// assert_eq!(scrambled, obfuscator.obfuscate("secret words"));
+/// assert_ne!(scrambled, "secret words");
+/// assert_eq!(scrambled.chars().nth(6), Some(' '));
+/// assert_eq!(scrambled.chars().count(), 12);
+///
+/// let obfuscator = Obfuscator::new("#").with_length(ObfuscatedLength::Fixed(3));
+/// assert_eq!(obfuscator.obfuscate("secret words"), "### ###");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Obfuscator {
+    /// The characters to choose from when scrambling text.
+    glyphs: Box<[char]>,
+    /// How long the replacement for each word is.
+    length: ObfuscatedLength,
+    /// Mixed into the hash of the text, to produce a different (but still deterministic) scramble.
+    seed: u64,
+}
+
+impl Obfuscator {
+    /// Creates a new [`Obfuscator`] that chooses from the characters in `glyphs`.
+    ///
+    /// Falls back to [`DEFAULT_GLYPHS`] if `glyphs` is empty.
+    #[must_use]
+    pub fn new(glyphs: &str) -> Self {
+        let glyphs = if glyphs.is_empty() {
+            DEFAULT_GLYPHS
+        } else {
+            glyphs
+        };
+
+        Self {
+            glyphs: glyphs.chars().collect(),
+            length: ObfuscatedLength::Keep,
+            seed: 0,
+        }
+    }
+
+    /// Sets how long the replacement for each word is.
+    #[must_use]
+    pub const fn with_length(mut self, length: ObfuscatedLength) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Sets a value to mix into the hash of the text.
+    ///
+    /// Different seeds produce different scrambles of the same text.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the characters to choose from when scrambling text.
+    #[must_use]
+    pub const fn glyphs(&self) -> &[char] {
+        &self.glyphs
+    }
+
+    /// Returns how long the replacement for each word is.
+    #[must_use]
+    pub const fn length(&self) -> ObfuscatedLength {
+        self.length
+    }
+
+    /// Replace `text` with a deterministic scramble of glyphs, preserving whitespace.
+    #[must_use]
+    pub fn obfuscate(&self, text: &str) -> String {
+        let mut rng = SplitMix64(fnv1a(text.as_bytes()) ^ self.seed);
+        let mut output = String::with_capacity(text.len());
+        // Whether or not the previous character was part of a word
+        let mut in_word = false;
+
+        for char in text.chars() {
+            if char.is_whitespace() {
+                output.push(char);
+                in_word = false;
+                continue;
+            }
+
+            let count = match self.length {
+                ObfuscatedLength::Keep => 1,
+                ObfuscatedLength::Fixed(_) if in_word => 0,
+                ObfuscatedLength::Fixed(count) => count,
+            };
+            in_word = true;
+
+            for _ in 0..count {
+                output.push(self.glyphs[rng.below(self.glyphs.len())]);
+            }
+        }
+
+        output
+    }
+}
+
+impl Default for Obfuscator {
+    fn default() -> Self {
+        Self::new(DEFAULT_GLYPHS)
+    }
+}
+
+/// Hash `bytes` with 64-bit FNV-1a, which is stable across platforms and Rust versions.
+const fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(PRIME);
+        i += 1;
+    }
+
+    hash
+}
+
+/// A small, deterministic pseudo-random number generator.
+///
+/// Not suitable for anything that needs to be unpredictable.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Returns the next pseudo-random value.
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a pseudo-random value in `0..max`.
+    #[allow(clippy::cast_possible_truncation)] // The result is always less than `max`
+    const fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+}
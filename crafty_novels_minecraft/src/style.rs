@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The decorations that can apply to text, as a set.
+//!
+//! See [`Style`].
+
+use super::Format;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not};
+
+/// A set of the decorations that can apply to text, like [`Style::BOLD`] and [`Style::ITALIC`].
+///
+/// Together with an [`Option<Color>`][`super::Color`], this is all of the formatting that can
+/// apply to a point in a document. See [`super::FormatState`].
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels_minecraft::{Format, Style};
+///
+/// let style = Style::BOLD | Style::ITALIC;
+/// assert!(style.contains(Style::BOLD));
+/// assert!(!style.contains(Style::BOLD | Style::UNDERLINE));
+/// assert_eq!(Style::from_format(Format::Italic), Some(Style::ITALIC));
+/// assert_eq!(style.formats(), [Format::Bold, Format::Italic]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Style(u8);
+
+impl Style {
+    /// No decorations.
+    pub const NONE: Self = Self(0);
+    /// AKA "Magical Text Source", see [`Format::Obfuscated`].
+    pub const OBFUSCATED: Self = Self(1 << 0);
+    /// See [`Format::Bold`].
+    pub const BOLD: Self = Self(1 << 1);
+    /// See [`Format::Strikethrough`].
+    pub const STRIKETHROUGH: Self = Self(1 << 2);
+    /// See [`Format::Underline`].
+    pub const UNDERLINE: Self = Self(1 << 3);
+    /// See [`Format::Italic`].
+    pub const ITALIC: Self = Self(1 << 4);
+    /// Every decoration.
+    pub const ALL: Self = Self(0b1_1111);
+
+    /// Every decoration, and its [`Format`], in the order that Minecraft lists their format codes.
+    const FORMATS: [(Self, Format); 5] = [
+        (Self::OBFUSCATED, Format::Obfuscated),
+        (Self::BOLD, Format::Bold),
+        (Self::STRIKETHROUGH, Format::Strikethrough),
+        (Self::UNDERLINE, Format::Underline),
+        (Self::ITALIC, Format::Italic),
+    ];
+
+    /// Returns the decoration that a [`Format`] adds, if it is a decoration.
+    #[must_use]
+    pub const fn from_format(format: Format) -> Option<Self> {
+        match format {
+            Format::Obfuscated => Some(Self::OBFUSCATED),
+            Format::Bold => Some(Self::BOLD),
+            Format::Strikethrough => Some(Self::STRIKETHROUGH),
+            Format::Underline => Some(Self::UNDERLINE),
+            Format::Italic => Some(Self::ITALIC),
+            Format::Color(_) | Format::Reset | Format::Unknown(_) => None,
+        }
+    }
+
+    /// Returns the raw bits of the set.
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether or not the set has no decorations.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether or not every decoration in `other` is also in this set.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Adds every decoration in `other` to this set.
+    pub const fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Removes every decoration in `other` from this set.
+    pub const fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Returns the [`Format`]s of every decoration in the set, in the order that Minecraft lists
+    /// their format codes.
+    #[must_use]
+    pub fn formats(self) -> Vec<Format> {
+        Self::FORMATS
+            .into_iter()
+            .filter(|(style, _)| self.contains(*style))
+            .map(|(_, format)| format)
+            .collect()
+    }
+}
+
+impl BitOr for Style {
+    type Output = Self;
+
+    /// The union of both sets.
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Style {
+    /// Adds every decoration in `rhs` to this set.
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+impl BitAnd for Style {
+    type Output = Self;
+
+    /// The intersection of both sets.
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for Style {
+    type Output = Self;
+
+    /// Every decoration that is not in the set.
+    fn not(self) -> Self::Output {
+        Self(!self.0 & Self::ALL.0)
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Command-line argument parsing.
+//!
+//! See [`Args`].
+
+use crafty_novels::import::InputFormat;
+use std::path::PathBuf;
+
+/// The help text printed alongside argument errors.
+pub const USAGE: &str = "\
+Usage: crafty_novels_site <INPUT> <OUTPUT> [OPTIONS]
+
+Converts every book in the directory INPUT into a static site in the directory OUTPUT, with an
+index page, a page for each book, `search.json` for searching the books, and `feed.xml` as an RSS
+feed.
+
+Only the files that changed since the last build in OUTPUT are written, and the pages of books that
+are gone are deleted. `changes.json` lists what changed, by the SHA-256 digest of each file.
+
+The format of each book is taken from its file extension, like `book.stendhal` or `book.ghb.gz`.
+
+Options:
+  --from <FORMAT>      The format of books whose file extension doesn't name one, which are
+                       otherwise skipped
+  --theme <THEME>      How to style each book, where THEME is one of `light`, `dark`, or `sepia`
+                       [default: light]
+  --base-url <URL>     The URL that the site will be hosted at, used for the links in the feed
+                       [default: none, so the links are relative]
+  -h, --help           Print this help text";
+
+/// The parsed command-line arguments.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Args {
+    /// The directory to read books from.
+    pub input: PathBuf,
+    /// The directory to write the site into.
+    pub output: PathBuf,
+    /// The format of books whose file extension doesn't name one, if any.
+    pub from: Option<InputFormat>,
+    /// The name of the theme to style each book with.
+    pub theme: Box<str>,
+    /// The URL that the site will be hosted at, without a trailing slash.
+    pub base_url: Box<str>,
+    /// Whether to print the help text and exit.
+    pub help: bool,
+}
+
+impl Args {
+    /// Parse arguments, not including the name of the program.
+    ///
+    /// # Errors
+    ///
+    /// - A description of the problem if an argument is unknown, missing a value, or has an
+    ///   invalid value, or if there are not exactly two directories
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self {
+            theme: "light".into(),
+            ..Self::default()
+        };
+        let mut directories: Vec<PathBuf> = vec![];
+
+        while let Some(arg) = args.next() {
+            /// Get the value following the current argument, or return an error.
+            macro_rules! value {
+                () => {
+                    args.next()
+                        .ok_or_else(|| format!("expected a value after '{arg}'"))?
+                };
+            }
+
+            match arg.as_str() {
+                "--from" => parsed.from = Some(value!().parse().map_err(|e| format!("{e}"))?),
+                "--theme" => parsed.theme = value!().into(),
+                "--base-url" => parsed.base_url = value!().trim_end_matches('/').into(),
+                "-h" | "--help" => parsed.help = true,
+                _ if arg.starts_with('-') => return Err(format!("unknown argument '{arg}'")),
+                _ => directories.push(arg.into()),
+            }
+        }
+
+        if parsed.help {
+            return Ok(parsed);
+        }
+
+        let [input, output] = <[PathBuf; 2]>::try_from(directories).map_err(|directories| {
+            format!(
+                "expected an input and an output directory, found {}",
+                directories.len()
+            )
+        })?;
+        parsed.input = input;
+        parsed.output = output;
+
+        Ok(parsed)
+    }
+}
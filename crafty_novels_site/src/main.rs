@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+#![warn(clippy::cargo, clippy::nursery, clippy::pedantic)]
+#![cfg_attr(debug_assertions, allow(clippy::missing_errors_doc))]
+// `serde_json` and `thiserror` depend on different major versions of `syn`
+#![allow(clippy::multiple_crate_versions)]
+
+use args::{Args, USAGE};
+use crafty_novels::{
+    export::HtmlExportOptions,
+    import::InputFormat,
+    session::{Session, Severity},
+};
+use site::Site;
+use std::{
+    error::Error,
+    fs::{self, File},
+    path::Path,
+    process::ExitCode,
+};
+
+mod args;
+mod site;
+mod themes;
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    if let Err(e) = build_site(&args) {
+        eprintln!("error: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Convert every book in the input directory of `args` and write them into a site in the output
+/// directory.
+///
+/// A book that fails to convert doesn't stop the others, but is reported on stderr, and the site
+/// is still written before returning an error. Books that are already in the site are skipped.
+fn build_site(args: &Args) -> Result<(), Box<dyn Error>> {
+    themes::register();
+    let theme = HtmlExportOptions::named(&args.theme).ok_or_else(|| {
+        format!(
+            "unknown theme '{}', expected one of {}",
+            args.theme,
+            themes::NAMES.join(", ")
+        )
+    })?;
+
+    // Let browsers find the feed from any book
+    let head = format!(
+        r#"{}<link rel="alternate" type="application/rss+xml" title="Library" href="feed.xml" />"#,
+        theme.extra_head().unwrap_or_default()
+    );
+    let options = (*theme).clone().with_extra_head(Some(head.into()));
+
+    let mut paths = fs::read_dir(&args.input)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Directories are read in an arbitrary order
+    paths.sort();
+
+    let mut site = Site::new();
+    let mut failed = 0;
+
+    for path in paths.iter().filter(|path| path.is_file()) {
+        let Some(format) = input_format(path).or(args.from) else {
+            eprintln!(
+                "skipping {}: its extension doesn't name a format",
+                path.display()
+            );
+            continue;
+        };
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("error: {}: {e}", path.display());
+                failed += 1;
+                continue;
+            }
+        };
+
+        let mut session = Session::new().with_html_options(options.clone());
+        let added = session
+            .import(format, file)
+            .map(|tokens| site.add(&mut session, &tokens));
+
+        for diagnostic in session.diagnostics() {
+            if diagnostic.severity() >= Severity::Warning {
+                eprintln!("{}: {diagnostic}", path.display());
+            }
+        }
+
+        match added {
+            Ok(true) => (),
+            Ok(false) => eprintln!("skipping {}: it is already in the site", path.display()),
+            Err(_) => failed += 1,
+        }
+    }
+
+    let changes = site.write_to_directory(&args.output, &args.base_url)?;
+    let plural = if site.len() == 1 { "" } else { "s" };
+    eprintln!(
+        "wrote {} book{plural} into {} ({changes})",
+        site.len(),
+        args.output.display()
+    );
+
+    if failed > 0 {
+        let plural = if failed == 1 { "" } else { "s" };
+        return Err(format!("{failed} book{plural} could not be converted").into());
+    }
+
+    Ok(())
+}
+
+/// Returns the format named by the extension of `path`, looking past a `.gz` extension, if any.
+fn input_format(path: &Path) -> Option<InputFormat> {
+    let mut extension = path.extension()?.to_str()?;
+
+    if extension.eq_ignore_ascii_case("gz") {
+        extension = Path::new(path.file_stem()?).extension()?.to_str()?;
+    }
+
+    extension.parse().ok()
+}
@@ -0,0 +1,356 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Building a static site from a library of converted books.
+//!
+//! See [`Site`].
+
+#[cfg(test)]
+mod test;
+
+use crafty_novels::{
+    export::{Html, OutputFormat, PlainText, PlainTextExportOptions},
+    session::Session,
+    syntax::{
+        analysis::FormattingCounts,
+        library::{Library, Work},
+        provenance::sha256,
+        TokenList,
+    },
+};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsStr,
+    fmt::Write,
+    fs,
+    io::ErrorKind,
+    path::Path,
+};
+
+/// The file that records the SHA-256 digest of every other file of the site, so that the next
+/// build can tell which of them changed.
+const MANIFEST: &str = "manifest.json";
+/// The file that lists what the last build changed.
+const CHANGES: &str = "changes.json";
+
+/// What writing a [`Site`] changed in its directory, compared to the last build written there.
+#[derive(Debug, Default)]
+pub struct Changes {
+    /// The files that are new, with the SHA-256 digest of each.
+    added: BTreeMap<String, String>,
+    /// The files that changed, with the SHA-256 digest of each.
+    modified: BTreeMap<String, String>,
+    /// The files that are no longer part of the site, which were deleted.
+    removed: Vec<String>,
+    /// How many files were left as they were.
+    unchanged: usize,
+}
+
+impl Changes {
+    /// Returns the change list as a JSON object, in the form:
+    ///
+    /// ```json
+    /// {
+    ///   "added": { "....html": "{sha256}" },
+    ///   "modified": { "index.html": "{sha256}" },
+    ///   "removed": ["....html"],
+    ///   "unchanged": 3
+    /// }
+    /// ```
+    pub fn to_json(&self) -> String {
+        json!({
+            "added": self.added,
+            "modified": self.modified,
+            "removed": self.removed,
+            "unchanged": self.unchanged,
+        })
+        .to_string()
+    }
+}
+
+impl std::fmt::Display for Changes {
+    /// Ex. `"2 added, 1 modified, 0 removed, 3 unchanged"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} added, {} modified, {} removed, {} unchanged",
+            self.added.len(),
+            self.modified.len(),
+            self.removed.len(),
+            self.unchanged
+        )
+    }
+}
+
+/// A converted book, ready to be written into a [`Site`].
+struct Book {
+    /// The book as HTML.
+    html: Box<str>,
+    /// The book as plain text, for searching.
+    text: Box<str>,
+    /// How heavily the whole book is formatted.
+    formatting: FormattingCounts,
+}
+
+/// A static site for a library of books.
+///
+/// The site has an index of every book by author, a page for each book, a search index, and an RSS
+/// feed. See [`Self::write_to_directory`].
+#[derive(Default)]
+pub struct Site {
+    /// The index of every book by author.
+    library: Library,
+    /// Every book, by its [`TokenList::content_hash`].
+    books: HashMap<Box<str>, Book>,
+}
+
+impl Site {
+    /// Creates an empty site.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts `tokens` with `session` and adds it to the site, returning whether it was added,
+    /// or `false` if the site already has a book with the same content.
+    pub fn add(&mut self, session: &mut Session, tokens: &TokenList) -> bool {
+        if !self.library.add(tokens) {
+            return false;
+        }
+
+        let book = Book {
+            html: session.export_to_string(tokens, OutputFormat::Html),
+            // Not through `session`, as dropping the formatting is the point, not worth a warning
+            text: PlainText::export_token_vector_to_string_with_options(
+                tokens,
+                &PlainTextExportOptions::default(),
+            ),
+            formatting: tokens.analyze().total(),
+        };
+        self.books.insert(tokens.content_hash().into(), book);
+
+        true
+    }
+
+    /// Returns the number of books in the site.
+    pub fn len(&self) -> usize {
+        self.library.len()
+    }
+
+    /// Write the site into `directory`, creating it if it doesn't exist yet, returning what
+    /// changed since the last build written there.
+    ///
+    /// The site is made up of:
+    ///
+    /// - `index.html`, listing every book by author, see [`Html::export_library_to_writer`]
+    /// - `{hash}.html` for each book, named after its [`TokenList::content_hash`]
+    /// - `search.json`, see [`Self::search_json`]
+    /// - `feed.xml`, see [`Self::feed`]
+    ///
+    /// Only the files whose content changed are written, and files of the last build that are no
+    /// longer part of the site are deleted, which keeps syncing to a host and invalidating its
+    /// cache cheap. To tell what changed, `manifest.json` records the SHA-256 digest of each file,
+    /// and `changes.json` lists the [`Changes`].
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot create `directory`, write into it, or delete a file of
+    ///   the last build from it
+    pub fn write_to_directory(&self, directory: &Path, base_url: &str) -> std::io::Result<Changes> {
+        fs::create_dir_all(directory)?;
+
+        let mut files: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        files.insert(
+            "index.html".into(),
+            Html::export_library_to_string(&self.library)
+                .as_bytes()
+                .into(),
+        );
+        for (hash, book) in &self.books {
+            files.insert(format!("{hash}.html"), book.html.as_bytes().into());
+        }
+        files.insert("search.json".into(), self.search_json().into_bytes());
+        files.insert("feed.xml".into(), self.feed(base_url).into_bytes());
+
+        let mut last_build = read_manifest(&directory.join(MANIFEST));
+        let mut changes = Changes::default();
+        let mut manifest: BTreeMap<String, String> = BTreeMap::new();
+
+        for (name, content) in files {
+            let path = directory.join(&name);
+            let digest = sha256::hex_digest(&content);
+
+            match last_build.remove(&name) {
+                Some(last) if last == digest && path.is_file() => changes.unchanged += 1,
+                last => {
+                    fs::write(&path, content)?;
+                    let entries = if last.is_some() {
+                        &mut changes.modified
+                    } else {
+                        &mut changes.added
+                    };
+                    entries.insert(name.clone(), digest.clone());
+                }
+            }
+
+            manifest.insert(name, digest);
+        }
+
+        // Only files that the last build wrote are ever deleted
+        for name in last_build.into_keys() {
+            match fs::remove_file(directory.join(&name)) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+                _ => changes.removed.push(name),
+            }
+        }
+
+        fs::write(directory.join(MANIFEST), json!(manifest).to_string())?;
+        fs::write(directory.join(CHANGES), changes.to_json())?;
+
+        Ok(changes)
+    }
+
+    /// Returns every book with its author, sorted like the index.
+    fn books(&self) -> impl Iterator<Item = (&str, &Work, &Book)> {
+        self.library.authors().flat_map(move |(author, works)| {
+            works
+                .iter()
+                .map(move |work| (author, work, &self.books[work.hash()]))
+        })
+    }
+
+    /// Returns a search index of every book as a JSON object, in the form:
+    ///
+    /// ```json
+    /// {
+    ///   "books": [
+    ///     {
+    ///       "title": "Book",
+    ///       "author": "RemasteredArch",
+    ///       "url": "....html",
+    ///       "pages": 2,
+    ///       "characters": 120,
+    ///       "density": 0.25,
+    ///       "text": "..."
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// `density` is the share of characters with any formatting, see [`FormattingCounts::density`].
+    pub fn search_json(&self) -> String {
+        let books: Vec<Value> = self
+            .books()
+            .map(|(author, work, book)| {
+                json!({
+                    "title": work.title(),
+                    "author": author,
+                    "url": format!("{}.html", work.hash()),
+                    "pages": work.pages(),
+                    "characters": book.formatting.characters(),
+                    "density": book.formatting.density(),
+                    "text": book.text,
+                })
+            })
+            .collect();
+
+        json!({ "books": books }).to_string()
+    }
+
+    /// Returns an RSS feed of every book, linking to each relative to `base_url`.
+    ///
+    /// An empty `base_url` makes relative links, which only some feed readers follow.
+    pub fn feed(&self, base_url: &str) -> String {
+        let mut feed = String::from(r#"<?xml version="1.0" encoding="utf-8"?><rss version="2.0">"#);
+        let link = if base_url.is_empty() {
+            "index.html".to_owned()
+        } else {
+            format!("{base_url}/")
+        };
+
+        write!(
+            feed,
+            "<channel><title>Library</title><link>{}</link>\
+            <description>Every book in the library</description>",
+            escape_xml(&link),
+        )
+        .expect("writing into a `String` is infallible");
+
+        for (author, work, _) in self.books() {
+            let title = if work.title().is_empty() {
+                "Untitled"
+            } else {
+                work.title()
+            };
+            let author = if author.is_empty() {
+                "Unknown author"
+            } else {
+                author
+            };
+            let plural = if work.pages() == 1 { "" } else { "s" };
+            let prefix = if base_url.is_empty() { "" } else { "/" };
+
+            write!(
+                feed,
+                r#"<item><title>{}</title><link>{}{prefix}{}.html</link><description>By {}, {} page{plural}</description><guid isPermaLink="false">{}</guid></item>"#,
+                escape_xml(title),
+                escape_xml(base_url),
+                work.hash(),
+                escape_xml(author),
+                work.pages(),
+                work.hash(),
+            )
+            .expect("writing into a `String` is infallible");
+        }
+
+        feed.push_str("</channel></rss>");
+        feed
+    }
+}
+
+/// Read the digest of each file from the manifest of the last build at `path`.
+///
+/// A manifest that is missing or malformed is treated as empty, so that every file is written.
+/// Names that aren't a plain file name, like `"../notes.txt"` or an absolute path, can't have been
+/// written by a build, so they are skipped rather than ever being deleted.
+fn read_manifest(path: &Path) -> BTreeMap<String, String> {
+    let mut manifest: BTreeMap<String, String> = fs::read(path)
+        .ok()
+        .and_then(|manifest| serde_json::from_slice(&manifest).ok())
+        .unwrap_or_default();
+
+    manifest.retain(|name, _| Path::new(name).file_name() == Some(OsStr::new(name)));
+    manifest
+}
+
+/// Escape the characters in `text` that have a special meaning in XML.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(char),
+        }
+    }
+
+    escaped
+}
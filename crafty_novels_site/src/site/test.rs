@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for writing a [`Site`] into a directory.
+
+use super::{Site, CHANGES, MANIFEST};
+use crafty_novels::{import::InputFormat, session::Session};
+use serde_json::{json, Value};
+use std::{fs, path::Path};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+/// Build a site of a book for each of `titles`.
+fn site(titles: &[&str]) -> std::result::Result<Site, Box<dyn std::error::Error>> {
+    let mut site = Site::new();
+    let mut session = Session::new();
+
+    for title in titles {
+        let input = format!("title: {title}\nauthor: RemasteredArch\npages:\n#- {title}, page one");
+        let tokens = session.import_str(InputFormat::Stendhal, &input)?;
+        site.add(&mut session, &tokens);
+    }
+
+    Ok(site)
+}
+
+/// Read `changes.json` from `directory`, with the digests left out.
+fn changes(directory: &Path) -> std::result::Result<Value, Box<dyn std::error::Error>> {
+    let changes: Value = serde_json::from_slice(&fs::read(directory.join(CHANGES))?)?;
+    let names = |key: &str| {
+        changes[key]
+            .as_object()
+            .map(|files| files.keys().cloned().collect::<Vec<_>>())
+    };
+
+    Ok(json!({
+        "added": names("added"),
+        "modified": names("modified"),
+        "removed": changes["removed"],
+        "unchanged": changes["unchanged"],
+    }))
+}
+
+#[test]
+fn site_changes() -> Result {
+    let directory =
+        std::env::temp_dir().join(format!("crafty_novels_site_changes_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+
+    let one = site(&["One"])?;
+    let one_and_two = site(&["One", "Two"])?;
+    let hash = |site: &Site, title: &str| {
+        site.books()
+            .find(|(_, work, _)| work.title() == title)
+            .map(|(_, work, _)| format!("{}.html", work.hash()))
+            .unwrap_or_default()
+    };
+    let (one_file, two_file) = (hash(&one, "One"), hash(&one_and_two, "Two"));
+
+    let results = (|| {
+        one.write_to_directory(&directory, "")?;
+        let first = changes(&directory)?;
+
+        one.write_to_directory(&directory, "")?;
+        let second = changes(&directory)?;
+
+        one_and_two.write_to_directory(&directory, "")?;
+        let third = changes(&directory)?;
+
+        one.write_to_directory(&directory, "")?;
+        let fourth = changes(&directory)?;
+        let two_exists = directory.join(&two_file).exists();
+
+        Ok::<_, Box<dyn std::error::Error>>((first, second, third, fourth, two_exists))
+    })();
+    fs::remove_dir_all(&directory)?;
+    let (first, second, third, fourth, two_exists) = results?;
+
+    let mut all = vec![
+        "feed.xml".to_owned(),
+        "index.html".to_owned(),
+        one_file,
+        "search.json".to_owned(),
+    ];
+    all.sort();
+    assert_eq!(
+        first,
+        json!({ "added": all, "modified": [], "removed": [], "unchanged": 0 })
+    );
+    assert_eq!(
+        second,
+        json!({ "added": [], "modified": [], "removed": [], "unchanged": 4 })
+    );
+    assert_eq!(
+        third,
+        json!({
+            "added": [two_file],
+            "modified": ["feed.xml", "index.html", "search.json"],
+            "removed": [],
+            "unchanged": 1,
+        })
+    );
+    assert_eq!(
+        fourth,
+        json!({
+            "added": [],
+            "modified": ["feed.xml", "index.html", "search.json"],
+            "removed": [two_file],
+            "unchanged": 1,
+        })
+    );
+    assert!(!two_exists);
+
+    Ok(())
+}
+
+#[test]
+fn site_manifest_outside_directory() -> Result {
+    let root = std::env::temp_dir().join(format!(
+        "crafty_novels_site_manifest_{}",
+        std::process::id()
+    ));
+    let directory = root.join("site");
+    fs::create_dir_all(&directory)?;
+
+    let outside = root.join("outside.txt");
+    fs::write(&outside, "not part of the site")?;
+    let manifest = json!({
+        "../outside.txt": "0",
+        outside.to_string_lossy(): "0",
+        "nested/file.html": "0",
+    });
+    fs::write(directory.join(MANIFEST), manifest.to_string())?;
+
+    let result = site(&["One"])?
+        .write_to_directory(&directory, "")
+        .map(|changes| changes.to_string());
+    let outside_exists = outside.exists();
+    fs::remove_dir_all(&root)?;
+
+    // Names that the build couldn't have written are never deleted
+    assert_eq!(result?, "4 added, 0 modified, 0 removed, 0 unchanged");
+    assert!(outside_exists);
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The themes that each book can be styled with.
+//!
+//! See [`register`].
+
+use crafty_novels::{
+    export::{HtmlExportOptions, Palette},
+    syntax::minecraft::{Color, Rgb},
+};
+
+/// The name of every theme, in the order they are listed in the help text.
+pub const NAMES: [&str; 3] = ["light", "dark", "sepia"];
+
+/// Register every theme in [`NAMES`] with [`HtmlExportOptions::register`].
+pub fn register() {
+    HtmlExportOptions::register("light", HtmlExportOptions::new());
+
+    // Minecraft's darkest colors are unreadable on a dark background
+    HtmlExportOptions::register(
+        "dark",
+        HtmlExportOptions::new()
+            .with_extra_head(Some(
+                "<style>body{background:#181818;color:#E0E0E0}</style>".into(),
+            ))
+            .with_palette(
+                Palette::new()
+                    .with(Color::Black, Rgb::new(0xAA, 0xAA, 0xAA))
+                    .with(Color::DarkBlue, Rgb::new(0x55, 0x55, 0xFF))
+                    .with(Color::DarkGray, Rgb::new(0x8A, 0x8A, 0x8A)),
+            ),
+    );
+
+    // Likewise, its lightest colors are unreadable on paper
+    HtmlExportOptions::register(
+        "sepia",
+        HtmlExportOptions::new()
+            .with_extra_head(Some(
+                "<style>body{background:#F4ECD8;color:#3B2F20;font-family:serif}</style>".into(),
+            ))
+            .with_palette(
+                Palette::new()
+                    .with(Color::White, Rgb::new(0x5B, 0x4B, 0x36))
+                    .with(Color::Yellow, Rgb::new(0xA0, 0x80, 0x00)),
+            ),
+    );
+}
@@ -17,4 +17,26 @@
 
 //! Implementations of [`Export`][`crate::Export`].
 
-pub use crate::format::html::Html;
+pub use crate::format::brf::Brf;
+pub use crate::format::brf::ExportOptions as BrfExportOptions;
+pub use crate::format::capabilities::Capabilities;
+pub use crate::format::compatibility::{check_compatibility, CompatibilityWarning};
+#[cfg(feature = "compression")]
+pub use crate::format::compression::GzipWriter;
+pub use crate::format::cp437::ColorDepth;
+pub use crate::format::cp437::Cp437;
+pub use crate::format::cp437::ExportOptions as Cp437ExportOptions;
+pub use crate::format::html::ExportOptions as HtmlExportOptions;
+pub use crate::format::html::{
+    AnnotationStyle, BlankPages, DocumentKind, Html, Legend, MetadataFormatCodes, PageBreaks,
+    PageOpening, Palette, Styling, TextDirection, WritingMode,
+};
+pub use crate::format::ids::Ids;
+pub use crate::format::line_ending::LineEnding;
+pub use crate::format::names::{OutputFormat, UnknownFormatError};
+pub use crate::format::plain_text::ExportOptions as PlainTextExportOptions;
+pub use crate::format::plain_text::PlainText;
+pub use crate::format::ssml::ExportOptions as SsmlExportOptions;
+pub use crate::format::ssml::Ssml;
+pub use crate::format::text::ExportOptions as TextExportOptions;
+pub use crate::format::text::Text;
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Translation from print into uncontracted Unified English Braille, written in North American
+//! ASCII Braille.
+//!
+//! Each ASCII character stands for a single braille cell, ex. `'A'` for dot 1 and `','` for
+//! dot 6.
+
+/// Starts a run of digits, dots 3456.
+pub const NUMERIC_INDICATOR: char = '#';
+/// Marks the next letter as a capital, dot 6.
+pub const CAPITAL_INDICATOR: char = ',';
+/// Marks a letter from `'a'` to `'j'` after digits as a letter, rather than a digit, dots 56.
+pub const GRADE_1_INDICATOR: char = ';';
+
+/// The typeforms that have braille indicators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Typeform {
+    /// Italic, dots 46.
+    Italic,
+    /// Bold, dots 45.
+    Bold,
+    /// Underline, dots 456.
+    Underline,
+}
+
+impl Typeform {
+    /// Returns the cells that start a passage in this typeform, which lasts until
+    /// [`Self::terminator`].
+    #[must_use]
+    pub const fn passage(self) -> &'static str {
+        match self {
+            Self::Italic => ".7",
+            Self::Bold => "^7",
+            Self::Underline => "_7",
+        }
+    }
+
+    /// Returns the cells that end a passage in this typeform.
+    #[must_use]
+    pub const fn terminator(self) -> &'static str {
+        match self {
+            Self::Italic => ".'",
+            Self::Bold => "^'",
+            Self::Underline => "_'",
+        }
+    }
+}
+
+/// Translates the characters of words into braille cells, tracking whether the word is in the
+/// middle of a number.
+#[derive(Debug, Default)]
+pub struct Translator {
+    /// Whether the previous character was a digit.
+    numeric: bool,
+}
+
+impl Translator {
+    /// Forget any number in progress, at the end of a word.
+    pub const fn end_word(&mut self) {
+        self.numeric = false;
+    }
+
+    /// Push the cells for `char` onto `output`.
+    ///
+    /// Characters without a braille equivalent are dropped.
+    pub fn push(&mut self, output: &mut String, char: char) {
+        if let Some(digit) = char.to_digit(10) {
+            if !self.numeric {
+                output.push(NUMERIC_INDICATOR);
+                self.numeric = true;
+            }
+            // Digits are the letters `'a'` through `'j'`, with zero last
+            output.push(letter((digit + 9) % 10));
+            return;
+        }
+
+        let was_numeric = std::mem::take(&mut self.numeric);
+
+        if char.is_ascii_alphabetic() {
+            let lowercase = char.to_ascii_lowercase();
+            if char.is_ascii_uppercase() {
+                output.push(CAPITAL_INDICATOR);
+            } else if was_numeric && lowercase <= 'j' {
+                output.push(GRADE_1_INDICATOR);
+            }
+            output.push(lowercase.to_ascii_uppercase());
+            return;
+        }
+
+        if let Some(cells) = punctuation(char) {
+            output.push_str(cells);
+        }
+    }
+}
+
+/// Returns the cell for the `index`th letter of the alphabet, starting from zero.
+fn letter(index: u32) -> char {
+    char::from_u32(u32::from('A') + index).unwrap_or('A')
+}
+
+/// Returns the cells for a punctuation mark, if it has any.
+const fn punctuation(char: char) -> Option<&'static str> {
+    Some(match char {
+        ',' => "1",
+        ';' => "2",
+        ':' => "3",
+        '.' => "4",
+        '!' => "6",
+        '?' | '“' => "8",
+        '”' => "0",
+        '"' => ",7",
+        '\'' | '’' => "'",
+        '-' => "-",
+        '(' => "\"<",
+        ')' => "\">",
+        '/' => "_/",
+        '&' => "@&",
+        _ => return None,
+    })
+}
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Exporting for Braille Ready Format, for embossers and refreshable braille displays.
+//!
+//! See [`Brf`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     export::Brf,
+//!     syntax::{minecraft::Format, Token, TokenList},
+//!     Export,
+//! };
+//!
+//! let input = TokenList::new_from_boxed(
+//!     Box::new([]),
+//!     Box::new([
+//!         Token::Text("Page".into()),
+//!         Token::Space,
+//!         Token::Format(Format::Bold),
+//!         Token::Text("12".into()),
+//!         Token::Format(Format::Reset),
+//!     ]),
+//! );
+//!
+//! assert_eq!(Brf::export_token_vector_to_string(input).as_ref(), ",PAGE ^7#AB^'\r\n");
+//! ```
+
+use crate::{
+    export::Capabilities,
+    syntax::{
+        align::Alignment,
+        minecraft::{strip_format_codes, FormatState},
+        Metadata, Token, TokenList,
+    },
+    writer::Utf8Writer,
+    Export, ExportWithOptions,
+};
+use braille::{Translator, Typeform};
+pub use options::ExportOptions;
+use std::io::Write;
+
+mod braille;
+mod options;
+#[cfg(test)]
+mod test;
+
+/// Starts a new braille page (form feed).
+const PAGE_BREAK: char = '\u{c}';
+
+/// Exporting for Braille Ready Format (BRF), uncontracted Unified English Braille written in North
+/// American ASCII Braille.
+///
+/// # Format
+///
+/// Each character of the output is a single braille cell. Lines end with `"\r\n"`, unless
+/// [`ExportOptions`] sets another [`LineEnding`][`crate::format::line_ending::LineEnding`], and
+/// are wrapped at the last space that fits in the line length set in [`ExportOptions`]. Words
+/// longer than a whole line are broken at the last cell that fits. Once a braille page is full, or at the start
+/// of each page of the work, a form feed (`'\u{c}'`) starts a new braille page.
+///
+/// If present, the title and the author are written first, followed by an empty line.
+///
+/// - Letters are written uncontracted (grade 1), with capital and numeric indicators
+/// - Common punctuation is translated, and characters without a braille equivalent are dropped
+/// - Paragraph breaks are written as empty lines
+/// - Images are written as their alt text
+/// - Italic, bold, and underlined text is written between the passage indicator and terminator of
+///   its typeform
+/// - Colors, obfuscation, and strikethrough are dropped
+pub struct Brf {}
+
+impl Brf {
+    /// Parse a given abstract syntax vector into BRF with the given options, then output that as
+    /// a string.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_token_vector_to_string_with_options(
+        tokens: &TokenList,
+        options: &ExportOptions,
+    ) -> Box<str> {
+        let mut bytes: Vec<u8> = vec![];
+
+        Self::export_token_vector_to_writer_with_options(tokens, &mut bytes, options)
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/std/src/io/impls.rs#L433-L437
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/alloc/src/vec/mod.rs#L2569-L2592
+            .expect(
+                "the `std::io::Write` implementations for `Vec<u8>` are infallible (as of 1.80.1)",
+            );
+
+        String::from_utf8(bytes)
+            .expect("`Utf8Writer` only writes UTF-8 encoded types")
+            .into_boxed_str()
+    }
+
+    /// Parse a given abstract syntax vector into BRF with the given options, then output that
+    /// into a writer, like a [`std::fs::File`].
+    ///
+    /// Guaranteed to only write ASCII.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    pub fn export_token_vector_to_writer_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        let mut writer = Utf8Writer::new(output);
+        let mut layout = Layout::new(options);
+
+        let mut wrote_metadata = false;
+        for data in tokens.metadata_as_slice() {
+            let (prefix, text) = match data {
+                Metadata::Title(title) => ("", title),
+                Metadata::Author(author) => ("by ", author),
+                _ => continue,
+            };
+
+            let text = format!("{prefix}{}", strip_format_codes(text));
+            for word in text.split(' ') {
+                layout.push_text(word);
+                layout.space(&mut writer)?;
+            }
+            layout.end_line(&mut writer)?;
+            wrote_metadata = true;
+        }
+        if wrote_metadata {
+            layout.end_line(&mut writer)?;
+        }
+
+        let mut state = FormatState::new();
+        for token in tokens.tokens_as_slice() {
+            match token {
+                Token::Text(text) | Token::Image { alt: text, .. } => layout.push_text(text),
+                Token::Space => layout.space(&mut writer)?,
+                Token::Align(alignment) => layout.alignment = Some(*alignment),
+                Token::Role(_) => (),
+                Token::LineBreak => layout.end_line(&mut writer)?,
+                Token::ParagraphBreak => {
+                    layout.finish_line(&mut writer)?;
+                    layout.end_line(&mut writer)?;
+                }
+                Token::ThematicBreak => layout.end_page(&mut writer)?,
+                Token::Format(format) => {
+                    let next_state = state.with(*format);
+                    layout.change_typeforms(state, next_state);
+                    state = next_state;
+                }
+            }
+        }
+        layout.change_typeforms(state, FormatState::new());
+        layout.finish_line(&mut writer)?;
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl ExportWithOptions for Brf {
+    type Options = ExportOptions;
+
+    /// See [`Brf::export_token_vector_to_writer_with_options`].
+    fn export_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &Self::Options,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(tokens, output, options)
+    }
+}
+
+impl Export for Brf {
+    fn capabilities() -> Capabilities {
+        Capabilities::PAGINATION | Capabilities::ALIGNMENT
+    }
+
+    /// Parse a given abstract syntax vector into BRF, then output that as a string.
+    fn export_token_vector_to_string(tokens: TokenList) -> Box<str> {
+        Self::export_token_vector_to_string_with_options(&tokens, &ExportOptions::default())
+    }
+
+    /// Parse a given abstract syntax vector into BRF, then output that into a writer, like a
+    /// [`std::fs::File`].
+    ///
+    /// Guaranteed to only write ASCII.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_token_vector_to_writer(
+        tokens: TokenList,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(&tokens, output, &ExportOptions::default())
+    }
+}
+
+/// Returns the typeforms that are active in `state`, in the order their indicators are opened.
+fn typeforms(state: FormatState) -> impl Iterator<Item = Typeform> {
+    [
+        (state.underline(), Typeform::Underline),
+        (state.bold(), Typeform::Bold),
+        (state.italic(), Typeform::Italic),
+    ]
+    .into_iter()
+    .filter_map(|(active, typeform)| active.then_some(typeform))
+}
+
+/// Wraps braille cells into lines and pages.
+#[derive(Debug)]
+struct Layout {
+    /// The number of cells on each line, at least one.
+    line_length: usize,
+    /// The number of lines on each page, at least one.
+    page_length: usize,
+    /// What ends each line.
+    line_ending: &'static str,
+    /// The number of lines written onto the current page.
+    lines_on_page: usize,
+    /// The cells of the line currently being wrapped.
+    line: String,
+    /// The cells of the word currently being built.
+    word: String,
+    /// Whether a space separates [`Self::word`] from the end of [`Self::line`].
+    space_pending: bool,
+    /// How the current line of the work is aligned, if at all, padding each line it wraps onto.
+    alignment: Option<Alignment>,
+    /// Translates the characters of [`Self::word`].
+    translator: Translator,
+}
+
+impl Layout {
+    /// Creates a new, empty [`Layout`].
+    fn new(options: &ExportOptions) -> Self {
+        Self {
+            line_length: options.line_length().max(1),
+            page_length: options.page_length().max(1),
+            line_ending: options.line_ending().as_str(),
+            lines_on_page: 0,
+            line: String::new(),
+            word: String::new(),
+            space_pending: false,
+            alignment: None,
+            translator: Translator::default(),
+        }
+    }
+
+    /// Translate `text` onto the current word.
+    fn push_text(&mut self, text: &str) {
+        for char in text.chars() {
+            self.translator.push(&mut self.word, char);
+        }
+    }
+
+    /// End the current word, separating it from the next.
+    fn space<W: Write>(&mut self, output: &mut Utf8Writer<W>) -> std::io::Result<()> {
+        self.flush_word(output)?;
+        self.space_pending = !self.line.is_empty();
+        Ok(())
+    }
+
+    /// Write the indicators that end the typeforms of `state` that are missing from `next_state`,
+    /// then those that start the ones that are new.
+    fn change_typeforms(&mut self, state: FormatState, next_state: FormatState) {
+        let before: Vec<Typeform> = typeforms(state).collect();
+        let after: Vec<Typeform> = typeforms(next_state).collect();
+
+        for typeform in before
+            .iter()
+            .rev()
+            .filter(|typeform| !after.contains(typeform))
+        {
+            // A terminator belongs to the end of the word before it, even across a space
+            if self.word.is_empty()
+                && !self.line.is_empty()
+                && self.line.len() + typeform.terminator().len() <= self.line_length
+            {
+                self.line.push_str(typeform.terminator());
+            } else {
+                self.word.push_str(typeform.terminator());
+            }
+        }
+
+        for typeform in after.iter().filter(|typeform| !before.contains(typeform)) {
+            self.word.push_str(typeform.passage());
+        }
+    }
+
+    /// Move the current word onto the line, wrapping first if it doesn't fit.
+    fn flush_word<W: Write>(&mut self, output: &mut Utf8Writer<W>) -> std::io::Result<()> {
+        self.translator.end_word();
+        if self.word.is_empty() {
+            return Ok(());
+        }
+
+        let separator = usize::from(self.space_pending);
+        if !self.line.is_empty() && self.line.len() + separator + self.word.len() > self.line_length
+        {
+            self.write_line(output)?;
+        }
+        if self.space_pending {
+            self.line.push(' ');
+        }
+
+        // Words wider than a whole line are broken at the last cell that fits
+        let mut word = std::mem::take(&mut self.word);
+        while word.len() > self.line_length {
+            let rest = word.split_off(self.line_length);
+            self.line = word;
+            self.write_line(output)?;
+            word = rest;
+        }
+        self.line.push_str(&word);
+        self.space_pending = false;
+
+        Ok(())
+    }
+
+    /// Write the current line, even if it's empty, starting a new braille page first if the
+    /// current one is full.
+    fn write_line<W: Write>(&mut self, output: &mut Utf8Writer<W>) -> std::io::Result<()> {
+        if self.lines_on_page >= self.page_length {
+            output.write_char(PAGE_BREAK)?;
+            self.lines_on_page = 0;
+        }
+
+        if let Some(alignment) = self.alignment.filter(|_| !self.line.is_empty()) {
+            let padding = alignment.offset(self.line_length.saturating_sub(self.line.len()));
+            output.write_str(" ".repeat(padding))?;
+        }
+        output.write_str(&self.line)?;
+        output.write_str(self.line_ending)?;
+        self.line.clear();
+        self.space_pending = false;
+        self.lines_on_page += 1;
+
+        Ok(())
+    }
+
+    /// End the current line, even if it's empty.
+    fn end_line<W: Write>(&mut self, output: &mut Utf8Writer<W>) -> std::io::Result<()> {
+        self.flush_word(output)?;
+        self.write_line(output)?;
+        self.alignment = None;
+        Ok(())
+    }
+
+    /// End the current line, if it has any cells.
+    fn finish_line<W: Write>(&mut self, output: &mut Utf8Writer<W>) -> std::io::Result<()> {
+        self.flush_word(output)?;
+        if !self.line.is_empty() {
+            self.write_line(output)?;
+        }
+        self.alignment = None;
+        Ok(())
+    }
+
+    /// End the current line, then start a new braille page if anything was written onto the
+    /// current one.
+    fn end_page<W: Write>(&mut self, output: &mut Utf8Writer<W>) -> std::io::Result<()> {
+        self.finish_line(output)?;
+        if self.lines_on_page > 0 {
+            output.write_char(PAGE_BREAK)?;
+            self.lines_on_page = 0;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration for [`super::Brf`].
+//!
+//! See [`ExportOptions`].
+
+use crate::format::line_ending::LineEnding;
+
+/// Options that control the layout of BRF output.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Brf, BrfExportOptions},
+///     syntax::{Token, TokenList},
+/// };
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::Text("one".into()),
+///         Token::Space,
+///         Token::Text("two".into()),
+///     ]),
+/// );
+/// let options = BrfExportOptions::new().with_line_length(4);
+///
+/// assert_eq!(
+///     Brf::export_token_vector_to_string_with_options(&input, &options).as_ref(),
+///     "ONE\r\nTWO\r\n"
+/// );
+/// ```
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExportOptions {
+    /// The number of braille cells on each line.
+    line_length: usize,
+    /// The number of lines on each braille page.
+    page_length: usize,
+    /// What ends each line.
+    line_ending: LineEnding,
+}
+
+impl ExportOptions {
+    /// Creates a new [`ExportOptions`] with the default options.
+    ///
+    /// Uses 40 cells per line and 25 lines per page, the most common size for embossed paper,
+    /// and ends lines with [`LineEnding::CrLf`], as BRF files are expected to.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            line_length: 40,
+            page_length: 25,
+            line_ending: LineEnding::CrLf,
+        }
+    }
+
+    /// Returns the number of braille cells on each line.
+    #[must_use]
+    pub const fn line_length(&self) -> usize {
+        self.line_length
+    }
+
+    /// Sets the number of braille cells on each line.
+    ///
+    /// Values smaller than one cell are treated as one cell.
+    #[must_use]
+    pub const fn with_line_length(mut self, line_length: usize) -> Self {
+        self.line_length = line_length;
+        self
+    }
+
+    /// Returns the number of lines on each braille page.
+    #[must_use]
+    pub const fn page_length(&self) -> usize {
+        self.page_length
+    }
+
+    /// Sets the number of lines on each braille page.
+    ///
+    /// Values smaller than one line are treated as one line.
+    #[must_use]
+    pub const fn with_page_length(mut self, page_length: usize) -> Self {
+        self.page_length = page_length;
+        self
+    }
+
+    /// Returns what ends each line.
+    #[must_use]
+    pub const fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Sets what ends each line.
+    ///
+    /// Most embossers and braille notetakers expect [`LineEnding::CrLf`], the default.
+    #[must_use]
+    pub const fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for exporting to the [BRF][`super::Brf`] format.
+
+use super::{Brf, ExportOptions};
+use crate::{
+    syntax::{
+        minecraft::{Color, Format},
+        Metadata, Token, TokenList,
+    },
+    Export,
+};
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+#[test]
+fn brf_export() {
+    use Token::{ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([
+            Metadata::Title("§6Fish & Chips".into()),
+            Metadata::Author("Arch".into()),
+        ]),
+        Box::new([
+            ThematicBreak,
+            text!("Chapter"),
+            Space,
+            text!("1b"),
+            ParagraphBreak,
+            Token::Format(Format::Italic),
+            text!("one"),
+            Space,
+            Token::Format(Format::Bold),
+            text!("two"),
+            Token::Format(Format::Reset),
+            Space,
+            text!("end."),
+        ]),
+    );
+
+    assert_eq!(
+        Brf::export_token_vector_to_string(tokens).as_ref(),
+        [
+            ",FISH @& ,CHIPS\r\nBY ,ARCH\r\n\r\n",
+            "\u{c},CHAPTER #A;B\r\n\r\n",
+            ".7ONE ^7TWO.'^' END4\r\n",
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn brf_layout() {
+    use Token::Space;
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([]),
+        Box::new([
+            Token::Format(Format::Color(Color::Gold)),
+            text!("abcdefghijkl"),
+            Space,
+            text!("xy"),
+            Space,
+            text!("z"),
+        ]),
+    );
+    let options = ExportOptions::new().with_line_length(5).with_page_length(2);
+
+    assert_eq!(
+        Brf::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "ABCDE\r\nFGHIJ\r\n\u{c}KL XY\r\nZ\r\n"
+    );
+    assert_eq!(
+        Brf::export_token_vector_to_string_with_options(
+            &tokens,
+            &options.with_line_ending(crate::format::line_ending::LineEnding::Lf),
+        )
+        .as_ref(),
+        "ABCDE\nFGHIJ\n\u{c}KL XY\nZ\n"
+    );
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! What each export format can represent, as a set.
+//!
+//! See [`Capabilities`].
+
+use crate::syntax::{minecraft::Format, Token, TokenList};
+use std::{
+    fmt::Display,
+    ops::{BitAnd, BitOr, Not},
+};
+
+/// A set of the features of a work that an export format can represent, like
+/// [`Capabilities::COLOR`] and [`Capabilities::PAGINATION`].
+///
+/// Anything missing from the [capabilities of an exporter][`crate::Export::capabilities`] is
+/// dropped or approximated, ex. an image written as its alt text. Front-ends can compare them with
+/// [`Capabilities::used_by`] to warn about what a conversion will lose.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Capabilities, OutputFormat},
+///     syntax::{
+///         minecraft::{Color, Format},
+///         Token, TokenList,
+///     },
+/// };
+///
+/// let tokens = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::ThematicBreak,
+///         Token::Format(Format::Color(Color::Gold)),
+///         Token::Text("gold".into()),
+///     ]),
+/// );
+///
+/// let used = Capabilities::used_by(&tokens);
+/// assert_eq!(used, Capabilities::COLOR | Capabilities::PAGINATION);
+///
+/// assert!(used.missing_from(OutputFormat::Html.capabilities()).is_empty());
+/// assert_eq!(
+///     used.missing_from(OutputFormat::Text.capabilities()),
+///     Capabilities::COLOR
+/// );
+/// assert_eq!(Capabilities::COLOR.to_string(), "colors");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    /// Nothing beyond plain text.
+    pub const NONE: Self = Self(0);
+    /// Colored text, see [`Format::Color`].
+    pub const COLOR: Self = Self(1 << 0);
+    /// Obfuscated text, see [`Format::Obfuscated`].
+    pub const OBFUSCATION: Self = Self(1 << 1);
+    /// Keeping the boundaries between pages, see [`Token::ThematicBreak`].
+    pub const PAGINATION: Self = Self(1 << 2);
+    /// Images, see [`Token::Image`].
+    pub const IMAGES: Self = Self(1 << 3);
+    /// Links to other locations, which no [`Token`] represents yet.
+    pub const LINKS: Self = Self(1 << 4);
+    /// Aligned lines, like centered titles, see [`Token::Align`].
+    pub const ALIGNMENT: Self = Self(1 << 5);
+    /// Text with a meaning, like headings, see [`Token::Role`].
+    pub const ROLES: Self = Self(1 << 6);
+    /// Every feature.
+    pub const ALL: Self = Self(0b111_1111);
+
+    /// Every feature, and its name, in the order they are listed.
+    const NAMES: [(Self, &'static str); 7] = [
+        (Self::COLOR, "colors"),
+        (Self::OBFUSCATION, "obfuscation"),
+        (Self::PAGINATION, "pagination"),
+        (Self::IMAGES, "images"),
+        (Self::LINKS, "links"),
+        (Self::ALIGNMENT, "alignment"),
+        (Self::ROLES, "roles"),
+    ];
+
+    /// Returns the features that `tokens` makes use of.
+    #[must_use]
+    pub fn used_by(tokens: &TokenList) -> Self {
+        tokens
+            .tokens_as_slice()
+            .iter()
+            .fold(Self::NONE, |used, token| match token {
+                Token::Format(Format::Color(_)) => used | Self::COLOR,
+                Token::Format(Format::Obfuscated) => used | Self::OBFUSCATION,
+                Token::ThematicBreak => used | Self::PAGINATION,
+                Token::Image { .. } => used | Self::IMAGES,
+                Token::Align(_) => used | Self::ALIGNMENT,
+                Token::Role(_) => used | Self::ROLES,
+                _ => used,
+            })
+    }
+
+    /// Returns the raw bits of the set.
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether or not the set has no features.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether or not every feature in `other` is also in this set.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the features in this set that are not in `supported`, ex. what a work uses that a
+    /// format would drop.
+    #[must_use]
+    pub const fn missing_from(self, supported: Self) -> Self {
+        Self(self.0 & !supported.0)
+    }
+
+    /// Returns the names of every feature in the set, in the order they are listed.
+    #[must_use]
+    pub fn names(self) -> Vec<&'static str> {
+        Self::NAMES
+            .into_iter()
+            .filter(|(feature, _)| self.contains(*feature))
+            .map(|(_, name)| name)
+            .collect()
+    }
+}
+
+impl Display for Capabilities {
+    /// The names of every feature in the set, separated by commas.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.names().join(", "))
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Self;
+
+    /// The union of both sets.
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for Capabilities {
+    type Output = Self;
+
+    /// The intersection of both sets.
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for Capabilities {
+    type Output = Self;
+
+    /// Every feature that is not in the set.
+    fn not(self) -> Self::Output {
+        Self(!self.0 & Self::ALL.0)
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Checking what a work will lose when it is exported to a given format, before exporting it.
+//!
+//! See [`check_compatibility`].
+
+use super::{capabilities::Capabilities, names::OutputFormat};
+use crate::syntax::{minecraft::Format, Token, TokenList};
+use std::fmt::Display;
+
+/// Concrete content of a work that a format cannot represent, and will drop or approximate.
+///
+/// See [`check_compatibility`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompatibilityWarning {
+    /// The feature that will be lost.
+    feature: Capabilities,
+    /// The index of the page that uses the feature, or [`None`] for the whole work.
+    page: Option<usize>,
+    /// How many times the feature is used.
+    count: usize,
+    /// The format that cannot represent the feature.
+    format: OutputFormat,
+}
+
+impl CompatibilityWarning {
+    /// Returns the feature that will be lost, a single flag of [`Capabilities`].
+    #[must_use]
+    pub const fn feature(&self) -> Capabilities {
+        self.feature
+    }
+
+    /// Returns the index of the page that uses the feature, starting from zero, or [`None`] if it
+    /// applies to the whole work, like [`Capabilities::PAGINATION`].
+    #[must_use]
+    pub const fn page(&self) -> Option<usize> {
+        self.page
+    }
+
+    /// Returns how many times the feature is used, on the page or in the whole work.
+    ///
+    /// For [`Capabilities::PAGINATION`], this is the number of pages.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the format that cannot represent the feature.
+    #[must_use]
+    pub const fn format(&self) -> OutputFormat {
+        self.format
+    }
+}
+
+impl Display for CompatibilityWarning {
+    /// Describes what is lost, numbering pages from one, like in Minecraft, ex. `"page 4 uses
+    /// obfuscated text, which text cannot represent"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plural = if self.count == 1 { "" } else { "s" };
+
+        if let Some(page) = self.page {
+            write!(f, "page {} ", page + 1)?;
+        } else {
+            f.write_str("the work ")?;
+        }
+
+        match self.feature {
+            Capabilities::COLOR => f.write_str("uses colored text")?,
+            Capabilities::OBFUSCATION => f.write_str("uses obfuscated text")?,
+            Capabilities::IMAGES => write!(f, "has {} image{plural}", self.count)?,
+            Capabilities::ALIGNMENT => write!(f, "has {} aligned line{plural}", self.count)?,
+            Capabilities::PAGINATION => write!(f, "has {} page{plural}", self.count)?,
+            feature => write!(f, "uses {feature}")?,
+        }
+
+        write!(f, ", which {} cannot represent", self.format)
+    }
+}
+
+/// Returns the content of `tokens` that `format` will drop or approximate, as found by comparing
+/// [`Capabilities::used_by`] each page with the [capabilities of the
+/// format][`OutputFormat::capabilities`].
+///
+/// Colors, obfuscation, images, aligned lines, and roles are reported for each page that uses
+/// them, in order of page, then in the order of [`Capabilities`]. Losing the boundaries between pages is reported once,
+/// for the whole work, and only if it has more than one page.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{check_compatibility, Capabilities, OutputFormat},
+///     syntax::{minecraft::Format, Token, TokenList},
+/// };
+///
+/// let mut tokens = TokenList::new_from_boxed(Box::new([]), Box::new([]));
+/// tokens.push_page([Token::Text("plain".into())]);
+/// tokens.push_page([Token::Format(Format::Obfuscated), Token::Text("secret".into())]);
+///
+/// let warnings = check_compatibility(&tokens, OutputFormat::Text);
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].feature(), Capabilities::OBFUSCATION);
+/// assert_eq!(
+///     warnings[0].to_string(),
+///     "page 2 uses obfuscated text, which text cannot represent"
+/// );
+///
+/// assert!(check_compatibility(&tokens, OutputFormat::Html).is_empty());
+/// ```
+#[must_use]
+pub fn check_compatibility(tokens: &TokenList, format: OutputFormat) -> Vec<CompatibilityWarning> {
+    let missing = Capabilities::used_by(tokens).missing_from(format.capabilities());
+    if missing.is_empty() {
+        return vec![];
+    }
+
+    let mut warnings = vec![];
+    let mut pages = 0;
+
+    for (index, page) in tokens.pages_iter().enumerate() {
+        pages += 1;
+
+        for feature in [
+            Capabilities::COLOR,
+            Capabilities::OBFUSCATION,
+            Capabilities::IMAGES,
+            Capabilities::ALIGNMENT,
+            Capabilities::ROLES,
+        ] {
+            if !missing.contains(feature) {
+                continue;
+            }
+
+            let count = page
+                .tokens_as_slice()
+                .iter()
+                .filter(|token| uses(token, feature))
+                .count();
+            if count > 0 {
+                warnings.push(CompatibilityWarning {
+                    feature,
+                    page: Some(index),
+                    count,
+                    format,
+                });
+            }
+        }
+    }
+
+    if missing.contains(Capabilities::PAGINATION) && pages > 1 {
+        warnings.push(CompatibilityWarning {
+            feature: Capabilities::PAGINATION,
+            page: None,
+            count: pages,
+            format,
+        });
+    }
+
+    warnings
+}
+
+/// Whether or not `token` makes use of `feature`.
+const fn uses(token: &Token, feature: Capabilities) -> bool {
+    match token {
+        Token::Format(Format::Color(_)) => feature.contains(Capabilities::COLOR),
+        Token::Format(Format::Obfuscated) => feature.contains(Capabilities::OBFUSCATION),
+        Token::Image { .. } => feature.contains(Capabilities::IMAGES),
+        Token::Align(_) => feature.contains(Capabilities::ALIGNMENT),
+        Token::Role(_) => feature.contains(Capabilities::ROLES),
+        _ => false,
+    }
+}
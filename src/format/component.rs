@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Walking Minecraft's JSON text components, like `{"text": "Hi", "color": "gold"}`.
+//!
+//! Shared by every importer that reads JSON text, see [`tokenize`].
+
+use crate::syntax::{
+    minecraft::{Color, FormatCode, FormatState, Rgb, Style},
+    StyledSpan, Token, TokenList,
+};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// The boolean fields of a component, and the decoration that each controls.
+const STYLE_FIELDS: [(&str, Style); 5] = [
+    ("obfuscated", Style::OBFUSCATED),
+    ("bold", Style::BOLD),
+    ("strikethrough", Style::STRIKETHROUGH),
+    ("underlined", Style::UNDERLINE),
+    ("italic", Style::ITALIC),
+];
+
+/// Convert a JSON text component into [`Token`]s.
+///
+/// - A string is plain text, and an array is its first element, with the rest of the elements
+///   inheriting the first's formatting
+/// - An object's `"color"` and decoration fields (ex. `"bold": true`) apply to its own text and
+///   all of its `"extra"` children, which can override them
+///     - Named colors map to [`Color`], and hexadecimal colors (`"#RRGGBB"`) to the nearest one
+/// - An object's text is its `"text"`, or for `"translate"` and `"keybind"`, the key itself, as
+///   there is no way to know the player's language or controls
+///     - Other dynamic content, like `"score"` and `"selector"`, is dropped
+/// - `'§'` format codes inside of text apply on top of the component's formatting
+/// - A `'\n'` inside of text is a line break
+///
+/// Ends with a [`Format::Reset`][`crate::syntax::minecraft::Format::Reset`] if any formatting is
+/// left over, so the tokens can be followed by more text.
+pub fn tokenize(component: &Value) -> Vec<Token> {
+    let mut spans: Vec<(String, FormatState)> = vec![];
+    walk(component, FormatState::new(), &mut spans);
+
+    let spans: Vec<StyledSpan> = spans
+        .into_iter()
+        .map(|(text, format)| StyledSpan::new(text, format))
+        .collect();
+    let ends_formatted = spans.last().is_some_and(|span| !span.format().is_plain());
+
+    let mut tokens = TokenList::from_styled_spans(Arc::new([]), &spans).into_vec();
+    if ends_formatted {
+        tokens.push(Token::Format(crate::syntax::minecraft::Format::Reset));
+    }
+
+    tokens
+}
+
+/// Push the text of `component` and its children into `spans`, on top of the `inherited`
+/// formatting.
+fn walk(component: &Value, inherited: FormatState, spans: &mut Vec<(String, FormatState)>) {
+    match component {
+        Value::Null => (),
+        Value::Bool(bool) => push_text(&bool.to_string(), inherited, spans),
+        Value::Number(number) => push_text(&number.to_string(), inherited, spans),
+        Value::String(text) => push_text(text, inherited, spans),
+        Value::Array(components) => {
+            let Some((first, rest)) = components.split_first() else {
+                return;
+            };
+
+            walk(first, inherited, spans);
+
+            let parent = match first {
+                Value::Object(fields) => apply_style(fields, inherited),
+                _ => inherited,
+            };
+            for component in rest {
+                walk(component, parent, spans);
+            }
+        }
+        Value::Object(fields) => {
+            let format = apply_style(fields, inherited);
+
+            let text = ["text", "translate", "keybind"]
+                .into_iter()
+                .find_map(|key| fields.get(key).and_then(Value::as_str));
+            if let Some(text) = text {
+                push_text(text, format, spans);
+            }
+
+            if let Some(Value::Array(extra)) = fields.get("extra") {
+                for component in extra {
+                    walk(component, format, spans);
+                }
+            }
+        }
+    }
+}
+
+/// Returns `inherited` with the formatting fields of a component applied.
+fn apply_style(fields: &Map<String, Value>, inherited: FormatState) -> FormatState {
+    let color = match fields.get("color").and_then(Value::as_str) {
+        Some("reset") => None,
+        Some(name) => Color::from_name(name)
+            .or_else(|| parse_hex(name).map(Color::nearest))
+            .or_else(|| inherited.color()),
+        None => inherited.color(),
+    };
+
+    let mut style = inherited.style();
+    for (key, decoration) in STYLE_FIELDS {
+        match fields.get(key).and_then(Value::as_bool) {
+            Some(true) => style.insert(decoration),
+            Some(false) => style.remove(decoration),
+            None => (),
+        }
+    }
+
+    FormatState::from_parts(color, style)
+}
+
+/// Parse a hexadecimal color, like `"#FFAA00"`.
+fn parse_hex(color: &str) -> Option<Rgb> {
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let channel = |range| u8::from_str_radix(hex.get(range)?, 16).ok();
+
+    Some(Rgb::new(channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+/// Push `text` into `spans`, applying any `'§'` format codes inside of it on top of `format`.
+fn push_text(text: &str, mut format: FormatState, spans: &mut Vec<(String, FormatState)>) {
+    /// Push `text` into `spans`, merging it into the last span if it has the same formatting.
+    fn push(spans: &mut Vec<(String, FormatState)>, text: &str, format: FormatState) {
+        if text.is_empty() {
+            return;
+        }
+
+        match spans.last_mut() {
+            Some((last, last_format)) if *last_format == format => last.push_str(text),
+            _ => spans.push((text.to_owned(), format)),
+        }
+    }
+
+    let mut start = 0;
+    let mut iter = text.char_indices();
+
+    while let Some((index, char)) = iter.next() {
+        if char != '§' {
+            continue;
+        }
+
+        push(spans, &text[start..index], format);
+        match iter.next() {
+            Some((code_index, code)) => {
+                format.apply(FormatCode::new_or_unknown(code).format());
+                start = code_index + code.len_utf8();
+            }
+            None => start = text.len(),
+        }
+    }
+
+    push(spans, &text[start..], format);
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transparent decompression of gzip and zip inputs, and gzip compression of outputs.
+//!
+//! See [`decompress`] and [`GzipWriter`].
+
+mod zip;
+
+#[cfg(test)]
+mod test;
+
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
+use std::io::{BufRead, BufReader, Cursor, Read, Write};
+
+/// The bytes that every gzip member starts with.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// The bytes that every zip archive starts with, the signature of its first local file header.
+const ZIP_MAGIC: [u8; 4] = *b"PK\x03\x04";
+
+/// Wrap `input` so that reading from it yields decompressed bytes.
+///
+/// - gzip input, like a `.stendhal.gz`, is decompressed as it is read
+/// - zip input is read in full, and the entry named `entry` is extracted, or the first file if
+///   `entry` is [`None`]
+/// - anything else is passed through unchanged
+///
+/// Only stored and deflated zip entries are supported, and not zip64 archives.
+///
+/// # Errors
+///
+/// - [`DecompressError::Io`] if it cannot read from `input`
+/// - [`DecompressError::Zip`] if a zip archive is malformed
+/// - [`DecompressError::UnsupportedMethod`] if the zip entry is compressed with anything but
+///   deflate
+/// - [`DecompressError::MissingEntry`] if the zip archive has no entry named `entry`
+/// - [`DecompressError::Empty`] if `entry` is [`None`] and the zip archive has no files
+pub fn decompress<'a>(
+    input: impl Read + 'a,
+    entry: Option<&str>,
+) -> Result<Box<dyn Read + 'a>, DecompressError> {
+    let mut input = BufReader::new(input);
+    let magic = input.fill_buf()?;
+
+    if magic.starts_with(&GZIP_MAGIC) {
+        return Ok(Box::new(MultiGzDecoder::new(input)));
+    }
+
+    if magic.starts_with(&ZIP_MAGIC) {
+        let mut archive = vec![];
+        input.read_to_end(&mut archive)?;
+
+        return Ok(Box::new(Cursor::new(zip::extract(&archive, entry)?)));
+    }
+
+    Ok(Box::new(input))
+}
+
+/// Wraps a writer, gzip-compressing everything written through it.
+///
+/// Call [`Self::finish`] once done, otherwise the end of the output may be lost.
+pub struct GzipWriter<W: Write> {
+    /// Compresses into the wrapped writer.
+    encoder: GzEncoder<W>,
+}
+
+impl<W: Write> GzipWriter<W> {
+    /// Wrap `output`, compressing with the default compression level.
+    pub fn new(output: W) -> Self {
+        Self {
+            encoder: GzEncoder::new(output, Compression::default()),
+        }
+    }
+
+    /// Write the rest of the compressed output, then return the wrapped writer.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into the wrapped writer
+    pub fn finish(self) -> std::io::Result<W> {
+        self.encoder.finish()
+    }
+}
+
+impl<W: Write> Write for GzipWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.encoder.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.encoder.flush()
+    }
+}
+
+/// All the errors that could occur while decompressing an input.
+#[allow(clippy::module_name_repetitions)] // This will be exported outside of `compression`
+#[derive(thiserror::Error, Debug)]
+pub enum DecompressError {
+    /// Encountered when a zip archive is truncated or otherwise malformed.
+    #[error("malformed zip archive: {0}")]
+    Zip(&'static str),
+    /// Encountered when a zip entry uses a compression method other than stored or deflate.
+    #[error("unsupported zip compression method {0}")]
+    UnsupportedMethod(u16),
+    /// Encountered when a zip archive does not have the requested entry.
+    #[error("zip archive has no entry named '{0}'")]
+    MissingEntry(Box<str>),
+    /// Encountered when a zip archive does not have any files.
+    #[error("zip archive has no files")]
+    Empty,
+    /// Encoutered when an I/O action fails in some way.
+    #[error("could not perform I/O action: {0}")]
+    Io(#[from] std::io::Error),
+}
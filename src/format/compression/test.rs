@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{decompress, DecompressError, GzipWriter};
+use flate2::{
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use std::io::{Read, Write};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+/// Read everything from `input` after decompressing it.
+fn read(input: &[u8], entry: Option<&str>) -> std::result::Result<String, DecompressError> {
+    let mut string = String::new();
+    decompress(input, entry)?.read_to_string(&mut string)?;
+    Ok(string)
+}
+
+/// Build a zip archive of `(name, contents, deflated)` entries.
+fn zip(entries: &[(&str, &str, bool)]) -> Vec<u8> {
+    let mut archive = vec![];
+    let mut central_directory = vec![];
+
+    for &(name, contents, deflated) in entries {
+        let (method, data) = if deflated {
+            let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+            encoder.write_all(contents.as_bytes()).unwrap();
+            (8_u16, encoder.finish().unwrap())
+        } else {
+            (0_u16, contents.as_bytes().to_vec())
+        };
+        let offset = u32::try_from(archive.len()).unwrap();
+        let data_len = u32::try_from(data.len()).unwrap();
+        let name_len = u16::try_from(name.len()).unwrap();
+
+        // Local file header, with a zeroed time, date, and CRC-32, which are not checked
+        archive.extend(0x0403_4b50_u32.to_le_bytes());
+        archive.extend([20, 0, 0, 0]);
+        archive.extend(method.to_le_bytes());
+        archive.extend([0; 8]);
+        archive.extend(data_len.to_le_bytes());
+        archive.extend(u32::try_from(contents.len()).unwrap().to_le_bytes());
+        archive.extend(name_len.to_le_bytes());
+        archive.extend([0, 0]);
+        archive.extend(name.as_bytes());
+        archive.extend(&data);
+
+        central_directory.extend(0x0201_4b50_u32.to_le_bytes());
+        central_directory.extend([20, 0, 20, 0, 0, 0]);
+        central_directory.extend(method.to_le_bytes());
+        central_directory.extend([0; 8]);
+        central_directory.extend(data_len.to_le_bytes());
+        central_directory.extend(u32::try_from(contents.len()).unwrap().to_le_bytes());
+        central_directory.extend(name_len.to_le_bytes());
+        central_directory.extend([0; 12]);
+        central_directory.extend(offset.to_le_bytes());
+        central_directory.extend(name.as_bytes());
+    }
+
+    let count = u16::try_from(entries.len()).unwrap();
+    let offset = u32::try_from(archive.len()).unwrap();
+    let size = u32::try_from(central_directory.len()).unwrap();
+
+    archive.extend(central_directory);
+    archive.extend(0x0605_4b50_u32.to_le_bytes());
+    archive.extend([0; 4]);
+    archive.extend(count.to_le_bytes());
+    archive.extend(count.to_le_bytes());
+    archive.extend(size.to_le_bytes());
+    archive.extend(offset.to_le_bytes());
+    archive.extend([0, 0]);
+
+    archive
+}
+
+#[test]
+fn decompress_passthrough() -> Result {
+    assert_eq!(read(b"title: Plain", None)?, "title: Plain");
+    assert_eq!(read(b"", None)?, "");
+
+    Ok(())
+}
+
+#[test]
+fn decompress_gzip() -> Result {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(b"title: Compressed\n")?;
+    let gzip = encoder.finish()?;
+
+    assert_eq!(read(&gzip, None)?, "title: Compressed\n");
+
+    Ok(())
+}
+
+#[test]
+fn decompress_zip() -> Result {
+    let archive = zip(&[
+        ("books/", "", false),
+        ("books/first.stendhal", "title: First", true),
+        ("books/second.stendhal", "title: Second", false),
+    ]);
+
+    // The first file, skipping the directory
+    assert_eq!(read(&archive, None)?, "title: First");
+    assert_eq!(
+        read(&archive, Some("books/second.stendhal"))?,
+        "title: Second"
+    );
+
+    assert!(matches!(
+        read(&archive, Some("books/third.stendhal")),
+        Err(DecompressError::MissingEntry(name)) if &*name == "books/third.stendhal"
+    ));
+    assert!(matches!(
+        read(&zip(&[("books/", "", false)]), None),
+        Err(DecompressError::Empty)
+    ));
+    assert!(matches!(
+        read(&archive[..archive.len() - 4], None),
+        Err(DecompressError::Zip(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn gzip_writer() -> Result {
+    let mut writer = GzipWriter::new(vec![]);
+    writer.write_all(b"<p>Compressed</p>")?;
+    let gzip = writer.finish()?;
+
+    assert!(gzip.starts_with(&[0x1f, 0x8b]));
+    assert_eq!(read(&gzip, None)?, "<p>Compressed</p>");
+
+    Ok(())
+}
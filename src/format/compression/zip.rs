@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! A minimal zip archive reader.
+//!
+//! Reads the central directory to find an entry, then inflates its data from the local file
+//! header. See <https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT>.
+
+use super::DecompressError;
+use flate2::read::DeflateDecoder;
+use std::io::Read;
+
+/// The signature of a local file header.
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+/// The signature of a central directory file header.
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+/// The signature of the end of central directory record.
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+
+/// The length of the end of central directory record, not including its comment.
+const END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+/// The length of a central directory file header, not including its variable-length fields.
+const CENTRAL_HEADER_LEN: usize = 46;
+/// The length of a local file header, not including its variable-length fields.
+const LOCAL_HEADER_LEN: usize = 30;
+
+/// The compression method of an entry that is not compressed.
+const STORED: u16 = 0;
+/// The compression method of an entry compressed with deflate.
+const DEFLATED: u16 = 8;
+
+/// An entry of the central directory.
+struct Entry<'a> {
+    /// The path of the entry inside the archive.
+    name: &'a [u8],
+    /// How the entry's data is compressed.
+    method: u16,
+    /// The length of the entry's data in the archive.
+    compressed_size: usize,
+    /// Where the entry's local file header starts.
+    offset: usize,
+}
+
+impl Entry<'_> {
+    /// Whether the entry is a directory, rather than a file.
+    fn is_directory(&self) -> bool {
+        self.name.ends_with(b"/")
+    }
+}
+
+/// Extract the entry named `name` from `archive`, or the first file if `name` is [`None`].
+pub fn extract(archive: &[u8], name: Option<&str>) -> Result<Vec<u8>, DecompressError> {
+    let entry = central_directory(archive)?
+        .into_iter()
+        .filter(|entry| !entry.is_directory())
+        .find(|entry| name.is_none_or(|name| entry.name == name.as_bytes()))
+        .ok_or_else(|| {
+            name.map_or(DecompressError::Empty, |name| {
+                DecompressError::MissingEntry(name.into())
+            })
+        })?;
+
+    if u32_at(archive, entry.offset)? != LOCAL_HEADER {
+        return Err(DecompressError::Zip("missing local file header"));
+    }
+
+    let name_len = usize::from(u16_at(archive, entry.offset + 26)?);
+    let extra_len = usize::from(u16_at(archive, entry.offset + 28)?);
+    let start = entry.offset + LOCAL_HEADER_LEN + name_len + extra_len;
+    let data = archive
+        .get(start..start + entry.compressed_size)
+        .ok_or(DecompressError::Zip("entry data is truncated"))?;
+
+    match entry.method {
+        STORED => Ok(data.to_vec()),
+        DEFLATED => {
+            let mut bytes = vec![];
+            DeflateDecoder::new(data).read_to_end(&mut bytes)?;
+            Ok(bytes)
+        }
+        method => Err(DecompressError::UnsupportedMethod(method)),
+    }
+}
+
+/// Read every entry of the central directory of `archive`.
+fn central_directory(archive: &[u8]) -> Result<Vec<Entry<'_>>, DecompressError> {
+    let end = find_end_of_central_directory(archive)?;
+    let count = u16_at(archive, end + 10)?;
+    let mut offset = usize_at(archive, end + 16)?;
+    let mut entries = Vec::with_capacity(usize::from(count));
+
+    for _ in 0..count {
+        if u32_at(archive, offset)? != CENTRAL_HEADER {
+            return Err(DecompressError::Zip(
+                "missing central directory file header",
+            ));
+        }
+
+        let name_len = usize::from(u16_at(archive, offset + 28)?);
+        let extra_len = usize::from(u16_at(archive, offset + 30)?);
+        let comment_len = usize::from(u16_at(archive, offset + 32)?);
+        let name_start = offset + CENTRAL_HEADER_LEN;
+
+        entries.push(Entry {
+            name: archive
+                .get(name_start..name_start + name_len)
+                .ok_or(DecompressError::Zip("entry name is truncated"))?,
+            method: u16_at(archive, offset + 10)?,
+            compressed_size: usize_at(archive, offset + 20)?,
+            offset: usize_at(archive, offset + 42)?,
+        });
+
+        offset = name_start + name_len + extra_len + comment_len;
+    }
+
+    Ok(entries)
+}
+
+/// Find where the end of central directory record starts, searching backwards past its comment.
+fn find_end_of_central_directory(archive: &[u8]) -> Result<usize, DecompressError> {
+    let last = archive
+        .len()
+        .checked_sub(END_OF_CENTRAL_DIRECTORY_LEN)
+        .ok_or(DecompressError::Zip(
+            "missing end of central directory record",
+        ))?;
+    // The comment is at most `u16::MAX` bytes long
+    let first = last.saturating_sub(usize::from(u16::MAX));
+
+    (first..=last)
+        .rev()
+        .find(|&offset| u32_at(archive, offset).ok() == Some(END_OF_CENTRAL_DIRECTORY))
+        .ok_or(DecompressError::Zip(
+            "missing end of central directory record",
+        ))
+}
+
+/// Read a little-endian [`u16`] from `archive` at `offset`.
+fn u16_at(archive: &[u8], offset: usize) -> Result<u16, DecompressError> {
+    archive
+        .get(offset..offset + 2)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u16::from_le_bytes)
+        .ok_or(DecompressError::Zip("unexpected end of archive"))
+}
+
+/// Read a little-endian [`u32`] from `archive` at `offset`.
+fn u32_at(archive: &[u8], offset: usize) -> Result<u32, DecompressError> {
+    archive
+        .get(offset..offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or(DecompressError::Zip("unexpected end of archive"))
+}
+
+/// Read a little-endian [`u32`] from `archive` at `offset`, as a [`usize`].
+fn usize_at(archive: &[u8], offset: usize) -> Result<usize, DecompressError> {
+    usize::try_from(u32_at(archive, offset)?)
+        .map_err(|_| DecompressError::Zip("offset is out of range"))
+}
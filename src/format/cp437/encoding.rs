@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Converting between Unicode and code page 437, the character set of the IBM PC.
+//!
+//! See [`encode`] and [`decode`].
+
+/// The characters of bytes `0x80` through `0xFF` of code page 437, in order.
+///
+/// Bytes `0x20` through `0x7E` are the same as ASCII.
+const UPPER_HALF: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}', //
+];
+
+/// Returns the code page 437 byte for `char`, if it has one.
+///
+/// Only printable characters are mapped, along with the escape character used by ANSI escape
+/// sequences, and carriage returns and line feeds.
+pub fn encode(char: char) -> Option<u8> {
+    match char {
+        ' '..='~' | '\u{1b}' | '\r' | '\n' => u8::try_from(char).ok(),
+        _ => UPPER_HALF
+            .iter()
+            .position(|upper| *upper == char)
+            .and_then(|index| u8::try_from(index + 0x80).ok()),
+    }
+}
+
+/// Returns the character of a code page 437 byte.
+///
+/// Control bytes other than escapes, carriage returns, and line feeds are decoded as `'?'`, the
+/// opposite of [`printable`].
+pub fn decode(byte: u8) -> char {
+    match byte {
+        b' '..=b'~' | 0x1b | b'\r' | b'\n' => char::from(byte),
+        0x80.. => UPPER_HALF[usize::from(byte - 0x80)],
+        _ => '?',
+    }
+}
+
+/// Returns `char` if it can be encoded into code page 437, otherwise `'?'`.
+pub fn printable(char: char) -> char {
+    match char {
+        '\u{1b}' | '\r' | '\n' => '?',
+        _ if encode(char).is_some() => char,
+        _ => '?',
+    }
+}
@@ -0,0 +1,548 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Exporting for code page 437 text with ANSI colors, for retro terminals and BBS-style displays.
+//!
+//! See [`Cp437`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     export::Cp437,
+//!     syntax::{
+//!         minecraft::{Color, Format},
+//!         Token, TokenList,
+//!     },
+//!     Export,
+//! };
+//!
+//! let input = TokenList::new_from_boxed(
+//!     Box::new([]),
+//!     Box::new([
+//!         Token::ThematicBreak,
+//!         Token::Format(Format::Color(Color::Gold)),
+//!         Token::Text("Café".into()),
+//!     ]),
+//! );
+//!
+//! let mut bytes = vec![];
+//! Cp437::export_token_vector_to_writer(input, &mut bytes)?;
+//!
+//! // `'é'` is `0x82` and `'║'` is `0xBA` in code page 437
+//! let line = b"\xBA \x1b[0;33mCaf\x82\x1b[0m";
+//! assert!(bytes.windows(line.len()).any(|window| window == line));
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::{
+    export::Capabilities,
+    syntax::{
+        align::Alignment,
+        minecraft::{strip_format_codes, Color, ColorLayer, ColorValue, FormatState, Rgb},
+        Metadata, Token, TokenList,
+    },
+    Export, ExportWithOptions,
+};
+pub use options::{ColorDepth, ExportOptions};
+use std::{
+    borrow::Cow,
+    fmt::Write as _,
+    io::{BufWriter, Write},
+};
+
+mod encoding;
+mod options;
+#[cfg(test)]
+mod test;
+
+/// The sixteen colors of the IBM PC's text mode, as their RGB values on a CGA display, in the
+/// order of their attribute numbers.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),       // Black
+    (0, 0, 170),     // Blue
+    (0, 170, 0),     // Green
+    (0, 170, 170),   // Cyan
+    (170, 0, 0),     // Red
+    (170, 0, 170),   // Magenta
+    (170, 85, 0),    // Brown
+    (170, 170, 170), // Light gray
+    (85, 85, 85),    // Dark gray
+    (85, 85, 255),   // Light blue
+    (85, 255, 85),   // Light green
+    (85, 255, 255),  // Light cyan
+    (255, 85, 85),   // Light red
+    (255, 85, 255),  // Light magenta
+    (255, 255, 85),  // Yellow
+    (255, 255, 255), // White
+];
+
+/// The ANSI color number (`3x` in an SGR sequence) of each of the first eight colors of
+/// [`PALETTE`], which orders red and blue the other way around.
+const ANSI_ORDER: [u8; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
+
+/// The levels of each channel of xterm's 6×6×6 color cube, colors 16 through 231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Exporting for code page 437 (CP-437) text, the character set of the IBM PC, with colors as
+/// classic 16-color ANSI escape sequences.
+///
+/// # Format
+///
+/// [`Export::export_token_vector_to_writer`] writes CP-437 bytes, ready for a DOS console or a
+/// BBS. [`Export::export_token_vector_to_string`] returns the same text as Unicode, for terminals
+/// that already decode CP-437. Lines end with `"\r\n"`, unless [`ExportOptions`] sets another
+/// [`LineEnding`][`crate::format::line_ending::LineEnding`].
+///
+/// If present, the title and the author are written first, followed by an empty line:
+///
+/// ```text
+/// {title}
+/// by {author}
+///
+/// ```
+///
+/// Each page is drawn in a double-lined box, as wide as set in [`ExportOptions`], with its number
+/// in the top edge, counting from one. Inside the box, lines are wrapped at the last space that
+/// fits, and words wider than a whole line are broken at the last character that fits.
+///
+/// - Characters without a CP-437 equivalent are written as `'?'`
+/// - Paragraph breaks are written as empty lines
+/// - Images are written as their alt text
+/// - Colors are written as set by [`ColorDepth`]. By default, that's the nearest of the sixteen
+///   DOS colors, with the high intensity attribute (`ESC[1m`) for the bright half of the palette
+/// - Bold text is written in high intensity, like bold text in `ANSI.SYS`, so with
+///   [`ColorDepth::Ansi16`], bold dark colors become their bright counterparts
+/// - Underlined text is written as underlined (`ESC[4m`)
+/// - Obfuscated text is written as blinking (`ESC[5m`), and scrambled if [`ExportOptions`] sets an
+///   [`Obfuscator`][`crate::syntax::minecraft::Obfuscator`]
+/// - Italics and strikethrough are dropped, as DOS had no way to show them
+/// - Colors are reset before the right edge of each box, so the frame is never colored
+pub struct Cp437 {}
+
+impl Cp437 {
+    /// Parse a given abstract syntax vector into CP-437 text with the given options, then output
+    /// that as a string, decoded back into Unicode.
+    #[must_use]
+    pub fn export_token_vector_to_string_with_options(
+        tokens: &TokenList,
+        options: &ExportOptions,
+    ) -> Box<str> {
+        render(tokens, options).into_boxed_str()
+    }
+
+    /// Parse a given abstract syntax vector into CP-437 text with the given options, then output
+    /// that into a writer, like a [`std::fs::File`].
+    ///
+    /// Writes CP-437 bytes, not UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    pub fn export_token_vector_to_writer_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(output);
+
+        let bytes: Vec<u8> = render(tokens, options)
+            .chars()
+            // `render` only writes characters that `encoding::printable` allows
+            .map(|char| encoding::encode(char).unwrap_or(b'?'))
+            .collect();
+        writer.write_all(&bytes)?;
+
+        writer.flush()
+    }
+
+    /// Decode CP-437 bytes, like those written by [`Export::export_token_vector_to_writer`], into
+    /// Unicode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::export::Cp437;
+    ///
+    /// assert_eq!(Cp437::decode(b"\xC9\xCD\xBB Caf\x82").as_ref(), "╔═╗ Café");
+    /// ```
+    #[must_use]
+    pub fn decode(bytes: &[u8]) -> Box<str> {
+        bytes.iter().copied().map(encoding::decode).collect()
+    }
+
+    /// Returns the nearest of the sixteen DOS colors to `color`, as its attribute number.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::{export::Cp437, syntax::minecraft::Color};
+    ///
+    /// assert_eq!(Cp437::dos_color(Color::DarkAqua), 3); // Cyan
+    /// assert_eq!(Cp437::dos_color(Color::Gold), 6); // Brown
+    /// assert_eq!(Cp437::dos_color(Color::Yellow), 14);
+    /// ```
+    #[must_use]
+    pub fn dos_color(color: Color) -> u8 {
+        let rgb = ColorValue::from(color).fg().as_tuple();
+
+        PALETTE
+            .into_iter()
+            .enumerate()
+            .min_by_key(|(index, color)| (distance(*color, rgb), *index))
+            .and_then(|(index, _)| u8::try_from(index).ok())
+            .unwrap_or(7)
+    }
+
+    /// Returns the nearest of xterm's 256 colors to `rgb`, as its color number.
+    ///
+    /// Only the 6×6×6 color cube and the grayscale ramp are used, as terminals disagree on the
+    /// first sixteen colors.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::{export::Cp437, syntax::minecraft::Rgb};
+    ///
+    /// assert_eq!(Cp437::xterm_color(Rgb::new(255, 170, 0)), 214); // Gold
+    /// assert_eq!(Cp437::xterm_color(Rgb::new(170, 170, 170)), 248); // Gray
+    /// ```
+    #[must_use]
+    pub fn xterm_color(rgb: Rgb) -> u8 {
+        let rgb = rgb.as_tuple();
+        let level = |channel: u8| {
+            (0u8..6)
+                .min_by_key(|level| CUBE_LEVELS[usize::from(*level)].abs_diff(channel))
+                .unwrap_or(0)
+        };
+
+        let (red, green, blue) = (level(rgb.0), level(rgb.1), level(rgb.2));
+        let cube = (
+            CUBE_LEVELS[usize::from(red)],
+            CUBE_LEVELS[usize::from(green)],
+            CUBE_LEVELS[usize::from(blue)],
+        );
+
+        // The grayscale ramp runs from 8 to 238, in steps of 10
+        let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+        let step = u8::try_from((average.saturating_sub(3) / 10).min(23)).unwrap_or(23);
+        let gray = 8 + step * 10;
+
+        if distance((gray, gray, gray), rgb) < distance(cube, rgb) {
+            232 + step
+        } else {
+            16 + red * 36 + green * 6 + blue
+        }
+    }
+}
+
+impl ExportWithOptions for Cp437 {
+    type Options = ExportOptions;
+
+    /// See [`Cp437::export_token_vector_to_writer_with_options`].
+    fn export_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &Self::Options,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(tokens, output, options)
+    }
+}
+
+impl Export for Cp437 {
+    fn capabilities() -> Capabilities {
+        Capabilities::COLOR
+            | Capabilities::OBFUSCATION
+            | Capabilities::PAGINATION
+            | Capabilities::ALIGNMENT
+    }
+
+    /// Parse a given abstract syntax vector into CP-437 text, then output that as a string,
+    /// decoded back into Unicode.
+    fn export_token_vector_to_string(tokens: TokenList) -> Box<str> {
+        Self::export_token_vector_to_string_with_options(&tokens, &ExportOptions::default())
+    }
+
+    /// Parse a given abstract syntax vector into CP-437 text, then output that into a writer,
+    /// like a [`std::fs::File`].
+    ///
+    /// Writes CP-437 bytes, not UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_token_vector_to_writer(
+        tokens: TokenList,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(&tokens, output, &ExportOptions::default())
+    }
+}
+
+/// Lay out `tokens` as framed pages, using only characters that can be encoded into CP-437.
+fn render(tokens: &TokenList, options: &ExportOptions) -> String {
+    let mut output = String::new();
+
+    let mut wrote_metadata = false;
+    for data in tokens.metadata_as_slice() {
+        let (prefix, text) = match data {
+            Metadata::Title(title) => ("", title),
+            Metadata::Author(author) => ("by ", author),
+            _ => continue,
+        };
+
+        output.push_str(prefix);
+        output.extend(strip_format_codes(text).chars().map(encoding::printable));
+        output.push_str(options.line_ending().as_str());
+        wrote_metadata = true;
+    }
+    if wrote_metadata {
+        output.push_str(options.line_ending().as_str());
+    }
+
+    let mut page = Page::new(options);
+    for token in tokens.tokens_as_slice() {
+        match token {
+            Token::Text(text) => {
+                let text = match options.obfuscation() {
+                    Some(obfuscator) if page.state.obfuscated() => {
+                        Cow::Owned(obfuscator.obfuscate(text))
+                    }
+                    _ => Cow::Borrowed(&**text),
+                };
+                for char in text.chars() {
+                    page.push(char);
+                }
+            }
+            Token::Image { alt, .. } => {
+                for char in alt.chars() {
+                    page.push(char);
+                }
+            }
+            Token::Space => page.push(' '),
+            Token::Format(format) => page.state = page.state.with(*format),
+            Token::Align(alignment) => page.alignment = Some(*alignment),
+            Token::Role(_) => (),
+            Token::LineBreak => page.end_line(),
+            Token::ParagraphBreak => {
+                if !page.line.is_empty() {
+                    page.end_line();
+                }
+                page.end_line();
+            }
+            Token::ThematicBreak => {
+                page.write(&mut output);
+                page.open = true;
+            }
+        }
+        if token.is_break() && *token != Token::Space {
+            page.alignment = None;
+        }
+    }
+    page.write(&mut output);
+
+    output
+}
+
+/// Returns the squared distance between two colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    [(a.0, b.0), (a.1, b.1), (a.2, b.2)]
+        .into_iter()
+        .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+        .sum()
+}
+
+/// Returns the SGR escape sequence that sets the attributes of `state`, replacing any before it,
+/// writing its color on `layer`.
+fn sgr(state: FormatState, depth: ColorDepth, layer: ColorLayer) -> String {
+    let mut sequence = String::from("\u{1b}[0");
+
+    // Only the foreground has a bright half, set through the high intensity attribute
+    let bright = layer == ColorLayer::Foreground
+        && depth == ColorDepth::Ansi16
+        && state
+            .color()
+            .is_some_and(|color| Cp437::dos_color(color) >= 8);
+    if state.bold() || bright {
+        sequence.push_str(";1");
+    }
+    if state.underline() {
+        sequence.push_str(";4");
+    }
+    if state.obfuscated() {
+        sequence.push_str(";5");
+    }
+    if let Some(color) = state.color() {
+        let rgb = color.rgb(layer);
+        let (basic, extended) = match layer {
+            ColorLayer::Foreground => (3, 38),
+            ColorLayer::Background => (4, 48),
+        };
+        let _ = match depth {
+            // The terminal shades its own background colors, so only the hue is kept
+            ColorDepth::Ansi16 => write!(
+                sequence,
+                ";{basic}{}",
+                ANSI_ORDER[usize::from(Cp437::dos_color(color) % 8)]
+            ),
+            ColorDepth::Ansi256 => write!(sequence, ";{extended};5;{}", Cp437::xterm_color(rgb)),
+            ColorDepth::TrueColor => {
+                let (red, green, blue) = rgb.as_tuple();
+                write!(sequence, ";{extended};2;{red};{green};{blue}")
+            }
+        };
+    }
+
+    sequence.push('m');
+    sequence
+}
+
+/// The lines of the page currently being exported.
+#[derive(Debug)]
+struct Page {
+    /// The number of columns of text inside the frame, at least one.
+    width: usize,
+    /// How to write colors, or [`None`] to write no ANSI escape sequences.
+    color: Option<ColorDepth>,
+    /// Whether to write colors as the color of the text or of its background.
+    color_layer: ColorLayer,
+    /// What ends each line.
+    line_ending: &'static str,
+    /// The number of the page, starting from one, once it has been written.
+    number: usize,
+    /// The finished, wrapped lines of the page.
+    lines: Vec<Vec<(char, FormatState)>>,
+    /// The characters of the line currently being wrapped, and their formatting.
+    line: Vec<(char, FormatState)>,
+    /// The formatting of the next character.
+    state: FormatState,
+    /// How the current line of the work is aligned, if at all, padding each line it wraps onto.
+    alignment: Option<Alignment>,
+    /// Whether or not a page has been started, even if it has no lines yet.
+    open: bool,
+}
+
+impl Page {
+    /// Creates a new, empty [`Page`].
+    fn new(options: &ExportOptions) -> Self {
+        Self {
+            width: options.width().max(5) - 4,
+            color: options.color().then_some(options.color_depth()),
+            color_layer: options.color_layer(),
+            line_ending: options.line_ending().as_str(),
+            number: 0,
+            lines: vec![],
+            line: vec![],
+            state: FormatState::new(),
+            alignment: None,
+            open: false,
+        }
+    }
+
+    /// Push a character onto the current line, wrapping it if it doesn't fit.
+    fn push(&mut self, char: char) {
+        if self.line.len() >= self.width {
+            // Breaking at a space consumes it
+            if char == ' ' {
+                self.end_line();
+                return;
+            }
+
+            match self.line.iter().rposition(|(char, _)| *char == ' ') {
+                // Carry the partial word over to the next line
+                Some(space) => {
+                    let carried = self.line.split_off(space + 1);
+                    self.line.pop();
+                    self.end_line();
+                    self.line = carried;
+                }
+                // The word is wider than a whole line
+                None => self.end_line(),
+            }
+        }
+
+        self.line.push((encoding::printable(char), self.state));
+    }
+
+    /// Finish the current line, padding it to its alignment.
+    fn end_line(&mut self) {
+        let mut line = std::mem::take(&mut self.line);
+        if let Some(alignment) = self.alignment.filter(|_| !line.is_empty()) {
+            let padding = alignment.offset(self.width.saturating_sub(line.len()));
+            line.splice(
+                0..0,
+                std::iter::repeat_n((' ', FormatState::new()), padding),
+            );
+        }
+        self.lines.push(line);
+    }
+
+    /// Write the page into `output`, if one was started or has any text.
+    fn write(&mut self, output: &mut String) {
+        if !self.line.is_empty() {
+            self.end_line();
+        }
+        if !self.open && self.lines.is_empty() {
+            return;
+        }
+
+        self.number += 1;
+        let edge = self.width + 2;
+        let label = format!(" Page {} ", self.number);
+        output.push('╔');
+        if label.len() + 1 < edge {
+            output.push('═');
+            output.push_str(&label);
+            output.extend(std::iter::repeat_n('═', edge - label.len() - 1));
+        } else {
+            output.extend(std::iter::repeat_n('═', edge));
+        }
+        output.push('╗');
+        output.push_str(self.line_ending);
+
+        for line in self.lines.drain(..) {
+            output.push_str("║ ");
+
+            if let Some(depth) = self.color {
+                let plain = sgr(FormatState::new(), depth, self.color_layer);
+                let mut current = plain.clone();
+                for (char, state) in &line {
+                    let next = sgr(*state, depth, self.color_layer);
+                    if next != current {
+                        output.push_str(&next);
+                        current = next;
+                    }
+                    output.push(*char);
+                }
+                if current != plain {
+                    output.push_str(&plain);
+                }
+            } else {
+                output.extend(line.iter().map(|(char, _)| *char));
+            }
+
+            output.extend(std::iter::repeat_n(' ', self.width - line.len()));
+            output.push_str(" ║");
+            output.push_str(self.line_ending);
+        }
+
+        output.push('╚');
+        output.extend(std::iter::repeat_n('═', edge));
+        output.push('╝');
+        output.push_str(self.line_ending);
+    }
+}
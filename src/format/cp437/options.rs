@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration for [`super::Cp437`].
+//!
+//! See [`ExportOptions`] and [`ColorDepth`].
+
+use crate::{
+    format::line_ending::LineEnding,
+    syntax::minecraft::{ColorLayer, Obfuscator},
+};
+
+/// How many colors the terminal showing the output can display, which decides how colors are
+/// written.
+///
+/// Minecraft's colors are quantized down to the nearest color the terminal can display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// The sixteen colors of DOS and classic ANSI art (`ESC[3xm`), with the high intensity
+    /// attribute for the bright half, which every color terminal supports.
+    #[default]
+    Ansi16,
+    /// The 256 colors of xterm (`ESC[38;5;Nm`), using its 6×6×6 color cube and grayscale ramp.
+    Ansi256,
+    /// Exact 24-bit colors (`ESC[38;2;R;G;Bm`).
+    TrueColor,
+}
+
+/// Options that control the layout and colors of code page 437 output.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Cp437, Cp437ExportOptions},
+///     syntax::{Token, TokenList},
+/// };
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::ThematicBreak,
+///         Token::Text("one".into()),
+///         Token::Space,
+///         Token::Text("two".into()),
+///     ]),
+/// );
+/// let options = Cp437ExportOptions::new().with_width(7).with_color(false);
+///
+/// assert_eq!(
+///     Cp437::export_token_vector_to_string_with_options(&input, &options).as_ref(),
+///     "╔═════╗\r\n║ one ║\r\n║ two ║\r\n╚═════╝\r\n"
+/// );
+/// ```
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExportOptions {
+    /// The number of columns taken up by each page, including its frame.
+    width: usize,
+    /// Whether or not to write ANSI escape sequences for colors and formatting.
+    color: bool,
+    /// How colors are written, if at all.
+    color_depth: ColorDepth,
+    /// Whether colors are written as the color of the text or of its background.
+    color_layer: ColorLayer,
+    /// What ends each line.
+    line_ending: LineEnding,
+    /// What replaces obfuscated text, if anything.
+    obfuscation: Option<Obfuscator>,
+}
+
+impl ExportOptions {
+    /// Creates a new [`ExportOptions`] with the default options.
+    ///
+    /// Uses the 80 columns of a standard text mode screen, writes colors for
+    /// [`ColorDepth::Ansi16`], and ends lines with [`LineEnding::CrLf`], like DOS.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            width: 80,
+            color: true,
+            color_depth: ColorDepth::Ansi16,
+            color_layer: ColorLayer::Foreground,
+            line_ending: LineEnding::CrLf,
+            obfuscation: None,
+        }
+    }
+
+    /// Returns the number of columns taken up by each page, including its frame.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Sets the number of columns taken up by each page, including its frame.
+    ///
+    /// Values smaller than five columns, enough for a frame around a single column of text, are
+    /// treated as five columns.
+    #[must_use]
+    pub const fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Returns whether or not ANSI escape sequences are written for colors and formatting.
+    #[must_use]
+    pub const fn color(&self) -> bool {
+        self.color
+    }
+
+    /// Sets whether or not ANSI escape sequences are written for colors and formatting.
+    ///
+    /// Without them, the output is plain code page 437 text.
+    #[must_use]
+    pub const fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns how colors are written, if at all.
+    #[must_use]
+    pub const fn color_depth(&self) -> ColorDepth {
+        self.color_depth
+    }
+
+    /// Sets how colors are written, if at all.
+    ///
+    /// Anything but [`ColorDepth::Ansi16`] is only understood by modern terminal emulators, not
+    /// by DOS consoles.
+    #[must_use]
+    pub const fn with_color_depth(mut self, color_depth: ColorDepth) -> Self {
+        self.color_depth = color_depth;
+        self
+    }
+
+    /// Returns whether colors are written as the color of the text or of its background.
+    #[must_use]
+    pub const fn color_layer(&self) -> ColorLayer {
+        self.color_layer
+    }
+
+    /// Sets whether colors are written as the color of the text or of its background.
+    ///
+    /// With [`ColorLayer::Background`], colors are written as background colors (`ESC[4xm`,
+    /// `ESC[48;5;Nm`, or `ESC[48;2;R;G;Bm`), with the darker background value that Minecraft draws
+    /// as the shadow of the text. DOS only has eight background colors, so with
+    /// [`ColorDepth::Ansi16`], they are quantized down to the darker half.
+    #[must_use]
+    pub const fn with_color_layer(mut self, color_layer: ColorLayer) -> Self {
+        self.color_layer = color_layer;
+        self
+    }
+
+    /// Returns what ends each line.
+    #[must_use]
+    pub const fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Sets what ends each line.
+    ///
+    /// Use [`LineEnding::Lf`] to show the output in a terminal on a Unix-like system.
+    #[must_use]
+    pub const fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Returns what replaces obfuscated text, if anything.
+    #[must_use]
+    pub const fn obfuscation(&self) -> Option<&Obfuscator> {
+        self.obfuscation.as_ref()
+    }
+
+    /// Sets what replaces obfuscated text, or [`None`] to write it as it is.
+    ///
+    /// Obfuscated text still blinks where colors are written, but its characters are scrambled,
+    /// so it can't be read any more than it can in-game.
+    #[must_use]
+    pub fn with_obfuscation(mut self, obfuscation: Option<Obfuscator>) -> Self {
+        self.obfuscation = obfuscation;
+        self
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
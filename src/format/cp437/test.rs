@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for exporting to the [CP-437][`super::Cp437`] format.
+
+use super::{encoding, ColorDepth, Cp437, ExportOptions};
+use crate::{
+    format::line_ending::LineEnding,
+    syntax::{
+        minecraft::{Color, ColorLayer, Format, Obfuscator, Rgb},
+        Metadata, Token, TokenList,
+    },
+    Export,
+};
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+#[test]
+fn cp437_export() {
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([
+            Metadata::Title("§6Fish & Chips".into()),
+            Metadata::Author("Arch".into()),
+        ]),
+        Box::new([
+            ThematicBreak,
+            text!("one"),
+            Space,
+            Token::Format(Format::Bold),
+            text!("two"),
+            Token::Format(Format::Reset),
+            Space,
+            text!("three"),
+            ParagraphBreak,
+            Token::Format(Format::Color(Color::DarkAqua)),
+            text!("日本"),
+            Token::Format(Format::Reset),
+            ThematicBreak,
+            text!("abcdefghijklmnop"),
+            LineBreak,
+        ]),
+    );
+    let options = ExportOptions::new().with_width(16);
+
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "Fish & Chips\r\n\
+         by Arch\r\n\
+         \r\n\
+         ╔═ Page 1 ═════╗\r\n\
+         ║ one \u{1b}[0;1mtwo\u{1b}[0m      ║\r\n\
+         ║ three        ║\r\n\
+         ║              ║\r\n\
+         ║ \u{1b}[0;36m??\u{1b}[0m           ║\r\n\
+         ╚══════════════╝\r\n\
+         ╔═ Page 2 ═════╗\r\n\
+         ║ abcdefghijkl ║\r\n\
+         ║ mnop         ║\r\n\
+         ╚══════════════╝\r\n"
+    );
+
+    let plain = options.with_color(false).with_width(0);
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &plain).as_ref(),
+        "Fish & Chips\r\nby Arch\r\n\r\n\
+         ╔═══╗\r\n║ o ║\r\n║ n ║\r\n║ e ║\r\n║ t ║\r\n║ w ║\r\n║ o ║\r\n\
+         ║ t ║\r\n║ h ║\r\n║ r ║\r\n║ e ║\r\n║ e ║\r\n║   ║\r\n║ ? ║\r\n║ ? ║\r\n╚═══╝\r\n\
+         ╔═══╗\r\n║ a ║\r\n║ b ║\r\n║ c ║\r\n║ d ║\r\n║ e ║\r\n║ f ║\r\n║ g ║\r\n\
+         ║ h ║\r\n║ i ║\r\n║ j ║\r\n║ k ║\r\n║ l ║\r\n║ m ║\r\n║ n ║\r\n║ o ║\r\n\
+         ║ p ║\r\n╚═══╝\r\n"
+    );
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(
+            &tokens,
+            &plain.clone().with_line_ending(LineEnding::Lf),
+        ),
+        Cp437::export_token_vector_to_string_with_options(&tokens, &plain)
+            .replace("\r\n", "\n")
+            .into(),
+    );
+
+    // The writer encodes the same text
+    let mut bytes = vec![];
+    Cp437::export_token_vector_to_writer(tokens.clone(), &mut bytes).unwrap();
+    assert_eq!(bytes.first(), Some(&b'F'));
+    assert!(bytes.contains(&0xC9)); // '╔'
+    assert_eq!(
+        Cp437::decode(&bytes),
+        Cp437::export_token_vector_to_string(tokens)
+    );
+}
+
+#[test]
+fn cp437_colors() {
+    let tokens = TokenList::new_from_boxed(
+        Box::new([]),
+        Box::new([
+            Token::ThematicBreak,
+            Token::Format(Format::Color(Color::Red)),
+            Token::Format(Format::Underline),
+            text!("a"),
+            Token::Format(Format::Color(Color::DarkBlue)),
+            Token::Format(Format::Bold),
+            text!("b"),
+            Token::Format(Format::Color(Color::Gold)),
+            Token::Format(Format::Obfuscated),
+            text!("c"),
+            Token::Format(Format::Reset),
+            Token::Format(Format::Italic),
+            text!("d"),
+        ]),
+    );
+    let options = ExportOptions::new().with_width(8);
+
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "╔══════╗\r\n\
+         ║ \u{1b}[0;1;4;31ma\u{1b}[0;1;4;34mb\u{1b}[0;1;4;5;33mc\u{1b}[0md ║\r\n\
+         ╚══════╝\r\n"
+    );
+
+    let dos: Vec<u8> = Color::ALL.into_iter().map(Cp437::dos_color).collect();
+    assert_eq!(dos, (0..16).collect::<Vec<_>>());
+
+    let options = options.with_color_depth(ColorDepth::Ansi256);
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "╔══════╗\r\n\
+         ║ \u{1b}[0;4;38;5;203ma\u{1b}[0;1;4;38;5;19mb\u{1b}[0;1;4;5;38;5;214mc\u{1b}[0md ║\r\n\
+         ╚══════╝\r\n"
+    );
+
+    let options = options.with_color_depth(ColorDepth::TrueColor);
+    assert!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &options)
+            .contains("\u{1b}[0;4;38;2;255;85;85ma")
+    );
+
+    for (rgb, expected) in [
+        ((0, 0, 0), 16),
+        ((255, 255, 255), 231),
+        ((85, 85, 85), 240),
+        ((128, 128, 128), 244),
+        ((0, 0, 170), 19),
+    ] {
+        assert_eq!(Cp437::xterm_color(Rgb::from(rgb)), expected, "{rgb:?}");
+    }
+}
+
+#[test]
+fn cp437_background_colors() {
+    let tokens = TokenList::new_from_boxed(
+        Box::new([]),
+        Box::new([
+            Token::ThematicBreak,
+            Token::Format(Format::Color(Color::Red)),
+            text!("a"),
+            Token::Format(Format::Reset),
+            text!("b"),
+        ]),
+    );
+    let options = ExportOptions::new()
+        .with_width(6)
+        .with_color_layer(ColorLayer::Background);
+
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "╔════╗\r\n\
+         ║ \u{1b}[0;41ma\u{1b}[0mb ║\r\n\
+         ╚════╝\r\n"
+    );
+
+    let options = options.with_color_depth(ColorDepth::TrueColor);
+    assert!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &options)
+            .contains("\u{1b}[0;48;2;63;21;21ma")
+    );
+}
+
+#[test]
+fn cp437_encoding() {
+    for byte in 0x20..=0xFF {
+        let char = encoding::decode(byte);
+        if byte == 0x7F {
+            assert_eq!(char, '?');
+        } else {
+            assert_eq!(encoding::encode(char), Some(byte), "{char}");
+        }
+    }
+
+    assert_eq!(encoding::encode('€'), None);
+    assert_eq!(encoding::printable('€'), '?');
+    assert_eq!(encoding::printable('\u{1b}'), '?');
+    assert_eq!(encoding::printable('é'), 'é');
+}
+
+#[test]
+fn cp437_obfuscation() {
+    let tokens = TokenList::from(vec![
+        Token::Format(Format::Obfuscated),
+        text!("secret"),
+        Token::Format(Format::Reset),
+        Token::Space,
+        text!("open"),
+    ]);
+    let options = ExportOptions::new()
+        .with_color(false)
+        .with_width(15)
+        .with_line_ending(LineEnding::Lf);
+
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "╔═ Page 1 ════╗\n║ secret open ║\n╚═════════════╝\n"
+    );
+    assert_eq!(
+        Cp437::export_token_vector_to_string_with_options(
+            &tokens,
+            &options.with_obfuscation(Some(Obfuscator::new("#"))),
+        )
+        .as_ref(),
+        "╔═ Page 1 ════╗\n║ ###### open ║\n╚═════════════╝\n"
+    );
+}
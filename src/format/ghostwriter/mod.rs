@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parsing for the book exports of Ghostwriter-style mods, like `.ghb` files.
+//!
+//! See [`Ghostwriter`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     import::Ghostwriter,
+//!     syntax::{minecraft::Format, Metadata, Token},
+//!     Tokenize,
+//! };
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let input = "Title: crafty_novels
+//! Author: RemasteredArch
+//!
+//! ###PAGE##
+//! Page §lone
+//! ###PAGE##
+//! Page two";
+//!
+//! let tokens = Ghostwriter::tokenize_string(input)?;
+//!
+//! assert_eq!(
+//!     tokens.metadata_as_slice(),
+//!     [
+//!         Metadata::Title("crafty_novels".into()),
+//!         Metadata::Author("RemasteredArch".into()),
+//!     ]
+//! );
+//! assert_eq!(
+//!     tokens.tokens_as_slice(),
+//!     [
+//!         Token::ThematicBreak,
+//!         Token::Text("Page".into()),
+//!         Token::Space,
+//!         Token::Format(Format::Bold),
+//!         Token::Text("one".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::LineBreak,
+//!         Token::ThematicBreak,
+//!         Token::Text("Page".into()),
+//!         Token::Space,
+//!         Token::Text("two".into()),
+//!         Token::LineBreak,
+//!     ]
+//! );
+//! #
+//! #     Ok(())
+//! # }
+//! ```
+
+use crate::{
+    format::limits::{tokenize_limited, Limits},
+    syntax::{
+        minecraft::{tokenize_formatted, Format},
+        Metadata, Token, TokenList,
+    },
+    Tokenize,
+};
+pub use error::TokenizeError;
+use std::io::{BufRead, Read};
+
+mod error;
+#[cfg(test)]
+mod test;
+
+/// The line that starts each page.
+const PAGE_MARKER: &str = "##PAGE##";
+
+/// Parses the book exports of Ghostwriter-style mods, like `.ghb` files.
+///
+/// # Expected format
+///
+/// The header is a series of `"Key: value"` lines, up until the first page:
+/// - Keys are case-insensitive, so `"Title: "` and `"title: "` are the same field
+/// - `"Title"` and `"Author"` are required, and other fields become [`Metadata`] as in
+///   [`Metadata::from_field`], like `"Date"` or `"Description"`
+/// - Blank lines are skipped
+///
+/// For the rest of the book:
+/// - A line that is only `"##PAGE##"`, ignoring surrounding whitespace, starts a new page
+/// - Empty lines are paragraph breaks
+/// - `'§'` followed by a character is a format code, which continues until the end of the line,
+///   and unknown format codes are preserved as [`Format::Unknown`]
+pub struct Ghostwriter;
+
+impl Ghostwriter {
+    /// Parse the lines of a Ghostwriter export into an abstract syntax vector.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::InvalidHeaderLine`] if a line of the header is not a field
+    /// - [`TokenizeError::MissingField`] if the header has no title or no author
+    /// - [`TokenizeError::Io`] if a line from `lines` is an I/O error of some kind
+    fn tokenize_lines<S: AsRef<str>>(
+        lines: impl Iterator<Item = std::io::Result<S>>,
+    ) -> Result<TokenList, TokenizeError> {
+        let mut metadata: Vec<Metadata> = vec![];
+        let mut tokens: Vec<Token> = vec![];
+        let mut in_header = true;
+
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            let line = line.as_ref();
+
+            if line.trim() == PAGE_MARKER {
+                in_header = false;
+                tokens.push(Token::ThematicBreak);
+            } else if in_header {
+                if !line.trim().is_empty() {
+                    let field = header_field(line)
+                        .ok_or(TokenizeError::InvalidHeaderLine { line: index + 1 })?;
+                    metadata.push(field);
+                }
+            } else if line.is_empty() {
+                tokens.push(Token::ParagraphBreak);
+            } else {
+                let text = tokenize_formatted(line);
+                let trailing_formatting = text.iter().rev().find_map(|token| match token {
+                    Token::Format(format) => Some(*format != Format::Reset),
+                    _ => None,
+                });
+
+                tokens.extend(text);
+                if trailing_formatting == Some(true) {
+                    tokens.push(Token::Format(Format::Reset));
+                }
+                tokens.push(Token::LineBreak);
+            }
+        }
+
+        if !metadata
+            .iter()
+            .any(|data| matches!(data, Metadata::Title(_)))
+        {
+            return Err(TokenizeError::MissingField("Title"));
+        }
+        if !metadata
+            .iter()
+            .any(|data| matches!(data, Metadata::Author(_)))
+        {
+            return Err(TokenizeError::MissingField("Author"));
+        }
+
+        Ok(TokenList::new_from_boxed(metadata.into(), tokens.into()))
+    }
+
+    /// Parse a Ghostwriter export from a reader, like a [`std::fs::File`], into an abstract syntax
+    /// vector, according to `limits`.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::InvalidHeaderLine`] if a line of the header is not a field
+    /// - [`TokenizeError::MissingField`] if the header has no title or no author
+    /// - [`TokenizeError::LimitExceeded`] if the input or output exceeds `limits`
+    /// - [`TokenizeError::Io`] if it cannot read from `input`
+    pub fn tokenize_reader_with_limits(
+        input: impl Read,
+        limits: &Limits,
+    ) -> Result<TokenList, TokenizeError> {
+        tokenize_limited(input, limits, |input| Self::tokenize_lines(input.lines()))
+    }
+}
+
+/// Parse a `"Key: value"` line of the header into [`Metadata`], if it is a field.
+fn header_field(line: &str) -> Option<Metadata> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    let value = value.trim();
+
+    if key.is_empty() {
+        return None;
+    }
+
+    Some(Metadata::from_field(&key.to_ascii_lowercase(), value))
+}
+
+impl Tokenize for Ghostwriter {
+    type Error = TokenizeError;
+
+    /// Parse a Ghostwriter export into an abstract syntax vector.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::InvalidHeaderLine`] if a line of the header is not a field
+    /// - [`TokenizeError::MissingField`] if the header has no title or no author
+    fn tokenize_string(input: &str) -> Result<TokenList, Self::Error> {
+        Self::tokenize_lines(input.lines().map(Ok))
+    }
+
+    /// Parse a Ghostwriter export from a reader, like a [`std::fs::File`], into an abstract syntax
+    /// vector, with the default [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Ghostwriter::tokenize_reader_with_limits`].
+    fn tokenize_reader(input: impl Read) -> Result<TokenList, Self::Error> {
+        Self::tokenize_reader_with_limits(input, &Limits::default())
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{Ghostwriter, TokenizeError};
+use crate::{
+    syntax::{
+        minecraft::{Color, Format},
+        Metadata, Token,
+    },
+    Tokenize,
+};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn test_ghostwriter() -> Result {
+    use Token::{LineBreak, ParagraphBreak, Space, Text, ThematicBreak};
+
+    let input = "TITLE: The Lore of the Lands\r
+author:Steve\r
+Generation: 1\r
+\r
+##PAGE##\r
+§2Grass§r first\r
+\r
+  ##PAGE##  \r
+§cRed\r
+";
+
+    let tokens = Ghostwriter::tokenize_string(input)?;
+
+    assert_eq!(
+        tokens.metadata_as_slice(),
+        [
+            Metadata::Title("The Lore of the Lands".into()),
+            Metadata::Author("Steve".into()),
+            Metadata::Custom {
+                key: "generation".into(),
+                value: "1".into(),
+            },
+        ]
+    );
+    assert_eq!(
+        tokens.tokens_as_slice(),
+        [
+            ThematicBreak,
+            Token::Format(Format::Color(Color::DarkGreen)),
+            Text("Grass".into()),
+            Token::Format(Format::Reset),
+            Space,
+            Text("first".into()),
+            LineBreak,
+            ParagraphBreak,
+            ThematicBreak,
+            Token::Format(Format::Color(Color::Red)),
+            Text("Red".into()),
+            Token::Format(Format::Reset),
+            LineBreak,
+        ]
+    );
+    assert_eq!(Ghostwriter::tokenize_reader(input.as_bytes())?, tokens);
+
+    Ok(())
+}
+
+#[test]
+fn test_ghostwriter_errors() {
+    assert!(matches!(
+        Ghostwriter::tokenize_string("Title: Book\nnot a field\n##PAGE##"),
+        Err(TokenizeError::InvalidHeaderLine { line: 2 })
+    ));
+    assert!(matches!(
+        Ghostwriter::tokenize_string("Title: Book\n##PAGE##\nAuthor: not a field"),
+        Err(TokenizeError::MissingField("Author"))
+    ));
+    assert!(matches!(
+        Ghostwriter::tokenize_string(""),
+        Err(TokenizeError::MissingField("Title"))
+    ));
+}
@@ -19,8 +19,6 @@
 //!
 //! See [`ExportError`].
 
-use crate::syntax::Token;
-
 /// Represents the various possible errors encountered when exporting to HTML.
 #[derive(thiserror::Error, Debug)]
 #[allow(clippy::module_name_repetitions)]
@@ -28,9 +26,6 @@ pub enum ExportError {
     /// Encountered when an no HTML entity is associated with the given [`char`].
     #[error("no HTML entity associated with character '{0}'")]
     NoSuchCharLiteral(char),
-    /// Encoutered a given [`Token`] in an unexpected place.
-    #[error("did not expect token")]
-    UnexpectedToken(Token),
     /// Encoutered when an I/O action fails in some way.
     #[error("could not perform I/O action")]
     Io(#[from] std::io::Error),
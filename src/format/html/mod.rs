@@ -61,13 +61,23 @@
 //! ```
 
 use crate::{
-    syntax::{minecraft::Format, TokenList},
+    export::Capabilities,
+    format::ids::Ids,
+    syntax::{edit::PageIndexError, library::Library, Metadata, Token, TokenList},
     writer::Utf8Writer,
-    Export,
+    Export, ExportWithOptions,
+};
+pub use options::{
+    AnnotationStyle, BlankPages, DocumentKind, ExportOptions, Legend, MetadataFormatCodes,
+    PageBreaks, PageOpening, Palette, Styling, TextDirection, WritingMode,
 };
 use std::io::Write;
+use token_handling::Element;
 
 mod error;
+mod options;
+mod passthrough;
+mod registry;
 mod syntax;
 #[cfg(test)]
 mod test;
@@ -84,43 +94,87 @@ mod token_handling;
 ///
 /// ```html
 /// <!DOCTYPE html>
-/// <html lang="en" dir="ltr">
+/// <html lang="{language}" dir="{direction}">
 /// <head>
 ///     <meta charset="utf-8" />
 /// ```
 ///
-/// At this point, [metadata][`crate::syntax::Metadata`] is written:
+/// Where the [language][`ExportOptions::with_language`] is `en` and the
+/// [direction][`ExportOptions::with_direction`] is `ltr` by default.
+///
+/// At this point, [metadata][`crate::syntax::Metadata`] is written, without any format codes:
 ///
 /// ```html
 ///     <title>{title}</title>
 ///     <meta name="author" content="{author}" />
+///     <meta name="{key}" content="{value}" />
 /// ```
 ///
 /// And the `<head>` is closed and the contents are opened:
 ///
 /// ```html
-///     <meta name="viewport" content="width=device-width, initial-scale=1.0" /
+///     <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+///     {extra head content}
 /// </head>
 /// <body>
 ///     <article style=white-space:break-spaces>
 /// ```
 ///
+/// [`ExportOptions`] can instead write only a [fragment][`DocumentKind::Fragment`] starting at the
+/// `<article>`, and can write [classes][`Styling::Classes`] for a stylesheet in place of every
+/// `style` attribute. For books written in Japanese or Chinese, it can lay out lines vertically,
+/// see [`WritingMode`], and space full-width characters proportionally.
+///
+/// If [`ExportOptions`] is set to [render][`MetadataFormatCodes::Render`] format codes in metadata,
+/// the title is written as a formatted `<h1 id="{slug}">{title}</h1>` just before the `<article>`.
+///
 /// Inside of the contents:
 ///
 /// - Plain text is written as HTML entities where applicable
 ///     - Ex. `'&'` -> `"&amp;"`
+///     - Characters outside of the Basic Multilingual Plane, like most emoji, are written as
+///       numeric character references, ex. `'\u{1F600}'` -> `"&#128512;"`
+///     - [`ExportOptions`] can instead write a few harmless tags as HTML, like `<sub>`, for
+///       trusted works, see [`ExportOptions::with_passthrough_html`]
 /// - Spaces are written as just plain spaces: `' '` (without the `'`)
 ///     - `<article>` having the style `white-space:break-spaces` (mostly) preserves the spaces
 ///       without the need for `&nbsp;`
 /// - Line breaks and paragraph breaks are represented by `<br />`
+///     - [`ExportOptions`] can style the first letter and first line of each page, see
+///       [`PageOpening`]
 /// - Thematic breaks are represented by `<hr />`
+///     - [`ExportOptions`] can instead wrap each page in a
+///       `<section class="page" data-page="{number}">`, see [`PageBreaks::Sections`], with
+///       classes derived from its contents, see [`ExportOptions::with_page_classes`]
+///     - [`ExportOptions`] can write [`BlankPages`] as an empty `<section class="page blank">`, or
+///       leave them out
+/// - Images are represented by `<img src="{src}" alt="{alt}" />`
 /// - Colored text is represented as `<span style='color:{color}'>`
 ///     - Where `color` is a hexademical representation of the color, ex. `#FFFFFF` for pure white
+///     - [`ExportOptions`] can replace the hexadecimal value of each color with a [`Palette`]
 /// - Obfuscated text is represented as `<code>`
 /// - Bold text is represented as `<b>`
 /// - Strikethrough text is represented as `<s>`
 /// - Underline text is represented as `<u>`
 /// - Italic text is represented as `<i>`
+/// - [Roles][`crate::syntax::role::Role`] are represented by their element, like `<h2>` for a
+///   [`Role::Heading2`][`crate::syntax::role::Role::Heading2`] or `<q>` for a
+///   [`Role::Quote`][`crate::syntax::role::Role::Quote`]
+///     - See [`ColorRoles`][`crate::syntax::role::ColorRoles`] to write colors that stand for a
+///       role as one
+/// - [Unknown format codes][`crate::syntax::minecraft::Format::Unknown`] are dropped
+/// - Resets close every element opened since the last reset, and anything left open at the end of
+///   the work is closed before the `</article>`
+///
+/// If [`ExportOptions`] has a [`Legend`], it is written after the `</article>`, with a `<dt>` and
+/// `<dd>` for each [`Format`][`crate::syntax::minecraft::Format`] in the work:
+///
+/// ```html
+///     <aside><h2>Legend</h2><dl>
+///         <dt><span style='color:{color}'>{name}</span></dt><dd>{color}: {annotation}</dd>
+///         <dt><b>bold</b></dt><dd>{annotation}</dd>
+///     </dl></aside>
+/// ```
 ///
 /// And finally, the contents are closed:
 ///
@@ -131,12 +185,18 @@ mod token_handling;
 /// ```
 pub struct Html {}
 
-impl Export for Html {
-    /// Parse a given abstract syntax vector into HTML, then output that as a string.
-    fn export_token_vector_to_string(tokens: TokenList) -> Box<str> {
+impl Html {
+    /// Parse a given abstract syntax vector into HTML with the given options, then output that as
+    /// a string.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_token_vector_to_string_with_options(
+        tokens: &TokenList,
+        options: &ExportOptions,
+    ) -> Box<str> {
         let mut bytes: Vec<u8> = vec![];
 
-        Self::export_token_vector_to_writer(tokens, &mut bytes)
+        Self::export_token_vector_to_writer_with_options(tokens, &mut bytes, options)
             // https://github.com/rust-lang/rust/blob/1.80.1/library/std/src/io/impls.rs#L433-L437
             // https://github.com/rust-lang/rust/blob/1.80.1/library/alloc/src/vec/mod.rs#L2569-L2592
             .expect(
@@ -148,52 +208,397 @@ impl Export for Html {
             .into_boxed_str()
     }
 
-    /// Parse a given abstract syntax vector into HTML, then output that into a writer, like a
-    /// [`std::fs::File`].
+    /// Parse a given abstract syntax vector into HTML with the given options, then output that
+    /// into a writer, like a [`std::fs::File`].
     ///
     /// Guaranteed to only write valid UTF-8.
     ///
     /// # Errors
     ///
     /// - [`std::io::Error`] if it cannot write into `output`
-    fn export_token_vector_to_writer(
-        tokens: TokenList,
+    pub fn export_token_vector_to_writer_with_options(
+        tokens: &TokenList,
         output: &mut impl Write,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        Self::export_with_stack(tokens, output, options, &mut vec![])
+    }
+
+    /// Export each of `pages` into its own writer, as returned by `writer_for`, with the given
+    /// options.
+    ///
+    /// Unlike exporting each page separately, the same allocations are reused for every page.
+    ///
+    /// See [`Export::export_pages_to_writers`].
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into one of the writers
+    pub fn export_pages_to_writers_with_options<W: Write>(
+        pages: impl IntoIterator<Item = TokenList>,
+        mut writer_for: impl FnMut(usize) -> W,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        let mut format_token_stack: Vec<Element> = vec![];
+
+        for (index, page) in pages.into_iter().enumerate() {
+            let mut output = writer_for(index);
+            Self::export_with_stack(&page, &mut output, options, &mut format_token_stack)?;
+            output.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Export only the page at `index` as a fragment of HTML with the given options, to be inserted
+    /// into a page that is already open, like when a web reader lazy-loads each page of a long
+    /// work.
+    ///
+    /// The fragment is a single `<section class="page" id="page-{number}">`, numbered from one,
+    /// starting with the formatting carried over from the pages before it and closing all of its
+    /// own, so fragments can be streamed in any order and still nest correctly. Pages without any
+    /// text are also given the `blank` class, and [`ExportOptions::with_page_classes`] adds the
+    /// classes derived from its contents.
+    ///
+    /// There is no `<head>`, no [`Legend`], and [annotations][`ExportOptions::with_annotations`]
+    /// are not written, as they refer to positions in the whole work. See [`TokenList::page`].
+    ///
+    /// # Errors
+    ///
+    /// - [`PageIndexError`] if `index` is past the last page
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::{
+    ///     export::{Html, HtmlExportOptions},
+    ///     syntax::{minecraft::Format, Token, TokenList},
+    /// };
+    ///
+    /// let mut tokens = TokenList::new_from_boxed(Box::new([]), Box::new([]));
+    /// tokens.push_page([Token::Format(Format::Italic), Token::Text("one".into())]);
+    /// tokens.push_page([Token::Text("two".into())]);
+    ///
+    /// assert_eq!(
+    ///     Html::export_page_fragment(&tokens, 1, &HtmlExportOptions::new())?.as_ref(),
+    ///     r#"<section class="page" id="page-2" style=white-space:break-spaces><i>two</i></section>"#
+    /// );
+    /// # Ok::<(), crafty_novels::syntax::edit::PageIndexError>(())
+    /// ```
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_page_fragment(
+        tokens: &TokenList,
+        index: usize,
+        options: &ExportOptions,
+    ) -> Result<Box<str>, PageIndexError> {
+        let page = tokens.page(index)?;
+        let mut bytes: Vec<u8> = vec![];
+
+        Self::write_page_fragment(page.tokens_as_slice(), index + 1, &mut bytes, options)
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/std/src/io/impls.rs#L433-L437
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/alloc/src/vec/mod.rs#L2569-L2592
+            .expect(
+                "the `std::io::Write` implementations for `Vec<u8>` are infallible (as of 1.80.1)",
+            );
+
+        Ok(String::from_utf8(bytes)
+            .expect("`Utf8Writer` only writes UTF-8 encoded types")
+            .into_boxed_str())
+    }
+
+    /// Write the tokens of a single page as a fragment of HTML into `output`.
+    ///
+    /// See [`Self::export_page_fragment`].
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn write_page_fragment(
+        page: &[Token],
+        number: usize,
+        output: &mut impl Write,
+        options: &ExportOptions,
     ) -> std::io::Result<()> {
         let mut writer = Utf8Writer::new(output);
+        let mut format_token_stack: Vec<Element> = vec![];
+
+        let page = page.strip_prefix(&[Token::ThematicBreak]).unwrap_or(page);
+        let class =
+            token_handling::section_class(page, token_handling::is_blank_page(page), options);
+
+        write!(writer, r#"<section class="{class}" id="page-{number}""#)?;
+        writer.write_str(match options.styling() {
+            Styling::Inline => " style=white-space:break-spaces>",
+            Styling::Classes => ">",
+        })?;
+        let mut opening = token_handling::Opening::new(options);
+        for token in page {
+            if !opening.handle_token(&mut writer, token, options)? {
+                token_handling::handle_token(&mut writer, &mut format_token_stack, token, options)
+                    .map_err(unwrap_io_error)?;
+            }
+        }
+        token_handling::close_formatting_tags(&mut writer, &mut format_token_stack)
+            .map_err(unwrap_io_error)?;
+        writer.write_str("</section>")?;
 
-        token_handling::start_document(&mut writer, tokens.metadata_as_slice())?;
+        writer.flush()?;
+        Ok(())
+    }
 
-        // Most readable
-        writer.write_str("<body><article style=white-space:break-spaces>")?;
+    /// Write an index page for `library` as HTML, then output that as a string.
+    ///
+    /// See [`Self::export_library_to_writer`].
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_library_to_string(library: &Library) -> Box<str> {
+        let mut bytes: Vec<u8> = vec![];
+
+        Self::export_library_to_writer(library, &mut bytes)
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/std/src/io/impls.rs#L433-L437
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/alloc/src/vec/mod.rs#L2569-L2592
+            .expect(
+                "the `std::io::Write` implementations for `Vec<u8>` are infallible (as of 1.80.1)",
+            );
+
+        String::from_utf8(bytes)
+            .expect("`Utf8Writer` only writes UTF-8 encoded types")
+            .into_boxed_str()
+    }
+
+    /// Write an index page for `library` as HTML into a writer, like a [`std::fs::File`].
+    ///
+    /// Each author gets a heading and a list of their works, which link to `{hash}.html`, named
+    /// after their [`TokenList::content_hash`], alongside their page counts. Exporting each work
+    /// to a file of that name in the same directory makes a browsable library.
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    pub fn export_library_to_writer(
+        library: &Library,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        let mut writer = Utf8Writer::new(output);
+
+        token_handling::start_document(
+            &mut writer,
+            &[Metadata::Title("Library".into())],
+            &ExportOptions::default(),
+        )?;
+        writer.write_str("<body><h1>Library</h1>")?;
+        token_handling::library_index(&mut writer, library)?;
+        writer.write_str("</body></html>")?;
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Parse a given abstract syntax vector into HTML with the given options, using
+    /// `format_token_stack` to track the open formatting elements.
+    ///
+    /// `format_token_stack` is always left empty, so it can be reused for the next work.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_with_stack(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &ExportOptions,
+        format_token_stack: &mut Vec<Element>,
+    ) -> std::io::Result<()> {
+        let mut writer = Utf8Writer::new(output);
+        // Every anchor in the document, so that none of them collide
+        let mut ids = Ids::new();
+
+        let standalone = options.document_kind() == DocumentKind::Standalone;
+        token_handling::start_article(&mut writer, tokens.metadata_as_slice(), options, &mut ids)
+            .map_err(unwrap_io_error)?;
 
         // Most accurate
         // Does, however, still consume spaces that break, which Minecraft books do not
         // writer.write_str("<article style=line-break:anywhere>");
 
-        let mut format_token_stack: Vec<Format> = vec![];
-        for token in tokens.tokens_as_slice() {
-            token_handling::handle_token(&mut writer, &mut format_token_stack, token).map_err(
-                |e| match e {
-                    error::ExportError::Io(e) => e,
-                    _ => {
-                        // [`token_handling::handle_token`] states that it could return
-                        // [`Error::UnexpectedToken`], but that it will never cause the necessary
-                        // state to occur on its own.
-                        //
-                        // Because nothing else every mutates `format_token_stack`, this state will
-                        // never occur, and this particular error can be ignored.
-                        unreachable!(
-                            "`token_handling::handle_token` cannot create this error on its own"
-                        )
-                    }
-                },
+        // Whether or not the current page is blank and `options` handles blank pages differently
+        let mut in_blank_page = false;
+        // With `PageBreaks::Sections`, the number of the current page, and whether a `<section>` has
+        // been opened yet
+        let sections = options.page_breaks() == PageBreaks::Sections;
+        let mut page = 0;
+        let mut in_section = false;
+        // The annotations that have yet to be opened, and those that are open, innermost last
+        let mut pending_annotations = options.annotations().iter().peekable();
+        let mut open_annotations = vec![];
+        let mut aligned_line = token_handling::AlignedLine::default();
+        let mut opening = token_handling::Opening::new(options);
+        let mut cursor = tokens.cursor();
+        while let Some(token) = cursor.next() {
+            token_handling::annotate(
+                &mut writer,
+                cursor.position() - 1,
+                &mut pending_annotations,
+                &mut open_annotations,
+                options.annotation_style(),
+                &mut ids,
             )?;
+
+            if aligned_line
+                .handle_token(
+                    &mut writer,
+                    token,
+                    format_token_stack,
+                    &open_annotations,
+                    options,
+                )
+                .map_err(unwrap_io_error)?
+            {
+                continue;
+            }
+
+            // Content before the first page break is its own page, like in `TokenList::pages_iter`
+            if sections && (*token == Token::ThematicBreak || page == 0) {
+                page += 1;
+                in_blank_page = token_handling::turn_page(
+                    &mut writer,
+                    page,
+                    &tokens.tokens_as_slice()[cursor.position() - 1..],
+                    &mut in_section,
+                    format_token_stack,
+                    &open_annotations,
+                    options,
+                )
+                .map_err(unwrap_io_error)?;
+            }
+
+            if *token == Token::ThematicBreak {
+                opening.start_page(options);
+                if sections {
+                    continue;
+                }
+            } else if opening.handle_token(&mut writer, token, options)? {
+                continue;
+            }
+
+            match token {
+                Token::ThematicBreak if options.blank_pages() != BlankPages::Collapse => {
+                    in_blank_page = token_handling::is_blank_page(cursor.remaining());
+
+                    if in_blank_page {
+                        token_handling::blank_page(&mut writer, options)?;
+                        continue;
+                    }
+                }
+                // Still track formatting, which carries over into the next page
+                Token::Format(_) => (),
+                _ if in_blank_page => continue,
+                _ => (),
+            }
+
+            token_handling::handle_token(&mut writer, format_token_stack, token, options)
+                .map_err(unwrap_io_error)?;
+        }
+        token_handling::annotate(
+            &mut writer,
+            usize::MAX,
+            &mut pending_annotations,
+            &mut open_annotations,
+            options.annotation_style(),
+            &mut ids,
+        )?;
+        aligned_line
+            .finish(&mut writer, format_token_stack, &open_annotations, options)
+            .map_err(unwrap_io_error)?;
+        // Importers aren't required to reset the formatting at the end of the work
+        token_handling::close_formatting_tags(&mut writer, format_token_stack)
+            .map_err(unwrap_io_error)?;
+        if in_section {
+            writer.write_str("</section>")?;
         }
 
-        writer.write_str("</article></body></html>")?;
+        writer.write_str("</article>")?;
+
+        if let Some(legend) = options.legend() {
+            token_handling::legend(&mut writer, tokens.tokens_as_slice(), legend, options)
+                .map_err(unwrap_io_error)?;
+        }
+
+        if standalone {
+            writer.write_str("</body></html>")?;
+        }
 
         writer.flush()?;
         Ok(())
     }
 }
+
+impl ExportWithOptions for Html {
+    type Options = ExportOptions;
+
+    /// See [`Html::export_token_vector_to_writer_with_options`].
+    fn export_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &Self::Options,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(tokens, output, options)
+    }
+}
+
+impl Export for Html {
+    fn capabilities() -> Capabilities {
+        Capabilities::COLOR
+            | Capabilities::OBFUSCATION
+            | Capabilities::PAGINATION
+            | Capabilities::IMAGES
+            | Capabilities::ROLES
+    }
+
+    /// Parse a given abstract syntax vector into HTML, then output that as a string.
+    fn export_token_vector_to_string(tokens: TokenList) -> Box<str> {
+        Self::export_token_vector_to_string_with_options(&tokens, &ExportOptions::default())
+    }
+
+    /// Parse a given abstract syntax vector into HTML, then output that into a writer, like a
+    /// [`std::fs::File`].
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_token_vector_to_writer(
+        tokens: TokenList,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(&tokens, output, &ExportOptions::default())
+    }
+
+    /// Export each of `pages` into its own writer, as returned by `writer_for`.
+    ///
+    /// See [`Html::export_pages_to_writers_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into one of the writers
+    fn export_pages_to_writers<W: Write>(
+        pages: impl IntoIterator<Item = TokenList>,
+        writer_for: impl FnMut(usize) -> W,
+    ) -> std::io::Result<()> {
+        Self::export_pages_to_writers_with_options(pages, writer_for, &ExportOptions::default())
+    }
+}
+
+/// Extract the [`std::io::Error`] from an [`error::ExportError`] returned by [`token_handling`].
+fn unwrap_io_error(error: error::ExportError) -> std::io::Error {
+    match error {
+        error::ExportError::Io(e) => e,
+        _ => {
+            // [`token_handling`] only ever writes, and never stores [`Format::Reset`] on the
+            // format token stack, so I/O is the only thing that can fail.
+            unreachable!("`token_handling` can only return I/O errors")
+        }
+    }
+}
@@ -0,0 +1,770 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration for [`super::Html`].
+//!
+//! See [`ExportOptions`].
+
+use super::registry::Registry;
+pub use crate::syntax::direction::TextDirection;
+use crate::syntax::{
+    minecraft::{Color, ColorLayer, ColorValue, Format, Rgb},
+    Annotations, Metadata, TokenList,
+};
+use std::sync::Arc;
+
+/// The [`Palette`]s registered with [`Palette::register`].
+static PALETTES: Registry<Palette> = Registry::new();
+/// The [`ExportOptions`] registered with [`ExportOptions::register`].
+static THEMES: Registry<ExportOptions> = Registry::new();
+
+/// How to handle format codes inside of [metadata][`crate::syntax::Metadata`] values, like
+/// `"§6Golden §lChronicle"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MetadataFormatCodes {
+    /// Remove the format codes, writing only the plain text.
+    #[default]
+    Strip,
+    /// Remove the format codes from the `<head>`, but also write the title as a formatted `<h1>`
+    /// heading at the start of the `<body>`.
+    Render,
+}
+
+/// How to write pages without any text, which books often contain on purpose.
+///
+/// A page is blank if there is no [`Token::Text`][`crate::syntax::Token::Text`] between its
+/// [`Token::ThematicBreak`][`crate::syntax::Token::ThematicBreak`] and the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlankPages {
+    /// Write blank pages like any other page, which collapses them to little more than an
+    /// `<hr />`.
+    #[default]
+    Collapse,
+    /// Write blank pages as an empty `<section class="page blank">` after the `<hr />`, as tall as
+    /// the 14 lines of a Minecraft page.
+    ///
+    /// With [`PageBreaks::Sections`], the section of the blank page itself is given the class.
+    Render,
+    /// Leave blank pages out entirely.
+    Skip,
+}
+
+/// How to write the breaks between pages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PageBreaks {
+    /// Write each [`Token::ThematicBreak`][`crate::syntax::Token::ThematicBreak`] as an `<hr />`,
+    /// leaving the pages as one continuous run of text.
+    #[default]
+    Rule,
+    /// Wrap each page in its own `<section class="page" data-page="{number}">`, numbered from one
+    /// like in Minecraft, for a stylesheet to render as separate pages.
+    ///
+    /// Formatting and annotations that carry over into the next page are closed at the end of each
+    /// section and reopened at the start of the next, so that the elements still nest.
+    Sections,
+}
+
+/// How to style the start of each page, for novel-style presentation.
+///
+/// The first line of a page is the text up to the first line break after any text, and its first
+/// letter is the first character of that text. Rather than wrapping the whole line in one element,
+/// each piece of text is wrapped in its own, inside of any formatting elements, so that the
+/// elements always nest.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PageOpening {
+    /// Write the start of each page like the rest of it.
+    #[default]
+    Plain,
+    /// Wrap the first letter of each page in `<span class="drop-cap">`, styled as a large letter
+    /// floated into the text.
+    DropCap,
+    /// Wrap the text of the first line of each page in `<span class="first-line">`, styled in
+    /// small capitals.
+    FirstLine,
+    /// Both [`Self::DropCap`] and [`Self::FirstLine`], with the drop cap inside of the first line.
+    DropCapAndFirstLine,
+}
+
+impl PageOpening {
+    /// Returns whether the first letter of each page is a drop cap.
+    #[must_use]
+    pub const fn drop_cap(self) -> bool {
+        matches!(self, Self::DropCap | Self::DropCapAndFirstLine)
+    }
+
+    /// Returns whether the first line of each page is styled.
+    #[must_use]
+    pub const fn first_line(self) -> bool {
+        matches!(self, Self::FirstLine | Self::DropCapAndFirstLine)
+    }
+}
+
+/// How to write the [`Annotations`] of a work.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AnnotationStyle {
+    /// Highlight each annotated range with `<mark title="{note}">`, showing the note on hover.
+    #[default]
+    Mark,
+    /// Highlight each annotated range with `<mark>`, followed by the note in an
+    /// `<aside class="margin-note" id="note-{number}">` for a stylesheet to float into the margin.
+    MarginNote,
+}
+
+/// The direction that lines of text are laid out in, as the `writing-mode` of the `<article>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WritingMode {
+    /// Horizontal lines, stacked from top to bottom.
+    #[default]
+    Horizontal,
+    /// Vertical lines, stacked from right to left, like Japanese or Chinese books.
+    VerticalRightToLeft,
+    /// Vertical lines, stacked from left to right, like Mongolian.
+    VerticalLeftToRight,
+}
+
+impl WritingMode {
+    /// Returns the value of the CSS `writing-mode` property for this mode.
+    #[must_use]
+    pub const fn css(self) -> &'static str {
+        match self {
+            Self::Horizontal => "horizontal-tb",
+            Self::VerticalRightToLeft => "vertical-rl",
+            Self::VerticalLeftToRight => "vertical-lr",
+        }
+    }
+
+    /// Returns whether lines are vertical.
+    #[must_use]
+    pub const fn is_vertical(self) -> bool {
+        !matches!(self, Self::Horizontal)
+    }
+}
+
+/// How to style the HTML, either inline or through classes for a stylesheet to target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Styling {
+    /// Write `style` attributes, like `<span style='color:#FFAA00'>`, so that the document looks
+    /// right on its own.
+    #[default]
+    Inline,
+    /// Write `class` attributes instead, like `<span class="color-gold">`, and leave every style
+    /// to a stylesheet, like one linked with [`ExportOptions::with_extra_head`].
+    ///
+    /// The contents are `<article class="book">`, colors are `color-{name}` with the names used by
+    /// Minecraft's JSON text components, like `color-dark_blue`, and pages are
+    /// `<section class="page">`, as before.
+    Classes,
+}
+
+/// Whether to write a whole HTML document, or only the part that goes inside of a `<body>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DocumentKind {
+    /// Write a whole document, from `<!DOCTYPE html>` to `</html>`.
+    #[default]
+    Standalone,
+    /// Write only the contents of the `<body>`, for embedding in another page.
+    ///
+    /// The `<article>` carries the language and text direction instead of the `<html>`, and
+    /// metadata is only written if it is [rendered][`MetadataFormatCodes::Render`].
+    Fragment,
+}
+
+/// A key to the colors and decorations used in a work, written at the end of the document.
+///
+/// Lists every [`Format`] that appears in the work, with the hexadecimal value of each color, and
+/// any meaning that the user has annotated it with. Useful for accessibility and print, where
+/// colors can be hard to tell apart.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Html, HtmlExportOptions, Legend},
+///     syntax::{
+///         minecraft::{Color, Format},
+///         Token, TokenList,
+///     },
+/// };
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([Token::Format(Format::Color(Color::Red)), Token::Text("Danger!".into())]),
+/// );
+/// let legend = Legend::new().with_annotation(Format::Color(Color::Red), "danger");
+/// let options = HtmlExportOptions::new().with_legend(Some(legend));
+///
+/// let output = Html::export_token_vector_to_string_with_options(&input, &options);
+/// assert!(output.contains(
+///     "<dt><span style='color:#FF5555'>red</span></dt><dd>#FF5555: danger</dd>"
+/// ));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Legend {
+    /// The meanings that the user has given to each [`Format`].
+    annotations: Vec<(Format, Box<str>)>,
+}
+
+impl Legend {
+    /// Creates a new [`Legend`] without any annotations.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            annotations: vec![],
+        }
+    }
+
+    /// Sets the meaning of a [`Format`], replacing any previous meaning.
+    #[must_use]
+    pub fn with_annotation(mut self, format: Format, annotation: impl Into<Box<str>>) -> Self {
+        self.annotations.retain(|(existing, _)| *existing != format);
+        self.annotations.push((format, annotation.into()));
+        self
+    }
+
+    /// Returns the meaning of a [`Format`], if the user has given it one.
+    #[must_use]
+    pub fn annotation(&self, format: Format) -> Option<&str> {
+        self.annotations
+            .iter()
+            .find(|(existing, _)| *existing == format)
+            .map(|(_, annotation)| annotation.as_ref())
+    }
+}
+
+/// The [`Rgb`] values to write for each [`Color`], in place of the ones used by Minecraft.
+///
+/// Useful for themes where the default colors are hard to read, like black text on a dark
+/// background.
+///
+/// Palettes can be registered by name once, then looked up from anywhere, like request handlers in
+/// a long-running server.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Html, HtmlExportOptions, Palette},
+///     syntax::{
+///         minecraft::{Color, Format, Rgb},
+///         Token, TokenList,
+///     },
+/// };
+///
+/// Palette::register("dark", Palette::new().with(Color::Black, Rgb::new(0xAA, 0xAA, 0xAA)));
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([Token::Format(Format::Color(Color::Black)), Token::Text("Night".into())]),
+/// );
+/// let palette = Palette::named("dark").expect("the palette was just registered");
+/// let options = HtmlExportOptions::new().with_palette((*palette).clone());
+///
+/// let output = Html::export_token_vector_to_string_with_options(&input, &options);
+/// assert!(output.contains("<span style='color:#AAAAAA'>Night"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Palette {
+    /// The colors that differ from Minecraft.
+    overrides: Vec<(Color, Rgb)>,
+}
+
+impl Palette {
+    /// Creates a new [`Palette`] that uses Minecraft's colors.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { overrides: vec![] }
+    }
+
+    /// Sets the [`Rgb`] value to write for a [`Color`], replacing any previous value.
+    #[must_use]
+    pub fn with(mut self, color: Color, rgb: Rgb) -> Self {
+        self.overrides.retain(|(existing, _)| *existing != color);
+        self.overrides.push((color, rgb));
+        self
+    }
+
+    /// Returns the [`Rgb`] value to write for a [`Color`].
+    #[must_use]
+    pub fn rgb(&self, color: Color) -> Rgb {
+        self.overrides
+            .iter()
+            .find(|(existing, _)| *existing == color)
+            .map_or_else(|| ColorValue::from(color).fg(), |(_, rgb)| *rgb)
+    }
+
+    /// Register `palette` as `name` for the rest of the program, replacing any palette previously
+    /// registered as `name`.
+    pub fn register(name: impl Into<Box<str>>, palette: Self) {
+        PALETTES.register(name.into(), palette);
+    }
+
+    /// Returns the palette registered as `name`, if any.
+    #[must_use]
+    pub fn named(name: &str) -> Option<Arc<Self>> {
+        PALETTES.get(name)
+    }
+}
+
+/// Options that control how a work is exported to HTML.
+///
+/// Options can be registered by name as a theme, like `"print"`, once, then looked up from
+/// anywhere with [`Self::named`].
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Html, HtmlExportOptions, MetadataFormatCodes},
+///     syntax::{Metadata, TokenList},
+/// };
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([Metadata::Title("§6Golden".into())]),
+///     Box::new([]),
+/// );
+/// let options = HtmlExportOptions::new().with_metadata_format_codes(MetadataFormatCodes::Render);
+///
+/// let output = Html::export_token_vector_to_string_with_options(&input, &options);
+/// assert!(output.contains("<title>Golden</title>"));
+/// assert!(output.contains(r#"<h1 id="golden"><span style='color:#FFAA00'>Golden</span></h1>"#));
+/// ```
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExportOptions {
+    /// How to handle format codes inside of metadata values.
+    metadata_format_codes: MetadataFormatCodes,
+    /// The key to write at the end of the document, if any.
+    legend: Option<Legend>,
+    /// The values to write for each color.
+    palette: Palette,
+    /// Whether colors are written as the color of the text or of its background.
+    color_layer: ColorLayer,
+    /// How to write pages without any text.
+    blank_pages: BlankPages,
+    /// How to write the breaks between pages.
+    page_breaks: PageBreaks,
+    /// How to style the start of each page.
+    page_opening: PageOpening,
+    /// How deeply formatting elements can be nested before they are coalesced.
+    max_nesting_depth: usize,
+    /// The editorial notes to write alongside the text.
+    annotations: Annotations,
+    /// How to write the annotations.
+    annotation_style: AnnotationStyle,
+    /// The language of the work, or [`None`] for [`ExportOptions::DEFAULT_LANGUAGE`].
+    language: Option<Box<str>>,
+    /// The direction that the text of the work is written in.
+    direction: TextDirection,
+    /// The direction that lines of text are laid out in.
+    writing_mode: WritingMode,
+    /// Whether to space full-width characters proportionally.
+    proportional_spacing: bool,
+    /// Whether to add classes derived from the contents of each page to its `<section>`.
+    page_classes: bool,
+    /// Whether to write the allowed tags in the text of the work as HTML.
+    passthrough_html: bool,
+    /// Raw HTML to write at the end of the `<head>`, if any.
+    extra_head: Option<Box<str>>,
+    /// Whether to style inline or with classes.
+    styling: Styling,
+    /// Whether to write a whole document or a fragment.
+    document_kind: DocumentKind,
+}
+
+impl ExportOptions {
+    /// The default for [`Self::max_nesting_depth`].
+    ///
+    /// Far deeper than any book written by hand, which rarely uses more than a few formats at
+    /// once.
+    pub const DEFAULT_MAX_NESTING_DEPTH: usize = 32;
+
+    /// The tags that [`Self::with_passthrough_html`] allows, which only ever change how text
+    /// looks or reads, never load anything or run scripts.
+    ///
+    /// Importers split text into a [`Token::Text`][`crate::syntax::Token::Text`] for each word,
+    /// and each must open and close its own tags, so these can only mark up a single word, like
+    /// `<em>word</em>` or `H<sub>2</sub>O`. `<em>two words</em>` is escaped, because neither
+    /// word is balanced on its own, and formats or annotations between the words could otherwise
+    /// be closed across the element.
+    pub const PASSTHROUGH_TAGS: &'static [&'static str] = &[
+        "abbr", "b", "br", "cite", "code", "del", "dfn", "em", "i", "ins", "kbd", "mark", "q", "s",
+        "samp", "small", "strong", "sub", "sup", "u", "var", "wbr",
+    ];
+
+    /// The default for [`Self::language`].
+    pub const DEFAULT_LANGUAGE: &'static str = "en";
+
+    /// Creates a new [`ExportOptions`] with the default options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            metadata_format_codes: MetadataFormatCodes::Strip,
+            legend: None,
+            palette: Palette::new(),
+            color_layer: ColorLayer::Foreground,
+            blank_pages: BlankPages::Collapse,
+            page_breaks: PageBreaks::Rule,
+            page_opening: PageOpening::Plain,
+            max_nesting_depth: Self::DEFAULT_MAX_NESTING_DEPTH,
+            annotations: Annotations::new(),
+            annotation_style: AnnotationStyle::Mark,
+            language: None,
+            direction: TextDirection::LeftToRight,
+            writing_mode: WritingMode::Horizontal,
+            proportional_spacing: false,
+            page_classes: false,
+            passthrough_html: false,
+            extra_head: None,
+            styling: Styling::Inline,
+            document_kind: DocumentKind::Standalone,
+        }
+    }
+
+    /// Returns how format codes inside of metadata values are handled.
+    #[must_use]
+    pub const fn metadata_format_codes(&self) -> MetadataFormatCodes {
+        self.metadata_format_codes
+    }
+
+    /// Sets how format codes inside of metadata values are handled.
+    #[must_use]
+    pub const fn with_metadata_format_codes(
+        mut self,
+        metadata_format_codes: MetadataFormatCodes,
+    ) -> Self {
+        self.metadata_format_codes = metadata_format_codes;
+        self
+    }
+
+    /// Returns the key to write at the end of the document, if any.
+    #[must_use]
+    pub const fn legend(&self) -> Option<&Legend> {
+        self.legend.as_ref()
+    }
+
+    /// Sets the key to write at the end of the document, if any.
+    #[must_use]
+    pub fn with_legend(mut self, legend: Option<Legend>) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Returns the values to write for each color.
+    #[must_use]
+    pub const fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Sets the values to write for each color.
+    #[must_use]
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Returns whether colors are written as the color of the text or of its background.
+    #[must_use]
+    pub const fn color_layer(&self) -> ColorLayer {
+        self.color_layer
+    }
+
+    /// Sets whether colors are written as the color of the text or of its background.
+    ///
+    /// With [`ColorLayer::Background`], colored text is written as
+    /// `<span style='background-color:{color}'>`, with the darker background value that Minecraft
+    /// draws as the shadow of the text, or as `<span class="background-{name}">` with
+    /// [`Styling::Classes`]. The [`Palette`] only replaces foreground values.
+    #[must_use]
+    pub const fn with_color_layer(mut self, color_layer: ColorLayer) -> Self {
+        self.color_layer = color_layer;
+        self
+    }
+
+    /// Returns how pages without any text are written.
+    #[must_use]
+    pub const fn blank_pages(&self) -> BlankPages {
+        self.blank_pages
+    }
+
+    /// Sets how pages without any text are written.
+    #[must_use]
+    pub const fn with_blank_pages(mut self, blank_pages: BlankPages) -> Self {
+        self.blank_pages = blank_pages;
+        self
+    }
+
+    /// Returns how the breaks between pages are written.
+    #[must_use]
+    pub const fn page_breaks(&self) -> PageBreaks {
+        self.page_breaks
+    }
+
+    /// Sets how the breaks between pages are written.
+    #[must_use]
+    pub const fn with_page_breaks(mut self, page_breaks: PageBreaks) -> Self {
+        self.page_breaks = page_breaks;
+        self
+    }
+
+    /// Returns how the start of each page is styled.
+    #[must_use]
+    pub const fn page_opening(&self) -> PageOpening {
+        self.page_opening
+    }
+
+    /// Sets how the start of each page is styled.
+    #[must_use]
+    pub const fn with_page_opening(mut self, page_opening: PageOpening) -> Self {
+        self.page_opening = page_opening;
+        self
+    }
+
+    /// Returns how deeply formatting elements can be nested before they are coalesced.
+    #[must_use]
+    pub const fn max_nesting_depth(&self) -> usize {
+        self.max_nesting_depth
+    }
+
+    /// Sets how deeply formatting elements can be nested before they are coalesced.
+    ///
+    /// Once the limit is reached, format codes that don't change the formatting, like a run of
+    /// thousands of `"§l"`, are dropped, and any other format code closes every element and
+    /// reopens only those that are still needed. The nesting can still reach the number of
+    /// formats that can be active at once, six, even if the limit is lower.
+    #[must_use]
+    pub const fn with_max_nesting_depth(mut self, max_nesting_depth: usize) -> Self {
+        self.max_nesting_depth = max_nesting_depth;
+        self
+    }
+
+    /// Returns the editorial notes to write alongside the text.
+    #[must_use]
+    pub const fn annotations(&self) -> &Annotations {
+        &self.annotations
+    }
+
+    /// Sets the editorial notes to write alongside the text.
+    ///
+    /// The indices of each [`Annotation`][`crate::syntax::Annotation`] refer to the tokens of the
+    /// work being exported, so these should be replaced when exporting a different work. Ranges
+    /// that overlap without nesting are extended to nest, and ranges past the end of the work end
+    /// with it.
+    #[must_use]
+    pub fn with_annotations(mut self, annotations: Annotations) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Returns how the annotations are written.
+    #[must_use]
+    pub const fn annotation_style(&self) -> AnnotationStyle {
+        self.annotation_style
+    }
+
+    /// Sets how the annotations are written.
+    #[must_use]
+    pub const fn with_annotation_style(mut self, annotation_style: AnnotationStyle) -> Self {
+        self.annotation_style = annotation_style;
+        self
+    }
+
+    /// Returns the language of the work, as a BCP 47 language tag like `"en"` or `"pt-BR"`.
+    #[must_use]
+    pub fn language(&self) -> &str {
+        self.language.as_deref().unwrap_or(Self::DEFAULT_LANGUAGE)
+    }
+
+    /// Sets the language of the work, as a BCP 47 language tag like `"en"` or `"pt-BR"`.
+    #[must_use]
+    pub fn with_language(mut self, language: impl Into<Box<str>>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Returns the direction that the text of the work is written in.
+    #[must_use]
+    pub const fn direction(&self) -> TextDirection {
+        self.direction
+    }
+
+    /// Sets the direction that the text of the work is written in.
+    #[must_use]
+    pub const fn with_direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the language and direction to those named by the metadata of `tokens`, keeping the
+    /// current ones where it names neither.
+    ///
+    /// The language comes from [`Metadata::language`], and the
+    /// direction from [`TextDirection::for_work`], so an Arabic work is written with
+    /// `lang="ar" dir="rtl"` without setting either by hand.
+    #[must_use]
+    pub fn with_work_language(mut self, tokens: &TokenList) -> Self {
+        if let Some(language) = tokens
+            .metadata_as_slice()
+            .iter()
+            .find_map(Metadata::language)
+        {
+            self.language = Some(language.into());
+        }
+        if let Some(direction) = TextDirection::for_work(tokens) {
+            self.direction = direction;
+        }
+
+        self
+    }
+
+    /// Returns the direction that lines of text are laid out in.
+    #[must_use]
+    pub const fn writing_mode(&self) -> WritingMode {
+        self.writing_mode
+    }
+
+    /// Sets the direction that lines of text are laid out in.
+    ///
+    /// With [`Styling::Classes`], the `<article>` gets a class of the mode's
+    /// [CSS value][`WritingMode::css`], like `vertical-rl`, instead of a style.
+    #[must_use]
+    pub const fn with_writing_mode(mut self, writing_mode: WritingMode) -> Self {
+        self.writing_mode = writing_mode;
+        self
+    }
+
+    /// Returns whether full-width characters are spaced proportionally.
+    #[must_use]
+    pub const fn proportional_spacing(&self) -> bool {
+        self.proportional_spacing
+    }
+
+    /// Sets whether full-width characters, like Japanese punctuation, are spaced proportionally
+    /// rather than each taking up a whole em, through the `palt` font feature, or `vpal` for a
+    /// vertical [`WritingMode`].
+    ///
+    /// Only fonts that have the feature are affected. With [`Styling::Classes`], the `<article>`
+    /// gets the class `proportional` instead of a style.
+    #[must_use]
+    pub const fn with_proportional_spacing(mut self, proportional_spacing: bool) -> Self {
+        self.proportional_spacing = proportional_spacing;
+        self
+    }
+
+    /// Returns whether classes derived from the contents of each page are added to its
+    /// `<section>`.
+    #[must_use]
+    pub const fn page_classes(&self) -> bool {
+        self.page_classes
+    }
+
+    /// Sets whether classes derived from the contents of each page are added to its `<section>`,
+    /// for a stylesheet to style pages by what is on them, with [`PageBreaks::Sections`] or
+    /// [`super::Html::export_page_fragment`]:
+    ///
+    /// - `blank` for a page without any text
+    /// - `has-color-{name}` for each color used on the page, with the names used by Minecraft's
+    ///   JSON text components, like `has-color-dark_red`
+    /// - `mostly-{style}` for each style that more than half of the text on the page is written
+    ///   in, one of `bold`, `italic`, `underline`, `strikethrough`, and `obfuscated`
+    #[must_use]
+    pub const fn with_page_classes(mut self, page_classes: bool) -> Self {
+        self.page_classes = page_classes;
+        self
+    }
+
+    /// Returns whether the allowed tags in the text of the work are written as HTML.
+    #[must_use]
+    pub const fn passthrough_html(&self) -> bool {
+        self.passthrough_html
+    }
+
+    /// Sets whether the allowed tags in the text of the work are written as HTML, for trusted
+    /// works whose authors wrote markup like `H<sub>2</sub>O` on purpose, rather than escaped as
+    /// they are by default.
+    ///
+    /// Only tags out of [`Self::PASSTHROUGH_TAGS`] without attributes are allowed, written in
+    /// lowercase, like `<em>` or `<br />`, and each must be closed in the order it was opened
+    /// inside of the same [`Token::Text`][`crate::syntax::Token::Text`], so that they can't
+    /// break the elements around them. A word with any other tag, or with tags that aren't
+    /// closed, is escaped as a whole.
+    #[must_use]
+    pub const fn with_passthrough_html(mut self, passthrough_html: bool) -> Self {
+        self.passthrough_html = passthrough_html;
+        self
+    }
+
+    /// Returns the raw HTML written at the end of the `<head>`, if any.
+    #[must_use]
+    pub fn extra_head(&self) -> Option<&str> {
+        self.extra_head.as_deref()
+    }
+
+    /// Sets the raw HTML written at the end of the `<head>`, if any, like a
+    /// `<link rel="stylesheet" href="book.css" />`.
+    ///
+    /// It is written exactly as given, without escaping, so it should never come from an untrusted
+    /// source. It is not written for a [`DocumentKind::Fragment`], which has no `<head>`.
+    #[must_use]
+    pub fn with_extra_head(mut self, extra_head: Option<Box<str>>) -> Self {
+        self.extra_head = extra_head;
+        self
+    }
+
+    /// Returns whether the HTML is styled inline or with classes.
+    #[must_use]
+    pub const fn styling(&self) -> Styling {
+        self.styling
+    }
+
+    /// Sets whether the HTML is styled inline or with classes.
+    #[must_use]
+    pub const fn with_styling(mut self, styling: Styling) -> Self {
+        self.styling = styling;
+        self
+    }
+
+    /// Returns whether a whole document or a fragment is written.
+    #[must_use]
+    pub const fn document_kind(&self) -> DocumentKind {
+        self.document_kind
+    }
+
+    /// Sets whether a whole document or a fragment is written.
+    #[must_use]
+    pub const fn with_document_kind(mut self, document_kind: DocumentKind) -> Self {
+        self.document_kind = document_kind;
+        self
+    }
+
+    /// Register `options` as the theme `name` for the rest of the program, replacing any theme
+    /// previously registered as `name`.
+    pub fn register(name: impl Into<Box<str>>, options: Self) {
+        THEMES.register(name.into(), options);
+    }
+
+    /// Returns the theme registered as `name`, if any.
+    #[must_use]
+    pub fn named(name: &str) -> Option<Arc<Self>> {
+        THEMES.get(name)
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parsing for the raw HTML written by [`ExportOptions::with_passthrough_html`].
+//!
+//! See [`split`].
+
+use super::options::ExportOptions;
+
+/// The tags out of [`ExportOptions::PASSTHROUGH_TAGS`] that have no contents or closing tag.
+const VOID_TAGS: &[&str] = &["br", "wbr"];
+
+/// A part of a [`Token::Text`][`crate::syntax::Token::Text`], split by [`split`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Piece<'a> {
+    /// Text to be escaped.
+    Text(&'a str),
+    /// An allowed tag that opens an element, like `<em>`, with its name in lowercase.
+    Open(&'static str),
+    /// An allowed tag that closes an element, like `</em>`, with its name in lowercase.
+    Close(&'static str),
+    /// An allowed void tag, like `<br>` or `<br/>`, with its name in lowercase.
+    Void(&'static str),
+}
+
+/// Split `input` into text and the tags in it, or [`None`] if any tag is not one of
+/// [`ExportOptions::PASSTHROUGH_TAGS`], has attributes, or isn't closed in the right order
+/// inside of `input`.
+///
+/// A `'<'` that isn't followed by a letter, or a `'/'` and a letter, is left as text, like in
+/// `"a<3"`.
+pub fn split(input: &str) -> Option<Vec<Piece<'_>>> {
+    let mut pieces = vec![];
+    let mut open: Vec<&str> = vec![];
+    let mut rest = input;
+
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let closing = after.starts_with('/');
+        let name_start = usize::from(closing);
+        if !after[name_start..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            pieces.push(Piece::Text(&rest[..=start]));
+            rest = after;
+            continue;
+        }
+
+        if start > 0 {
+            pieces.push(Piece::Text(&rest[..start]));
+        }
+
+        let name_end = after[name_start..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .map_or(after.len(), |end| name_start + end);
+        let name = allowed_tag(&after[name_start..name_end])?;
+        let void = VOID_TAGS.contains(&name);
+        let (end, self_closing) = if after[name_end..].starts_with("/>") {
+            (name_end + 2, true)
+        } else if after[name_end..].starts_with('>') {
+            (name_end + 1, false)
+        } else {
+            return None;
+        };
+
+        pieces.push(match (closing, void) {
+            (false, true) => Piece::Void(name),
+            (false, false) if !self_closing => {
+                open.push(name);
+                Piece::Open(name)
+            }
+            (true, false) if !self_closing && open.pop() == Some(name) => Piece::Close(name),
+            _ => return None,
+        });
+        rest = &after[end..];
+    }
+
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest));
+    }
+
+    open.is_empty().then_some(pieces)
+}
+
+/// Returns the name out of [`ExportOptions::PASSTHROUGH_TAGS`] that matches `name`, ignoring
+/// case.
+fn allowed_tag(name: &str) -> Option<&'static str> {
+    ExportOptions::PASSTHROUGH_TAGS
+        .iter()
+        .copied()
+        .find(|tag| tag.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Named values that are shared across the whole program.
+//!
+//! See [`Registry`].
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+/// A thread-safe map from names to values, intended to be used as a `static`.
+///
+/// Values are registered once, then read from anywhere, like request handlers in a long-running
+/// server.
+pub struct Registry<T> {
+    /// The registered values, created on first use.
+    values: OnceLock<RwLock<HashMap<Box<str>, Arc<T>>>>,
+}
+
+impl<T> Registry<T> {
+    /// Creates a new, empty [`Registry`].
+    pub const fn new() -> Self {
+        Self {
+            values: OnceLock::new(),
+        }
+    }
+
+    /// Register `value` as `name`, replacing any value previously registered as `name`.
+    pub fn register(&self, name: Box<str>, value: T) {
+        self.values()
+            .write()
+            // A panic while holding the lock can't leave the map half-updated
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name, Arc::new(value));
+    }
+
+    /// Returns the value registered as `name`, if any.
+    pub fn get(&self, name: &str) -> Option<Arc<T>> {
+        self.values()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned()
+    }
+
+    /// Returns the registered values, creating the map if it doesn't exist yet.
+    fn values(&self) -> &RwLock<HashMap<Box<str>, Arc<T>>> {
+        self.values.get_or_init(RwLock::default)
+    }
+}
@@ -17,7 +17,8 @@
 
 //! Syntax definitions for the [HTML][`super::Html`] format.
 //!
-//! Responsible for [`HtmlEntity`], [`HtmlEntityValue`], and the accompanying conversions.
+//! Responsible for [`HtmlEntity`], [`HtmlEntityValue`], [`NumericCharacterReference`], and the
+//! accompanying conversions.
 
 #![allow(clippy::too_many_lines)]
 #![warn(clippy::non_ascii_literal)]
@@ -295,7 +296,7 @@ pub struct HtmlEntityValue {
     // The Unicode code point for the character.
     //
     // Represented in HTML as `"&#NUMBER;"`.
-    number: u16,
+    number: u32,
     /// The textual code name for the character.
     ///
     /// Represented in HTML as `"&NAME;"`.
@@ -303,7 +304,7 @@ pub struct HtmlEntityValue {
 }
 
 impl HtmlEntityValue {
-    pub fn new(literal: char, number: u16, name: Box<str>) -> Self {
+    pub const fn new(literal: char, number: u32, name: Box<str>) -> Self {
         Self {
             literal,
             number,
@@ -318,6 +319,34 @@ impl Display for HtmlEntityValue {
     }
 }
 
+/// A character written as its Unicode code point, like `"&#128512;"`.
+///
+/// Every character can be represented this way, but it is only used for characters outside of the
+/// Basic Multilingual Plane (like most emoji), which do not have an [`HtmlEntity`] and are
+/// inconsistently handled by tools that assume every character fits in a single UTF-16 code unit.
+pub struct NumericCharacterReference(char);
+
+impl NumericCharacterReference {
+    /// The highest code point in the Basic Multilingual Plane.
+    const MAX_BMP: u32 = 0xFFFF;
+
+    /// Returns a [`NumericCharacterReference`] if `literal` is outside of the Basic Multilingual
+    /// Plane.
+    pub const fn astral(literal: char) -> Option<Self> {
+        if literal as u32 > Self::MAX_BMP {
+            Some(Self(literal))
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for NumericCharacterReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "&#{};", u32::from(self.0))
+    }
+}
+
 impl From<HtmlEntity> for HtmlEntityValue {
     /// Match the input [`HtmlEntity`] to a hardcoded [`HtmlEntityValue`].
     fn from(value: HtmlEntity) -> Self {
@@ -126,6 +126,29 @@ fn html_string() {
                 "<title>test title</title>",
                 r#"<meta name="author" content="test author" />"#
             ), "body";
+        [
+            title!("§6Golden §lA & B"),
+            author!("\"§oquoted\""),
+        ], [
+            text!("body"),
+        ] =>
+            concat!(
+                "<title>Golden A &amp; B</title>",
+                r#"<meta name="author" content="&quot;quoted&quot;" />"#
+            ), "body";
+        [
+            crate::syntax::Metadata::Date("2024-05-01".into()),
+            crate::syntax::Metadata::Description("A §lbook".into()),
+            crate::syntax::Metadata::Language("de".into()),
+            crate::syntax::Metadata::Generator("Stendhal".into()),
+        ], [
+            text!("body"),
+        ] =>
+            concat!(
+                r#"<meta name="date" content="2024-05-01" />"#,
+                r#"<meta name="description" content="A book" />"#,
+                r#"<meta name="generator" content="Stendhal" />"#
+            ), "body";
     );
     test!(
         [
@@ -188,5 +211,843 @@ fn html_string() {
             text!("&"), Space,
             text!("&amp;</div>"), LineBreak,
         ] => "&lt;div&gt;HTML &amp;gt; &amp; &amp;amp;&lt;/div&gt;<br />";
+        [
+            text!("Emoji:"), Space,
+            text!("\u{1F600}"), Space,
+            text!("\u{2764}\u{FE0F}"), Space,
+            text!("\u{1F468}\u{200D}\u{1F469}"), LineBreak,
+        ] => "Emoji: &#128512; \u{2764}\u{FE0F} &#128104;&zwj;&#128105;<br />";
+        [
+            text!("Modded"), Space,
+            crate::syntax::Token::Format(crate::syntax::minecraft::Format::Unknown('z')),
+            text!("code"),
+            format!(Reset), LineBreak,
+        ] => "Modded code<br />";
+    );
+}
+
+#[test]
+fn html_render_metadata_format_codes() {
+    use super::{ExportOptions, MetadataFormatCodes};
+
+    let token_list = TokenList::new(
+        Arc::new([title!("§6Golden §lChronicle")]),
+        Arc::new([text!("body")]),
+    );
+    let options = ExportOptions::new().with_metadata_format_codes(MetadataFormatCodes::Render);
+
+    let expects = concat!(
+        r#"<!DOCTYPE html><html lang="en" dir="ltr"><head><meta charset="utf-8" />"#,
+        "<title>Golden Chronicle</title>",
+        r#"<meta name="viewport" content="width=device-width, initial-scale=1.0" />"#,
+        "</head><body>",
+        r#"<h1 id="golden-chronicle"><span style='color:#FFAA00'>Golden <b>Chronicle</b></span>"#,
+        "</h1>",
+        "<article style=white-space:break-spaces>body</article></body></html>",
+    );
+
+    assert_eq!(
+        Html::export_token_vector_to_string_with_options(&token_list, &options).as_ref(),
+        expects
+    );
+}
+
+#[test]
+fn html_legend() {
+    use super::{ExportOptions, Legend};
+    use crate::syntax::minecraft::{Color, Format};
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            color!(Gold),
+            text!("heading"),
+            format!(Bold),
+            text!("loud"),
+            format!(Reset),
+            color!(Red),
+            format!(Italic),
+            text!("danger"),
+            format!(Reset),
+            color!(Gold),
+        ]),
+    );
+    let legend = Legend::new()
+        .with_annotation(Format::Color(Color::Red), "danger & peril")
+        .with_annotation(Format::Bold, "shouting");
+    let options = ExportOptions::new().with_legend(Some(legend));
+
+    let output = Html::export_token_vector_to_string_with_options(&token_list, &options);
+
+    assert!(output.ends_with(concat!(
+        "</article><aside><h2>Legend</h2><dl>",
+        "<dt><span style='color:#FFAA00'>gold</span></dt><dd>#FFAA00</dd>",
+        "<dt><span style='color:#FF5555'>red</span></dt><dd>#FF5555: danger &amp; peril</dd>",
+        "<dt><b>bold</b></dt><dd>shouting</dd>",
+        "<dt><i>italic</i></dt>",
+        "</dl></aside></body></html>",
+    )));
+}
+
+#[test]
+fn html_roles() {
+    use crate::syntax::{
+        minecraft::Color,
+        role::{ColorRoles, Role},
+    };
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            color!(Gold),
+            text!("Chapter"),
+            format!(Reset),
+            crate::syntax::Token::LineBreak,
+            color!(Gray),
+            format!(Bold),
+            text!("aside"),
+            format!(Reset),
+            color!(Red),
+            text!("red"),
+            format!(Reset),
+        ]),
+    );
+    let roles = ColorRoles::new()
+        .with(Color::Gold, Role::Heading2)
+        .with(Color::Gray, Role::Small);
+
+    let output = Html::export_token_vector_to_string(roles.apply(&token_list));
+
+    assert!(output.contains(concat!(
+        "<h2>Chapter</h2><br />",
+        "<small><b>aside</b></small>",
+        "<span style='color:#FF5555'>red</span>",
+    )));
+}
+
+#[test]
+fn html_export_pages() -> std::io::Result<()> {
+    let token_list = TokenList::new(
+        Arc::new([title!("crafty_novels")]),
+        Arc::new([
+            Token::ThematicBreak,
+            text!("one"),
+            Token::ThematicBreak,
+            text!("two"),
+        ]),
+    );
+    let mut outputs: Vec<Vec<u8>> = vec![vec![]; 2];
+    let mut indices = vec![];
+
+    let mut remaining = outputs.iter_mut();
+    Html::export_pages_to_writers(token_list.pages_iter(), |index| {
+        indices.push(index);
+        remaining.next().expect("there should only be two pages")
+    })?;
+
+    assert_eq!(indices, [0, 1]);
+    for (output, text) in outputs.iter().zip(["one", "two"]) {
+        let output = std::str::from_utf8(output).expect("HTML should be UTF-8");
+        assert!(output.contains("<title>crafty_novels</title>"));
+        assert!(output.ends_with(&std::format!(
+            "<article style=white-space:break-spaces>{text}</article></body></html>"
+        )));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn html_registered_themes() {
+    use super::{ExportOptions, Legend, Palette};
+    use crate::syntax::minecraft::{Color, Rgb};
+
+    let palette = Palette::new()
+        .with(Color::Black, Rgb::new(0xAA, 0xAA, 0xAA))
+        .with(Color::Black, Rgb::new(0xEE, 0xEE, 0xEE));
+    assert_eq!(palette.rgb(Color::Black), Rgb::new(0xEE, 0xEE, 0xEE));
+    assert_eq!(palette.rgb(Color::Gold), Rgb::new(0xFF, 0xAA, 0x00));
+
+    ExportOptions::register(
+        "test-dark",
+        ExportOptions::new()
+            .with_palette(palette)
+            .with_legend(Some(Legend::new())),
+    );
+    assert_eq!(ExportOptions::named("test-missing"), None);
+    let options = ExportOptions::named("test-dark").expect("the theme was just registered");
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([color!(Black), text!("night"), format!(Reset)]),
+    );
+    let output = Html::export_token_vector_to_string_with_options(&token_list, &options);
+
+    assert!(output.contains("<span style='color:#EEEEEE'>night</span>"));
+    assert!(output.contains("<dd>#EEEEEE</dd>"));
+}
+
+#[test]
+fn html_adversarial_formatting() {
+    use crate::syntax::minecraft::Format;
+
+    /// Export `tokens` and return only the contents of the `<article>`.
+    fn article(tokens: Vec<Token>) -> String {
+        let html = Html::export_token_vector_to_string(TokenList::new(Arc::new([]), tokens.into()));
+        let start = html
+            .find("<article style=white-space:break-spaces>")
+            .unwrap()
+            + "<article style=white-space:break-spaces>".len();
+        let end = html.find("</article>").unwrap();
+
+        html[start..end].to_owned()
+    }
+
+    // Leading and repeated resets
+    assert_eq!(
+        article(vec![
+            format!(Reset),
+            format!(Reset),
+            text!("plain"),
+            format!(Bold),
+            format!(Reset),
+            format!(Reset),
+            text!("again"),
+        ]),
+        "plain<b></b>again"
+    );
+
+    // Unknown codes are dropped, and are never closed
+    assert_eq!(
+        article(vec![
+            Token::Format(Format::Unknown('z')),
+            format!(Italic),
+            Token::Format(Format::Unknown('y')),
+            text!("text"),
+            format!(Reset),
+        ]),
+        "<i>text</i>"
+    );
+
+    // Repeated formats are nested, then all closed
+    assert_eq!(
+        article(vec![
+            format!(Bold),
+            format!(Bold),
+            color!(Red),
+            color!(Blue),
+            text!("deep"),
+            format!(Reset),
+        ]),
+        "<b><b><span style='color:#FF5555'><span style='color:#5555FF'>deep</span></span></b></b>"
+    );
+
+    // Formatting left open at the end of the work is closed
+    assert_eq!(
+        article(vec![
+            format!(Underline),
+            text!("open"),
+            Token::ThematicBreak,
+            format!(Strikethrough),
+        ]),
+        "<u>open<hr /><s></s></u>"
+    );
+
+    // Only resets
+    assert_eq!(article(vec![format!(Reset); 16]), "");
+}
+
+#[test]
+fn html_blank_pages() {
+    use super::{BlankPages, ExportOptions};
+    use crate::syntax::minecraft::Format;
+    use Token::{LineBreak, ParagraphBreak, ThematicBreak};
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            ThematicBreak,
+            text!("one"),
+            LineBreak,
+            ThematicBreak,
+            ParagraphBreak,
+            Token::Format(Format::Bold),
+            ThematicBreak,
+            text!("three"),
+        ]),
+    );
+    let export = |blank_pages| {
+        Html::export_token_vector_to_string_with_options(
+            &token_list,
+            &ExportOptions::new().with_blank_pages(blank_pages),
+        )
+    };
+
+    assert!(export(BlankPages::Collapse)
+        .contains("<hr />one<br /><hr /><br /><b><hr />three</b></article>"));
+    assert!(export(BlankPages::Render).contains(concat!(
+        "<hr />one<br />",
+        r#"<hr /><section class="page blank" style=min-height:14lh></section><b>"#,
+        "<hr />three</b></article>",
+    )));
+    assert!(export(BlankPages::Skip).contains("<hr />one<br /><b><hr />three</b></article>"));
+}
+
+#[test]
+fn html_max_nesting_depth() {
+    use super::ExportOptions;
+    use crate::syntax::minecraft::{Color, Format};
+
+    let mut tokens = vec![Token::Format(Format::Italic); 5000];
+    tokens.push(Token::Format(Format::Color(Color::Red)));
+    tokens.push(text!("deep"));
+    let token_list = TokenList::new(Arc::new([]), tokens.into());
+
+    // Repeats past the limit are dropped, and a change reopens only what is needed
+    let options = ExportOptions::new().with_max_nesting_depth(3);
+    let output = Html::export_token_vector_to_string_with_options(&token_list, &options);
+    assert!(output.contains(concat!(
+        "<article style=white-space:break-spaces><i><i><i></i></i></i>",
+        "<span style='color:#FF5555'><i>deep</i></span></article>",
+    )));
+
+    // Up to the limit, then one more when the color reopens it
+    let output = Html::export_token_vector_to_string(token_list);
+    assert_eq!(
+        output.matches("<i>").count(),
+        ExportOptions::DEFAULT_MAX_NESTING_DEPTH + 1
+    );
+    assert_eq!(
+        output.matches("<i>").count(),
+        output.matches("</i>").count()
+    );
+}
+
+#[test]
+fn html_images() {
+    use super::{BlankPages, ExportOptions};
+    use Token::ThematicBreak;
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            ThematicBreak,
+            Token::Image {
+                src: "maps/0.png".into(),
+                alt: "A \"map\" of <spawn>".into(),
+            },
+            ThematicBreak,
+        ]),
+    );
+
+    // A page with only an image is not blank
+    let output = Html::export_token_vector_to_string_with_options(
+        &token_list,
+        &ExportOptions::new().with_blank_pages(BlankPages::Skip),
+    );
+    assert!(output.contains(concat!(
+        r#"<hr /><img src="maps/0.png" alt="A &quot;map&quot; of &lt;spawn&gt;" />"#,
+        "</article>",
+    )));
+}
+
+#[test]
+fn html_annotations() {
+    use super::{AnnotationStyle, ExportOptions};
+    use crate::syntax::{Annotation, Annotations};
+    use Token::Space;
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([text!("one"), Space, text!("two"), Space, text!("three")]),
+    );
+    let annotations = Annotations::new()
+        .with(Annotation::new(2..3, "a \"later\" hand"))
+        .with(Annotation::new(0..5, "smudged"))
+        .with(Annotation::new(9..12, "past the end"));
+    let export = |style| {
+        Html::export_token_vector_to_string_with_options(
+            &token_list,
+            &ExportOptions::new()
+                .with_annotations(annotations.clone())
+                .with_annotation_style(style),
+        )
+    };
+
+    assert!(export(AnnotationStyle::Mark).contains(concat!(
+        r#"<mark title="smudged">one <mark title="a &quot;later&quot; hand">two</mark> three"#,
+        r#"</mark><mark title="past the end"></mark></article>"#,
+    )));
+    assert!(export(AnnotationStyle::MarginNote).contains(concat!(
+        r#"<mark>one <mark>two</mark><aside class="margin-note" id="note-1">a &quot;later&quot; hand</aside>"#,
+        r#" three</mark><aside class="margin-note" id="note-2">smudged</aside>"#,
+    )));
+}
+
+#[test]
+fn html_library() {
+    use crate::syntax::{library::Library, TextBuilder};
+
+    let first = TextBuilder::new()
+        .title("§lFish & Chips")
+        .author("RemasteredArch")
+        .text("one")
+        .page()
+        .text("two")
+        .build();
+    let second = TextBuilder::new().text("anonymous").build();
+    // An author whose name has the same slug as the first
+    let third = TextBuilder::new()
+        .author("remastered arch")
+        .text("three")
+        .build();
+    let library = Library::new().with(&first).with(&second).with(&third);
+
+    let output = Html::export_library_to_string(&library);
+    assert!(output.contains("<title>Library</title>"));
+    assert!(output.ends_with(&std::format!(
+        concat!(
+            "<body><h1>Library</h1>",
+            r#"<section id="unknown-author"><h2>Unknown author</h2><ul>"#,
+            r#"<li><a href="{}.html">Untitled</a> (1 page)</li></ul></section>"#,
+            r#"<section id="remasteredarch"><h2>RemasteredArch</h2><ul>"#,
+            r#"<li><a href="{}.html">Fish &amp; Chips</a> (2 pages)</li></ul></section>"#,
+            r#"<section id="remastered-arch"><h2>remastered arch</h2><ul>"#,
+            r#"<li><a href="{}.html">Untitled</a> (1 page)</li></ul></section></body></html>"#,
+        ),
+        second.content_hash(),
+        first.content_hash(),
+        third.content_hash(),
+    )));
+}
+
+#[test]
+fn html_page_fragments() {
+    use super::ExportOptions;
+    use crate::syntax::minecraft::Format;
+    use Token::{LineBreak, ThematicBreak};
+
+    let token_list = TokenList::new(
+        Arc::new([title!("crafty_novels")]),
+        Arc::new([
+            ThematicBreak,
+            Token::Format(Format::Bold),
+            text!("one"),
+            LineBreak,
+            ThematicBreak,
+            LineBreak,
+            ThematicBreak,
+            text!("<three>"),
+            Token::Format(Format::Reset),
+        ]),
+    );
+    let options = ExportOptions::new();
+    let fragment = |index| Html::export_page_fragment(&token_list, index, &options);
+
+    assert_eq!(
+        fragment(0).unwrap().as_ref(),
+        r#"<section class="page" id="page-1" style=white-space:break-spaces><b>one<br /></b></section>"#
+    );
+    assert_eq!(
+        fragment(1).unwrap().as_ref(),
+        r#"<section class="page blank" id="page-2" style=white-space:break-spaces><b><br /></b></section>"#
+    );
+    // Exporting the earlier pages first doesn't change the later ones
+    assert_eq!(
+        fragment(2).unwrap().as_ref(),
+        r#"<section class="page" id="page-3" style=white-space:break-spaces><b>&lt;three&gt;</b></section>"#
+    );
+    assert_eq!(fragment(3).unwrap_err().pages(), 3);
+
+    // Exporting a single page as a whole document
+    let page = Html::export_page(&token_list, 2).unwrap();
+    assert!(page.contains("<title>crafty_novels</title>"));
+    assert!(page
+        .contains("<article style=white-space:break-spaces><hr /><b>&lt;three&gt;</b></article>"));
+}
+
+#[test]
+fn html_document_options() {
+    use super::{BlankPages, DocumentKind, ExportOptions, Styling, TextDirection};
+    use crate::syntax::minecraft::{Color, Format};
+    use Token::ThematicBreak;
+
+    let token_list = TokenList::new(
+        Arc::new([title!("Book")]),
+        Arc::new([
+            ThematicBreak,
+            Token::Format(Format::Color(Color::DarkBlue)),
+            text!("one"),
+            ThematicBreak,
+        ]),
+    );
+    let export = |options: ExportOptions| {
+        Html::export_token_vector_to_string_with_options(&token_list, &options)
+    };
+
+    let output = export(
+        ExportOptions::new()
+            .with_language("he")
+            .with_direction(TextDirection::RightToLeft)
+            .with_extra_head(Some(r#"<link rel="stylesheet" href="book.css" />"#.into())),
+    );
+    assert!(output.starts_with(r#"<!DOCTYPE html><html lang="he" dir="rtl"><head>"#));
+    assert!(output.contains(concat!(
+        r#"<link rel="stylesheet" href="book.css" /></head>"#,
+        "<body><article style=white-space:break-spaces>"
+    )));
+
+    assert_eq!(
+        &*export(
+            ExportOptions::new()
+                .with_styling(Styling::Classes)
+                .with_blank_pages(BlankPages::Render)
+                .with_document_kind(DocumentKind::Fragment)
+        ),
+        concat!(
+            r#"<article lang="en" dir="ltr" class="book">"#,
+            r#"<hr /><span class="color-dark_blue">one"#,
+            r#"<hr /><section class="page blank"></section></span></article>"#,
+        )
+    );
+}
+
+#[test]
+fn html_page_sections() {
+    use super::{BlankPages, DocumentKind, ExportOptions, PageBreaks};
+    use crate::syntax::minecraft::Format;
+    use Token::{LineBreak, ThematicBreak};
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            ThematicBreak,
+            Token::Format(Format::Bold),
+            text!("one"),
+            ThematicBreak,
+            LineBreak,
+            ThematicBreak,
+            text!("three"),
+        ]),
+    );
+    let export = |blank_pages| {
+        Html::export_token_vector_to_string_with_options(
+            &token_list,
+            &ExportOptions::new()
+                .with_page_breaks(PageBreaks::Sections)
+                .with_blank_pages(blank_pages)
+                .with_document_kind(DocumentKind::Fragment),
+        )
+    };
+
+    // Formatting is closed at the end of each page and reopened in the next
+    assert_eq!(
+        &*export(BlankPages::Collapse),
+        concat!(
+            r#"<article lang="en" dir="ltr" style=white-space:break-spaces>"#,
+            r#"<section class="page" data-page="1"><b>one</b></section>"#,
+            r#"<section class="page" data-page="2"><b><br /></b></section>"#,
+            r#"<section class="page" data-page="3"><b>three</b></section></article>"#,
+        )
+    );
+    assert!(export(BlankPages::Render).contains(concat!(
+        r#"<section class="page blank" data-page="2" style=min-height:14lh><b></b></section>"#,
+        r#"<section class="page" data-page="3">"#,
+    )));
+    // Skipped pages keep their numbers
+    assert!(export(BlankPages::Skip).contains(concat!(
+        r#"<b>one</b></section>"#,
+        r#"<section class="page" data-page="3"><b>three</b></section>"#,
+    )));
+
+    // Content before the first page break is the first page
+    let token_list = TokenList::new(Arc::new([]), Arc::new([text!("one"), ThematicBreak]));
+    assert!(Html::export_token_vector_to_string_with_options(
+        &token_list,
+        &ExportOptions::new().with_page_breaks(PageBreaks::Sections),
+    )
+    .contains(concat!(
+        r#"<section class="page" data-page="1">one</section>"#,
+        r#"<section class="page" data-page="2"></section></article>"#,
+    )));
+}
+
+#[test]
+fn html_page_classes() {
+    use super::{BlankPages, DocumentKind, ExportOptions, PageBreaks};
+    use crate::syntax::minecraft::{Color, Format};
+    use Token::{LineBreak, Space, ThematicBreak};
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            ThematicBreak,
+            Token::Format(Format::Italic),
+            text!("mostly"),
+            Space,
+            Token::Format(Format::Color(Color::Red)),
+            text!("italic"),
+            Token::Format(Format::Reset),
+            text!("!"),
+            ThematicBreak,
+            LineBreak,
+            ThematicBreak,
+            Token::Format(Format::Bold),
+            text!("half"),
+            Token::Format(Format::Reset),
+            text!("half"),
+        ]),
+    );
+    let options = ExportOptions::new()
+        .with_page_breaks(PageBreaks::Sections)
+        .with_document_kind(DocumentKind::Fragment)
+        .with_page_classes(true);
+
+    let output = Html::export_token_vector_to_string_with_options(&token_list, &options);
+    assert!(output.contains(r#"<section class="page has-color-red mostly-italic" data-page="1">"#));
+    assert!(output.contains(r#"<section class="page blank" data-page="2">"#));
+    assert!(output.contains(r#"<section class="page" data-page="3">"#));
+
+    // The class for blank pages isn't repeated
+    let output = Html::export_token_vector_to_string_with_options(
+        &token_list,
+        &options.clone().with_blank_pages(BlankPages::Render),
+    );
+    assert!(output.contains(r#"<section class="page blank" data-page="2" style=min-height:14lh>"#));
+
+    let fragment = Html::export_page_fragment(&token_list, 0, &options).expect("page exists");
+    assert!(fragment.starts_with(r#"<section class="page has-color-red mostly-italic""#));
+}
+
+#[test]
+fn html_page_opening() {
+    use super::{DocumentKind, ExportOptions, PageOpening, Styling};
+    use crate::syntax::minecraft::Format;
+    use Token::{LineBreak, Space, ThematicBreak};
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            ThematicBreak,
+            Token::Format(Format::Bold),
+            text!("Once"),
+            Space,
+            text!("upon"),
+            LineBreak,
+            text!("a time"),
+            ThematicBreak,
+            LineBreak,
+            text!("<x"),
+        ]),
+    );
+    let export = |page_opening, styling| {
+        Html::export_token_vector_to_string_with_options(
+            &token_list,
+            &ExportOptions::new()
+                .with_page_opening(page_opening)
+                .with_styling(styling)
+                .with_document_kind(DocumentKind::Fragment),
+        )
+    };
+
+    // Each piece of text is wrapped inside of the formatting, and a line break before any text
+    // doesn't end the first line
+    assert_eq!(
+        &*export(PageOpening::DropCapAndFirstLine, Styling::Classes),
+        concat!(
+            r#"<article lang="en" dir="ltr" class="book"><hr /><b>"#,
+            r#"<span class="first-line"><span class="drop-cap">O</span>nce</span> "#,
+            r#"<span class="first-line">upon</span><br />a time<hr /><br />"#,
+            r#"<span class="first-line"><span class="drop-cap">&lt;</span>x</span></b></article>"#,
+        )
+    );
+    assert!(
+        export(PageOpening::DropCap, Styling::Inline).contains(concat!(
+            "<b><span class=\"drop-cap\" style=float:left;font-size:3em;line-height:1>O</span>nce ",
+            "upon<br />",
+        ))
+    );
+    assert!(!export(PageOpening::Plain, Styling::Inline).contains("<span"));
+}
+
+#[test]
+fn html_background_colors() {
+    use super::{ExportOptions, Styling};
+    use crate::syntax::minecraft::ColorLayer;
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([color!(Red), text!("red"), format!(Reset)]),
+    );
+    let options = ExportOptions::new().with_color_layer(ColorLayer::Background);
+
+    assert!(
+        Html::export_token_vector_to_string_with_options(&token_list, &options)
+            .contains("<span style='background-color:#3F1515'>red</span>")
+    );
+
+    let options = options.with_styling(Styling::Classes);
+
+    assert!(
+        Html::export_token_vector_to_string_with_options(&token_list, &options)
+            .contains("<span class=\"background-red\">red</span>")
+    );
+}
+
+#[test]
+fn html_writing_mode() {
+    use super::{ExportOptions, Styling, WritingMode};
+
+    let token_list = TokenList::new(Arc::new([]), Arc::new([text!("縦書き")]));
+    let export = |options: &ExportOptions| {
+        Html::export_token_vector_to_string_with_options(&token_list, options)
+    };
+
+    let options = ExportOptions::new().with_writing_mode(WritingMode::VerticalRightToLeft);
+    assert!(export(&options)
+        .contains(r#"<article style="white-space:break-spaces;writing-mode:vertical-rl">"#));
+
+    let options = options.with_proportional_spacing(true);
+    assert!(export(&options).contains(concat!(
+        r#"<article style="white-space:break-spaces;writing-mode:vertical-rl;"#,
+        r#"font-feature-settings:'vpal'">"#,
+    )));
+
+    let options = options.with_writing_mode(WritingMode::Horizontal);
+    assert!(export(&options)
+        .contains(r#"<article style="white-space:break-spaces;font-feature-settings:'palt'">"#));
+
+    let options = options
+        .with_writing_mode(WritingMode::VerticalLeftToRight)
+        .with_styling(Styling::Classes);
+    assert!(export(&options).contains(r#"<article class="book vertical-lr proportional">"#));
+}
+
+#[test]
+fn html_right_to_left() {
+    use super::{ExportOptions, TextDirection};
+    use crate::{
+        import::Stendhal,
+        syntax::{direction::DIRECTION_KEY, typography::LANGUAGE_KEY},
+    };
+
+    // Format codes split the text in its logical order, however an editor displays them
+    let token_list =
+        Stendhal::tokenize_string_headless("#- مرحبا §lبالعالم§r (1)").expect("valid Stendhal");
+    let token_list = TokenList::new(
+        Arc::new([crate::syntax::Metadata::Language("ar-EG".into())]),
+        token_list.tokens_as_slice().into(),
+    );
+
+    let output = Html::export_token_vector_to_string_with_options(
+        &token_list,
+        &ExportOptions::new().with_work_language(&token_list),
+    );
+    assert!(output.contains(r#"<html lang="ar-EG" dir="rtl">"#));
+    assert!(output.contains("مرحبا <b>بالعالم</b> (1)"));
+
+    // An explicit direction takes precedence over the language
+    let token_list = TokenList::new(
+        Arc::new([
+            crate::syntax::Metadata::Custom {
+                key: LANGUAGE_KEY.into(),
+                value: "ar".into(),
+            },
+            crate::syntax::Metadata::Custom {
+                key: DIRECTION_KEY.into(),
+                value: "auto".into(),
+            },
+        ]),
+        Arc::new([]),
+    );
+    let options = ExportOptions::new().with_work_language(&token_list);
+    assert_eq!(options.direction(), TextDirection::Auto);
+
+    // Nothing to go by keeps the options as they were
+    let options = options.with_work_language(&TokenList::new(Arc::new([]), Arc::new([])));
+    assert_eq!(options.language(), "ar");
+    assert_eq!(options.direction(), TextDirection::Auto);
+}
+
+#[test]
+fn html_aligned_lines() {
+    use super::{ExportOptions, Styling};
+    use crate::syntax::align::Alignment;
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            Token::Align(Alignment::Center),
+            format!(Bold),
+            text!("Title"),
+            Token::LineBreak,
+            text!("body"),
+        ]),
+    );
+
+    assert!(Html::export_token_vector_to_string(token_list.clone()).contains(
+        "<span class=\"align-center\" style=display:inline-block;inline-size:100%;text-align:center>\
+        <b>Title</b></span><b><br />body</b>"
+    ));
+
+    let options = ExportOptions::new().with_styling(Styling::Classes);
+    assert!(
+        Html::export_token_vector_to_string_with_options(&token_list, &options)
+            .contains("<span class=\"align-center\"><b>Title</b></span><b><br />")
+    );
+}
+
+#[test]
+fn html_passthrough() {
+    use super::{passthrough, DocumentKind, ExportOptions};
+
+    let token_list = TokenList::new(
+        Arc::new([]),
+        Arc::new([
+            text!("H<SUB>2</sub>O"),
+            Token::Space,
+            text!("a<br/>b"),
+            Token::Space,
+            text!("<script>alert(1)</script>"),
+            Token::Space,
+            text!("<em>open"),
+            Token::Space,
+            text!("<b>a<i>b</b></i>"),
+            Token::Space,
+            text!("<em class=x>"),
+            Token::Space,
+            text!("1<2"),
+            Token::Space,
+            text!("<em>two"),
+            Token::Space,
+            text!("words</em>"),
+        ]),
+    );
+    let export = |passthrough_html| {
+        Html::export_token_vector_to_string_with_options(
+            &token_list,
+            &ExportOptions::new()
+                .with_document_kind(DocumentKind::Fragment)
+                .with_passthrough_html(passthrough_html),
+        )
+    };
+
+    // Escaped unless asked for
+    assert!(export(false).contains("H&lt;SUB&gt;2&lt;/sub&gt;O"));
+
+    // Only allowed tags, balanced inside of the same word, are written as HTML
+    assert!(export(true).contains(concat!(
+        "H<sub>2</sub>O a<br />b &lt;script&gt;alert(1)&lt;/script&gt; &lt;em&gt;open ",
+        "&lt;b&gt;a&lt;i&gt;b&lt;/b&gt;&lt;/i&gt; &lt;em class=x&gt; 1&lt;2 ",
+        // Tags only apply within a single word
+        "&lt;em&gt;two words&lt;/em&gt;",
+    )));
+
+    assert_eq!(
+        passthrough::split("a<3"),
+        Some(vec![
+            passthrough::Piece::Text("a<"),
+            passthrough::Piece::Text("3")
+        ])
     );
 }
@@ -17,45 +17,535 @@
 
 //! The actual, under the hood, token-by-token exporting for the [HTML][`super::Html`] format.
 
-use super::{error::ExportError, syntax::HtmlEntity};
+use super::{
+    error::ExportError,
+    options::{
+        AnnotationStyle, BlankPages, DocumentKind, ExportOptions, Legend, MetadataFormatCodes,
+        Styling,
+    },
+    passthrough::{self, Piece},
+    syntax::{HtmlEntity, NumericCharacterReference},
+};
 use crate::{
-    syntax::{minecraft::Format, Metadata, Token},
+    format::ids::Ids,
+    syntax::{
+        library::Library,
+        minecraft::{
+            strip_format_codes, tokenize_formatted, ColorLayer, ColorValue, Format, FormatState,
+            Style,
+        },
+        role::Role,
+        styled::to_styled_spans,
+        Annotation, Metadata, Token,
+    },
     writer::Utf8Writer,
 };
-use std::io::Write;
+use std::{collections::BTreeSet, io::Write, iter::Peekable};
+
+/// An HTML element that stays open until the next [`Format::Reset`], as tracked by the format
+/// token stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Element {
+    /// Opened by a [`Token::Format`].
+    Format(Format),
+    /// Opened by a [`Token::Role`].
+    Role(Role),
+}
+
+/// Returns the name of the tag of the element for `role`, ex. `"h2"`.
+const fn role_tag(role: Role) -> &'static str {
+    match role {
+        Role::Heading1 => "h1",
+        Role::Heading2 => "h2",
+        Role::Heading3 => "h3",
+        Role::Heading4 => "h4",
+        Role::Heading5 => "h5",
+        Role::Heading6 => "h6",
+        Role::Strong => "strong",
+        Role::Emphasis => "em",
+        Role::Small => "small",
+        Role::Mark => "mark",
+        Role::Cite => "cite",
+        Role::Quote => "q",
+    }
+}
 
 /// Push the appropriate HTML element(s) for `token` into `output`.
-/// If `token` is [`Token::Format`], it is pushed onto `format_token_stack`.
+/// If `token` is [`Token::Format`] or [`Token::Role`], it is pushed onto `format_token_stack`.
+///
+/// Any sequence of tokens is handled, including repeated or leading [`Format::Reset`]s.
 ///
 /// # Errors
 ///
-/// - [`ExportError::UnexpectedToken`] if `format_token_stack` contains [`Format::Reset`] and
-///   `token` is of variant [`Format::Reset`]
-///   - [`handle_token`] itself cannot cause this state, but assumes that the owner of
-///     `format_token_stack` could have done it
 /// - [`ExportError::Io`] if it cannot write into `output`
 pub fn handle_token(
     output: &mut Utf8Writer<impl Write>,
-    format_token_stack: &mut Vec<Format>,
+    format_token_stack: &mut Vec<Element>,
     token: &Token,
+    options: &ExportOptions,
 ) -> Result<(), ExportError> {
     match &token {
+        Token::Text(s) if options.passthrough_html() => insert_passthrough_html(output, s)?,
         Token::Text(s) => insert_string_as_html(output, s)?,
-        Token::Format(f) => handle_format(output, format_token_stack, *f)?,
+        Token::Format(f) => {
+            handle_element(output, format_token_stack, Element::Format(*f), options)?;
+        }
+        Token::Role(role) => {
+            handle_element(output, format_token_stack, Element::Role(*role), options)?;
+        }
         Token::Space => output.write_str(" ")?,
-        Token::LineBreak => output.write_str("<br />")?,
-        Token::ParagraphBreak => output.write_str("<br />")?,
+        Token::LineBreak | Token::ParagraphBreak => output.write_str("<br />")?,
         Token::ThematicBreak => output.write_str("<hr />")?,
+        // Handled by `AlignedLine`, which needs to know where the line ends
+        Token::Align(_) => (),
+        Token::Image { src, alt } => {
+            output.write_str("<img src=\"")?;
+            insert_string_as_html(output, src)?;
+            output.write_str("\" alt=\"")?;
+            insert_string_as_html(output, alt)?;
+            output.write_str("\" />")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Push the elements that close the annotations in `open` that end at or before `index`, and that
+/// open the annotations in `pending` that start at or before `index`, into `output`.
+///
+/// `pending` should be in the order of [`crate::syntax::Annotations::iter`], and `open` holds the
+/// annotations that are open, innermost last. An annotation is only closed once every annotation
+/// inside of it is, so overlapping ranges are extended to nest.
+///
+/// Margin notes are given `id`s from `ids`, `"note-1"` onward.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+pub fn annotate<'a>(
+    output: &mut Utf8Writer<impl Write>,
+    index: usize,
+    pending: &mut Peekable<impl Iterator<Item = &'a Annotation>>,
+    open: &mut Vec<&'a Annotation>,
+    style: AnnotationStyle,
+    ids: &mut Ids,
+) -> std::io::Result<()> {
+    loop {
+        while let Some(annotation) = open.last().filter(|annotation| annotation.end() <= index) {
+            close_annotation(output, annotation, style, ids)?;
+            open.pop();
+        }
+
+        let Some(annotation) = pending.next_if(|annotation| annotation.start() <= index) else {
+            return Ok(());
+        };
+        open_annotation(output, annotation, style)?;
+        open.push(annotation);
+    }
+}
+
+/// Push the element that starts `annotation` into `output`.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+fn open_annotation(
+    output: &mut Utf8Writer<impl Write>,
+    annotation: &Annotation,
+    style: AnnotationStyle,
+) -> std::io::Result<()> {
+    match style {
+        AnnotationStyle::Mark => {
+            output.write_str("<mark title=\"")?;
+            insert_string_as_html(output, annotation.note())?;
+            output.write_str("\">")
+        }
+        AnnotationStyle::MarginNote => output.write_str("<mark>"),
+    }
+}
+
+/// Push the element(s) that end `annotation` into `output`, taking the `id` of a margin note from
+/// `ids`.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+fn close_annotation(
+    output: &mut Utf8Writer<impl Write>,
+    annotation: &Annotation,
+    style: AnnotationStyle,
+    ids: &mut Ids,
+) -> std::io::Result<()> {
+    output.write_str("</mark>")?;
+
+    if style == AnnotationStyle::MarginNote {
+        write!(
+            output,
+            r#"<aside class="margin-note" id="{}">"#,
+            ids.numbered("note")
+        )?;
+        insert_string_as_html(output, annotation.note())?;
+        output.write_str("</aside>")?;
+    }
+
+    Ok(())
+}
+
+/// Returns whether the page starting at `page` has no [`Token::Text`] or [`Token::Image`] before
+/// the next [`Token::ThematicBreak`].
+///
+/// `page` should be the tokens following the [`Token::ThematicBreak`] that starts the page.
+pub fn is_blank_page(page: &[Token]) -> bool {
+    page.iter()
+        .take_while(|token| **token != Token::ThematicBreak)
+        .all(|token| !token.is_text() && !token.is_image())
+}
+
+/// The styles that a page can mostly be written in, and the names of their classes, see
+/// [`page_classes`].
+const PAGE_STYLES: [(Style, &str); 5] = [
+    (Style::BOLD, "bold"),
+    (Style::ITALIC, "italic"),
+    (Style::UNDERLINE, "underline"),
+    (Style::STRIKETHROUGH, "strikethrough"),
+    (Style::OBFUSCATED, "obfuscated"),
+];
+
+/// Returns the classes derived from the contents of the page starting at `page`, for
+/// [`ExportOptions::with_page_classes`], in the order `blank`, then each `has-color-{name}` in the
+/// order the colors first appear, then each `mostly-{style}`.
+///
+/// Only text that isn't whitespace counts towards colors and styles.
+///
+/// `page` should be the tokens following the [`Token::ThematicBreak`] that starts the page.
+pub fn page_classes(page: &[Token]) -> Vec<String> {
+    let end = page
+        .iter()
+        .position(|token| *token == Token::ThematicBreak)
+        .unwrap_or(page.len());
+    let mut classes = vec![];
+    if is_blank_page(page) {
+        classes.push("blank".to_string());
+    }
+
+    let mut total = 0;
+    let mut styled = [0; PAGE_STYLES.len()];
+    for span in to_styled_spans(&page[..end]) {
+        let length = span.text().chars().filter(|c| !c.is_whitespace()).count();
+        if length == 0 {
+            continue;
+        }
+        total += length;
+
+        if let Some(color) = span.format().color() {
+            let class = format!("has-color-{}", ColorValue::from(color).name());
+            if !classes.contains(&class) {
+                classes.push(class);
+            }
+        }
+        for (count, (style, _)) in styled.iter_mut().zip(PAGE_STYLES) {
+            if span.format().style().contains(style) {
+                *count += length;
+            }
+        }
+    }
+
+    for (count, (_, name)) in styled.into_iter().zip(PAGE_STYLES) {
+        if count * 2 > total {
+            classes.push(format!("mostly-{name}"));
+        }
+    }
+
+    classes
+}
+
+/// Returns the `class` of the `<section>` of the page starting at `page`: `"page"`, then
+/// `"blank"` if `blank`, then any [`page_classes`] if `options` asks for them.
+///
+/// `page` should be the tokens following the [`Token::ThematicBreak`] that starts the page.
+pub fn section_class(page: &[Token], blank: bool, options: &ExportOptions) -> String {
+    let mut class = String::from(if blank { "page blank" } else { "page" });
+    if options.page_classes() {
+        for derived in page_classes(page) {
+            if !(blank && derived == "blank") {
+                class.push(' ');
+                class.push_str(&derived);
+            }
+        }
+    }
+
+    class
+}
+
+/// Push the HTML for a blank page into `output`, in place of the [`Token::ThematicBreak`] that
+/// starts it.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+pub fn blank_page(
+    output: &mut Utf8Writer<impl Write>,
+    options: &ExportOptions,
+) -> std::io::Result<()> {
+    match (options.blank_pages(), options.styling()) {
+        (BlankPages::Collapse, _) => output.write_str("<hr />"),
+        (BlankPages::Render, Styling::Inline) => output
+            .write_str(r#"<hr /><section class="page blank" style=min-height:14lh></section>"#),
+        (BlankPages::Render, Styling::Classes) => {
+            output.write_str(r#"<hr /><section class="page blank"></section>"#)
+        }
+        (BlankPages::Skip, _) => Ok(()),
+    }
+}
+
+/// Push the elements that end the current page and start page `number` into `output`, for
+/// [`super::PageBreaks::Sections`], returning whether the new page is blank and `options` handles
+/// blank pages differently.
+///
+/// `page` should be the tokens of the new page, starting at its [`Token::ThematicBreak`], if any.
+/// Closes the elements opened for `format_token_stack` and for each of `open_annotations`, then
+/// the `<section>` if `in_section`, which is only `false` before the first page. Then opens the
+/// `<section>` of the new page, with the class from [`section_class`], and reopens the
+/// annotations and formats inside of it, so that the elements still nest.
+///
+/// A blank page that is [skipped][`BlankPages::Skip`] is left inside of the previous section
+/// instead, like the `<hr />`s of [`super::PageBreaks::Rule`].
+///
+/// # Errors
+///
+/// - [`ExportError::Io`] if it cannot write into `output`
+pub fn turn_page(
+    output: &mut Utf8Writer<impl Write>,
+    number: usize,
+    page: &[Token],
+    in_section: &mut bool,
+    format_token_stack: &mut Vec<Element>,
+    open_annotations: &[&Annotation],
+    options: &ExportOptions,
+) -> Result<bool, ExportError> {
+    let page = page.strip_prefix(&[Token::ThematicBreak]).unwrap_or(page);
+    let blank = options.blank_pages() != BlankPages::Collapse && is_blank_page(page);
+    if blank && options.blank_pages() == BlankPages::Skip {
+        return Ok(true);
+    }
+
+    let close = if *in_section { "</section>" } else { "" };
+    *in_section = true;
+
+    let class = section_class(page, blank, options);
+    let style = match (blank, options.styling()) {
+        (true, Styling::Inline) => " style=min-height:14lh>",
+        _ => ">",
     };
+    between_elements(
+        output,
+        &format!(r#"{close}<section class="{class}" data-page="{number}"{style}"#),
+        format_token_stack,
+        open_annotations,
+        options,
+    )?;
+
+    Ok(blank)
+}
+
+/// Push `html` into `output` between the elements opened for `format_token_stack` and for each of
+/// `open_annotations`, closing them before it and reopening them after it, so that the elements
+/// still nest.
+///
+/// # Errors
+///
+/// - [`ExportError::Io`] if it cannot write into `output`
+fn between_elements(
+    output: &mut Utf8Writer<impl Write>,
+    html: &str,
+    format_token_stack: &mut Vec<Element>,
+    open_annotations: &[&Annotation],
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let elements = format_token_stack.clone();
+    close_formatting_tags(output, format_token_stack)?;
+    for _ in open_annotations {
+        output.write_str("</mark>")?;
+    }
+
+    output.write_str(html)?;
+
+    for annotation in open_annotations {
+        open_annotation(output, annotation, options.annotation_style())?;
+    }
+    for element in elements {
+        open_element(output, format_token_stack, element, options)?;
+    }
 
     Ok(())
 }
 
+/// Tracks whether the exporter is inside of an aligned line, see [`Token::Align`].
+///
+/// An aligned line is wrapped in a `<span class="align-{alignment}">`, which is styled inline as a
+/// block as wide as the line, so that the `<br />` after it still ends the line.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AlignedLine {
+    /// Whether an aligned line is open.
+    open: bool,
+}
+
+impl AlignedLine {
+    /// Push the elements that start or end an aligned line for `token` into `output`, returning
+    /// whether it was handled, or `false` if it should be handled as usual with [`handle_token`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ExportError::Io`] if it cannot write into `output`
+    pub fn handle_token(
+        &mut self,
+        output: &mut Utf8Writer<impl Write>,
+        token: &Token,
+        format_token_stack: &mut Vec<Element>,
+        open_annotations: &[&Annotation],
+        options: &ExportOptions,
+    ) -> Result<bool, ExportError> {
+        match token {
+            Token::Align(_) if self.open => Ok(true),
+            Token::Align(alignment) => {
+                let html = match options.styling() {
+                    Styling::Inline => format!(
+                        r#"<span class="align-{0}" style=display:inline-block;inline-size:100%;text-align:{0}>"#,
+                        alignment.css()
+                    ),
+                    Styling::Classes => format!(r#"<span class="align-{}">"#, alignment.css()),
+                };
+                between_elements(output, &html, format_token_stack, open_annotations, options)?;
+                self.open = true;
+                Ok(true)
+            }
+            Token::LineBreak | Token::ParagraphBreak | Token::ThematicBreak => {
+                self.finish(output, format_token_stack, open_annotations, options)?;
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Push the end of the aligned line into `output`, if one is open.
+    ///
+    /// # Errors
+    ///
+    /// - [`ExportError::Io`] if it cannot write into `output`
+    pub fn finish(
+        &mut self,
+        output: &mut Utf8Writer<impl Write>,
+        format_token_stack: &mut Vec<Element>,
+        open_annotations: &[&Annotation],
+        options: &ExportOptions,
+    ) -> Result<(), ExportError> {
+        if std::mem::take(&mut self.open) {
+            between_elements(
+                output,
+                "</span>",
+                format_token_stack,
+                open_annotations,
+                options,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks how far into a page the exporter is, to style its first letter and first line, see
+/// [`super::PageOpening`].
+#[derive(Clone, Copy, Debug)]
+pub struct Opening {
+    /// Whether the next character of text is the first letter of the page.
+    first_letter: bool,
+    /// Whether the next piece of text is on the first line of the page.
+    first_line: bool,
+    /// Whether the first line has any text on it yet.
+    seen_text: bool,
+}
+
+impl Opening {
+    /// Start tracking the first page.
+    pub const fn new(options: &ExportOptions) -> Self {
+        Self {
+            first_letter: options.page_opening().drop_cap(),
+            first_line: options.page_opening().first_line(),
+            seen_text: false,
+        }
+    }
+
+    /// Start tracking a new page.
+    pub const fn start_page(&mut self, options: &ExportOptions) {
+        *self = Self::new(options);
+    }
+
+    /// Push `token` into `output` if it is text at the start of the page, returning whether it
+    /// was written, or `false` if it should be handled as usual with [`handle_token`].
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    pub fn handle_token(
+        &mut self,
+        output: &mut Utf8Writer<impl Write>,
+        token: &Token,
+        options: &ExportOptions,
+    ) -> std::io::Result<bool> {
+        let inline = options.styling() == Styling::Inline;
+
+        match token {
+            Token::Text(text) if !text.is_empty() && (self.first_letter || self.first_line) => {
+                if self.first_line {
+                    output.write_str(r#"<span class="first-line""#)?;
+                    output.write_str(if inline {
+                        " style=font-variant:small-caps>"
+                    } else {
+                        ">"
+                    })?;
+                }
+
+                let mut rest = text.chars();
+                if self.first_letter {
+                    output.write_str(r#"<span class="drop-cap""#)?;
+                    output.write_str(if inline {
+                        " style=float:left;font-size:3em;line-height:1>"
+                    } else {
+                        ">"
+                    })?;
+                    let letter: String = rest.next().into_iter().collect();
+                    insert_string_as_html(output, &letter)?;
+                    output.write_str("</span>")?;
+                }
+                insert_string_as_html(output, rest.as_str())?;
+
+                if self.first_line {
+                    output.write_str("</span>")?;
+                }
+
+                self.first_letter = false;
+                self.seen_text = true;
+                Ok(true)
+            }
+            Token::Image { .. } => {
+                self.first_letter = false;
+                Ok(false)
+            }
+            Token::LineBreak | Token::ParagraphBreak if self.seen_text => {
+                self.first_line = false;
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
 /// Inserts a string of arbitrary text into HTML output in a syntax-aware manner.
 ///
 /// For every character in `input`:
 ///
 /// - If a literal character corresponds to an [`HtmlEntity`], write that entity into `output`
+/// - If a literal character is outside of the Basic Multilingual Plane, write it into `output` as a
+///   [`NumericCharacterReference`]
 /// - Otherwise, write the character to `output`
 ///
 /// # Errors
@@ -65,6 +555,8 @@ fn insert_string_as_html(output: &mut Utf8Writer<impl Write>, input: &str) -> st
     for char in input.chars() {
         if let Ok(as_html_entity) = HtmlEntity::try_from(&char) {
             write!(output, "{as_html_entity}")?;
+        } else if let Some(as_reference) = NumericCharacterReference::astral(char) {
+            write!(output, "{as_reference}")?;
         } else {
             output.write_char(char)?;
         }
@@ -73,22 +565,122 @@ fn insert_string_as_html(output: &mut Utf8Writer<impl Write>, input: &str) -> st
     Ok(())
 }
 
-/// Push the appropriate HTML element for `format_token` into `output`.
-/// Pushes the `format_token` onto `format_token_stack`.
+/// Inserts a string of text into HTML output like [`insert_string_as_html`], except that the tags
+/// allowed by [`ExportOptions::with_passthrough_html`] are written as HTML, unless any tag in
+/// `input` is not allowed.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+fn insert_passthrough_html(
+    output: &mut Utf8Writer<impl Write>,
+    input: &str,
+) -> std::io::Result<()> {
+    let Some(pieces) = passthrough::split(input) else {
+        return insert_string_as_html(output, input);
+    };
+
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => insert_string_as_html(output, text)?,
+            Piece::Open(name) => write!(output, "<{name}>")?,
+            Piece::Close(name) => write!(output, "</{name}>")?,
+            Piece::Void(name) => write!(output, "<{name} />")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Push the appropriate HTML element for `element` into `output`.
+/// Pushes the `element` onto `format_token_stack` with [`push_element`].
 ///
 /// If it hits [`Format::Reset`], it will call [`close_formatting_tags`].
 ///
+/// If `format_token_stack` is already as deep as the maximum nesting depth in `options`, the
+/// elements are instead coalesced: an `element` that doesn't change the formatting or the role is
+/// dropped, and otherwise every element is closed and only those needed for the latest role and
+/// the new formatting are reopened.
+///
+/// # Errors
+///
+/// - [`ExportError::Io`] if it cannot write into `output`
+fn handle_element(
+    output: &mut Utf8Writer<impl Write>,
+    format_token_stack: &mut Vec<Element>,
+    element: Element,
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    /// Returns the latest role and the formatting of `elements`.
+    fn state<'a>(elements: impl Iterator<Item = &'a Element>) -> (Option<Role>, FormatState) {
+        elements.fold(
+            (None, FormatState::new()),
+            |(role, state), element| match element {
+                Element::Format(format) => (role, state.with(*format)),
+                Element::Role(next) => (Some(*next), state),
+            },
+        )
+    }
+
+    let opens_element = !matches!(element, Element::Format(Format::Reset | Format::Unknown(_)));
+    if !opens_element || format_token_stack.len() < options.max_nesting_depth() {
+        return open_element(output, format_token_stack, element, options);
+    }
+
+    let current = state(format_token_stack.iter());
+    let (role, next_state) = state(format_token_stack.iter().chain([&element]));
+    if (role, next_state) == current {
+        return Ok(());
+    }
+
+    close_formatting_tags(output, format_token_stack)?;
+    if let Some(role) = role {
+        open_element(output, format_token_stack, Element::Role(role), options)?;
+    }
+    for format in next_state.formats() {
+        open_format(output, format_token_stack, format, options)?;
+    }
+
+    Ok(())
+}
+
+/// Push the appropriate HTML element for `element` into `output`, regardless of how deeply the
+/// elements are nested.
+///
+/// See [`handle_element`].
+///
+/// # Errors
+///
+/// - [`ExportError::Io`] if it cannot write into `output`
+fn open_element(
+    output: &mut Utf8Writer<impl Write>,
+    format_token_stack: &mut Vec<Element>,
+    element: Element,
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    match element {
+        Element::Format(format) => open_format(output, format_token_stack, format, options),
+        Element::Role(role) => {
+            push_element(format_token_stack, element);
+            write!(output, "<{}>", role_tag(role))?;
+            Ok(())
+        }
+    }
+}
+
+/// Push the appropriate HTML element for `format_token` into `output`, regardless of how deeply
+/// the elements are nested.
+///
+/// See [`handle_element`].
+///
 /// # Errors
 ///
-/// - [`ExportError::UnexpectedToken`] if `format_token_stack` contains [`Format::Reset`] and
-///   `format_token` is of variant [`Format::Reset`]
-///   - [`handle_format`] itself cannot cause this state, but assumes that the owner of
-///     `format_token_stack` could have done it
 /// - [`ExportError::Io`] if it cannot write into `output`
-fn handle_format(
+fn open_format(
     output: &mut Utf8Writer<impl Write>,
-    format_token_stack: &mut Vec<Format>,
+    format_token_stack: &mut Vec<Element>,
     format_token: Format,
+    options: &ExportOptions,
 ) -> Result<(), ExportError> {
     /// Generates a match statement with [`Format`] variants to write the given HTML (containing
     /// opening tags) into `output`.
@@ -104,16 +696,18 @@ fn handle_format(
         ) => {
             match $format_token {
                 Format::Color($color_var) => {
-                    $format_token_stack.push($format_token);
-                    write!($output, $color_html)?;
+                    push_element($format_token_stack, Element::Format($format_token));
+                    $color_html;
                 }
                 $(
                     Format::$format => {
-                        $format_token_stack.push($format_token);
+                        push_element($format_token_stack, Element::Format($format_token));
                         $output.write_str($html)?;
                     }
                 ),+ ,
                 Format::Reset => $reset_fn,
+                // There is no reasonable HTML equivalent, so these are dropped
+                Format::Unknown(_) => (),
             }
         };
 
@@ -121,7 +715,20 @@ fn handle_format(
 
     open_html!(
         output, format_token_stack, format_token;
-        Color(c) => "<span style='color:{c}'>";
+        Color(c) => match (options.styling(), options.color_layer()) {
+            (Styling::Inline, ColorLayer::Foreground) => {
+                write!(output, "<span style='color:{}'>", options.palette().rgb(c))?;
+            }
+            (Styling::Inline, ColorLayer::Background) => {
+                write!(output, "<span style='background-color:{}'>", c.rgb(ColorLayer::Background))?;
+            }
+            (Styling::Classes, ColorLayer::Foreground) => {
+                write!(output, r#"<span class="color-{}">"#, ColorValue::from(c).name())?;
+            }
+            (Styling::Classes, ColorLayer::Background) => {
+                write!(output, r#"<span class="background-{}">"#, ColorValue::from(c).name())?;
+            }
+        };
         Obfuscated => "<code>",
         Bold => "<b>",
         Strikethrough => "<s>",
@@ -133,15 +740,24 @@ fn handle_format(
     Ok(())
 }
 
-/// Closes all the HTML elements opened in [`handle_format`] by the tokens in `format_token_stack`.
+/// Push `element` onto `format_token_stack`, unless it does not open an HTML element.
+///
+/// [`Format::Reset`] and [`Format::Unknown`] are never stored, so [`close_formatting_tags`] can
+/// rely on every entry having a closing tag, no matter what tokens an importer produced.
+fn push_element(format_token_stack: &mut Vec<Element>, element: Element) {
+    if !matches!(element, Element::Format(Format::Reset | Format::Unknown(_))) {
+        format_token_stack.push(element);
+    }
+}
+
+/// Closes all the HTML elements opened in [`handle_element`] by the tokens in `format_token_stack`.
 ///
 /// # Errors
 ///
-/// - [`ExportError::UnexpectedToken`] if `format_token_stack` contains [`Format::Reset`]
 /// - [`ExportError::Io`] if it cannot write into `output`
-fn close_formatting_tags(
+pub fn close_formatting_tags(
     output: &mut Utf8Writer<impl Write>,
-    format_token_stack: &mut Vec<Format>,
+    format_token_stack: &mut Vec<Element>,
 ) -> Result<(), ExportError> {
     /// Generates a match statement with [`Format`] variants to write the given HTML (containing
     /// closing tags) into `output`.
@@ -156,14 +772,23 @@ fn close_formatting_tags(
                 $(
                     Format::$format => $output.write_str($html)?
                 ),+ ,
-                Format::Reset => return Err(
-                    ExportError::UnexpectedToken(Token::Format(Format::Reset))
-                ),
+                // Filtered out by [`push_element`]
+                Format::Reset | Format::Unknown(_) => {
+                    unreachable!("`push_element` never stores {:?}", $format_token)
+                }
             }
         };
     }
 
-    while let Some(format_token) = format_token_stack.pop() {
+    while let Some(element) = format_token_stack.pop() {
+        let format_token = match element {
+            Element::Format(format) => format,
+            Element::Role(role) => {
+                write!(output, "</{}>", role_tag(role))?;
+                continue;
+            }
+        };
+
         close_html!(
             output, format_token;
             Color => "</span>";
@@ -178,8 +803,97 @@ fn close_formatting_tags(
     Ok(())
 }
 
+/// Write the `lang` and `dir` attributes from `options` to `output`, each starting with a space.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+pub fn language_attributes(
+    output: &mut Utf8Writer<impl Write>,
+    options: &ExportOptions,
+) -> std::io::Result<()> {
+    output.write_str(r#" lang=""#)?;
+    insert_string_as_html(output, options.language())?;
+    write!(output, r#"" dir="{}""#, options.direction().attribute())
+}
+
+/// Write everything that comes before the contents of the work to `output`, up to and including
+/// the `<article>`: the start of the document and the `<body>` if it is standalone, and the title
+/// heading if `options` renders format codes in metadata, taking its `id` from `ids`.
+///
+/// # Errors
+///
+/// - [`ExportError::Io`] if it cannot write into `output`
+pub fn start_article(
+    output: &mut Utf8Writer<impl Write>,
+    metadata: &[Metadata],
+    options: &ExportOptions,
+    ids: &mut Ids,
+) -> Result<(), ExportError> {
+    let standalone = options.document_kind() == DocumentKind::Standalone;
+    if standalone {
+        start_document(output, metadata, options)?;
+        output.write_str("<body>")?;
+    }
+
+    if options.metadata_format_codes() == MetadataFormatCodes::Render {
+        title_heading(output, metadata, options, ids)?;
+    }
+
+    output.write_str("<article")?;
+    if !standalone {
+        language_attributes(output, options)?;
+    }
+    article_style(output, options)?;
+
+    Ok(())
+}
+
+/// Write the `style` or `class` attribute of the `<article>` to `output`, closing its tag, with
+/// the writing mode and spacing from `options`.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+pub fn article_style(
+    output: &mut Utf8Writer<impl Write>,
+    options: &ExportOptions,
+) -> std::io::Result<()> {
+    let writing_mode = options.writing_mode();
+    let vertical = writing_mode.is_vertical();
+    let proportional = options.proportional_spacing();
+
+    match options.styling() {
+        // Most readable
+        Styling::Inline if !vertical && !proportional => {
+            output.write_str(" style=white-space:break-spaces>")
+        }
+        Styling::Inline => {
+            output.write_str(r#" style="white-space:break-spaces"#)?;
+            if vertical {
+                write!(output, ";writing-mode:{}", writing_mode.css())?;
+            }
+            if proportional {
+                let feature = if vertical { "vpal" } else { "palt" };
+                write!(output, ";font-feature-settings:'{feature}'")?;
+            }
+            output.write_str(r#"">"#)
+        }
+        Styling::Classes => {
+            output.write_str(r#" class="book"#)?;
+            if vertical {
+                write!(output, " {}", writing_mode.css())?;
+            }
+            if proportional {
+                output.write_str(" proportional")?;
+            }
+            output.write_str(r#"">"#)
+        }
+    }
+}
+
 /// With the given [`Metadata`], write some HTML boilerplate, inlcuding `"<head>....</head>"` to
-/// `output`.
+/// `output`, with the language, text direction, and extra `<head>` content from `options`.
 ///
 /// # Errors
 ///
@@ -187,22 +901,189 @@ fn close_formatting_tags(
 pub fn start_document(
     output: &mut Utf8Writer<impl Write>,
     metadata: &[Metadata],
+    options: &ExportOptions,
 ) -> std::io::Result<()> {
-    // Should this really be assuming English and LTR text?
-    output
-        .write_str(r#"<!DOCTYPE html><html lang="en" dir="ltr"><head><meta charset="utf-8" />"#)?;
+    output.write_str("<!DOCTYPE html><html")?;
+    language_attributes(output, options)?;
+    output.write_str(r#"><head><meta charset="utf-8" />"#)?;
 
     for data in metadata {
         match data {
-            // These should be using [`write_string_as_html`]
-            Metadata::Title(t) => write!(output, r#"<title>{t}</title>"#)?,
-            Metadata::Author(a) => write!(output, r#"<meta name="author" content="{a}" />"#)?,
+            Metadata::Title(t) => {
+                output.write_str("<title>")?;
+                insert_string_as_html(output, &strip_format_codes(t))?;
+                output.write_str("</title>")?;
+            }
+            Metadata::Author(a) => {
+                output.write_str(r#"<meta name="author" content=""#)?;
+                insert_string_as_html(output, &strip_format_codes(a))?;
+                output.write_str(r#"" />"#)?;
+            }
+            // Written as the `lang` attribute, see `ExportOptions::with_work_language`
+            Metadata::Language(_) => (),
+            Metadata::Date(_)
+            | Metadata::Description(_)
+            | Metadata::Generator(_)
+            | Metadata::Custom { .. } => {
+                output.write_str(r#"<meta name=""#)?;
+                insert_string_as_html(output, data.key())?;
+                output.write_str(r#"" content=""#)?;
+                insert_string_as_html(output, &strip_format_codes(data.value()))?;
+                output.write_str(r#"" />"#)?;
+            }
         }
     }
 
-    output.write_str(
-        r#"<meta name="viewport" content="width=device-width, initial-scale=1.0" /></head>"#,
-    )?;
+    output
+        .write_str(r#"<meta name="viewport" content="width=device-width, initial-scale=1.0" />"#)?;
+    if let Some(extra_head) = options.extra_head() {
+        output.write_str(extra_head)?;
+    }
+    output.write_str("</head>")?;
+
+    Ok(())
+}
+
+/// Write the [`Metadata::Title`]s in `metadata` to `output` as `<h1>` headings, rendering their
+/// format codes, each with an `id` from `ids` for the title without its format codes.
+///
+/// # Errors
+///
+/// - [`ExportError::Io`] if it cannot write into `output`
+pub fn title_heading(
+    output: &mut Utf8Writer<impl Write>,
+    metadata: &[Metadata],
+    options: &ExportOptions,
+    ids: &mut Ids,
+) -> Result<(), ExportError> {
+    for data in metadata {
+        let Metadata::Title(title) = data else {
+            continue;
+        };
+
+        write!(
+            output,
+            r#"<h1 id="{}">"#,
+            ids.id(&strip_format_codes(title))
+        )?;
+
+        let mut format_token_stack: Vec<Element> = vec![];
+        for token in tokenize_formatted(title) {
+            handle_token(output, &mut format_token_stack, &token, options)?;
+        }
+        close_formatting_tags(output, &mut format_token_stack)?;
+
+        output.write_str("</h1>")?;
+    }
+
+    Ok(())
+}
+
+/// Write a [`Legend`] for every [`Format`] used in `tokens` to `output`.
+///
+/// # Errors
+///
+/// - [`ExportError::Io`] if it cannot write into `output`
+pub fn legend(
+    output: &mut Utf8Writer<impl Write>,
+    tokens: &[Token],
+    legend: &Legend,
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let used: BTreeSet<Format> = tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Format(Format::Reset | Format::Unknown(_)) => None,
+            Token::Format(format) => Some(*format),
+            _ => None,
+        })
+        .collect();
+
+    output.write_str("<aside><h2>Legend</h2><dl>")?;
+
+    for format in used {
+        let name: Box<str> = match format {
+            Format::Color(color) => ColorValue::from(color).name().into(),
+            Format::Obfuscated => "obfuscated".into(),
+            Format::Bold => "bold".into(),
+            Format::Strikethrough => "strikethrough".into(),
+            Format::Underline => "underline".into(),
+            Format::Italic => "italic".into(),
+            Format::Reset | Format::Unknown(_) => continue,
+        };
+
+        // Written in its own format
+        output.write_str("<dt>")?;
+        let mut format_token_stack: Vec<Element> = vec![];
+        handle_element(
+            output,
+            &mut format_token_stack,
+            Element::Format(format),
+            options,
+        )?;
+        insert_string_as_html(output, &name)?;
+        close_formatting_tags(output, &mut format_token_stack)?;
+        output.write_str("</dt>")?;
+
+        let hex = match format {
+            Format::Color(color) => Some(options.palette().rgb(color).to_string()),
+            _ => None,
+        };
+        let description = match (hex, legend.annotation(format)) {
+            (Some(hex), Some(annotation)) => format!("{hex}: {annotation}"),
+            (Some(hex), None) => hex,
+            (None, Some(annotation)) => annotation.to_owned(),
+            (None, None) => continue,
+        };
+
+        output.write_str("<dd>")?;
+        insert_string_as_html(output, &description)?;
+        output.write_str("</dd>")?;
+    }
+
+    output.write_str("</dl></aside>")?;
+
+    Ok(())
+}
+
+/// Write every author in `library` to `output` as a `<section>` with a list of links to their
+/// works, each at `{hash}.html`.
+///
+/// Each section has an `id` for its author, unique even if two names have the same slug.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+pub fn library_index(
+    output: &mut Utf8Writer<impl Write>,
+    library: &Library,
+) -> std::io::Result<()> {
+    let mut ids = Ids::new();
+
+    for (author, works) in library.authors() {
+        let name = if author.is_empty() {
+            "Unknown author"
+        } else {
+            author
+        };
+
+        write!(output, r#"<section id="{}"><h2>"#, ids.id(name))?;
+        insert_string_as_html(output, name)?;
+        output.write_str("</h2><ul>")?;
+
+        for work in works {
+            write!(output, r#"<li><a href="{}.html">"#, work.hash())?;
+            if work.title().is_empty() {
+                output.write_str("Untitled")?;
+            } else {
+                insert_string_as_html(output, work.title())?;
+            }
+            let plural = if work.pages() == 1 { "" } else { "s" };
+            write!(output, "</a> ({} page{plural})</li>", work.pages())?;
+        }
+
+        output.write_str("</ul></section>")?;
+    }
 
     Ok(())
 }
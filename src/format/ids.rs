@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Generating `id`s for anchors, like headings, sections, and notes.
+//!
+//! See [`Ids`].
+
+use std::collections::{HashMap, HashSet};
+
+/// Hands out `id`s that are unique within a document, and the same every time the same document
+/// is exported.
+///
+/// Each `id` is a slug of some text, with a counter appended if that slug was already handed out,
+/// so repeated titles still get distinct anchors. The same calls in the same order always return
+/// the same `id`s.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::export::Ids;
+///
+/// let mut ids = Ids::new();
+///
+/// assert_eq!(ids.id("Chapter One").as_ref(), "chapter-one");
+/// assert_eq!(ids.id("chapter one!").as_ref(), "chapter-one-2");
+/// assert_eq!(ids.id("Chapter One").as_ref(), "chapter-one-3");
+/// assert_eq!(ids.numbered("note").as_ref(), "note-1");
+/// assert_eq!(ids.numbered("note").as_ref(), "note-2");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ids {
+    /// Every `id` handed out so far.
+    used: HashSet<Box<str>>,
+    /// The last counter tried for each slug or prefix.
+    counters: HashMap<Box<str>, usize>,
+}
+
+impl Ids {
+    /// Creates a new [`Ids`] that hasn't handed out any `id`s.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a slug of `text`, suitable for an `id` or a URL fragment.
+    ///
+    /// Letters and numbers are lowercased and kept, including those outside of ASCII, and each run
+    /// of anything else becomes a single `'-'`, except at the start or end. If nothing is left,
+    /// the slug is `"id"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::export::Ids;
+    ///
+    /// assert_eq!(Ids::slug("  The End?! (Part 2) "), "the-end-part-2");
+    /// assert_eq!(Ids::slug("Über Straße"), "über-straße");
+    /// assert_eq!(Ids::slug("§§§"), "id");
+    /// ```
+    #[must_use]
+    pub fn slug(text: &str) -> String {
+        let mut slug = String::with_capacity(text.len());
+        let mut separate = false;
+
+        for char in text.chars() {
+            if char.is_alphanumeric() {
+                if separate && !slug.is_empty() {
+                    slug.push('-');
+                }
+                slug.extend(char.to_lowercase());
+                separate = false;
+            } else {
+                separate = true;
+            }
+        }
+
+        if slug.is_empty() {
+            slug.push_str("id");
+        }
+        slug
+    }
+
+    /// Returns an `id` for `text`: its [slug][`Self::slug`], or if that was already handed out,
+    /// the slug followed by the first counter from 2 that makes it unique, like `"intro-2"`.
+    pub fn id(&mut self, text: &str) -> Box<str> {
+        let slug = Self::slug(text);
+        if !self.used.contains(slug.as_str()) {
+            return self.claim(slug);
+        }
+
+        self.next_free(&slug, 2)
+    }
+
+    /// Returns the next `id` in a numbered series, starting from 1, like `"note-1"`, skipping any
+    /// that were already handed out.
+    pub fn numbered(&mut self, prefix: &str) -> Box<str> {
+        self.next_free(&Self::slug(prefix), 1)
+    }
+
+    /// Returns `"{slug}-{counter}"` for the first counter, starting from the last one tried for
+    /// `slug` or `first`, that hasn't been handed out.
+    fn next_free(&mut self, slug: &str, first: usize) -> Box<str> {
+        let mut counter = self.counters.get(slug).map_or(first, |last| last + 1);
+
+        loop {
+            let id = format!("{slug}-{counter}");
+            if !self.used.contains(id.as_str()) {
+                self.counters.insert(slug.into(), counter);
+                return self.claim(id);
+            }
+            counter += 1;
+        }
+    }
+
+    /// Mark `id` as handed out, then return it.
+    fn claim(&mut self, id: String) -> Box<str> {
+        let id: Box<str> = id.into();
+        self.used.insert(id.clone());
+        id
+    }
+}
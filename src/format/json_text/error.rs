@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Error definitions for [`super::JsonText`].
+//!
+//! See [`TokenizeError`].
+
+use crate::format::limits::Limit;
+
+/// All the errors that could occur while tokenizing JSON text components.
+#[allow(clippy::module_name_repetitions)] // This will be exported outside of `error`
+#[derive(thiserror::Error, Debug)]
+pub enum TokenizeError {
+    /// Encountered when the input is not valid JSON.
+    ///
+    /// The error includes the line and column that it was found at.
+    #[error("invalid JSON text: {0}")]
+    Json(serde_json::Error),
+    /// Encountered when the input or output exceeds one of the configured
+    /// [`Limits`][`crate::format::limits::Limits`].
+    #[error("exceeded resource limit: {0}")]
+    LimitExceeded(#[from] Limit),
+    /// Encoutered when an I/O action fails in some way.
+    #[error("could not perform I/O action: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parsing for Minecraft's raw JSON text components, as used by signs, book pages, and `tellraw`.
+//!
+//! See [`JsonText`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     import::JsonText,
+//!     syntax::{minecraft::{Color, Format}, Token},
+//!     Tokenize,
+//! };
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let input = r#"{"text": "Hi ", "color": "gold", "extra": [{"text": "there", "bold": true}]}
+//! "and goodbye""#;
+//!
+//! let tokens = JsonText::tokenize_string(input)?;
+//!
+//! assert_eq!(
+//!     tokens.tokens_as_slice(),
+//!     [
+//!         Token::Format(Format::Color(Color::Gold)),
+//!         Token::Text("Hi".into()),
+//!         Token::Space,
+//!         Token::Format(Format::Bold),
+//!         Token::Text("there".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::LineBreak,
+//!         Token::Text("and".into()),
+//!         Token::Space,
+//!         Token::Text("goodbye".into()),
+//!         Token::LineBreak,
+//!     ]
+//! );
+//! #
+//! #     Ok(())
+//! # }
+//! ```
+
+use crate::{
+    format::{
+        component,
+        limits::{tokenize_limited, Limits},
+    },
+    syntax::{Token, TokenList},
+    Tokenize,
+};
+pub use error::TokenizeError;
+use serde_json::{Deserializer, Value};
+use std::io::Read;
+
+mod error;
+#[cfg(test)]
+mod test;
+
+/// Parses one or more of Minecraft's raw JSON text components, like
+/// `{"text": "Hi", "color": "gold", "bold": true, "extra": [...]}`.
+///
+/// # Expected format
+///
+/// The input is a series of JSON text components, separated by any amount of whitespace, like the
+/// lines of a sign, a few `tellraw` messages, or the pages of a book copied out of NBT data:
+/// - Each component becomes one line of the document
+/// - If a component has no text, it becomes a [`Token::ParagraphBreak`] instead
+/// - A component can be a string, an object, or an array of components, where the rest of the
+///   array inherits the formatting of the first element
+/// - Named colors, decorations like `"bold": true`, `"extra"` children, and `'§'` format codes are
+///   all supported
+/// - Hexadecimal colors become the nearest [`Color`][`crate::syntax::minecraft::Color`]
+/// - Translated text and key binds are written as their keys
+/// - Other dynamic content, like scores and selectors, is dropped
+/// - Only strict JSON is supported, not the looser SNBT syntax of recent Minecraft versions, see
+///   [`Nbt`][`crate::import::Nbt`] for that
+///
+/// There is no metadata, so the [`TokenList`] has none.
+pub struct JsonText;
+
+impl JsonText {
+    /// Parse a series of JSON text components into an abstract syntax vector.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Json`] if `components` is not a series of JSON values
+    /// - [`TokenizeError::Io`] if `components` is reading from a source that fails
+    fn tokenize_components(
+        components: impl Iterator<Item = serde_json::Result<Value>>,
+    ) -> Result<TokenList, TokenizeError> {
+        let mut tokens: Vec<Token> = vec![];
+
+        for component in components {
+            let component = component.map_err(|source| {
+                if source.is_io() {
+                    TokenizeError::Io(source.into())
+                } else {
+                    TokenizeError::Json(source)
+                }
+            })?;
+            let text = component::tokenize(&component);
+
+            if text.is_empty() {
+                tokens.push(Token::ParagraphBreak);
+            } else {
+                tokens.extend(text);
+                tokens.push(Token::LineBreak);
+            }
+        }
+
+        Ok(TokenList::new_from_boxed(Box::new([]), tokens.into()))
+    }
+
+    /// Parse a series of JSON text components from a reader, like a [`std::fs::File`], into an
+    /// abstract syntax vector, according to `limits`.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Json`] if `input` is not a series of JSON values
+    /// - [`TokenizeError::LimitExceeded`] if the input or output exceeds `limits`
+    /// - [`TokenizeError::Io`] if it cannot read from `input`
+    pub fn tokenize_reader_with_limits(
+        input: impl Read,
+        limits: &Limits,
+    ) -> Result<TokenList, TokenizeError> {
+        tokenize_limited(input, limits, |input| {
+            Self::tokenize_components(Deserializer::from_reader(input).into_iter())
+        })
+    }
+}
+
+impl Tokenize for JsonText {
+    type Error = TokenizeError;
+
+    /// Parse a series of JSON text components into an abstract syntax vector.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Json`] if `input` is not a series of JSON values
+    fn tokenize_string(input: &str) -> Result<TokenList, Self::Error> {
+        Self::tokenize_components(Deserializer::from_str(input).into_iter())
+    }
+
+    /// Parse a series of JSON text components from a reader, like a [`std::fs::File`], into an
+    /// abstract syntax vector, with the default [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// See [`JsonText::tokenize_reader_with_limits`].
+    fn tokenize_reader(input: impl Read) -> Result<TokenList, Self::Error> {
+        Self::tokenize_reader_with_limits(input, &Limits::default())
+    }
+}
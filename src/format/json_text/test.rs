@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for parsing [JSON text components][`super::JsonText`].
+
+use super::{JsonText, TokenizeError};
+use crate::{
+    syntax::{
+        minecraft::{Color, Format},
+        Token,
+    },
+    Tokenize,
+};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn test_json_text() -> Result {
+    use Token::{LineBreak, ParagraphBreak, Space};
+
+    // The lines of a sign, one component after another
+    let input = r#"{"text": "Welcome", "color": "dark_green", "bold": true}
+"" ["to ", {"text": "the", "italic": true}]
+  "village""#;
+
+    let tokens = JsonText::tokenize_string(input)?;
+    assert!(tokens.metadata_as_slice().is_empty());
+    assert_eq!(
+        tokens.tokens_as_slice(),
+        [
+            Token::Format(Format::Color(Color::DarkGreen)),
+            Token::Format(Format::Bold),
+            Token::Text("Welcome".into()),
+            Token::Format(Format::Reset),
+            LineBreak,
+            ParagraphBreak,
+            Token::Text("to".into()),
+            Space,
+            Token::Format(Format::Italic),
+            Token::Text("the".into()),
+            Token::Format(Format::Reset),
+            LineBreak,
+            Token::Text("village".into()),
+            LineBreak,
+        ]
+    );
+    assert_eq!(JsonText::tokenize_reader(input.as_bytes())?, tokens);
+
+    assert!(JsonText::tokenize_string("")?.tokens_as_slice().is_empty());
+
+    let error = JsonText::tokenize_string("\"fine\"\n{\"text\": oops}").unwrap_err();
+    assert!(matches!(&error, TokenizeError::Json(source) if source.line() == 2));
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Resource limits for importers.
+//!
+//! See [`Limits`].
+
+use super::lines::Piece;
+use crate::syntax::TokenList;
+use std::io::{BufReader, Read, Take};
+
+/// Upper bounds on the resources an importer may consume while tokenizing a single document.
+///
+/// Protects against pathological inputs, like a document that is one enormous line or that is
+/// made of millions of format codes. The defaults are far larger than any book Minecraft: Java
+/// Edition can produce.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::Limits;
+///
+/// let limits = Limits::default().with_max_tokens(1_000);
+/// assert_eq!(limits.max_tokens(), 1_000);
+///
+/// // Effectively disables all limits
+/// assert_eq!(Limits::none().max_input_size(), usize::MAX);
+/// ```
+#[allow(clippy::struct_field_names)] // Each of these is the maximum of something
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// The maximum size of the whole input, in bytes.
+    max_input_size: usize,
+    /// The maximum length of a single line of input, in bytes, excluding the line ending.
+    max_line_length: usize,
+    /// The maximum number of [`Token`][`crate::syntax::Token`]s in the output.
+    max_tokens: usize,
+    /// Whether to split lines longer than the maximum line length, rather than failing.
+    split_long_lines: bool,
+}
+
+impl Limits {
+    /// The default maximum size of the whole input: 16 MiB.
+    pub const DEFAULT_MAX_INPUT_SIZE: usize = 16 * 1024 * 1024;
+    /// The default maximum length of a single line of input: 64 KiB.
+    pub const DEFAULT_MAX_LINE_LENGTH: usize = 64 * 1024;
+    /// The default maximum number of tokens in the output.
+    pub const DEFAULT_MAX_TOKENS: usize = 4 * 1024 * 1024;
+
+    /// Creates a new [`Limits`] with the default limits.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_input_size: Self::DEFAULT_MAX_INPUT_SIZE,
+            max_line_length: Self::DEFAULT_MAX_LINE_LENGTH,
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+            split_long_lines: false,
+        }
+    }
+
+    /// Creates a new [`Limits`] that will never be exceeded.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            max_input_size: usize::MAX,
+            max_line_length: usize::MAX,
+            max_tokens: usize::MAX,
+            split_long_lines: false,
+        }
+    }
+
+    /// Returns the maximum size of the whole input, in bytes.
+    #[must_use]
+    pub const fn max_input_size(&self) -> usize {
+        self.max_input_size
+    }
+
+    /// Returns the maximum length of a single line of input, in bytes.
+    #[must_use]
+    pub const fn max_line_length(&self) -> usize {
+        self.max_line_length
+    }
+
+    /// Returns the maximum number of tokens in the output.
+    #[must_use]
+    pub const fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Returns whether lines longer than the maximum line length are split, rather than failing.
+    #[must_use]
+    pub const fn split_long_lines(&self) -> bool {
+        self.split_long_lines
+    }
+
+    /// Sets the maximum size of the whole input, in bytes.
+    #[must_use]
+    pub const fn with_max_input_size(mut self, max_input_size: usize) -> Self {
+        self.max_input_size = max_input_size;
+        self
+    }
+
+    /// Sets the maximum length of a single line of input, in bytes.
+    #[must_use]
+    pub const fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Sets the maximum number of tokens in the output.
+    #[must_use]
+    pub const fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Sets whether lines longer than the maximum line length are split, rather than failing.
+    ///
+    /// When set, an overlong line is read in pieces no longer than the maximum line length, and the
+    /// words on either side of each split become separate
+    /// [`Token::Text`][`crate::syntax::Token::Text`]s. Either way, an overlong line is never held
+    /// in memory all at once while reading.
+    #[must_use]
+    pub const fn with_split_long_lines(mut self, split_long_lines: bool) -> Self {
+        self.split_long_lines = split_long_lines;
+        self
+    }
+
+    /// Returns how many bytes to read from an input, at most.
+    ///
+    /// Reading one byte past the maximum is enough to know that the input is too large.
+    pub(crate) fn max_read(&self) -> u64 {
+        u64::try_from(self.max_input_size).map_or(u64::MAX, |max| max.saturating_add(1))
+    }
+
+    /// Returns [`Limit::InputSize`] if `size` is larger than the maximum input size.
+    pub(crate) const fn check_input_size(&self, size: usize) -> Result<(), Limit> {
+        if size > self.max_input_size {
+            return Err(Limit::InputSize(self.max_input_size));
+        }
+
+        Ok(())
+    }
+
+    /// Returns [`Limit::LineLength`] if `piece` was split from a line longer than the maximum line
+    /// length, unless those lines are split.
+    pub(crate) const fn check_piece<S>(&self, piece: &Piece<S>) -> Result<(), Limit> {
+        if !piece.ends_line && !self.split_long_lines {
+            return Err(Limit::LineLength(self.max_line_length));
+        }
+
+        Ok(())
+    }
+
+    /// Returns [`Limit::Tokens`] if `count` is more than the maximum number of tokens.
+    pub(crate) const fn check_tokens(&self, count: usize) -> Result<(), Limit> {
+        if count > self.max_tokens {
+            return Err(Limit::Tokens(self.max_tokens));
+        }
+
+        Ok(())
+    }
+}
+
+/// Run `tokenize` on `input`, reading no more of it than the maximum input size of `limits`, then
+/// check the number of tokens it returns.
+///
+/// For importers that don't enforce any limits themselves.
+///
+/// # Errors
+///
+/// - [`Limit::InputSize`] if `input` is larger than the maximum input size
+/// - [`Limit::Tokens`] if `tokenize` returns more than the maximum number of tokens
+/// - Anything that `tokenize` returns
+pub fn tokenize_limited<R: Read, E: From<Limit>>(
+    input: R,
+    limits: &Limits,
+    tokenize: impl FnOnce(&mut BufReader<Take<R>>) -> Result<TokenList, E>,
+) -> Result<TokenList, E> {
+    let mut input = BufReader::new(input.take(limits.max_read()));
+    let result = tokenize(&mut input);
+
+    // Truncating the input could have caused any other error, so this takes precedence
+    if input.get_ref().limit() == 0 {
+        return Err(Limit::InputSize(limits.max_input_size()).into());
+    }
+
+    let tokens = result?;
+    limits.check_tokens(tokens.tokens_as_slice().len())?;
+
+    Ok(tokens)
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A limit from [`Limits`] that was exceeded, holding the value of that limit.
+#[derive(thiserror::Error, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// The whole input was larger than this many bytes.
+    #[error("input is larger than {0} bytes")]
+    InputSize(usize),
+    /// A line was longer than this many bytes.
+    #[error("line is longer than {0} bytes")]
+    LineLength(usize),
+    /// The output had more than this many tokens.
+    #[error("output has more than {0} tokens")]
+    Tokens(usize),
+}
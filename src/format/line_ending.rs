@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The character sequence that ends each line of text output.
+//!
+//! See [`LineEnding`].
+
+use std::fmt::Display;
+
+/// The character sequence that ends each line, for the exporters that write lines of text.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::export::LineEnding;
+///
+/// assert_eq!(LineEnding::Lf.as_str(), "\n");
+/// assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// A line feed, `"\n"`, as on Unix-like systems and the web.
+    #[default]
+    Lf,
+    /// A carriage return and a line feed, `"\r\n"`, as on Windows and DOS, and required by some
+    /// formats, like BRF.
+    CrLf,
+    /// Whichever of [`Self::Lf`] and [`Self::CrLf`] is native to the platform that this was
+    /// compiled for.
+    Platform,
+}
+
+impl LineEnding {
+    /// Returns the characters that end a line.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::CrLf => "\r\n",
+            Self::Platform if cfg!(windows) => "\r\n",
+            Self::Lf | Self::Platform => "\n",
+        }
+    }
+
+    /// Returns the name of the line ending, which is also its [`Display`] representation.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Lf => "lf",
+            Self::CrLf => "crlf",
+            Self::Platform => "platform",
+        }
+    }
+}
+
+impl Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reading input line by line, without ever holding more than one limited piece of a line.
+//!
+//! [`std::io::BufRead::lines`] buffers a whole line at a time, however long it is, so a single
+//! enormous line can exhaust memory before any limit is checked. [`BoundedLines`] instead splits
+//! lines that are longer than its maximum into [`Piece`]s.
+
+use std::io::{BufRead, ErrorKind};
+
+/// Part or all of a line of input, excluding the line ending.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Piece<S> {
+    /// The text of this piece of the line.
+    pub text: S,
+    /// Whether this is the last piece of its line.
+    pub ends_line: bool,
+}
+
+impl<S> Piece<S> {
+    /// Creates a new [`Piece`] that is a whole line.
+    pub const fn whole(text: S) -> Self {
+        Self {
+            text,
+            ends_line: true,
+        }
+    }
+}
+
+/// Returns the byte index to split a line that is longer than `max_length` at.
+///
+/// Lines are split at the last character boundary that fits, but never between a `'§'` and the
+/// format code that follows it. The first piece is only longer than `max_length` if even a single
+/// character (or format code) is wider than it, so that splitting always makes progress.
+fn split_index(line: &[u8], max_length: usize) -> usize {
+    /// The UTF-8 encoding of `'§'`.
+    const SECTION_SIGN: &[u8] = "§".as_bytes();
+
+    let is_boundary = |index: usize| line.get(index).is_none_or(|byte| byte & 0xC0 != 0x80);
+    let next_boundary = |mut index: usize| {
+        index += 1;
+        while !is_boundary(index) {
+            index += 1;
+        }
+        index
+    };
+
+    let mut end = max_length.min(line.len());
+    while !is_boundary(end) {
+        end -= 1;
+    }
+    if line[..end].ends_with(SECTION_SIGN) {
+        end -= SECTION_SIGN.len();
+    }
+
+    if end == 0 {
+        end = next_boundary(0);
+        if line[..end] == *SECTION_SIGN {
+            end = next_boundary(end);
+        }
+    }
+
+    end.min(line.len())
+}
+
+/// Split `line` into [`Piece`]s that are no longer than `max_length` bytes.
+///
+/// See [`split_index`] for where lines are split.
+pub fn split_str(line: &str, max_length: usize) -> impl Iterator<Item = Piece<&str>> {
+    let mut rest = Some(line);
+
+    std::iter::from_fn(move || {
+        let line = rest?;
+        if line.len() <= max_length {
+            rest = None;
+            return Some(Piece::whole(line));
+        }
+
+        let (text, after) = line.split_at(split_index(line.as_bytes(), max_length));
+        rest = Some(after);
+        Some(Piece {
+            text,
+            ends_line: false,
+        })
+    })
+}
+
+/// An iterator over the lines of a reader, split into [`Piece`]s that are no longer than a maximum
+/// length in bytes.
+///
+/// Like [`BufRead::lines`], lines end with `'\n'` or `"\r\n"`, which are not included, and invalid
+/// UTF-8 is an [`std::io::Error`]. Never holds more than a couple of bytes past the maximum length
+/// in memory.
+#[derive(Debug)]
+pub struct BoundedLines<R: BufRead> {
+    /// The input being read.
+    reader: R,
+    /// The maximum length of a [`Piece`], in bytes.
+    max_length: usize,
+    /// Bytes that have been read, but not yet returned.
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> BoundedLines<R> {
+    /// Creates a new [`BoundedLines`] that reads from `reader`.
+    pub const fn new(reader: R, max_length: usize) -> Self {
+        Self {
+            reader,
+            max_length,
+            buffer: vec![],
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub const fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Read until the buffer holds the end of a line, enough to know that the line is too long, or
+    /// the rest of the input.
+    fn fill(&mut self) -> std::io::Result<()> {
+        // Enough for a line of the maximum length followed by `"\r\n"`
+        let wanted = self.max_length.saturating_add(2);
+
+        while self.buffer.last() != Some(&b'\n') && self.buffer.len() < wanted {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            if available.is_empty() {
+                break;
+            }
+
+            let take = available
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map_or(available.len(), |newline| newline + 1)
+                .min(wanted - self.buffer.len());
+            self.buffer.extend_from_slice(&available[..take]);
+            self.reader.consume(take);
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for BoundedLines<R> {
+    type Item = std::io::Result<Piece<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(error) = self.fill() {
+            return Some(Err(error));
+        }
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        // The length of the line without its ending, unless it is too long to have found the end
+        let line_length = match self.buffer.strip_suffix(b"\n") {
+            Some(line) => Some(line.strip_suffix(b"\r").unwrap_or(line).len()),
+            // The rest of the input has no line ending
+            None if self.buffer.len() < self.max_length.saturating_add(2) => {
+                Some(self.buffer.len())
+            }
+            None => None,
+        };
+
+        match line_length {
+            Some(line_length) if line_length <= self.max_length => {
+                let mut text = std::mem::take(&mut self.buffer);
+                text.truncate(line_length);
+                Some(to_piece(text, true))
+            }
+            _ => {
+                // The rest of the line, including its ending, stays in the buffer
+                let line = &self.buffer[..line_length.unwrap_or(self.buffer.len())];
+                let rest = self.buffer.split_off(split_index(line, self.max_length));
+                Some(to_piece(std::mem::replace(&mut self.buffer, rest), false))
+            }
+        }
+    }
+}
+
+/// Convert `bytes` into a [`Piece`], or an [`std::io::Error`] if it is not valid UTF-8.
+fn to_piece(bytes: Vec<u8>, ends_line: bool) -> std::io::Result<Piece<String>> {
+    String::from_utf8(bytes)
+        .map(|text| Piece { text, ends_line })
+        .map_err(|error| std::io::Error::new(ErrorKind::InvalidData, error))
+}
@@ -20,5 +20,27 @@
 //! This module should never be public. Instead, these modules' implementations should be
 //! re-exported under [`crate::import`] and [`crate::export`].
 
+pub mod brf;
+pub mod capabilities;
+pub mod compatibility;
+pub mod component;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod cp437;
+pub mod ghostwriter;
 pub mod html;
+pub mod ids;
+pub mod json_text;
+pub mod limits;
+pub mod line_ending;
+pub mod lines;
+pub mod names;
+pub mod nbt;
+pub mod plain_text;
+pub mod scan;
+pub mod ssml;
 pub mod stendhal;
+pub mod suggestion;
+pub mod tellraw;
+pub mod text;
+pub mod text_encoding;
@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The names of the supported formats, shared by every front-end.
+//!
+//! See [`InputFormat`] and [`OutputFormat`].
+
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// Returned when parsing a format name that is not supported.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown format '{name}', expected one of: {expected}")]
+pub struct UnknownFormatError {
+    /// The name that was not recognized.
+    name: Box<str>,
+    /// The supported names, separated by commas.
+    expected: Box<str>,
+}
+
+impl UnknownFormatError {
+    /// Returns the name that was not recognized.
+    #[must_use]
+    pub const fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Generates an enum of formats, with a canonical name and any number of aliases for each.
+macro_rules! formats {
+    (
+        $( #[$attr:meta] )*
+        $enum:ident {
+            $(
+                $( #[$variant_attr:meta] )*
+                $variant:ident => $name:literal $( | $alias:literal )*
+            ),+ $(,)?
+        }
+    ) => {
+        $( #[$attr] )*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $enum {
+            $(
+                $( #[$variant_attr] )*
+                $variant
+            ),+
+        }
+
+        impl $enum {
+            /// Every supported format.
+            pub const ALL: &'static [Self] = &[ $( Self::$variant ),+ ];
+
+            /// Returns the canonical name of the format, which is also its [`Display`]
+            /// representation.
+            #[must_use]
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $( Self::$variant => $name ),+
+                }
+            }
+
+            /// Returns the other names that parse as this format, not including
+            /// [`Self::name`].
+            #[must_use]
+            pub const fn aliases(self) -> &'static [&'static str] {
+                match self {
+                    $( Self::$variant => &[ $( $alias ),* ] ),+
+                }
+            }
+        }
+
+        impl Display for $enum {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $enum {
+            type Err = UnknownFormatError;
+
+            /// Parse a format from its name or one of its aliases, ignoring ASCII case.
+            ///
+            /// # Errors
+            ///
+            /// - [`UnknownFormatError`] if `s` does not name a supported format
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|format| {
+                        std::iter::once(format.name())
+                            .chain(format.aliases().iter().copied())
+                            .any(|name| name.eq_ignore_ascii_case(s))
+                    })
+                    .ok_or_else(|| UnknownFormatError {
+                        name: s.into(),
+                        expected: Self::ALL
+                            .iter()
+                            .map(|format| format.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                            .into(),
+                    })
+            }
+        }
+    };
+}
+
+formats!(
+    /// The formats that can be [imported][`crate::import`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::import::InputFormat;
+    ///
+    /// assert_eq!("Stendhal".parse::<InputFormat>()?, InputFormat::Stendhal);
+    /// assert_eq!(InputFormat::Stendhal.to_string(), "stendhal");
+    /// # Ok::<(), crafty_novels::import::UnknownFormatError>(())
+    /// ```
+    InputFormat {
+        /// [`Stendhal`][`crate::import::Stendhal`].
+        Stendhal => "stendhal",
+        /// [`Tellraw`][`crate::import::Tellraw`].
+        Tellraw => "tellraw" | "mcfunction",
+        /// [`Nbt`][`crate::import::Nbt`].
+        Nbt => "nbt" | "snbt",
+        /// [`JsonText`][`crate::import::JsonText`].
+        JsonText => "json",
+        /// [`Ghostwriter`][`crate::import::Ghostwriter`].
+        Ghostwriter => "ghostwriter" | "ghb",
+    }
+);
+
+formats!(
+    /// The formats that can be [exported][`crate::export`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::export::OutputFormat;
+    ///
+    /// assert_eq!("htm".parse::<OutputFormat>()?, OutputFormat::Html);
+    /// assert!("docx".parse::<OutputFormat>().is_err());
+    /// # Ok::<(), crafty_novels::export::UnknownFormatError>(())
+    /// ```
+    OutputFormat {
+        /// [`Html`][`crate::export::Html`].
+        Html => "html" | "htm",
+        /// [`Text`][`crate::export::Text`].
+        Text => "text" | "txt",
+        /// [`Ssml`][`crate::export::Ssml`].
+        Ssml => "ssml",
+        /// [`Brf`][`crate::export::Brf`].
+        Brf => "brf",
+        /// [`Cp437`][`crate::export::Cp437`].
+        Cp437 => "cp437" | "ans",
+        /// [`PlainText`][`crate::export::PlainText`].
+        PlainText => "plain",
+    }
+);
+
+impl OutputFormat {
+    /// Returns the features of a work that the format can represent.
+    ///
+    /// See [`crate::Export::capabilities`].
+    #[must_use]
+    pub fn capabilities(self) -> crate::export::Capabilities {
+        use crate::{
+            export::{Brf, Cp437, Html, PlainText, Ssml, Text},
+            Export,
+        };
+
+        match self {
+            Self::Html => Html::capabilities(),
+            Self::Text => Text::capabilities(),
+            Self::Ssml => Ssml::capabilities(),
+            Self::Brf => Brf::capabilities(),
+            Self::Cp437 => Cp437::capabilities(),
+            Self::PlainText => PlainText::capabilities(),
+        }
+    }
+}
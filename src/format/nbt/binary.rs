@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reading binary NBT, as stored in world saves and sent over the network.
+//!
+//! See [`read`].
+
+use super::{tag::Tag, TokenizeError};
+
+/// How deeply lists and compounds can nest, as in Minecraft.
+pub const MAX_DEPTH: usize = 512;
+
+/// The ID of the tag that ends a compound.
+const END: u8 = 0;
+/// The ID of [`Tag::Compound`].
+const COMPOUND: u8 = 10;
+
+/// Whether `bytes` start like uncompressed binary NBT, with a compound at the root.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&COMPOUND)
+}
+
+/// Read uncompressed binary NBT, which must have a compound at the root.
+///
+/// The name of the root is ignored, as is anything after it.
+///
+/// # Errors
+///
+/// - [`TokenizeError::Binary`] if the data is malformed, ends early, or nests too deeply
+pub fn read(bytes: &[u8]) -> Result<Tag, TokenizeError> {
+    let mut reader = Reader { bytes, offset: 0 };
+
+    if reader.byte()? != COMPOUND {
+        return Err(reader.error("expected a compound at the root"));
+    }
+    reader.string()?;
+
+    reader.payload(COMPOUND, 0)
+}
+
+/// Reads values out of binary NBT, keeping track of where it is for errors.
+struct Reader<'a> {
+    /// All of the data.
+    bytes: &'a [u8],
+    /// The index of the next byte to read.
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Returns a [`TokenizeError::Binary`] at the current offset.
+    const fn error(&self, reason: &'static str) -> TokenizeError {
+        TokenizeError::Binary {
+            offset: self.offset,
+            reason,
+        }
+    }
+
+    /// Read the next `length` bytes.
+    fn take(&mut self, length: usize) -> Result<&'a [u8], TokenizeError> {
+        let bytes = self
+            .offset
+            .checked_add(length)
+            .and_then(|end| self.bytes.get(self.offset..end))
+            .ok_or_else(|| self.error("unexpected end of data"))?;
+
+        self.offset += length;
+        Ok(bytes)
+    }
+
+    /// Read the next `N` bytes as an array.
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], TokenizeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    /// Read an unsigned byte.
+    fn byte(&mut self) -> Result<u8, TokenizeError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    /// Read the length of an array or list, which is a signed 32-bit integer that can't be
+    /// negative.
+    fn length(&mut self) -> Result<usize, TokenizeError> {
+        let length = i32::from_be_bytes(self.array()?);
+        usize::try_from(length).map_err(|_| self.error("negative length"))
+    }
+
+    /// Read a string, prefixed with its length as an unsigned 16-bit integer.
+    fn string(&mut self) -> Result<String, TokenizeError> {
+        let length = u16::from_be_bytes(self.array()?);
+        let bytes = self.take(length.into())?;
+
+        decode_modified_utf8(bytes).ok_or_else(|| self.error("invalid string"))
+    }
+
+    /// Read `length` values of `N` bytes each, converting each with `convert`.
+    fn numbers<const N: usize, T>(
+        &mut self,
+        convert: fn([u8; N]) -> T,
+    ) -> Result<Vec<T>, TokenizeError> {
+        let length = self.length()?;
+        let bytes = self.take(
+            length
+                .checked_mul(N)
+                .ok_or_else(|| self.error("array is too long"))?,
+        )?;
+
+        Ok(bytes
+            .chunks_exact(N)
+            .map(|chunk| {
+                let mut array = [0; N];
+                array.copy_from_slice(chunk);
+                convert(array)
+            })
+            .collect())
+    }
+
+    /// Read the value of a tag with the ID `id`, nested `depth` lists or compounds deep.
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag, TokenizeError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+
+        Ok(match id {
+            1 => Tag::Byte(i8::from_be_bytes(self.array()?)),
+            2 => Tag::Short(i16::from_be_bytes(self.array()?)),
+            3 => Tag::Int(i32::from_be_bytes(self.array()?)),
+            4 => Tag::Long(i64::from_be_bytes(self.array()?)),
+            5 => Tag::Float(f32::from_be_bytes(self.array()?)),
+            6 => Tag::Double(f64::from_be_bytes(self.array()?)),
+            7 => Tag::ByteArray(self.numbers(i8::from_be_bytes)?),
+            8 => Tag::String(self.string()?),
+            9 => {
+                let id = self.byte()?;
+                let length = self.length()?;
+                if id == END && length > 0 {
+                    return Err(self.error("list of end tags"));
+                }
+
+                let mut values = vec![];
+                for _ in 0..length {
+                    values.push(self.payload(id, depth + 1)?);
+                }
+                Tag::List(values)
+            }
+            COMPOUND => {
+                let mut fields = vec![];
+                loop {
+                    let id = self.byte()?;
+                    if id == END {
+                        break;
+                    }
+
+                    let name = self.string()?;
+                    fields.push((name, self.payload(id, depth + 1)?));
+                }
+                Tag::Compound(fields)
+            }
+            11 => Tag::IntArray(self.numbers(i32::from_be_bytes)?),
+            12 => Tag::LongArray(self.numbers(i64::from_be_bytes)?),
+            _ => return Err(self.error("unknown tag")),
+        })
+    }
+}
+
+/// Decode Java's "modified UTF-8", which NBT strings are stored in.
+///
+/// It differs from UTF-8 by storing `'\0'` as two bytes, and characters outside of the Basic
+/// Multilingual Plane as a pair of three-byte surrogates.
+fn decode_modified_utf8(bytes: &[u8]) -> Option<String> {
+    /// Returns the bits of a continuation byte.
+    fn continuation(byte: Option<&u8>) -> Option<u16> {
+        byte.filter(|byte| *byte & 0xC0 == 0x80)
+            .map(|byte| u16::from(byte & 0x3F))
+    }
+
+    // The usual case, which only differs for those rare characters
+    if let Ok(string) = std::str::from_utf8(bytes) {
+        return Some(string.to_owned());
+    }
+
+    let mut units: Vec<u16> = vec![];
+    let mut iter = bytes.iter();
+
+    while let Some(&byte) = iter.next() {
+        units.push(match byte {
+            0x00..=0x7F => u16::from(byte),
+            0xC0..=0xDF => (u16::from(byte & 0x1F) << 6) | continuation(iter.next())?,
+            0xE0..=0xEF => {
+                let high = continuation(iter.next())?;
+                (u16::from(byte & 0x0F) << 12) | (high << 6) | continuation(iter.next())?
+            }
+            _ => return None,
+        });
+    }
+
+    String::from_utf16(&units).ok()
+}
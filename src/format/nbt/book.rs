@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Finding a written book in NBT data and converting it into tokens.
+//!
+//! See [`tokenize`].
+
+use super::{tag::Tag, TokenizeError};
+use crate::{
+    format::component,
+    syntax::{Metadata, Token, TokenList},
+};
+use serde_json::Value;
+
+/// The key of a book's contents in the components of an item, since Minecraft 1.20.5.
+const CONTENT: &str = "minecraft:written_book_content";
+
+/// Find the first written book in `tag` and convert it into tokens.
+///
+/// # Errors
+///
+/// - [`TokenizeError::NoBook`] if there is no written book in `tag`
+pub fn tokenize(tag: &Tag) -> Result<TokenList, TokenizeError> {
+    let book = find(tag).ok_or(TokenizeError::NoBook)?;
+
+    let mut metadata = vec![];
+    if let Some(title) = book.get("title").and_then(plain_text) {
+        metadata.push(Metadata::Title(title.into()));
+    }
+    if let Some(author) = book.get("author").and_then(plain_text) {
+        metadata.push(Metadata::Author(author.into()));
+    }
+
+    let mut tokens = vec![];
+    for page in book.get("pages").into_iter().flat_map(Tag::children) {
+        tokens.push(Token::ThematicBreak);
+
+        let text = page_text(page);
+        if !text.is_empty() {
+            tokens.extend(text);
+            tokens.push(Token::LineBreak);
+        }
+    }
+
+    Ok(TokenList::new_from_boxed(metadata.into(), tokens.into()))
+}
+
+/// Returns the first compound in `tag`, searching depth-first, that holds the contents of a book.
+///
+/// That's either the value of [`CONTENT`], or a compound with a list of `"pages"`, like the `"tag"`
+/// of an item before Minecraft 1.20.5.
+fn find(tag: &Tag) -> Option<&Tag> {
+    if let Some(content) = tag.get(CONTENT) {
+        return Some(content);
+    }
+    if matches!(tag.get("pages"), Some(Tag::List(_))) {
+        return Some(tag);
+    }
+
+    tag.children().find_map(find)
+}
+
+/// Returns the text of a plain string, which may be filterable, like `{raw: "Title"}`.
+fn plain_text(tag: &Tag) -> Option<&str> {
+    tag.get("raw").unwrap_or(tag).as_str()
+}
+
+/// Convert a page into tokens.
+///
+/// A page can be filterable, like `{raw: ...}`, and is either a text component stored as NBT, or a
+/// string. A string is a JSON text component if it parses as one, and otherwise plain text, like
+/// the pages of a book that was never signed.
+fn page_text(page: &Tag) -> Vec<Token> {
+    let page = page.get("raw").unwrap_or(page);
+
+    let Tag::String(text) = page else {
+        return component::tokenize(&page.to_json());
+    };
+
+    match serde_json::from_str(text) {
+        Ok(json @ (Value::Object(_) | Value::Array(_) | Value::String(_))) => {
+            component::tokenize(&json)
+        }
+        _ => component::tokenize(&Value::String(text.clone())),
+    }
+}
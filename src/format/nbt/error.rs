@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Error definitions for [`super::Nbt`].
+//!
+//! See [`TokenizeError`].
+
+use crate::format::limits::Limit;
+
+/// All the errors that could occur while tokenizing NBT or SNBT data.
+#[allow(clippy::module_name_repetitions)] // This will be exported outside of `error`
+#[derive(thiserror::Error, Debug)]
+pub enum TokenizeError {
+    /// Encountered when binary NBT data is malformed.
+    #[error("invalid NBT at byte {offset}: {reason}")]
+    Binary {
+        /// The byte of the (decompressed) data where the problem was found, starting from zero.
+        offset: usize,
+        /// What was wrong with the data.
+        reason: &'static str,
+    },
+    /// Encountered when SNBT text is malformed.
+    #[error("invalid SNBT at character {position}: {reason}")]
+    Snbt {
+        /// The character of the text where the problem was found, starting from one.
+        position: usize,
+        /// What was wrong with the text.
+        reason: &'static str,
+    },
+    /// Encountered when the data is valid, but doesn't contain a written book.
+    #[error("no written book was found in the NBT data")]
+    NoBook,
+    /// Encountered when the input, after decompressing it, or the output exceeds one of the
+    /// configured [`Limits`][`crate::format::limits::Limits`].
+    #[error("exceeded resource limit: {0}")]
+    LimitExceeded(#[from] Limit),
+    /// Encountered when a gzip or zip input cannot be decompressed.
+    #[cfg(feature = "compression")]
+    #[error("could not decompress input: {0}")]
+    Decompress(#[from] crate::format::compression::DecompressError),
+    /// Encoutered when an I/O action fails in some way.
+    #[error("could not perform I/O action: {0}")]
+    Io(#[from] std::io::Error),
+}
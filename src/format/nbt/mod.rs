@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parsing for the NBT data of Minecraft's written books.
+//! See [`Nbt`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     import::Nbt,
+//!     syntax::{minecraft::Format, Metadata, Token},
+//!     Tokenize,
+//! };
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let input = r#"Steve has the following entity data: {
+//!     count: 1, id: "minecraft:written_book",
+//!     components: {"minecraft:written_book_content": {
+//!         author: "RemasteredArch", title: {raw: "crafty_novels"},
+//!         pages: [{raw: '{"text":"Page one","bold":true}'}, {raw: "§oPage two"}]
+//!     }}
+//! }"#;
+//!
+//! let tokens = Nbt::tokenize_string(input)?;
+//!
+//! assert_eq!(
+//!     tokens.metadata_as_slice(),
+//!     [
+//!         Metadata::Title("crafty_novels".into()),
+//!         Metadata::Author("RemasteredArch".into()),
+//!     ]
+//! );
+//! assert_eq!(
+//!     tokens.tokens_as_slice(),
+//!     [
+//!         Token::ThematicBreak,
+//!         Token::Format(Format::Bold),
+//!         Token::Text("Page".into()),
+//!         Token::Space,
+//!         Token::Text("one".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::LineBreak,
+//!         Token::ThematicBreak,
+//!         Token::Format(Format::Italic),
+//!         Token::Text("Page".into()),
+//!         Token::Space,
+//!         Token::Text("two".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::LineBreak,
+//!     ]
+//! );
+//! #
+//! #     Ok(())
+//! # }
+//! ```
+
+use crate::{format::limits::Limits, syntax::TokenList, Tokenize};
+pub use error::TokenizeError;
+use std::io::Read;
+use tag::Tag;
+
+mod binary;
+mod book;
+mod error;
+mod snbt;
+mod tag;
+#[cfg(test)]
+mod test;
+
+/// Parses a written book out of Minecraft's NBT data, either binary or as SNBT text.
+///
+/// This reads books straight out of a world save, like a player's `playerdata/<uuid>.dat`, or out
+/// of what `/data get` prints, without any mods.
+///
+/// # Expected format
+///
+/// Binary NBT has a compound at the root, and may be gzip-compressed, as world saves are, with the
+/// `compression` feature, see [`crate::import::decompress`]. SNBT is the text form of NBT, like
+/// `{title: "Book", pages: ["Page one"]}`. Any text before the first `'{'`, like the
+/// `"Steve has the following entity data: "` of `/data get`, is skipped.
+///
+/// The first written book found in the data is converted, searching depth-first, so the data can
+/// be the book item itself or anything that holds it, like a player or a chest. A book is either:
+/// - The `"minecraft:written_book_content"` component of an item, since Minecraft 1.20.5
+/// - A compound with a list of `"pages"`, like the `"tag"` of an item in earlier versions
+///
+/// Its `"title"` and `"author"` become [`Metadata`][`crate::syntax::Metadata`], and each page
+/// starts with a [`Token::ThematicBreak`][`crate::syntax::Token::ThematicBreak`]. A page is either
+/// a text component stored as NBT, or a string, which is a JSON text component if it parses as
+/// one, and otherwise plain text with `'§'` format codes. Text components are converted as
+/// described by [`Tellraw`][`crate::import::Tellraw`].
+pub struct Nbt;
+
+impl Nbt {
+    /// Parse the bytes of binary NBT, compressed or not, or of SNBT text into an abstract syntax
+    /// vector, with the default [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_reader_with_limits`].
+    pub fn tokenize_bytes(bytes: &[u8]) -> Result<TokenList, TokenizeError> {
+        Self::tokenize_reader_with_limits(bytes, &Limits::default())
+    }
+
+    /// Parse binary NBT, compressed or not, or SNBT text from a reader, like a
+    /// [`std::fs::File`], into an abstract syntax vector, according to `limits`.
+    ///
+    /// Compressed binary NBT is only decompressed with the `compression` feature. The input size
+    /// limit applies to the decompressed data, so that a small compressed input can't expand to
+    /// fill the memory.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Io`] if it cannot read from `input`
+    /// - [`TokenizeError::Decompress`] if `input` looks compressed, but cannot be decompressed
+    /// - [`TokenizeError::LimitExceeded`] if the data or the output exceeds `limits`
+    /// - [`TokenizeError::Binary`] if the data is neither valid binary NBT nor text
+    /// - [`TokenizeError::Snbt`] if the data is text, but not valid SNBT
+    /// - [`TokenizeError::NoBook`] if there is no written book in the data
+    pub fn tokenize_reader_with_limits(
+        input: impl Read,
+        limits: &Limits,
+    ) -> Result<TokenList, TokenizeError> {
+        #[cfg(feature = "compression")]
+        let input = crate::import::decompress(input, None)?;
+
+        let mut bytes = vec![];
+        input.take(limits.max_read()).read_to_end(&mut bytes)?;
+        limits.check_input_size(bytes.len())?;
+
+        let tokens = book::tokenize(&Self::parse(bytes)?)?;
+        limits.check_tokens(tokens.tokens_as_slice().len())?;
+
+        Ok(tokens)
+    }
+
+    /// Parse uncompressed binary NBT or SNBT text.
+    ///
+    /// Data that starts like binary NBT, but isn't valid, is parsed as SNBT if it is text, as SNBT
+    /// can start with a line break, which looks the same. If that fails too, the error is from
+    /// parsing it as binary NBT.
+    fn parse(bytes: Vec<u8>) -> Result<Tag, TokenizeError> {
+        let binary = if binary::is_binary(&bytes) {
+            match binary::read(&bytes) {
+                Ok(tag) => return Ok(tag),
+                Err(error) => Some(error),
+            }
+        } else {
+            None
+        };
+
+        match (String::from_utf8(bytes), binary) {
+            (Ok(text), None) => snbt::read(&text),
+            (Ok(text), Some(error)) => snbt::read(&text).map_err(|_| error),
+            (Err(_), Some(error)) => Err(error),
+            (Err(error), None) => Err(TokenizeError::Binary {
+                offset: error.utf8_error().valid_up_to(),
+                reason: "neither binary NBT nor text",
+            }),
+        }
+    }
+}
+
+impl Tokenize for Nbt {
+    type Error = TokenizeError;
+
+    /// Parse SNBT text into an abstract syntax vector.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Snbt`] if `input` is not valid SNBT
+    /// - [`TokenizeError::NoBook`] if there is no written book in `input`
+    fn tokenize_string(input: &str) -> Result<TokenList, Self::Error> {
+        book::tokenize(&snbt::read(input)?)
+    }
+
+    /// Parse binary NBT, compressed or not, or SNBT text from a reader, like a
+    /// [`std::fs::File`], into an abstract syntax vector, with the default [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Nbt::tokenize_reader_with_limits`].
+    fn tokenize_reader(input: impl Read) -> Result<TokenList, Self::Error> {
+        Self::tokenize_reader_with_limits(input, &Limits::default())
+    }
+}
@@ -0,0 +1,319 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parsing SNBT ("stringified NBT"), as written in commands and printed by `/data get`.
+//!
+//! See [`read`].
+
+use super::{binary::MAX_DEPTH, tag::Tag, TokenizeError};
+
+/// Parse SNBT text into a [`Tag`].
+///
+/// Text before the first `'{'`, like the `"Steve has the following entity data: "` that
+/// `/data get` prints, is skipped, unless the text starts with the data itself.
+///
+/// # Errors
+///
+/// - [`TokenizeError::Snbt`] if the text is malformed or nests too deeply
+pub fn read(input: &str) -> Result<Tag, TokenizeError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut parser = Parser {
+        chars: &chars,
+        index: 0,
+    };
+
+    parser.skip_white_space();
+    if !matches!(parser.peek(), Some('{' | '[')) {
+        parser.index = chars.iter().position(|char| *char == '{').unwrap_or(0);
+    }
+
+    let tag = parser.value(0)?;
+    parser.skip_white_space();
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected text after the data"));
+    }
+
+    Ok(tag)
+}
+
+/// Whether `char` can be part of an unquoted string or number.
+const fn is_unquoted(char: char) -> bool {
+    matches!(char, '0'..='9' | 'A'..='Z' | 'a'..='z' | '_' | '-' | '.' | '+')
+}
+
+/// Reads values out of SNBT, keeping track of where it is for errors.
+struct Parser<'a> {
+    /// All of the text.
+    chars: &'a [char],
+    /// The index of the next character to read.
+    index: usize,
+}
+
+impl Parser<'_> {
+    /// Returns a [`TokenizeError::Snbt`] at the current position.
+    const fn error(&self, reason: &'static str) -> TokenizeError {
+        TokenizeError::Snbt {
+            position: self.index + 1,
+            reason,
+        }
+    }
+
+    /// Returns the next character without reading it.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    /// Read the next character.
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.index += 1;
+        Some(char)
+    }
+
+    /// Skip any whitespace.
+    fn skip_white_space(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.index += 1;
+        }
+    }
+
+    /// Read `expected` after any whitespace, or return an error.
+    fn expect(&mut self, expected: char, reason: &'static str) -> Result<(), TokenizeError> {
+        self.skip_white_space();
+        if self.peek() == Some(expected) {
+            self.index += 1;
+            Ok(())
+        } else {
+            Err(self.error(reason))
+        }
+    }
+
+    /// Read the elements of a list, compound, or array up to `close`, reading each with `element`.
+    ///
+    /// Elements are separated by `','`, and a trailing `','` is allowed.
+    fn elements(
+        &mut self,
+        close: char,
+        mut element: impl FnMut(&mut Self) -> Result<(), TokenizeError>,
+    ) -> Result<(), TokenizeError> {
+        loop {
+            self.skip_white_space();
+            if self.peek() == Some(close) {
+                self.index += 1;
+                return Ok(());
+            }
+
+            element(self)?;
+
+            self.skip_white_space();
+            match self.next() {
+                Some(',') => (),
+                Some(char) if char == close => return Ok(()),
+                _ => {
+                    self.index = self.index.saturating_sub(1);
+                    return Err(self.error("expected ',' or the end of the list or compound"));
+                }
+            }
+        }
+    }
+
+    /// Read any value, nested `depth` lists or compounds deep.
+    fn value(&mut self, depth: usize) -> Result<Tag, TokenizeError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+
+        self.skip_white_space();
+        match self.peek() {
+            Some('{') => {
+                self.index += 1;
+                let mut fields = vec![];
+                self.elements('}', |parser| {
+                    let key = parser.string()?;
+                    parser.expect(':', "expected ':' after the key")?;
+                    fields.push((key, parser.value(depth + 1)?));
+                    Ok(())
+                })?;
+
+                Ok(Tag::Compound(fields))
+            }
+            Some('[') => {
+                self.index += 1;
+                self.list(depth)
+            }
+            Some('"' | '\'') => self.string().map(Tag::String),
+            Some(char) if is_unquoted(char) => Ok(self.unquoted()),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Read a list or an array, after its opening `'['`.
+    fn list(&mut self, depth: usize) -> Result<Tag, TokenizeError> {
+        let kind = match self.chars.get(self.index..self.index + 2) {
+            Some([kind @ ('B' | 'I' | 'L'), ';']) => Some(*kind),
+            _ => None,
+        };
+
+        let mut values = vec![];
+        if kind.is_some() {
+            self.index += 2;
+        }
+        self.elements(']', |parser| {
+            values.push(parser.value(depth + 1)?);
+            Ok(())
+        })?;
+
+        let Some(kind) = kind else {
+            return Ok(Tag::List(values));
+        };
+
+        let integers = values
+            .into_iter()
+            .map(|value| match value {
+                Tag::Byte(byte) => Some(i64::from(byte)),
+                Tag::Short(short) => Some(i64::from(short)),
+                Tag::Int(int) => Some(i64::from(int)),
+                Tag::Long(long) => Some(long),
+                _ => None,
+            })
+            .collect::<Option<Vec<i64>>>()
+            .ok_or_else(|| self.error("expected only integers in the array"))?;
+        let too_large = |_| self.error("integer is too large for the array");
+
+        match kind {
+            'B' => integers
+                .into_iter()
+                .map(i8::try_from)
+                .collect::<Result<_, _>>()
+                .map(Tag::ByteArray)
+                .map_err(too_large),
+            'I' => integers
+                .into_iter()
+                .map(i32::try_from)
+                .collect::<Result<_, _>>()
+                .map(Tag::IntArray)
+                .map_err(too_large),
+            _ => Ok(Tag::LongArray(integers)),
+        }
+    }
+
+    /// Read a quoted or unquoted string.
+    fn string(&mut self) -> Result<String, TokenizeError> {
+        self.skip_white_space();
+
+        let Some(quote @ ('"' | '\'')) = self.peek() else {
+            let start = self.index;
+            while self.peek().is_some_and(is_unquoted) {
+                self.index += 1;
+            }
+
+            if start == self.index {
+                return Err(self.error("expected a string"));
+            }
+            return Ok(self.chars[start..self.index].iter().collect());
+        };
+        self.index += 1;
+
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some(char) if char == quote => return Ok(string),
+                Some('\\') => string.push(self.escape()?),
+                Some(char) => string.push(char),
+            }
+        }
+    }
+
+    /// Read an escape sequence, after its `'\\'`.
+    fn escape(&mut self) -> Result<char, TokenizeError> {
+        let escaped = match self.next() {
+            Some(char @ ('\\' | '"' | '\'')) => Some(char),
+            Some('n') => Some('\n'),
+            Some('t') => Some('\t'),
+            Some('r') => Some('\r'),
+            Some('b') => Some('\u{8}'),
+            Some('f') => Some('\u{C}'),
+            Some('s') => Some(' '),
+            Some('x') => self.hex(2),
+            Some('u') => self.hex(4),
+            Some('U') => self.hex(8),
+            _ => None,
+        };
+
+        escaped.ok_or_else(|| self.error("invalid escape sequence"))
+    }
+
+    /// Read a character written as `length` hexadecimal digits.
+    fn hex(&mut self, length: usize) -> Option<char> {
+        let digits: String = self
+            .chars
+            .get(self.index..self.index + length)?
+            .iter()
+            .collect();
+        self.index += length;
+
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+    }
+
+    /// Read an unquoted value, which is a number if it looks like one, a boolean, or otherwise a
+    /// string.
+    fn unquoted(&mut self) -> Tag {
+        let start = self.index;
+        while self.peek().is_some_and(is_unquoted) {
+            self.index += 1;
+        }
+        let token: String = self.chars[start..self.index].iter().collect();
+
+        match token.as_str() {
+            "true" => return Tag::Byte(1),
+            "false" => return Tag::Byte(0),
+            _ => (),
+        }
+
+        number(&token).unwrap_or(Tag::String(token))
+    }
+}
+
+/// Parse an unquoted number, with an optional suffix for its type, like `"1b"` or `"2.5f"`.
+fn number(token: &str) -> Option<Tag> {
+    // Rules out words that Rust would parse as floats, like `"inf"`
+    if !token.starts_with(|char: char| char.is_ascii_digit() || matches!(char, '-' | '+' | '.')) {
+        return None;
+    }
+
+    let (digits, suffix) = match token.char_indices().last()? {
+        (index, suffix) if suffix.is_ascii_alphabetic() => (&token[..index], Some(suffix)),
+        _ => (token, None),
+    };
+
+    match suffix.map(|suffix| suffix.to_ascii_lowercase()) {
+        Some('b') => digits.parse().ok().map(Tag::Byte),
+        Some('s') => digits.parse().ok().map(Tag::Short),
+        Some('l') => digits.parse().ok().map(Tag::Long),
+        Some('f') => digits.parse().ok().map(Tag::Float),
+        Some('d') => digits.parse().ok().map(Tag::Double),
+        Some(_) => None,
+        None => digits
+            .parse()
+            .map(Tag::Int)
+            .or_else(|_| digits.parse().map(Tag::Double))
+            .ok(),
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The tree of values that NBT and SNBT both describe.
+//!
+//! See [`Tag`].
+
+use serde_json::{Map, Number, Value};
+
+/// A single value of NBT data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tag {
+    /// A signed 8-bit integer, also used for booleans.
+    Byte(i8),
+    /// A signed 16-bit integer.
+    Short(i16),
+    /// A signed 32-bit integer.
+    Int(i32),
+    /// A signed 64-bit integer.
+    Long(i64),
+    /// A 32-bit floating point number.
+    Float(f32),
+    /// A 64-bit floating point number.
+    Double(f64),
+    /// An array of signed 8-bit integers.
+    ByteArray(Vec<i8>),
+    /// A string.
+    String(String),
+    /// A list of values.
+    List(Vec<Self>),
+    /// Named values, in the order they were read.
+    Compound(Vec<(String, Self)>),
+    /// An array of signed 32-bit integers.
+    IntArray(Vec<i32>),
+    /// An array of signed 64-bit integers.
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    /// Returns the value of the first field named `key`, if this is a compound that has one.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Compound(fields) => fields
+                .iter()
+                .find_map(|(name, value)| (name == key).then_some(value)),
+            _ => None,
+        }
+    }
+
+    /// Returns the string, if this is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Returns every value directly inside of this one, if it is a list or a compound.
+    pub fn children(&self) -> Box<dyn Iterator<Item = &Self> + '_> {
+        match self {
+            Self::List(values) => Box::new(values.iter()),
+            Self::Compound(fields) => Box::new(fields.iter().map(|(_, value)| value)),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    /// Convert this value into JSON, as Minecraft does for text components.
+    ///
+    /// Bytes of `0` and `1` become booleans, as that is how NBT stores them.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Byte(0) => Value::Bool(false),
+            Self::Byte(1) => Value::Bool(true),
+            Self::Byte(byte) => Value::from(*byte),
+            Self::Short(short) => Value::from(*short),
+            Self::Int(int) => Value::from(*int),
+            Self::Long(long) => Value::from(*long),
+            Self::Float(float) => {
+                Number::from_f64(f64::from(*float)).map_or(Value::Null, Value::Number)
+            }
+            Self::Double(double) => Number::from_f64(*double).map_or(Value::Null, Value::Number),
+            Self::ByteArray(bytes) => bytes.iter().copied().map(Value::from).collect(),
+            Self::String(string) => Value::String(string.clone()),
+            Self::List(values) => values.iter().map(Self::to_json).collect(),
+            Self::Compound(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.to_json()))
+                    .collect::<Map<_, _>>(),
+            ),
+            Self::IntArray(ints) => ints.iter().copied().map(Value::from).collect(),
+            Self::LongArray(longs) => longs.iter().copied().map(Value::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for parsing [NBT data][`super::Nbt`].
+
+use super::{binary, snbt, tag::Tag, Nbt, TokenizeError};
+use crate::{
+    syntax::{
+        minecraft::{Color, Format},
+        Metadata, Token,
+    },
+    Tokenize,
+};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+/// Encode `name` as the length-prefixed string of binary NBT.
+fn string(name: &str) -> Vec<u8> {
+    let mut bytes = u16::try_from(name.len()).unwrap().to_be_bytes().to_vec();
+    bytes.extend_from_slice(name.as_bytes());
+    bytes
+}
+
+/// Encode a named string field of a binary NBT compound.
+fn string_field(name: &str, value: &str) -> Vec<u8> {
+    [vec![8], string(name), string(value)].concat()
+}
+
+/// An item from before Minecraft 1.20.5, as binary NBT, with its pages as JSON text.
+fn legacy_item() -> Vec<u8> {
+    [
+        vec![10],
+        string(""),
+        string_field("id", "minecraft:written_book"),
+        vec![1],
+        string("Count"),
+        vec![1],
+        vec![10],
+        string("tag"),
+        string_field("title", "crafty_novels"),
+        string_field("author", "RemasteredArch"),
+        vec![9],
+        string("pages"),
+        vec![8, 0, 0, 0, 2],
+        string(r#"{"text":"Chapter one","color":"gold"}"#),
+        string(r#""""#),
+        vec![0, 0],
+    ]
+    .concat()
+}
+
+#[test]
+fn test_binary() -> Result {
+    let expected_metadata = [
+        Metadata::Title("crafty_novels".into()),
+        Metadata::Author("RemasteredArch".into()),
+    ];
+    let expected_tokens = [
+        Token::ThematicBreak,
+        Token::Format(Format::Color(Color::Gold)),
+        text!("Chapter"),
+        Token::Space,
+        text!("one"),
+        Token::Format(Format::Reset),
+        Token::LineBreak,
+        Token::ThematicBreak,
+    ];
+
+    let tokens = Nbt::tokenize_reader(legacy_item().as_slice())?;
+    assert_eq!(tokens.metadata_as_slice(), expected_metadata);
+    assert_eq!(tokens.tokens_as_slice(), expected_tokens);
+
+    // As saved in a world
+    #[cfg(feature = "compression")]
+    {
+        use crate::format::limits::{Limit, Limits};
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&legacy_item())?;
+        let gzip = encoder.finish()?;
+        assert_eq!(Nbt::tokenize_bytes(&gzip)?, tokens);
+
+        // The limit applies to the decompressed data
+        let limits = Limits::default().with_max_input_size(legacy_item().len() - 1);
+        assert!(matches!(
+            Nbt::tokenize_reader_with_limits(gzip.as_slice(), &limits),
+            Err(TokenizeError::LimitExceeded(Limit::InputSize(_)))
+        ));
+    }
+
+    let truncated = &legacy_item()[..40];
+    assert!(matches!(
+        Nbt::tokenize_reader(truncated),
+        Err(TokenizeError::Binary { offset: 40, .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_modified_utf8() -> Result {
+    // `'\0'` as two bytes, then `'😀'` as a pair of surrogates
+    let bytes = [
+        &[10, 0, 0, 8, 0, 1, b'k', 0, 8][..],
+        &[0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80][..],
+        &[0][..],
+    ]
+    .concat();
+
+    assert_eq!(
+        binary::read(&bytes)?,
+        Tag::Compound(vec![("k".into(), Tag::String("\0😀".into()))])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_snbt() -> Result {
+    assert_eq!(
+        snbt::read(
+            r#"{a: 1b, "b": -2s, c: 3, d: 4L, e: 0.5f, f: 1.5, g: [B; 1b, 2b], h: [I;],
+                i: [L; 5l], j: 'it\'s', k: "\"§\"", l: true, m: word, n: [{}, {},],}"#
+        )?,
+        Tag::Compound(vec![
+            ("a".into(), Tag::Byte(1)),
+            ("b".into(), Tag::Short(-2)),
+            ("c".into(), Tag::Int(3)),
+            ("d".into(), Tag::Long(4)),
+            ("e".into(), Tag::Float(0.5)),
+            ("f".into(), Tag::Double(1.5)),
+            ("g".into(), Tag::ByteArray(vec![1, 2])),
+            ("h".into(), Tag::IntArray(vec![])),
+            ("i".into(), Tag::LongArray(vec![5])),
+            ("j".into(), Tag::String("it's".into())),
+            ("k".into(), Tag::String("\"§\"".into())),
+            ("l".into(), Tag::Byte(1)),
+            ("m".into(), Tag::String("word".into())),
+            (
+                "n".into(),
+                Tag::List(vec![Tag::Compound(vec![]), Tag::Compound(vec![])])
+            ),
+        ])
+    );
+
+    for (input, position) in [
+        ("[1, ;]", 5),
+        ("{a 1}", 4),
+        ("{a: 'open}", 11),
+        ("{} {}", 4),
+    ] {
+        match snbt::read(input) {
+            Err(TokenizeError::Snbt {
+                position: found, ..
+            }) => assert_eq!(found, position),
+            other => panic!("expected an SNBT error for {input:?}, found {other:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_components() -> Result {
+    // Text components stored as NBT, since Minecraft 1.21.5, in a player's inventory
+    let input = r#"{Inventory: [
+        {Slot: 0b, id: "minecraft:stone", count: 64},
+        {Slot: 1b, id: "minecraft:written_book", count: 1, components: {
+            "minecraft:written_book_content": {
+                title: {raw: "Book"}, author: "Steve",
+                pages: [{raw: {text: "Bold", bold: 1b, extra: [" text"]}}, "unsigned §lpage"]
+            }
+        }}
+    ]}"#;
+
+    let tokens = Nbt::tokenize_string(input)?;
+    assert_eq!(
+        tokens.metadata_as_slice(),
+        [
+            Metadata::Title("Book".into()),
+            Metadata::Author("Steve".into())
+        ]
+    );
+    assert_eq!(
+        tokens.tokens_as_slice(),
+        [
+            Token::ThematicBreak,
+            Token::Format(Format::Bold),
+            text!("Bold"),
+            Token::Space,
+            text!("text"),
+            Token::Format(Format::Reset),
+            Token::LineBreak,
+            Token::ThematicBreak,
+            text!("unsigned"),
+            Token::Space,
+            Token::Format(Format::Bold),
+            text!("page"),
+            Token::Format(Format::Reset),
+            Token::LineBreak,
+        ]
+    );
+
+    assert!(matches!(
+        Nbt::tokenize_string(r#"{id: "minecraft:stone"}"#),
+        Err(TokenizeError::NoBook)
+    ));
+
+    Ok(())
+}
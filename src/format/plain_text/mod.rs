@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Exporting for plain text without any formatting, for counting words, diffing revisions, and
+//! spellchecking.
+//!
+//! See [`PlainText`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     export::PlainText,
+//!     syntax::{minecraft::Format, Metadata, Token, TokenList},
+//!     Export,
+//! };
+//!
+//! let input = TokenList::new_from_boxed(
+//!     Box::new([Metadata::Title("Not written".into())]),
+//!     Box::new([
+//!         Token::ThematicBreak,
+//!         Token::Format(Format::Bold),
+//!         Token::Text("Chapter".into()),
+//!         Token::Space,
+//!         Token::Text("one".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::ParagraphBreak,
+//!         Token::Text("Once".into()),
+//!         Token::ThematicBreak,
+//!         Token::Text("upon".into()),
+//!     ]),
+//! );
+//!
+//! assert_eq!(
+//!     PlainText::export_token_vector_to_string(input).as_ref(),
+//!     "Chapter one\n\nOnce\u{c}upon"
+//! );
+//! ```
+
+use crate::{
+    export::Capabilities,
+    syntax::{
+        direction::TextDirection,
+        minecraft::FormatState,
+        stream::{StreamError, TokenStream},
+        Token, TokenList,
+    },
+    writer::{CountingWriter, Utf8Writer},
+    Export, ExportWithOptions,
+};
+pub use options::ExportOptions;
+use std::{borrow::Cow, io::Write};
+
+mod options;
+#[cfg(test)]
+mod test;
+mod wrap;
+
+/// Exporting for plain text, writing only the text of a work.
+///
+/// # Format
+///
+/// Unlike [`Text`][`crate::export::Text`], lines are not wrapped by default, so the output only
+/// changes where the text does. [`ExportOptions::with_wrap_width`] reflows each paragraph to a
+/// number of terminal columns instead.
+///
+/// - Metadata, like the title and the author, is not written
+/// - All formatting is dropped
+/// - Line breaks, paragraph breaks, and page breaks are written as set in [`ExportOptions`], by
+///   default `"\n"`, `"\n\n"`, and a form feed (`"\u{c}"`)
+///     - A page break at the very start of the work is not written, as there is no page before it
+/// - Images are written as their alt text
+/// - Obfuscated text is scrambled if [`ExportOptions`] sets an
+///   [`Obfuscator`][`crate::syntax::minecraft::Obfuscator`]
+/// - [`ExportOptions`] can start each line with a direction mark, for right-to-left works
+pub struct PlainText {}
+
+impl PlainText {
+    /// Parse a given abstract syntax vector into plain text with the given options, then output
+    /// that as a string.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_token_vector_to_string_with_options(
+        tokens: &TokenList,
+        options: &ExportOptions,
+    ) -> Box<str> {
+        let mut bytes: Vec<u8> = vec![];
+
+        Self::export_token_vector_to_writer_with_options(tokens, &mut bytes, options)
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/std/src/io/impls.rs#L433-L437
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/alloc/src/vec/mod.rs#L2569-L2592
+            .expect(
+                "the `std::io::Write` implementations for `Vec<u8>` are infallible (as of 1.80.1)",
+            );
+
+        String::from_utf8(bytes)
+            .expect("`Utf8Writer` only writes UTF-8 encoded types")
+            .into_boxed_str()
+    }
+
+    /// Parse a given abstract syntax vector into plain text with the given options, then output
+    /// that into a writer, like a [`std::fs::File`].
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    pub fn export_token_vector_to_writer_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        let mut writer = Utf8Writer::new(output);
+
+        let tokens = tokens.tokens_as_slice();
+        let tokens = tokens
+            .strip_prefix(&[Token::ThematicBreak])
+            .unwrap_or(tokens);
+
+        let mut cursor = Cursor::new();
+        for token in tokens {
+            write_token(&mut writer, token, options, &mut cursor)?;
+        }
+        end_line(&mut writer, options, &mut cursor)?;
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Export a [`TokenStream`] into plain text with the given options as its tokens are parsed,
+    /// writing the result into a writer, like a [`std::fs::File`].
+    ///
+    /// Each token is written as soon as it is parsed, so this runs in constant memory. The output
+    /// is flushed after each page break, so if writing fails, the error says how much of the work
+    /// is safely in `output`.
+    ///
+    /// # Errors
+    ///
+    /// - [`StreamError::Tokenize`] if a token of `tokens` fails to parse
+    /// - [`StreamError::Interrupted`] if it cannot write into `output`
+    pub fn export_token_iter_to_writer_with_options<E>(
+        tokens: TokenStream<'_, E>,
+        output: &mut impl Write,
+        options: &ExportOptions,
+    ) -> Result<(), StreamError<E>> {
+        let mut writer = Utf8Writer::new(CountingWriter {
+            inner: output,
+            count: 0,
+        });
+        // The pages, tokens, and bytes written in full so far
+        let mut written = (0, 0, 0);
+        let interrupted =
+            |(written_pages, written_tokens, written_bytes), source| StreamError::Interrupted {
+                written_pages,
+                written_tokens,
+                written_bytes,
+                source,
+            };
+
+        let mut cursor = Cursor::new();
+        for (index, token) in tokens.enumerate() {
+            let token = match token {
+                Ok(token) => token,
+                Err(e) => {
+                    // Keep everything before the error, including a line still being wrapped
+                    end_line(&mut writer, options, &mut cursor)
+                        .map_err(|e| interrupted(written, e))?;
+                    return Err(StreamError::Tokenize(e));
+                }
+            };
+            if index == 0 && token == Token::ThematicBreak {
+                continue;
+            }
+
+            write_token(&mut writer, &token, options, &mut cursor)
+                .map_err(|e| interrupted(written, e))?;
+
+            if token == Token::ThematicBreak {
+                writer.flush().map_err(|e| interrupted(written, e))?;
+                written = (written.0 + 1, index + 1, writer.get_ref().count);
+            }
+        }
+
+        end_line(&mut writer, options, &mut cursor).map_err(|e| interrupted(written, e))?;
+        writer.flush().map_err(|e| interrupted(written, e))?;
+        Ok(())
+    }
+}
+
+/// Where [`write_token`] is in the work.
+#[derive(Clone, Debug)]
+struct Cursor {
+    /// Whether nothing has been written on the current line yet, to know where to write the
+    /// direction mark set in the [`ExportOptions`], if any.
+    line_start: bool,
+    /// The formatting of the current text, to know whether it is obfuscated.
+    state: FormatState,
+    /// The text of the current line, held back until it ends if it is to be wrapped.
+    line: String,
+}
+
+impl Cursor {
+    /// Creates a new [`Cursor`] at the start of a work.
+    const fn new() -> Self {
+        Self {
+            line_start: true,
+            state: FormatState::new(),
+            line: String::new(),
+        }
+    }
+}
+
+/// Write the text of a single token into `writer`, moving `cursor` past it.
+///
+/// If [`ExportOptions`] sets a wrap width, text is held in `cursor` until the paragraph ends
+/// instead, with each line break replaced by a space.
+fn write_token(
+    writer: &mut Utf8Writer<impl Write>,
+    token: &Token,
+    options: &ExportOptions,
+    cursor: &mut Cursor,
+) -> std::io::Result<()> {
+    let text = match token {
+        Token::Text(text) => match options.obfuscation() {
+            Some(obfuscator) if cursor.state.obfuscated() => Cow::Owned(obfuscator.obfuscate(text)),
+            _ => Cow::Borrowed(&**text),
+        },
+        Token::Image { alt, .. } => Cow::Borrowed(&**alt),
+        Token::Space => Cow::Borrowed(" "),
+        // Wrapping reflows the lines of each paragraph
+        Token::LineBreak if options.wrap_width().is_some() => {
+            if !cursor.line.is_empty() && !cursor.line.ends_with(' ') {
+                cursor.line.push(' ');
+            }
+            return Ok(());
+        }
+        Token::LineBreak | Token::ParagraphBreak | Token::ThematicBreak => {
+            end_line(writer, options, cursor)?;
+            cursor.line_start = true;
+
+            return writer.write_str(match token {
+                Token::LineBreak => options.line_break(),
+                Token::ParagraphBreak => options.paragraph_break(),
+                _ => options.page_break(),
+            });
+        }
+        Token::Format(format) => {
+            cursor.state = cursor.state.with(*format);
+            return Ok(());
+        }
+        Token::Align(_) | Token::Role(_) => return Ok(()),
+    };
+
+    if options.wrap_width().is_some() {
+        cursor.line.push_str(&text);
+        return Ok(());
+    }
+
+    if let Some(mark) = options.direction().and_then(TextDirection::mark) {
+        if std::mem::take(&mut cursor.line_start) {
+            writer.write_char(mark)?;
+        }
+    }
+    writer.write_str(text)
+}
+
+/// Write the paragraph held back in `cursor`, if any, wrapped to the width set in [`ExportOptions`],
+/// starting each of the lines it wraps onto with the direction mark, if any.
+fn end_line(
+    writer: &mut Utf8Writer<impl Write>,
+    options: &ExportOptions,
+    cursor: &mut Cursor,
+) -> std::io::Result<()> {
+    let (Some(width), false) = (options.wrap_width(), cursor.line.is_empty()) else {
+        return Ok(());
+    };
+    let mark = options.direction().and_then(TextDirection::mark);
+
+    for (index, line) in wrap::wrap(&cursor.line, width).iter().enumerate() {
+        if index > 0 {
+            writer.write_str(options.line_break())?;
+        }
+        if let Some(mark) = mark {
+            writer.write_char(mark)?;
+        }
+        writer.write_str(line)?;
+    }
+
+    cursor.line.clear();
+    Ok(())
+}
+
+impl ExportWithOptions for PlainText {
+    type Options = ExportOptions;
+
+    /// See [`PlainText::export_token_vector_to_writer_with_options`].
+    fn export_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &Self::Options,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(tokens, output, options)
+    }
+}
+
+impl Export for PlainText {
+    fn capabilities() -> Capabilities {
+        Capabilities::PAGINATION
+    }
+
+    /// Parse a given abstract syntax vector into plain text, then output that as a string.
+    fn export_token_vector_to_string(tokens: TokenList) -> Box<str> {
+        Self::export_token_vector_to_string_with_options(&tokens, &ExportOptions::default())
+    }
+
+    /// Parse a given abstract syntax vector into plain text, then output that into a writer, like
+    /// a [`std::fs::File`].
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_token_vector_to_writer(
+        tokens: TokenList,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(&tokens, output, &ExportOptions::default())
+    }
+
+    /// Export a [`TokenStream`] into plain text as its tokens are parsed, in constant memory.
+    ///
+    /// # Errors
+    ///
+    /// - [`StreamError::Tokenize`] if a token of `tokens` fails to parse
+    /// - [`StreamError::Interrupted`] if it cannot write into `output`
+    fn export_token_iter_to_writer<E>(
+        tokens: TokenStream<'_, E>,
+        output: &mut impl Write,
+    ) -> Result<(), StreamError<E>> {
+        Self::export_token_iter_to_writer_with_options(tokens, output, &ExportOptions::default())
+    }
+}
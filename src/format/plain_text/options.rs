@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration for [`super::PlainText`].
+//!
+//! See [`ExportOptions`].
+
+use crate::{
+    format::line_ending::LineEnding,
+    syntax::{direction::TextDirection, minecraft::Obfuscator},
+};
+
+/// Options that control what is written for each kind of break in plain text output.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{PlainText, PlainTextExportOptions},
+///     syntax::{Token, TokenList},
+/// };
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::ThematicBreak,
+///         Token::Text("one".into()),
+///         Token::ThematicBreak,
+///         Token::Text("two".into()),
+///     ]),
+/// );
+/// let options = PlainTextExportOptions::new().with_page_break("\n\n---\n\n");
+///
+/// assert_eq!(
+///     PlainText::export_token_vector_to_string_with_options(&input, &options).as_ref(),
+///     "one\n\n---\n\ntwo"
+/// );
+/// ```
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[allow(clippy::struct_field_names)] // Named after the breaks they stand in for
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExportOptions {
+    /// What to write for a [`crate::syntax::Token::LineBreak`].
+    line_break: Box<str>,
+    /// What to write for a [`crate::syntax::Token::ParagraphBreak`].
+    paragraph_break: Box<str>,
+    /// What to write for a [`crate::syntax::Token::ThematicBreak`].
+    page_break: Box<str>,
+    /// The direction to mark each line with, if any.
+    direction: Option<TextDirection>,
+    /// What replaces obfuscated text, if anything.
+    obfuscation: Option<Obfuscator>,
+    /// The number of terminal columns to wrap lines to, if any.
+    wrap_width: Option<usize>,
+}
+
+impl ExportOptions {
+    /// Creates a new [`ExportOptions`] with the default options.
+    ///
+    /// Line breaks are `"\n"`, paragraph breaks are `"\n\n"`, and page breaks are a form feed
+    /// (`"\u{c}"`).
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            line_break: "\n".into(),
+            paragraph_break: "\n\n".into(),
+            page_break: "\u{c}".into(),
+            direction: None,
+            obfuscation: None,
+            wrap_width: None,
+        }
+    }
+
+    /// Returns what is written for a line break.
+    #[must_use]
+    pub const fn line_break(&self) -> &str {
+        &self.line_break
+    }
+
+    /// Sets what is written for a line break.
+    #[must_use]
+    pub fn with_line_break(mut self, line_break: impl Into<Box<str>>) -> Self {
+        self.line_break = line_break.into();
+        self
+    }
+
+    /// Returns what is written for a paragraph break.
+    #[must_use]
+    pub const fn paragraph_break(&self) -> &str {
+        &self.paragraph_break
+    }
+
+    /// Sets what is written for a paragraph break.
+    #[must_use]
+    pub fn with_paragraph_break(mut self, paragraph_break: impl Into<Box<str>>) -> Self {
+        self.paragraph_break = paragraph_break.into();
+        self
+    }
+
+    /// Sets the line and paragraph breaks to one and two of `line_ending`, like `"\r\n"` and
+    /// `"\r\n\r\n"` for [`LineEnding::CrLf`].
+    #[must_use]
+    pub fn with_line_ending(self, line_ending: LineEnding) -> Self {
+        let ending = line_ending.as_str();
+
+        self.with_line_break(ending)
+            .with_paragraph_break(ending.repeat(2))
+    }
+
+    /// Returns what is written between pages.
+    #[must_use]
+    pub const fn page_break(&self) -> &str {
+        &self.page_break
+    }
+
+    /// Sets what is written between pages.
+    ///
+    /// Use `"\n"` to read the work as one long page.
+    #[must_use]
+    pub fn with_page_break(mut self, page_break: impl Into<Box<str>>) -> Self {
+        self.page_break = page_break.into();
+        self
+    }
+
+    /// Returns the direction that each line is marked with, if any.
+    #[must_use]
+    pub const fn direction(&self) -> Option<TextDirection> {
+        self.direction
+    }
+
+    /// Sets the direction to mark each line with, or [`None`] to leave lines unmarked.
+    ///
+    /// Plain text has nowhere to say which direction it is written in, so each line that has any
+    /// text starts with the [mark][`TextDirection::mark`] of `direction` instead, and punctuation
+    /// and brackets at either end of a line are laid out on the correct side. Set it to
+    /// [`TextDirection::RightToLeft`] for Arabic or Hebrew, or to [`TextDirection::for_work`].
+    #[must_use]
+    pub const fn with_direction(mut self, direction: Option<TextDirection>) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns what replaces obfuscated text, if anything.
+    #[must_use]
+    pub const fn obfuscation(&self) -> Option<&Obfuscator> {
+        self.obfuscation.as_ref()
+    }
+
+    /// Sets what replaces obfuscated text, or [`None`] to write it as it is.
+    ///
+    /// The scramble is the same every time the same work is exported, so the output can be kept
+    /// under version control without noisy diffs.
+    #[must_use]
+    pub fn with_obfuscation(mut self, obfuscation: Option<Obfuscator>) -> Self {
+        self.obfuscation = obfuscation;
+        self
+    }
+
+    /// Returns the number of terminal columns that lines are wrapped to, if any.
+    #[must_use]
+    pub const fn wrap_width(&self) -> Option<usize> {
+        self.wrap_width
+    }
+
+    /// Sets the number of terminal columns to wrap lines to, like `Some(80)`, or [`None`] to leave
+    /// lines as long as they are.
+    ///
+    /// Wrapping reflows each paragraph: its line breaks are replaced by spaces, then it is broken
+    /// into lines again. Lines are broken at the last space that fits, which is dropped, and words that are wider
+    /// than a whole line are broken at the last character that fits. Widths are measured like a
+    /// terminal shows them: wide East Asian characters and emoji take up two columns, and
+    /// combining marks take up none. Wrapped lines are joined with the
+    /// [line break][`Self::line_break`].
+    #[must_use]
+    pub const fn with_wrap_width(mut self, wrap_width: Option<usize>) -> Self {
+        self.wrap_width = wrap_width;
+        self
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
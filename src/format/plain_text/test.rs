@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for exporting to [plain text][`super::PlainText`].
+
+use super::{ExportOptions, PlainText};
+use crate::{
+    format::line_ending::LineEnding,
+    syntax::{
+        minecraft::{Color, Format, Obfuscator},
+        Metadata, Token, TokenList,
+    },
+    Export,
+};
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+#[test]
+fn plain_text_export() {
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([Metadata::Author("Arch".into())]),
+        Box::new([
+            ThematicBreak,
+            Token::Format(Format::Color(Color::Gold)),
+            text!("Fish"),
+            Space,
+            Token::Format(Format::Obfuscated),
+            text!("&"),
+            Token::Format(Format::Reset),
+            Space,
+            text!("Chips"),
+            LineBreak,
+            ThematicBreak,
+            Token::Image {
+                src: "map.png".into(),
+                alt: "a map".into(),
+            },
+            ParagraphBreak,
+            text!("end"),
+        ]),
+    );
+
+    assert_eq!(
+        PlainText::export_token_vector_to_string(tokens.clone()).as_ref(),
+        "Fish & Chips\n\u{c}a map\n\nend"
+    );
+
+    let options = ExportOptions::new()
+        .with_line_break("\r\n")
+        .with_paragraph_break(" ¶ ")
+        .with_page_break("\r\n");
+    assert_eq!(
+        PlainText::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "Fish & Chips\r\n\r\na map ¶ end"
+    );
+
+    let options = ExportOptions::new().with_line_ending(LineEnding::CrLf);
+    assert_eq!(
+        PlainText::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "Fish & Chips\r\n\u{c}a map\r\n\r\nend"
+    );
+
+    // Only a leading page break is dropped
+    let tokens = TokenList::from(vec![ThematicBreak, ThematicBreak, text!("b")]);
+    assert_eq!(
+        PlainText::export_token_vector_to_string(tokens).as_ref(),
+        "\u{c}b"
+    );
+}
+
+#[test]
+fn plain_text_export_stream() {
+    use crate::syntax::stream::{StreamError, TokenStream};
+    use Token::{LineBreak, ThematicBreak};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([Metadata::Title("Book".into())]),
+        Box::new([
+            ThematicBreak,
+            text!("one"),
+            LineBreak,
+            ThematicBreak,
+            text!("two"),
+        ]),
+    );
+
+    let mut output = vec![];
+    PlainText::export_token_iter_to_writer(TokenStream::<()>::from(tokens.clone()), &mut output)
+        .expect("writing into a `Vec<u8>` is infallible");
+    assert_eq!(
+        String::from_utf8(output).as_deref(),
+        Ok(&*PlainText::export_token_vector_to_string(tokens))
+    );
+
+    // Stops at the first error, after writing everything before it
+    let stream = TokenStream::new(
+        Box::new([]),
+        [Ok(text!("one")), Err("bad token"), Ok(text!("two"))].into_iter(),
+    );
+    let mut output = vec![];
+    assert!(matches!(
+        PlainText::export_token_iter_to_writer(stream, &mut output),
+        Err(StreamError::Tokenize("bad token"))
+    ));
+    assert_eq!(output, b"one");
+}
+
+#[test]
+fn plain_text_export_stream_interrupted() {
+    use crate::syntax::stream::{StreamError, TokenStream};
+    use std::io::Write;
+    use Token::ThematicBreak;
+
+    /// Accepts writes until it holds `capacity` bytes, like a full disk.
+    struct FullWriter {
+        bytes: Vec<u8>,
+        capacity: usize,
+    }
+
+    impl Write for FullWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let space = self.capacity - self.bytes.len();
+            if space == 0 {
+                return Err(std::io::Error::other("disk full"));
+            }
+
+            let written = buf.len().min(space);
+            self.bytes.extend_from_slice(&buf[..written]);
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([]),
+        Box::new([
+            ThematicBreak,
+            text!("one"),
+            ThematicBreak,
+            text!("two"),
+            ThematicBreak,
+            text!("three"),
+        ]),
+    );
+
+    let mut output = FullWriter {
+        bytes: vec![],
+        capacity: 6,
+    };
+    let Err(StreamError::Interrupted {
+        written_pages,
+        written_tokens,
+        written_bytes,
+        source,
+    }) = PlainText::export_token_iter_to_writer(
+        TokenStream::<()>::from(tokens.clone()),
+        &mut output,
+    )
+    else {
+        panic!("writing should stop on the second page");
+    };
+    assert_eq!((written_pages, written_tokens, written_bytes), (1, 3, 4));
+    assert_eq!(source.to_string(), "disk full");
+    assert_eq!(output.bytes, b"one\x0ctw");
+
+    // Resume where it left off
+    output.bytes.truncate(written_bytes);
+    output.capacity = usize::MAX;
+    let rest = TokenStream::<()>::new(
+        Box::new([]),
+        tokens.into_vec().into_iter().skip(written_tokens).map(Ok),
+    );
+    PlainText::export_token_iter_to_writer(rest, &mut output).expect("there is space left");
+    assert_eq!(output.bytes, b"one\x0ctwo\x0cthree");
+}
+
+#[test]
+fn plain_text_direction() {
+    use crate::syntax::direction::TextDirection;
+    use Token::{LineBreak, Space, ThematicBreak};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([]),
+        Box::new([
+            ThematicBreak,
+            text!("2024"),
+            Space,
+            text!("(שלום)"),
+            LineBreak,
+            LineBreak,
+            Token::Format(Format::Bold),
+            text!("עולם!"),
+        ]),
+    );
+
+    let options = ExportOptions::new().with_direction(Some(TextDirection::RightToLeft));
+    assert_eq!(
+        PlainText::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "\u{200F}2024 (שלום)\n\n\u{200F}עולם!"
+    );
+
+    let options = options.with_direction(Some(TextDirection::Auto));
+    assert_eq!(
+        PlainText::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "2024 (שלום)\n\nעולם!"
+    );
+}
+
+#[test]
+fn plain_text_obfuscation() {
+    use crate::syntax::stream::TokenStream;
+
+    let tokens = TokenList::from(vec![
+        Token::Format(Format::Obfuscated),
+        text!("secret"),
+        Token::Format(Format::Reset),
+        Token::Space,
+        text!("open"),
+    ]);
+    let options = ExportOptions::new().with_obfuscation(Some(Obfuscator::default()));
+
+    let obfuscated = PlainText::export_token_vector_to_string_with_options(&tokens, &options);
+    assert!(obfuscated.ends_with(" open"));
+    assert!(!obfuscated.contains("secret"));
+    assert_eq!(obfuscated.chars().count(), "secret open".len());
+    // The same work is always scrambled in the same way
+    assert_eq!(
+        obfuscated,
+        PlainText::export_token_vector_to_string_with_options(&tokens, &options)
+    );
+
+    let mut output = vec![];
+    PlainText::export_token_iter_to_writer_with_options(
+        TokenStream::<()>::from(tokens),
+        &mut output,
+        &options,
+    )
+    .expect("writing into a `Vec<u8>` is infallible");
+    assert_eq!(String::from_utf8(output).as_deref(), Ok(&*obfuscated));
+}
+
+#[test]
+fn plain_text_wrap_width() {
+    use crate::syntax::direction::TextDirection;
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = TokenList::from(vec![
+        ThematicBreak,
+        text!("The"),
+        Space,
+        text!("quick"),
+        Space,
+        text!("brown"),
+        Space,
+        text!("fox"),
+        LineBreak,
+        text!("Supercalifragilistic"),
+        ThematicBreak,
+        // Each of these takes up two columns
+        text!("日本語の本です"),
+        LineBreak,
+        // Combining accents take up none
+        text!("cafe\u{301}"),
+        Space,
+        text!("cafe\u{301}"),
+        ParagraphBreak,
+        text!("End"),
+    ]);
+
+    assert_eq!(
+        PlainText::export_token_vector_to_string(tokens.clone()).as_ref(),
+        "The quick brown fox\nSupercalifragilistic\u{c}日本語の本です\ncafe\u{301} cafe\u{301}\n\nEnd"
+    );
+
+    // Line breaks are reflowed, paragraph and page breaks are kept
+    let options = ExportOptions::new().with_wrap_width(Some(10));
+    assert_eq!(
+        PlainText::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "The quick\nbrown fox\nSupercalif\nragilistic\u{c}日本語の本\nです cafe\u{301}\ncafe\u{301}\n\nEnd"
+    );
+
+    // Each wrapped line is marked, and the stream is wrapped the same way
+    let options = options
+        .with_wrap_width(Some(6))
+        .with_direction(Some(TextDirection::RightToLeft));
+    let mut output = vec![];
+    PlainText::export_token_iter_to_writer_with_options(
+        crate::syntax::stream::TokenStream::<()>::from(tokens.clone()),
+        &mut output,
+        &options,
+    )
+    .expect("writing into a `Vec<u8>` is infallible");
+    let expected = PlainText::export_token_vector_to_string_with_options(&tokens, &options);
+    assert_eq!(String::from_utf8(output).as_deref(), Ok(&*expected));
+    assert!(expected.starts_with("\u{200f}The\n\u{200f}quick\n"));
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Soft wrapping for [`super::PlainText`], measured in terminal columns.
+//!
+//! See [`wrap`].
+
+use unicode_width::UnicodeWidthChar;
+
+/// Break `line` into lines of at most `width` terminal columns.
+///
+/// Lines are broken at the last space that fits, which is dropped, and words that are wider than
+/// a whole line are broken at the last character that fits. A `width` of zero is treated as one.
+pub fn wrap(line: &str, width: usize) -> Vec<String> {
+    /// Returns the characters of `line`, leaving it empty.
+    fn finish(line: &mut Vec<(char, usize)>) -> String {
+        std::mem::take(line)
+            .into_iter()
+            .map(|(char, _)| char)
+            .collect()
+    }
+
+    let width = width.max(1);
+    let mut lines = vec![];
+    // The characters of the line currently being wrapped, and their widths
+    let mut current: Vec<(char, usize)> = vec![];
+
+    for char in line.chars() {
+        // Control characters take up no columns
+        let char_width = char.width().unwrap_or(0);
+        let fits = |current: &[(char, usize)]| {
+            current.is_empty()
+                || current.iter().map(|(_, width)| width).sum::<usize>() + char_width <= width
+        };
+
+        if !fits(&current) {
+            // Breaking at a space consumes it
+            if char == ' ' {
+                lines.push(finish(&mut current));
+                continue;
+            }
+
+            // Carry the partial word over to the next line
+            if let Some(space) = current.iter().rposition(|(char, _)| *char == ' ') {
+                let carried = current.split_off(space + 1);
+                current.pop();
+                lines.push(finish(&mut current));
+                current = carried;
+            }
+
+            // The word is wider than a whole line
+            if !fits(&current) {
+                lines.push(finish(&mut current));
+            }
+        }
+
+        current.push((char, char_width));
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(finish(&mut current));
+    }
+
+    lines
+}
@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Low-level scanning for writing importers, shared by the built-in formats.
+//!
+//! [`Cursor`] steps through a string a character at a time, [`FormatLexer`] splits a line into
+//! words, spaces, and format codes, and [`field`] reads a `"key: value"` frontmatter field.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     import::util::{FormatLexer, Lexeme},
+//!     syntax::{minecraft::FormatCode, Token},
+//! };
+//!
+//! // A tokenizer for a format where `"§"` starts a format code and `" "` separates words
+//! let tokens: Vec<Token> = FormatLexer::new("§lBold text")
+//!     .filter_map(|lexeme| match lexeme {
+//!         Lexeme::Word(word) => Some(Token::Text(word.into())),
+//!         Lexeme::Space => Some(Token::Space),
+//!         Lexeme::Code(code) => FormatCode::new(code).ok().map(|code| Token::Format(code.format())),
+//!         Lexeme::MissingCode => None,
+//!     })
+//!     .collect();
+//!
+//! assert_eq!(tokens.len(), 4);
+//! ```
+
+#[cfg(test)]
+mod test;
+
+/// Steps through a string one [`char`] at a time, with lookahead, keeping track of its byte
+/// position so that spans can be sliced straight out of the input.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::util::Cursor;
+///
+/// let mut cursor = Cursor::new("#- Page one");
+///
+/// assert!(cursor.eat_str("#- "));
+/// assert_eq!(cursor.eat_while(|char| char != ' '), "Page");
+/// assert_eq!(cursor.peek(), Some(' '));
+/// assert_eq!(cursor.rest(), " one");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cursor<'s> {
+    /// The whole input.
+    input: &'s str,
+    /// The byte index of the next character.
+    position: usize,
+}
+
+impl<'s> Cursor<'s> {
+    /// Creates a new [`Cursor`] at the start of `input`.
+    #[must_use]
+    pub const fn new(input: &'s str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Returns the whole input, including what has already been consumed.
+    #[must_use]
+    pub const fn input(&self) -> &'s str {
+        self.input
+    }
+
+    /// Returns the byte index of the next character.
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns whether every character has been consumed.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    /// Returns the input that has not been consumed yet.
+    #[must_use]
+    pub fn rest(&self) -> &'s str {
+        &self.input[self.position..]
+    }
+
+    /// Returns the input consumed since the byte index `start`, ex. a [`Self::position`] from
+    /// earlier.
+    ///
+    /// # Panics
+    ///
+    /// If `start` is after the current position or not on a [`char`] boundary.
+    #[must_use]
+    pub fn since(&self, start: usize) -> &'s str {
+        &self.input[start..self.position]
+    }
+
+    /// Returns the next character without consuming it.
+    #[must_use]
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Consumes the next character if it is `expected`, returning whether it was.
+    pub fn eat(&mut self, expected: char) -> bool {
+        let matches = self.peek() == Some(expected);
+        if matches {
+            self.position += expected.len_utf8();
+        }
+        matches
+    }
+
+    /// Consumes `prefix` if the rest of the input starts with it, returning whether it did.
+    pub fn eat_str(&mut self, prefix: &str) -> bool {
+        let matches = self.rest().starts_with(prefix);
+        if matches {
+            self.position += prefix.len();
+        }
+        matches
+    }
+
+    /// Consumes characters for as long as `predicate` is true of them, returning them.
+    pub fn eat_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'s str {
+        let start = self.position;
+        let rest = self.rest();
+        let length = rest.find(|char| !predicate(char)).unwrap_or(rest.len());
+        self.position += length;
+        self.since(start)
+    }
+}
+
+impl Iterator for Cursor<'_> {
+    type Item = char;
+
+    /// Consumes and returns the next character.
+    fn next(&mut self) -> Option<Self::Item> {
+        let char = self.peek()?;
+        self.position += char.len_utf8();
+        Some(char)
+    }
+}
+
+/// A piece of formatted text, see [`FormatLexer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lexeme<'s> {
+    /// Consecutive characters that are neither spaces nor `'§'`.
+    Word(&'s str),
+    /// A single `' '`.
+    Space,
+    /// `'§'` followed by a character, which is not necessarily a valid format code, see
+    /// [`FormatCode::new`][`crate::syntax::minecraft::FormatCode::new`].
+    Code(char),
+    /// `'§'` at the end of the input, with no character to follow it.
+    MissingCode,
+}
+
+/// Splits text that uses Minecraft's `'§'` format codes into [`Lexeme`]s.
+///
+/// Words are sliced straight out of the input, and every character of the input is part of
+/// exactly one lexeme. Interpreting the format codes is left to the importer, which might reject
+/// unknown codes, preserve them, or accept uppercase ones.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::util::{FormatLexer, Lexeme};
+///
+/// assert_eq!(
+///     FormatLexer::new("§ohi there§").collect::<Vec<_>>(),
+///     [
+///         Lexeme::Code('o'),
+///         Lexeme::Word("hi"),
+///         Lexeme::Space,
+///         Lexeme::Word("there"),
+///         Lexeme::MissingCode,
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FormatLexer<'s> {
+    /// Where the lexer is within the input.
+    cursor: Cursor<'s>,
+}
+
+impl<'s> FormatLexer<'s> {
+    /// Creates a new [`FormatLexer`] at the start of `input`.
+    #[must_use]
+    pub const fn new(input: &'s str) -> Self {
+        Self {
+            cursor: Cursor::new(input),
+        }
+    }
+
+    /// Creates a new [`FormatLexer`] that continues from `cursor`.
+    #[must_use]
+    pub const fn from_cursor(cursor: Cursor<'s>) -> Self {
+        Self { cursor }
+    }
+
+    /// Returns where the lexer is within the input.
+    #[must_use]
+    pub const fn cursor(&self) -> &Cursor<'s> {
+        &self.cursor
+    }
+}
+
+impl<'s> Iterator for FormatLexer<'s> {
+    type Item = Lexeme<'s>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor.eat(' ') {
+            return Some(Lexeme::Space);
+        }
+        if self.cursor.eat('§') {
+            return Some(self.cursor.next().map_or(Lexeme::MissingCode, Lexeme::Code));
+        }
+
+        let word = self.cursor.eat_while(|char| char != ' ' && char != '§');
+        (!word.is_empty()).then_some(Lexeme::Word(word))
+    }
+}
+
+/// Split a frontmatter line into its key and value, if it is a field.
+///
+/// A field is a key made of ASCII letters, digits, `'_'`, or `'-'`, followed by `':'` and an
+/// optional space-separated value, ex. `"description: A book"`.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::util::field;
+///
+/// assert_eq!(field("title: A book"), Some(("title", "A book")));
+/// assert_eq!(field("pages:"), Some(("pages", "")));
+/// assert_eq!(field("#- Page one: the start"), None);
+/// ```
+#[must_use]
+pub fn field(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '-');
+    if !is_key {
+        return None;
+    }
+
+    if value.is_empty() {
+        Some((key, value))
+    } else {
+        value.strip_prefix(' ').map(|value| (key, value))
+    }
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for [`super::Cursor`], [`super::FormatLexer`], and [`super::field`].
+
+use super::{field, Cursor, FormatLexer, Lexeme};
+
+#[test]
+fn cursor() {
+    let mut cursor = Cursor::new("§l 全角");
+
+    assert!(!cursor.eat('l'));
+    assert!(cursor.eat('§'));
+    assert_eq!(cursor.position(), '§'.len_utf8());
+    assert_eq!(cursor.next(), Some('l'));
+
+    let start = cursor.position();
+    assert_eq!(cursor.eat_while(char::is_whitespace), " ");
+    assert!(!cursor.eat_str("全部"));
+    assert!(cursor.eat_str("全"));
+    assert_eq!(cursor.since(start), " 全");
+    assert_eq!(cursor.peek(), Some('角'));
+
+    assert_eq!(cursor.eat_while(|_| true), "角");
+    assert!(cursor.is_empty());
+    assert_eq!(cursor.peek(), None);
+    assert_eq!(cursor.next(), None);
+    assert_eq!(cursor.eat_while(|_| true), "");
+}
+
+#[test]
+fn format_lexer() {
+    use Lexeme::{Code, MissingCode, Space, Word};
+
+    for (input, expects) in [
+        ("", vec![]),
+        ("  ", vec![Space, Space]),
+        ("§§a§", vec![Code('§'), Word("a"), MissingCode]),
+        (
+            "one§r two §Z",
+            vec![Word("one"), Code('r'), Space, Word("two"), Space, Code('Z')],
+        ),
+    ] {
+        assert_eq!(
+            FormatLexer::new(input).collect::<Vec<_>>(),
+            expects,
+            "{input}"
+        );
+    }
+
+    // Continuing from a cursor
+    let mut cursor = Cursor::new("#- §kword");
+    assert!(cursor.eat_str("#- "));
+    let mut lexer = FormatLexer::from_cursor(cursor);
+    assert_eq!(lexer.next(), Some(Code('k')));
+    assert_eq!(lexer.cursor().rest(), "word");
+}
+
+#[test]
+fn fields() {
+    assert_eq!(
+        field("author: RemasteredArch"),
+        Some(("author", "RemasteredArch"))
+    );
+    assert_eq!(field("made_with-v2: a: b"), Some(("made_with-v2", "a: b")));
+    assert_eq!(field("key:no-space"), None);
+    assert_eq!(field(": value"), None);
+    assert_eq!(field("two words: value"), None);
+    assert_eq!(field("no colon"), None);
+}
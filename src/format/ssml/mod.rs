@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Exporting for the Speech Synthesis Markup Language, for text-to-speech.
+//!
+//! See [`Ssml`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     export::Ssml,
+//!     syntax::{minecraft::Format, Metadata, Token, TokenList},
+//!     Export,
+//! };
+//!
+//! let input = TokenList::new_from_boxed(
+//!     Box::new([Metadata::Title("crafty_novels".into())]),
+//!     Box::new([
+//!         Token::ThematicBreak,
+//!         Token::Text("A".into()),
+//!         Token::Space,
+//!         Token::Format(Format::Bold),
+//!         Token::Text("loud".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::Space,
+//!         Token::Text("word".into()),
+//!         Token::LineBreak,
+//!     ]),
+//! );
+//!
+//! let expected = concat!(
+//!     r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+//!     r#"<speak version="1.1" xmlns="http://www.w3.org/2001/10/synthesis" xml:lang="en">"#,
+//!     "<p>crafty_novels</p>",
+//!     r#"<break time="1500ms"/>A <emphasis>loud</emphasis> word<break strength="weak"/>"#,
+//!     "</speak>",
+//! );
+//!
+//! assert_eq!(Ssml::export_token_vector_to_string(input).as_ref(), expected);
+//! ```
+
+use crate::{
+    export::Capabilities,
+    syntax::{
+        minecraft::{ColorValue, Format, FormatState},
+        Metadata, Token, TokenList,
+    },
+    writer::Utf8Writer,
+    Export, ExportWithOptions,
+};
+pub use options::ExportOptions;
+use std::io::Write;
+
+mod options;
+#[cfg(test)]
+mod test;
+
+/// Exporting for the [Speech Synthesis Markup Language][SSML], so that works can be fed into
+/// text-to-speech pipelines.
+///
+/// # Format
+///
+/// The document is a single `<speak>` element, in English. If present, the title and the author
+/// are read first, as `<p>{title}</p>` and `<p>by {author}</p>`.
+///
+/// Inside of the contents:
+///
+/// - Plain text is written with XML special characters escaped, ex. `'&'` -> `"&amp;"`
+/// - Spaces are written as just plain spaces
+/// - Images are read as their alt text
+/// - Line breaks are a weak pause, `<break strength="weak"/>`
+/// - Paragraph breaks are a longer pause, `<break time="{milliseconds}ms"/>`
+/// - Each page starts with an even longer pause, `<break time="{milliseconds}ms"/>`
+/// - Bold, italic, and underlined text is read with `<emphasis>`
+/// - Colors and other decorations are dropped, unless [`ExportOptions`] announces them, in which
+///   case their names are read out as they are applied, ex. `"(gold)"`
+///
+/// The length of each pause is set in [`ExportOptions`].
+///
+/// [SSML]: https://www.w3.org/TR/speech-synthesis11/
+pub struct Ssml {}
+
+impl Ssml {
+    /// Parse a given abstract syntax vector into SSML with the given options, then output that as
+    /// a string.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_token_vector_to_string_with_options(
+        tokens: &TokenList,
+        options: &ExportOptions,
+    ) -> Box<str> {
+        let mut bytes: Vec<u8> = vec![];
+
+        Self::export_token_vector_to_writer_with_options(tokens, &mut bytes, options)
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/std/src/io/impls.rs#L433-L437
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/alloc/src/vec/mod.rs#L2569-L2592
+            .expect(
+                "the `std::io::Write` implementations for `Vec<u8>` are infallible (as of 1.80.1)",
+            );
+
+        String::from_utf8(bytes)
+            .expect("`Utf8Writer` only writes UTF-8 encoded types")
+            .into_boxed_str()
+    }
+
+    /// Parse a given abstract syntax vector into SSML with the given options, then output that
+    /// into a writer, like a [`std::fs::File`].
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    pub fn export_token_vector_to_writer_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        let mut writer = Utf8Writer::new(output);
+
+        writer.write_str(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<speak version="1.1" xmlns="http://www.w3.org/2001/10/synthesis" xml:lang="en">"#,
+        ))?;
+
+        for data in tokens.metadata_as_slice() {
+            match data {
+                Metadata::Title(title) => write_paragraph(&mut writer, "", title)?,
+                Metadata::Author(author) => write_paragraph(&mut writer, "by ", author)?,
+                _ => (),
+            }
+        }
+
+        let mut state = FormatState::new();
+        for token in tokens.tokens_as_slice() {
+            match token {
+                Token::Text(text) | Token::Image { alt: text, .. } => {
+                    write_escaped(&mut writer, text)?;
+                }
+                Token::Space => writer.write_char(' ')?,
+                Token::Align(_) | Token::Role(_) => (),
+                Token::LineBreak => writer.write_str(r#"<break strength="weak"/>"#)?,
+                Token::ParagraphBreak => {
+                    write!(writer, r#"<break time="{}ms"/>"#, options.paragraph_pause())?;
+                }
+                Token::ThematicBreak => {
+                    write!(writer, r#"<break time="{}ms"/>"#, options.page_pause())?;
+                }
+                Token::Format(format) => {
+                    let next_state = state.with(*format);
+
+                    match (is_emphasized(state), is_emphasized(next_state)) {
+                        (false, true) => writer.write_str("<emphasis>")?,
+                        (true, false) => writer.write_str("</emphasis>")?,
+                        _ => (),
+                    }
+                    if options.announce_formatting() && next_state != state {
+                        if let Some(name) = spoken_name(*format) {
+                            write!(writer, "({name}) ")?;
+                        }
+                    }
+
+                    state = next_state;
+                }
+            }
+        }
+
+        if is_emphasized(state) {
+            writer.write_str("</emphasis>")?;
+        }
+        writer.write_str("</speak>")?;
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl ExportWithOptions for Ssml {
+    type Options = ExportOptions;
+
+    /// See [`Ssml::export_token_vector_to_writer_with_options`].
+    fn export_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &Self::Options,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(tokens, output, options)
+    }
+}
+
+impl Export for Ssml {
+    fn capabilities() -> Capabilities {
+        Capabilities::NONE
+    }
+
+    /// Parse a given abstract syntax vector into SSML, then output that as a string.
+    fn export_token_vector_to_string(tokens: TokenList) -> Box<str> {
+        Self::export_token_vector_to_string_with_options(&tokens, &ExportOptions::default())
+    }
+
+    /// Parse a given abstract syntax vector into SSML, then output that into a writer, like a
+    /// [`std::fs::File`].
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_token_vector_to_writer(
+        tokens: TokenList,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(&tokens, output, &ExportOptions::default())
+    }
+}
+
+/// Whether text with the formatting of `state` is read with `<emphasis>`.
+const fn is_emphasized(state: FormatState) -> bool {
+    state.bold() || state.italic() || state.underline()
+}
+
+/// Returns the name to read out when `format` is applied, if any.
+fn spoken_name(format: Format) -> Option<Box<str>> {
+    Some(match format {
+        Format::Color(color) => ColorValue::from(color).name().replace('_', " ").into(),
+        Format::Obfuscated => "obfuscated".into(),
+        Format::Bold => "bold".into(),
+        Format::Strikethrough => "strikethrough".into(),
+        Format::Underline => "underline".into(),
+        Format::Italic => "italic".into(),
+        Format::Reset | Format::Unknown(_) => return None,
+    })
+}
+
+/// Write `prefix` and `text` as a paragraph, without any format codes in `text`.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+fn write_paragraph(
+    output: &mut Utf8Writer<impl Write>,
+    prefix: &str,
+    text: &str,
+) -> std::io::Result<()> {
+    output.write_str("<p>")?;
+    output.write_str(prefix)?;
+    write_escaped(output, &crate::syntax::minecraft::strip_format_codes(text))?;
+    output.write_str("</p>")
+}
+
+/// Write `text`, escaping the characters that are special in XML.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+fn write_escaped(output: &mut Utf8Writer<impl Write>, text: &str) -> std::io::Result<()> {
+    for char in text.chars() {
+        match char {
+            '&' => output.write_str("&amp;")?,
+            '<' => output.write_str("&lt;")?,
+            '>' => output.write_str("&gt;")?,
+            '"' => output.write_str("&quot;")?,
+            '\'' => output.write_str("&apos;")?,
+            _ => output.write_char(char)?,
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration for [`super::Ssml`].
+//!
+//! See [`ExportOptions`].
+
+/// Options that control how a work is exported to SSML.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Ssml, SsmlExportOptions},
+///     syntax::{
+///         minecraft::{Color, Format},
+///         Token, TokenList,
+///     },
+/// };
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::Format(Format::Color(Color::DarkRed)),
+///         Token::Text("Danger".into()),
+///     ]),
+/// );
+/// let options = SsmlExportOptions::new().with_announce_formatting(true);
+///
+/// let output = Ssml::export_token_vector_to_string_with_options(&input, &options);
+/// assert!(output.contains("(dark red) Danger"));
+/// ```
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExportOptions {
+    /// Whether to read out colors and decorations as they are applied.
+    announce_formatting: bool,
+    /// How long to pause at a paragraph break, in milliseconds.
+    paragraph_pause: u32,
+    /// How long to pause at the start of a page, in milliseconds.
+    page_pause: u32,
+}
+
+impl ExportOptions {
+    /// Creates a new [`ExportOptions`] with the default options.
+    ///
+    /// Formatting is not announced, paragraphs pause for 750 milliseconds, and pages pause for
+    /// 1500 milliseconds.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            announce_formatting: false,
+            paragraph_pause: 750,
+            page_pause: 1500,
+        }
+    }
+
+    /// Returns whether colors and decorations are read out as they are applied.
+    #[must_use]
+    pub const fn announce_formatting(&self) -> bool {
+        self.announce_formatting
+    }
+
+    /// Sets whether colors and decorations are read out as they are applied, ex. `"(bold)"`.
+    ///
+    /// Useful when the formatting carries meaning, like a color for each speaker.
+    #[must_use]
+    pub const fn with_announce_formatting(mut self, announce_formatting: bool) -> Self {
+        self.announce_formatting = announce_formatting;
+        self
+    }
+
+    /// Returns how long to pause at a paragraph break, in milliseconds.
+    #[must_use]
+    pub const fn paragraph_pause(&self) -> u32 {
+        self.paragraph_pause
+    }
+
+    /// Sets how long to pause at a paragraph break, in milliseconds.
+    #[must_use]
+    pub const fn with_paragraph_pause(mut self, milliseconds: u32) -> Self {
+        self.paragraph_pause = milliseconds;
+        self
+    }
+
+    /// Returns how long to pause at the start of a page, in milliseconds.
+    #[must_use]
+    pub const fn page_pause(&self) -> u32 {
+        self.page_pause
+    }
+
+    /// Sets how long to pause at the start of a page, in milliseconds.
+    #[must_use]
+    pub const fn with_page_pause(mut self, milliseconds: u32) -> Self {
+        self.page_pause = milliseconds;
+        self
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for exporting to the [SSML][`super::Ssml`] format.
+
+use super::{ExportOptions, Ssml};
+use crate::{
+    syntax::{
+        minecraft::{Color, Format},
+        Metadata, Token, TokenList,
+    },
+    Export,
+};
+
+/// The start of every document.
+const HEADER: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    r#"<speak version="1.1" xmlns="http://www.w3.org/2001/10/synthesis" xml:lang="en">"#,
+);
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+#[test]
+fn ssml_export() {
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([
+            Metadata::Title("§6Fish & <Chips>".into()),
+            Metadata::Author("RemasteredArch".into()),
+        ]),
+        Box::new([
+            ThematicBreak,
+            Token::Format(Format::Italic),
+            text!("one"),
+            Token::Format(Format::Bold),
+            Space,
+            Token::Format(Format::Color(Color::Gold)),
+            text!("two"),
+            LineBreak,
+            ParagraphBreak,
+            ThematicBreak,
+            Token::Format(Format::Reset),
+            Token::Format(Format::Underline),
+            text!("\"three\""),
+        ]),
+    );
+
+    assert_eq!(
+        Ssml::export_token_vector_to_string(tokens.clone()).as_ref(),
+        [
+            HEADER,
+            "<p>Fish &amp; &lt;Chips&gt;</p><p>by RemasteredArch</p>",
+            r#"<break time="1500ms"/><emphasis>one two<break strength="weak"/>"#,
+            r#"<break time="750ms"/><break time="1500ms"/></emphasis>"#,
+            "<emphasis>&quot;three&quot;</emphasis></speak>",
+        ]
+        .concat()
+    );
+
+    let options = ExportOptions::new()
+        .with_announce_formatting(true)
+        .with_paragraph_pause(100)
+        .with_page_pause(200);
+    assert_eq!(
+        Ssml::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        [
+            HEADER,
+            "<p>Fish &amp; &lt;Chips&gt;</p><p>by RemasteredArch</p>",
+            r#"<break time="200ms"/><emphasis>(italic) one(bold)  (gold) two"#,
+            r#"<break strength="weak"/><break time="100ms"/><break time="200ms"/></emphasis>"#,
+            "<emphasis>(underline) &quot;three&quot;</emphasis></speak>",
+        ]
+        .concat()
+    );
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The variations of the Stendhal format written by different versions of the mod.
+//!
+//! See [`Dialect`].
+
+use crate::syntax::Metadata;
+use std::fmt::Display;
+
+/// A variation of the Stendhal format, distinguished by its frontmatter.
+///
+/// [`Self::Classic`] and [`Self::Extended`] share the same page syntax, and only differ in which
+/// frontmatter fields are allowed. [`Self::MsBookshelf`] separates pages differently.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::{Stendhal, StendhalDialect};
+///
+/// let input = "title: crafty_novels
+/// author: RemasteredArch
+/// pages:
+/// description: A book
+/// #- Page one";
+///
+/// assert_eq!(Stendhal::detect_dialect(input)?, StendhalDialect::Extended);
+/// # Ok::<(), crafty_novels::import::StendhalTokenizeError>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dialect {
+    /// Only the `"title: "` and `"author: "` fields, followed by `"pages:"`.
+    Classic,
+    /// Any other fields as well, like `"description: "`, written before or after `"pages:"` by
+    /// some versions of Stendhal.
+    Extended,
+    /// The layout written by the Bookshelf family of mods, which has no `"pages:"` line. Instead,
+    /// the frontmatter ends at the first page separator, a line that starts and ends with
+    /// `"---"`, like `"---"` or `"--- Page 2 ---"`, and every page starts after one.
+    ///
+    /// Any fields are allowed, as in [`Self::Extended`], and the rest of the page syntax is the
+    /// same as Stendhal's.
+    MsBookshelf,
+}
+
+impl Dialect {
+    /// Detect the dialect of a work from its [`Metadata`].
+    ///
+    /// Any [`Metadata`] other than [`Metadata::Title`] and [`Metadata::Author`] can only have
+    /// come from a field outside of [`Self::Classic`].
+    /// [`Self::MsBookshelf`] can only be detected from the layout of a document, see
+    /// [`super::Stendhal::detect_dialect`].
+    #[must_use]
+    pub fn detect(metadata: &[Metadata]) -> Self {
+        if metadata
+            .iter()
+            .any(|data| !matches!(data, Metadata::Title(_) | Metadata::Author(_)))
+        {
+            Self::Extended
+        } else {
+            Self::Classic
+        }
+    }
+
+    /// Returns whether a work in the `other` dialect is also valid in this one.
+    ///
+    /// [`Self::Extended`] is a superset of [`Self::Classic`], and [`Self::MsBookshelf`] only
+    /// accepts itself.
+    #[must_use]
+    pub const fn accepts(self, other: Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Extended, Self::Classic | Self::Extended)
+                | (Self::Classic, Self::Classic)
+                | (Self::MsBookshelf, Self::MsBookshelf)
+        )
+    }
+
+    /// Returns the name of the dialect, which is also its [`Display`] representation.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Extended => "extended",
+            Self::MsBookshelf => "bookshelf",
+        }
+    }
+}
+
+impl Display for Dialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
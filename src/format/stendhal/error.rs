@@ -17,24 +17,200 @@
 
 //! Error definitions for [`super::Stendhal`].
 //!
-//! See [`TokenizeError`].
+//! See [`TokenizeError`], [`TokenizeWarning`], and [`DirectoryError`].
 
-use crate::syntax::ConversionError;
+use super::Dialect;
+use crate::syntax::TokenList;
+use crate::{
+    format::limits::Limit,
+    syntax::{span::Position, ConversionError},
+};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 /// All the errors that could occur while tokenizing a Stendhal document.
 #[allow(clippy::module_name_repetitions)] // This will be exported outside of `error`
 #[derive(thiserror::Error, Debug)]
 pub enum TokenizeError {
     /// Encountered when trying to convert invalid syntax.
-    #[error("could not perform conversion: {0}")]
-    Conversion(#[from] ConversionError),
+    #[error("could not perform conversion at {position}: {error}")]
+    Conversion {
+        /// Where the invalid syntax starts in the input.
+        position: Position,
+        /// What was invalid about it.
+        #[source]
+        error: ConversionError,
+    },
     /// Encountered when trying to parse an frontmatter that is incomplete or entirely missing.
+    ///
+    /// Works without any frontmatter can be parsed with
+    /// [`TokenizeOptions::with_headless`][`super::TokenizeOptions::with_headless`].
     #[error("frontmatter is not present or incomplete")]
     IncompleteOrMissingFrontmatter,
+    /// Encountered when the frontmatter is not allowed by the [`Dialect`] set in
+    /// [`TokenizeOptions`][`super::TokenizeOptions`].
+    #[error("expected the {expected} dialect of Stendhal, found the {detected} dialect")]
+    DialectMismatch {
+        /// The dialect set in the options.
+        expected: Dialect,
+        /// The dialect of the input.
+        detected: Dialect,
+    },
     /// Encountered when an iterator ends before its consumer is finished.
     #[error("expected document to be longer")]
     UnexpectedEndOfDocument,
+    /// Encountered when the input or output exceeds one of the configured
+    /// [`Limits`][`crate::format::limits::Limits`].
+    #[error("exceeded resource limit: {0}")]
+    LimitExceeded(#[from] Limit),
     /// Encoutered when an I/O action fails in some way.
     #[error("could not perform I/O action: {0}")]
     Io(#[from] std::io::Error),
 }
+
+/// A malformed format code that was dropped instead of returning an error, when tokenizing
+/// [leniently][`super::TokenizeOptions::with_lenient`].
+#[allow(clippy::module_name_repetitions)] // This will be exported outside of `error`
+#[derive(Debug)]
+pub struct TokenizeWarning {
+    /// The index of the page the format code is on.
+    page: usize,
+    /// The index of the line within its page that the format code is on.
+    line: usize,
+    /// Where the format code starts in the input.
+    position: Position,
+    /// The error that the format code would have caused.
+    error: ConversionError,
+}
+
+impl TokenizeWarning {
+    /// Creates a new [`TokenizeWarning`].
+    pub(super) const fn new(
+        page: usize,
+        line: usize,
+        position: Position,
+        error: ConversionError,
+    ) -> Self {
+        Self {
+            page,
+            line,
+            position,
+            error,
+        }
+    }
+
+    /// Returns the index of the page the format code is on, starting from zero.
+    #[must_use]
+    pub const fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the index of the line within its page that the format code is on, starting from
+    /// zero.
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns where the format code starts in the input, as opposed to [`Self::page`] and
+    /// [`Self::line`], which are where it is in the book.
+    #[must_use]
+    pub const fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the error that the format code would have caused.
+    #[must_use]
+    pub const fn error(&self) -> &ConversionError {
+        &self.error
+    }
+}
+
+impl Display for TokenizeWarning {
+    /// Numbers pages and lines from one, like in Minecraft, ex. `"page 2, line 3: no such format
+    /// code 'x'"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "page {}, line {}: {}",
+            self.page + 1,
+            self.line + 1,
+            self.error
+        )
+    }
+}
+
+/// The errors that could occur while tokenizing a directory of Stendhal documents with
+/// [`super::Stendhal::tokenize_dir`].
+#[derive(thiserror::Error, Debug)]
+pub enum DirectoryError {
+    /// Encountered when the directory itself cannot be read.
+    #[error("could not read directory: {0}")]
+    Io(#[from] std::io::Error),
+    /// Encountered when one or more of the files in the directory could not be tokenized.
+    ///
+    /// Every file is still tried, so the works that were tokenized are kept alongside the errors.
+    #[error("could not tokenize {} of {} files, first {}", errors.len(), errors.len() + works.len(), errors[0])]
+    Files {
+        /// The works that were tokenized, with the paths they were read from.
+        works: Vec<(PathBuf, TokenList)>,
+        /// The errors of the files that could not be tokenized.
+        errors: Vec<FileError>,
+    },
+}
+
+/// A file that could not be tokenized, as part of a [`DirectoryError::Files`].
+#[derive(thiserror::Error, Debug)]
+#[error("{}: {error}", path.display())]
+pub struct FileError {
+    /// The path of the file.
+    path: PathBuf,
+    /// Why it could not be tokenized.
+    #[source]
+    error: TokenizeError,
+}
+
+impl FileError {
+    /// Creates a new [`FileError`].
+    pub(super) const fn new(path: PathBuf, error: TokenizeError) -> Self {
+        Self { path, error }
+    }
+
+    /// Returns the path of the file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns why the file could not be tokenized.
+    #[must_use]
+    pub const fn error(&self) -> &TokenizeError {
+        &self.error
+    }
+}
+
+/// The errors that could occur while parsing a [`FilenamePattern`][`super::FilenamePattern`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum FilenamePatternError {
+    /// Encountered when a brace is not part of a placeholder, like the `}` of `"title}"`.
+    #[error("unmatched `{0}`")]
+    UnmatchedBrace(char),
+    /// Encountered when a placeholder is not `{title}` or `{author}`.
+    #[error("unknown placeholder `{{{0}}}`, expected `{{title}}` or `{{author}}`")]
+    UnknownPlaceholder(Box<str>),
+    /// Encountered when a placeholder appears more than once.
+    #[error("the `{{{0}}}` placeholder appears more than once")]
+    RepeatedPlaceholder(&'static str),
+    /// Encountered when two placeholders have nothing between them, so where one ends and the
+    /// other starts is ambiguous.
+    #[error("two placeholders have nothing between them")]
+    AdjacentPlaceholders,
+    /// Encountered when a path component is empty, like in `"{author}//{title}"`.
+    #[error("the pattern has an empty path component")]
+    EmptyComponent,
+    /// Encountered when there is neither a `{title}` nor an `{author}` placeholder.
+    #[error("the pattern has no `{{title}}` or `{{author}}` placeholder")]
+    NoPlaceholders,
+}
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deriving the metadata of a Stendhal document from the name of its file.
+//!
+//! See [`FilenamePattern`].
+
+use super::FilenamePatternError;
+use crate::syntax::{Metadata, TokenList};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    path::{Component, Path},
+    str::FromStr,
+};
+
+/// How the path of a file lays out the title and author of the work inside of it.
+///
+/// Useful for works without frontmatter, like those read with
+/// [`TokenizeOptions::with_headless`][`super::TokenizeOptions::with_headless`].
+///
+/// A pattern is text with `{title}` and `{author}` placeholders, like `"{author} - {title}"`. It
+/// can include directories, like `"{author}/{title}.stendhal"`, which are matched against the
+/// last components of the path. The file name is matched without its extension, unless the
+/// pattern has one too.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::StendhalFilenamePattern;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let pattern: StendhalFilenamePattern = "{author} - {title}".parse()?;
+/// assert_eq!(
+///     pattern.parse("books/RemasteredArch - crafty_novels.stendhal"),
+///     (Some("crafty_novels".into()), Some("RemasteredArch".into()))
+/// );
+/// assert_eq!(pattern.parse("crafty_novels.stendhal"), (None, None));
+///
+/// let pattern: StendhalFilenamePattern = "{author}/{title}.stendhal".parse()?;
+/// assert_eq!(
+///     pattern.parse("books/RemasteredArch/crafty_novels.stendhal"),
+///     (Some("crafty_novels".into()), Some("RemasteredArch".into()))
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FilenamePattern {
+    /// The pattern as it was written.
+    pattern: Box<str>,
+    /// The parts of each path component, from the outermost directory to the file name.
+    components: Box<[Box<[Part]>]>,
+    /// Whether the file name is matched with its extension.
+    extension: bool,
+}
+
+/// A piece of a single path component of a [`FilenamePattern`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Part {
+    /// Text that must appear as written.
+    Literal(Box<str>),
+    /// The `{title}` placeholder.
+    Title,
+    /// The `{author}` placeholder.
+    Author,
+}
+
+impl FilenamePattern {
+    /// Returns the pattern as it was written.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Match `path` against the pattern, returning the title and author, in that order, skipping
+    /// either if it is empty or the pattern has no placeholder for it.
+    ///
+    /// Returns neither if `path` does not match. Titles are more likely to contain the text
+    /// around a placeholder than names, so where a match is ambiguous, the title is as long as it
+    /// can be and the author is as short as it can be.
+    #[must_use]
+    pub fn parse(&self, path: impl AsRef<Path>) -> (Option<String>, Option<String>) {
+        let path = path.as_ref();
+        let file_name = if self.extension {
+            path.file_name()
+        } else {
+            path.file_stem()
+        };
+        let names: Vec<Cow<'_, str>> = path
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name),
+                _ => None,
+            })
+            .chain(file_name)
+            .map(|name| name.to_string_lossy())
+            .collect();
+
+        let Some(skip) = names.len().checked_sub(self.components.len()) else {
+            return (None, None);
+        };
+        let mut captures = (None, None);
+        let matches = self
+            .components
+            .iter()
+            .zip(&names[skip..])
+            .all(|(parts, name)| capture(parts, name, &mut captures));
+        if !matches {
+            return (None, None);
+        }
+
+        let (title, author) = captures;
+        (
+            title.and_then(non_empty).map(str::to_owned),
+            author.and_then(non_empty).map(str::to_owned),
+        )
+    }
+
+    /// Returns a copy of `tokens` with the title and author from the path of the file at `path`,
+    /// where `tokens` does not already have a [`Metadata::Title`] or [`Metadata::Author`].
+    #[must_use]
+    pub fn fill(&self, path: &Path, tokens: &TokenList) -> TokenList {
+        let (title, author) = self.parse(path);
+        let existing = tokens.metadata_as_slice();
+
+        let title = title
+            .filter(|_| {
+                !existing
+                    .iter()
+                    .any(|data| matches!(data, Metadata::Title(_)))
+            })
+            .map(|title| Metadata::Title(title.into()));
+        let author = author
+            .filter(|_| {
+                !existing
+                    .iter()
+                    .any(|data| matches!(data, Metadata::Author(_)))
+            })
+            .map(|author| Metadata::Author(author.into()));
+
+        if title.is_none() && author.is_none() {
+            return tokens.clone();
+        }
+
+        let metadata = title
+            .into_iter()
+            .chain(author)
+            .chain(existing.iter().cloned())
+            .collect();
+        TokenList::new(metadata, tokens.tokens())
+    }
+}
+
+impl Default for FilenamePattern {
+    /// The author, then the title, like `"{author} - {title}"`.
+    fn default() -> Self {
+        "{author} - {title}"
+            .parse()
+            .expect("the default pattern is valid")
+    }
+}
+
+impl Display for FilenamePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+impl FromStr for FilenamePattern {
+    type Err = FilenamePatternError;
+
+    /// Parse a pattern, like `"{author}/{title}.stendhal"`.
+    ///
+    /// # Errors
+    ///
+    /// - [`FilenamePatternError::UnmatchedBrace`] if a brace is not part of a placeholder
+    /// - [`FilenamePatternError::UnknownPlaceholder`] if a placeholder is not `{title}` or
+    ///   `{author}`
+    /// - [`FilenamePatternError::RepeatedPlaceholder`] if a placeholder appears more than once
+    /// - [`FilenamePatternError::AdjacentPlaceholders`] if two placeholders have nothing between
+    ///   them
+    /// - [`FilenamePatternError::EmptyComponent`] if the pattern has an empty path component
+    /// - [`FilenamePatternError::NoPlaceholders`] if the pattern has no placeholders at all
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let components = pattern
+            .split('/')
+            .map(parse_component)
+            .collect::<Result<Box<[_]>, _>>()?;
+
+        let mut placeholders = components
+            .iter()
+            .flatten()
+            .filter(|part| !matches!(part, Part::Literal(_)));
+        let (title, author) = (
+            placeholders
+                .clone()
+                .filter(|part| **part == Part::Title)
+                .count(),
+            placeholders
+                .clone()
+                .filter(|part| **part == Part::Author)
+                .count(),
+        );
+        if title > 1 {
+            return Err(FilenamePatternError::RepeatedPlaceholder("title"));
+        }
+        if author > 1 {
+            return Err(FilenamePatternError::RepeatedPlaceholder("author"));
+        }
+        if placeholders.next().is_none() {
+            return Err(FilenamePatternError::NoPlaceholders);
+        }
+
+        let file_name = pattern.rsplit('/').next().unwrap_or(pattern);
+        Ok(Self {
+            pattern: pattern.into(),
+            components,
+            extension: Path::new(file_name).extension().is_some(),
+        })
+    }
+}
+
+/// Parse a single path component of a [`FilenamePattern`].
+///
+/// # Errors
+///
+/// See [`FilenamePattern::from_str`].
+fn parse_component(component: &str) -> Result<Box<[Part]>, FilenamePatternError> {
+    if component.is_empty() {
+        return Err(FilenamePatternError::EmptyComponent);
+    }
+
+    let mut parts = vec![];
+    let mut rest = component;
+    while let Some(start) = rest.find(['{', '}']) {
+        let (literal, placeholder) = rest.split_at(start);
+        if placeholder.starts_with('}') {
+            return Err(FilenamePatternError::UnmatchedBrace('}'));
+        }
+        let Some(end) = placeholder.find('}') else {
+            return Err(FilenamePatternError::UnmatchedBrace('{'));
+        };
+
+        let part = match &placeholder[1..end] {
+            "title" => Part::Title,
+            "author" => Part::Author,
+            name => return Err(FilenamePatternError::UnknownPlaceholder(name.into())),
+        };
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal.into()));
+        } else if matches!(parts.last(), Some(Part::Title | Part::Author)) {
+            return Err(FilenamePatternError::AdjacentPlaceholders);
+        }
+        parts.push(part);
+
+        rest = &placeholder[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest.into()));
+    }
+
+    Ok(parts.into())
+}
+
+/// Match `text` against `parts`, setting the title and author of `captures`, in that order, to
+/// the text of their placeholders.
+fn capture<'a>(
+    parts: &[Part],
+    text: &'a str,
+    captures: &mut (Option<&'a str>, Option<&'a str>),
+) -> bool {
+    let (placeholder, rest) = match parts {
+        [] => return text.is_empty(),
+        [Part::Literal(literal), rest @ ..] => {
+            return text
+                .strip_prefix(&**literal)
+                .is_some_and(|text| capture(rest, text, captures));
+        }
+        [placeholder, rest @ ..] => (placeholder, rest),
+    };
+
+    // Try the longest title first and the shortest author first
+    let mut ends: Vec<usize> = text
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([text.len()])
+        .collect();
+    if *placeholder == Part::Title {
+        ends.reverse();
+    }
+
+    ends.into_iter().any(|end| {
+        let (value, text) = text.split_at(end);
+        match placeholder {
+            Part::Title => captures.0 = Some(value),
+            _ => captures.1 = Some(value),
+        }
+        capture(rest, text, captures)
+    })
+}
+
+/// Returns `text` without surrounding whitespace, or [`None`] if that leaves nothing.
+fn non_empty(text: &str) -> Option<&str> {
+    Some(text.trim()).filter(|text| !text.is_empty())
+}
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Suggesting fixes for the errors of [`super::Stendhal`].
+//!
+//! See [`suggest`].
+
+use super::{parse, TokenizeError};
+use crate::{
+    format::{scan, suggestion::Suggestion},
+    syntax::{minecraft::FormatCode, ConversionError},
+};
+
+/// Returns fixes for `error`, which was returned when tokenizing `input`, most likely first.
+///
+/// Only [`ConversionError::NoSuchFormatCode`], [`ConversionError::MissingFormatCode`], and
+/// [`TokenizeError::IncompleteOrMissingFrontmatter`] have fixes, and only if the problem can be
+/// found in `input`.
+pub fn suggest(input: &str, error: &TokenizeError) -> Vec<Suggestion> {
+    match error {
+        TokenizeError::Conversion {
+            error: ConversionError::NoSuchFormatCode(code),
+            ..
+        } => unknown_format_code(input, *code),
+        TokenizeError::Conversion {
+            error: ConversionError::MissingFormatCode,
+            ..
+        } => missing_format_code(input),
+        TokenizeError::IncompleteOrMissingFrontmatter => frontmatter(input),
+        _ => vec![],
+    }
+}
+
+/// Returns each line of `input` with the byte index it starts at, without its line ending.
+fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.split_inclusive('\n').scan(0, |start, line| {
+        let line_start = *start;
+        *start += line.len();
+
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        Some((line_start, line.strip_suffix('\r').unwrap_or(line)))
+    })
+}
+
+/// Returns the byte index where the pages start, just after the `"pages:"` line, or zero if there
+/// isn't one.
+fn pages_start(input: &str) -> usize {
+    input
+        .split_inclusive('\n')
+        .scan(0, |end, line| {
+            *end += line.len();
+            Some((*end, line.trim_end_matches(['\n', '\r'])))
+        })
+        .find(|(_, line)| *line == "pages:")
+        .map_or(0, |(end, _)| end)
+}
+
+/// Suggest replacing the first `'§'` followed by `code` in the pages with a similar format code,
+/// if there is one, or removing it.
+fn unknown_format_code(input: &str, code: char) -> Vec<Suggestion> {
+    let start = pages_start(input);
+    let pattern = format!("§{code}");
+    let Some(index) = input[start..].find(&pattern).map(|index| start + index) else {
+        return vec![];
+    };
+    let range = index..index + pattern.len();
+
+    let lowercase = code.to_ascii_lowercase();
+    let similar = if FormatCode::new(lowercase).is_ok() {
+        Some(lowercase)
+    } else {
+        // Guesses based on the name of the format, rather than its code
+        match lowercase {
+            'i' => Some('o'),
+            'u' => Some('n'),
+            's' => Some('m'),
+            _ => None,
+        }
+    };
+
+    let mut suggestions = vec![];
+    if let Some(similar) = similar {
+        suggestions.push(Suggestion::new(
+            input,
+            range.clone(),
+            format!("§{similar}"),
+            format!("did you mean `§{similar}`?"),
+        ));
+    }
+    suggestions.push(Suggestion::new(
+        input,
+        range,
+        "",
+        format!("remove the unknown format code `{pattern}`"),
+    ));
+
+    suggestions
+}
+
+/// Suggest removing the first `'§'` at the end of a line in the pages.
+fn missing_format_code(input: &str) -> Vec<Suggestion> {
+    let start = pages_start(input);
+
+    lines(input)
+        .filter(|(line_start, _)| *line_start >= start)
+        .find(|(_, line)| line.ends_with('§'))
+        .map(|(line_start, line)| {
+            let end = line_start + line.len();
+            Suggestion::new(
+                input,
+                end - '§'.len_utf8()..end,
+                "",
+                "remove the `§` that isn't followed by a format code",
+            )
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Suggest the fields that the frontmatter is missing, and separating keys from their values.
+fn frontmatter(input: &str) -> Vec<Suggestion> {
+    /// Returns `line` as a line to insert at `index` of `input`, starting a new line first if
+    /// `index` is not at the start of one.
+    fn new_line(input: &str, index: usize, line: &str) -> String {
+        if index == 0 || input[..index].ends_with('\n') {
+            format!("{line}\n")
+        } else {
+            format!("\n{line}")
+        }
+    }
+
+    let mut suggestions = vec![];
+    // The end of the last field, including its line ending, and of the title
+    let mut fields_end = 0;
+    let mut title_end = None;
+    let mut has_author = false;
+    let mut has_pages = false;
+
+    for (line_start, line) in lines(input) {
+        if line == "pages:" || parse::is_page_separator(line) {
+            has_pages = true;
+            break;
+        }
+
+        let line_end = input[line_start..]
+            .find('\n')
+            .map_or(input.len(), |index| line_start + index + 1);
+        let key = match scan::field(line) {
+            Some((key, _)) => key,
+            // Ex. `"title Book"`
+            None => match ["title", "author"].into_iter().find(|key| {
+                line.strip_prefix(key)
+                    .is_some_and(|rest| rest.starts_with(' '))
+            }) {
+                Some(key) => {
+                    let index = line_start + key.len();
+                    suggestions.push(Suggestion::new(
+                        input,
+                        index..index,
+                        ":",
+                        format!("insert `:` after `{key}`"),
+                    ));
+                    key
+                }
+                None => break,
+            },
+        };
+
+        match key {
+            "title" => title_end = title_end.or(Some(line_end)),
+            "author" => has_author = true,
+            _ => (),
+        }
+        fields_end = line_end;
+    }
+
+    if title_end.is_none() {
+        suggestions.push(Suggestion::new(
+            input,
+            0..0,
+            "title: \n",
+            "insert a `title:` line",
+        ));
+    }
+    if !has_author {
+        let index = title_end.unwrap_or(0);
+        suggestions.push(Suggestion::new(
+            input,
+            index..index,
+            new_line(input, index, "author: "),
+            "insert an `author:` line",
+        ));
+    }
+    if !has_pages {
+        suggestions.push(Suggestion::new(
+            input,
+            fields_end..fields_end,
+            new_line(input, fields_end, "pages:"),
+            "insert `pages:` after the last field to end the frontmatter",
+        ));
+    }
+
+    suggestions
+}
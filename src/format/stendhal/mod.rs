@@ -62,13 +62,35 @@
 //! ```
 
 use crate::{
-    syntax::{Token, TokenList},
+    format::{
+        limits::Limit,
+        lines::{split_str, BoundedLines, Piece},
+        suggestion::Suggestion,
+        text_encoding::decode,
+    },
+    syntax::{
+        span::{Position, Span},
+        stream::TokenStream,
+        Metadata, Token, TokenList,
+    },
     Tokenize,
 };
-pub use error::TokenizeError;
-use std::io::{BufRead, BufReader, Read};
+pub use dialect::Dialect;
+pub use error::{DirectoryError, FileError, FilenamePatternError, TokenizeError, TokenizeWarning};
+pub use filename::FilenamePattern;
+pub use options::TokenizeOptions;
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader, Read, Take},
+    iter::Peekable,
+    path::{Path, PathBuf},
+};
 
+mod dialect;
 mod error;
+mod filename;
+mod fixes;
+mod options;
 mod parse;
 #[cfg(test)]
 mod test;
@@ -79,12 +101,17 @@ mod test;
 ///
 /// *Convention: `"a string"` `'a single character'` (the `"` or `'` are not necessarily present).*
 ///
-/// The first three lines make up the frontmatter:
+/// The first lines make up the frontmatter, a series of `"key: value"` fields:
 /// 1. Starts with `"title: "`, the rest is considered the title of the book
 /// 2. Starts with `"author: "`, the rest is considered the author's name, which is probably
 ///    whoever exported the book
 /// 3. Starts and ends with `"pages:"`
 ///
+/// Some versions of Stendhal write other fields, like `"description: "`, before or after
+/// `"pages:"`. `"date"`, `"description"`, `"language"`, and `"generator"` become their own
+/// [`Metadata`][`crate::syntax::Metadata`], and any others are captured as
+/// [`Metadata::Custom`][`crate::syntax::Metadata::Custom`].
+///
 /// For the rest of the book:
 /// - Any line that starts with `"#- "` is considered the start of a new page, and the text
 ///   following the `"#- "` makes up the first line of the new page
@@ -93,69 +120,738 @@ mod test;
 ///     - The resulting format continues until the next line ending or
 ///       [reset][`crate::syntax::minecraft::Format::Reset`] format code
 ///
+/// The fields that are allowed depend on the [`Dialect`], which is detected automatically unless
+/// set in [`TokenizeOptions`].
+///
+/// Exports from the Bookshelf family of mods are also accepted, which have no `"pages:"` line and
+/// separate pages with lines like `"--- Page 2 ---"`, see [`Dialect::MsBookshelf`].
+///
 /// [Stendhal]: https://modrinth.com/mod/stendhal
 pub struct Stendhal;
 
-impl Tokenize for Stendhal {
-    type Error = TokenizeError;
+impl Stendhal {
+    /// The extension of the files that the Stendhal mod exports, which are the files read by
+    /// [`Self::tokenize_dir`].
+    pub const EXTENSION: &'static str = "stendhal";
 
-    /// Parse a string in the Stendhal format into an abstract syntax vector.
+    /// Detect the [`Dialect`] of a string in the Stendhal format, only reading its frontmatter.
     ///
     /// # Errors
     ///
-    /// - [`crate::syntax::ConversionError::MissingFormatCode`] if it encounters a `'§'` that isn't
-    ///   followed by another character
-    /// - [`crate::syntax::ConversionError::NoSuchFormatCode`] if it encounters a `'§'` isn't
-    ///   followed by a valid [`Format`][`crate::syntax::minecraft::Format`] character
     /// - [`TokenizeError::IncompleteOrMissingFrontmatter`] if `input` ends before the frontmatter
-    ///   parsing is finished
-    fn tokenize_string(input: &str) -> Result<TokenList, Self::Error> {
-        let mut input = input.lines();
-        let mut tokens: Vec<Token> = vec![];
+    ///   parsing is finished, unless `options` is [headless][`TokenizeOptions::with_headless`]
+    pub fn detect_dialect(input: &str) -> Result<Dialect, TokenizeError> {
+        parse::frontmatter(&mut input.lines().map(|line| Ok(Piece::whole(line))).peekable())
+            .map(|(_, dialect, _)| dialect)
+    }
 
-        // Could be recovered by capturing the state of `input` before calling, then reverting on
-        // certain errors.
-        let metadata = parse::frontmatter(&mut input)?;
+    /// Returns fixes for `error`, which was returned when tokenizing `input`, most likely first.
+    ///
+    /// There are fixes for unknown or missing format codes and for incomplete frontmatter. Each
+    /// fix is a change to `input` that an editor could apply, see [`Suggestion`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::{import::Stendhal, Tokenize};
+    ///
+    /// let input = "title: Book
+    /// author: RemasteredArch
+    /// pages:
+    /// #- Italic:§i text";
+    ///
+    /// let error = Stendhal::tokenize_string(input).unwrap_err();
+    /// let suggestions = Stendhal::suggest_fixes(input, &error);
+    ///
+    /// assert_eq!(suggestions[0].message(), "did you mean `§o`?");
+    /// assert!(Stendhal::tokenize_string(&suggestions[0].apply(input)).is_ok());
+    /// ```
+    #[must_use]
+    pub fn suggest_fixes(input: &str, error: &TokenizeError) -> Vec<Suggestion> {
+        fixes::suggest(input, error)
+    }
 
-        for line in input {
-            parse::line(&mut tokens, line)?;
-        }
+    /// Parse the pages of a work in the Stendhal format, without any frontmatter, into an
+    /// abstract syntax vector with no metadata.
+    ///
+    /// Uses the default [`TokenizeOptions`], set to be [headless][`TokenizeOptions::with_headless`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::import::Stendhal;
+    ///
+    /// let tokens = Stendhal::tokenize_string_headless("#- Page one\n#- Page two")?;
+    ///
+    /// assert!(tokens.metadata_as_slice().is_empty());
+    /// assert_eq!(tokens.page_count(), 2);
+    /// # Ok::<(), crafty_novels::import::StendhalTokenizeError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_string_with_options`], except that there is no frontmatter to be
+    /// missing.
+    pub fn tokenize_string_headless(input: &str) -> Result<TokenList, TokenizeError> {
+        Self::tokenize_string_with_options(input, &TokenizeOptions::new().with_headless(true))
+    }
 
-        Ok(TokenList::new_from_boxed(metadata, tokens.into()))
+    /// Parse a string in the Stendhal format into an abstract syntax vector, according to
+    /// `options` but [lenient][`TokenizeOptions::with_lenient`], also returning a
+    /// [`TokenizeWarning`] for each malformed format code that was dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::import::{Stendhal, StendhalTokenizeOptions};
+    ///
+    /// let input = "title: Book
+    /// author: RemasteredArch
+    /// pages:
+    /// #- Italic:§i text§";
+    ///
+    /// let (tokens, warnings) =
+    ///     Stendhal::tokenize_string_lenient(input, &StendhalTokenizeOptions::new())?;
+    ///
+    /// assert_eq!(tokens.page_count(), 1);
+    /// assert_eq!(warnings[0].to_string(), "page 1, line 1: no such format code 'i'");
+    /// assert_eq!(
+    ///     warnings[1].to_string(),
+    ///     "page 1, line 1: expected a format code after '§'"
+    /// );
+    /// # Ok::<(), crafty_novels::import::StendhalTokenizeError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_string_with_options`], except for the
+    /// [`TokenizeError::Conversion`]s that are now warnings.
+    pub fn tokenize_string_lenient(
+        input: &str,
+        options: &TokenizeOptions,
+    ) -> Result<(TokenList, Vec<TokenizeWarning>), TokenizeError> {
+        Self::tokenize_string_detailed(input, &options.clone().with_lenient(true), false)
+            .map(|tokenized| (tokenized.tokens, tokenized.warnings))
     }
 
-    /// Parse a file in the Stendhal format into an abstract syntax vector.
+    /// Parse a string in the Stendhal format into an abstract syntax vector, according to
+    /// `options`, also returning the [`Span`] of each token, in the same order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::{
+    ///     import::{Stendhal, StendhalTokenizeOptions},
+    ///     syntax::{span::Position, Token},
+    /// };
+    ///
+    /// let input = "title: Book
+    /// author: RemasteredArch
+    /// pages:
+    /// #- Hello, world";
+    ///
+    /// let (tokens, spans) =
+    ///     Stendhal::tokenize_string_spanned(input, &StendhalTokenizeOptions::new())?;
+    ///
+    /// assert_eq!(tokens.tokens_as_slice()[3], Token::Text("world".into()));
+    /// assert_eq!(spans[3].start(), Position::new(4, 11));
+    /// assert_eq!(spans[3].end(), Position::new(4, 16));
+    /// # Ok::<(), crafty_novels::import::StendhalTokenizeError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_string_with_options`].
+    pub fn tokenize_string_spanned(
+        input: &str,
+        options: &TokenizeOptions,
+    ) -> Result<(TokenList, Vec<Span>), TokenizeError> {
+        Self::tokenize_string_detailed(input, options, true)
+            .map(|tokenized| (tokenized.tokens, tokenized.spans))
+    }
+
+    /// Parse a string in the Stendhal format into an abstract syntax vector, according to
+    /// `options`.
     ///
     /// # Errors
     ///
-    /// - [`crate::syntax::ConversionError::MissingFormatCode`] if it encounters a `'§'` that isn't
-    ///   followed by another character
-    /// - [`crate::syntax::ConversionError::NoSuchFormatCode`] if it encounters a `'§'` isn't
-    ///   followed by a valid [`Format`][`crate::syntax::minecraft::Format`] character
+    /// - [`TokenizeError::Conversion`], with where in `input` it happened, and:
+    ///     - [`crate::syntax::ConversionError::MissingFormatCode`] if it encounters a `'§'` that
+    ///       isn't followed by another character
+    ///     - [`crate::syntax::ConversionError::NoSuchFormatCode`] if it encounters a `'§'` isn't
+    ///       followed by a valid [`Format`][`crate::syntax::minecraft::Format`] character, unless
+    ///       `options` preserves unknown format codes
     /// - [`TokenizeError::IncompleteOrMissingFrontmatter`] if `input` ends before the frontmatter
-    ///   parsing is finished
+    ///   parsing is finished, unless `options` is [headless][`TokenizeOptions::with_headless`]
+    /// - [`TokenizeError::DialectMismatch`] if the frontmatter is not allowed by the [`Dialect`]
+    ///   set in `options`
+    /// - [`TokenizeError::LimitExceeded`] if `input` or the output exceed the limits set in
+    ///   `options`
+    pub fn tokenize_string_with_options(
+        input: &str,
+        options: &TokenizeOptions,
+    ) -> Result<TokenList, TokenizeError> {
+        Self::tokenize_string_detailed(input, options, false).map(|tokenized| tokenized.tokens)
+    }
+
+    /// Parse a string in the Stendhal format into an abstract syntax vector, according to
+    /// `options`, with the details of how it was tokenized, including the span of each token if
+    /// `spanned`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_string_with_options`].
+    fn tokenize_string_detailed(
+        input: &str,
+        options: &TokenizeOptions,
+        spanned: bool,
+    ) -> Result<Tokenized, TokenizeError> {
+        let limits = options.limits();
+        limits.check_input_size(input.len())?;
+
+        let pieces = input
+            .lines()
+            .flat_map(|line| split_str(line, limits.max_line_length()))
+            .map(Ok);
+
+        tokenize_lines(pieces, input.len(), options, spanned)
+    }
+
+    /// Parse a file in the Stendhal format into an abstract syntax vector, according to `options`.
+    ///
+    /// Never reads more than one byte past the maximum input size set in `options`.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Conversion`], with where in `input` it happened, and:
+    ///     - [`crate::syntax::ConversionError::MissingFormatCode`] if it encounters a `'§'` that
+    ///       isn't followed by another character
+    ///     - [`crate::syntax::ConversionError::NoSuchFormatCode`] if it encounters a `'§'` isn't
+    ///       followed by a valid [`Format`][`crate::syntax::minecraft::Format`] character, unless
+    ///       `options` preserves unknown format codes
+    /// - [`TokenizeError::IncompleteOrMissingFrontmatter`] if `input` ends before the frontmatter
+    ///   parsing is finished, unless `options` is [headless][`TokenizeOptions::with_headless`]
+    /// - [`TokenizeError::DialectMismatch`] if the frontmatter is not allowed by the [`Dialect`]
+    ///   set in `options`
+    /// - [`TokenizeError::LimitExceeded`] if `input` or the output exceed the limits set in
+    ///   `options`
     /// - [`TokenizeError::Io`] if the a line from `input` is an I/O error of some kind
-    fn tokenize_reader(input: impl Read) -> Result<TokenList, Self::Error> {
-        /// Get a refrence to the next element in `$iter` or return [`Error::UnexpectedEndOfIter`]
-        /// or the encapsulated [`Error::Io`].
-        macro_rules! next {
-            ($iter:expr) => {
-                &$iter
-                    .next()
-                    .ok_or(Self::Error::IncompleteOrMissingFrontmatter)??
+    pub fn tokenize_reader_with_options(
+        input: impl Read,
+        options: &TokenizeOptions,
+    ) -> Result<TokenList, TokenizeError> {
+        Self::tokenize_reader_detailed(input, options, false).map(|tokenized| tokenized.tokens)
+    }
+
+    /// Parse a file in the Stendhal format into an abstract syntax vector, according to `options`
+    /// but [lenient][`TokenizeOptions::with_lenient`], also returning a [`TokenizeWarning`] for
+    /// each malformed format code that was dropped.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_reader_with_options`], except for the
+    /// [`TokenizeError::Conversion`]s that are now warnings.
+    pub fn tokenize_reader_lenient(
+        input: impl Read,
+        options: &TokenizeOptions,
+    ) -> Result<(TokenList, Vec<TokenizeWarning>), TokenizeError> {
+        Self::tokenize_reader_detailed(input, &options.clone().with_lenient(true), false)
+            .map(|tokenized| (tokenized.tokens, tokenized.warnings))
+    }
+
+    /// Parse a file in the Stendhal format into an abstract syntax vector, according to `options`,
+    /// also returning the [`Span`] of each token, in the same order.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_reader_with_options`].
+    pub fn tokenize_reader_spanned(
+        input: impl Read,
+        options: &TokenizeOptions,
+    ) -> Result<(TokenList, Vec<Span>), TokenizeError> {
+        Self::tokenize_reader_detailed(input, options, true)
+            .map(|tokenized| (tokenized.tokens, tokenized.spans))
+    }
+
+    /// Parse every Stendhal export in the directory at `path` into an abstract syntax vector,
+    /// returning each with the path it was read from.
+    ///
+    /// See [`Self::tokenize_dir_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_dir_with_options`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use crafty_novels::import::Stendhal;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// for (path, tokens) in Stendhal::tokenize_dir("books")? {
+    ///     println!("{}: {} pages", path.display(), tokens.page_count());
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn tokenize_dir(
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<(PathBuf, TokenList)>, DirectoryError> {
+        Self::tokenize_dir_with_options(path, &TokenizeOptions::default())
+    }
+
+    /// Parse every Stendhal export in the directory at `path` into an abstract syntax vector,
+    /// according to `options`, returning each with the path it was read from, sorted by path.
+    ///
+    /// Only the files directly inside of the directory that end in [`Self::EXTENSION`], ignoring
+    /// case, are read, each like [`Self::tokenize_path_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// - [`DirectoryError::Io`] if the directory cannot be read
+    /// - [`DirectoryError::Files`] if any of the files could not be tokenized, see
+    ///   [`Self::tokenize_reader_with_options`], after trying every other file, which are kept
+    ///   alongside the errors
+    pub fn tokenize_dir_with_options(
+        path: impl AsRef<Path>,
+        options: &TokenizeOptions,
+    ) -> Result<Vec<(PathBuf, TokenList)>, DirectoryError> {
+        let mut paths = vec![];
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            let is_export = path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case(Self::EXTENSION));
+
+            if is_export && path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut works = vec![];
+        let mut errors = vec![];
+        for path in paths {
+            match Self::tokenize_path_with_options(&path, options) {
+                Ok(tokens) => works.push((path, tokens)),
+                Err(error) => errors.push(FileError::new(path, error)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(works)
+        } else {
+            Err(DirectoryError::Files { works, errors })
+        }
+    }
+
+    /// Parse the Stendhal export at `path` into an abstract syntax vector, according to `options`.
+    ///
+    /// The file is decoded first, like [`Tokenize::tokenize_path`]. With a
+    /// [filename pattern][`TokenizeOptions::with_filename_pattern`], a title or author missing
+    /// from the work is filled in from the path of the file, see [`FilenamePattern::fill`].
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Io`] if the file cannot be read
+    /// - See [`Self::tokenize_reader_with_options`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use crafty_novels::import::{Stendhal, StendhalTokenizeOptions};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = StendhalTokenizeOptions::new()
+    ///     .with_headless(true)
+    ///     .with_filename_pattern(Some("{author} - {title}".parse()?));
+    ///
+    /// // Titled "crafty_novels", by "RemasteredArch"
+    /// let tokens =
+    ///     Stendhal::tokenize_path_with_options("RemasteredArch - crafty_novels.txt", &options)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn tokenize_path_with_options(
+        path: impl AsRef<Path>,
+        options: &TokenizeOptions,
+    ) -> Result<TokenList, TokenizeError> {
+        let path = path.as_ref();
+        let tokens =
+            Self::tokenize_reader_with_options(decode(std::fs::File::open(path)?)?, options)?;
+
+        Ok(match options.filename_pattern() {
+            Some(pattern) => pattern.fill(path, &tokens),
+            None => tokens,
+        })
+    }
+
+    /// Parse a file in the Stendhal format into an abstract syntax vector, according to `options`,
+    /// with the details of how it was tokenized, including the span of each token if `spanned`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_reader_with_options`].
+    pub(crate) fn tokenize_reader_detailed(
+        input: impl Read,
+        options: &TokenizeOptions,
+        spanned: bool,
+    ) -> Result<Tokenized, TokenizeError> {
+        let limits = options.limits();
+        let mut lines = BoundedLines::new(
+            BufReader::new(input.take(options.limits().max_read())),
+            limits.max_line_length(),
+        );
+
+        // The size of the input isn't known ahead of time
+        let result = tokenize_lines(&mut lines, 0, options, spanned);
+
+        // Truncating the input could have caused any other error, so this takes precedence
+        if lines.get_ref().get_ref().limit() == 0 {
+            return Err(Limit::InputSize(limits.max_input_size()).into());
+        }
+
+        result
+    }
+
+    /// Parse a file in the Stendhal format lazily, according to `options`, returning its metadata
+    /// and an iterator that parses the rest a line at a time.
+    ///
+    /// Unlike [`Self::tokenize_reader_with_options`], only the current line and the tokens parsed
+    /// from it are held in memory, so any size of input can be converted in constant memory with
+    /// [`Export::export_token_iter_to_writer`][`crate::Export::export_token_iter_to_writer`]. The
+    /// limits set in `options` are still enforced, as they are reached.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::tokenize_reader_with_options`], which are returned here for the frontmatter,
+    /// or by the iterator for the rest of the input. The iterator ends after its first error.
+    pub fn token_iter_with_options<'a>(
+        input: impl BufRead + 'a,
+        options: &TokenizeOptions,
+    ) -> Result<TokenStream<'a, TokenizeError>, TokenizeError> {
+        let options = options.clone();
+        let limits = options.limits();
+        let mut lines = BoundedLines::new(
+            input.take(options.limits().max_read()),
+            limits.max_line_length(),
+        );
+
+        // Truncating the input could have caused any other error, so this takes precedence
+        let truncated = move |lines: &BoundedLines<Take<_>>, error: TokenizeError| {
+            if lines.get_ref().limit() == 0 {
+                Limit::InputSize(limits.max_input_size()).into()
+            } else {
+                error
+            }
+        };
+
+        let mut buffer: Vec<Token> = vec![];
+        let mut iter = lines.by_ref().peekable();
+        let started = Pages::start(&mut iter, &mut buffer, options, false);
+        // Whatever the frontmatter looked ahead at is the start of the pages
+        let mut pending = iter.next();
+        let (metadata, mut pages) = started.map_err(|error| truncated(&lines, error))?;
+
+        let mut buffer: VecDeque<Token> = buffer.into();
+        let mut scratch: Vec<Token> = vec![];
+        let mut done = false;
+
+        let tokens = std::iter::from_fn(move || loop {
+            if let Some(token) = buffer.pop_front() {
+                return Some(Ok(token));
+            }
+            if done {
+                return None;
+            }
+
+            let result = match pending.take().or_else(|| lines.next()) {
+                Some(Ok(piece)) => pages.push(&mut scratch, &piece),
+                Some(Err(error)) => Err(error.into()),
+                None if lines.get_ref().limit() == 0 => {
+                    Err(Limit::InputSize(limits.max_input_size()).into())
+                }
+                None => {
+                    done = true;
+                    continue;
+                }
             };
+
+            if let Err(error) = result {
+                done = true;
+                return Some(Err(truncated(&lines, error)));
+            }
+            buffer.extend(std::mem::take(&mut scratch));
+        });
+
+        Ok(TokenStream::new(metadata, tokens))
+    }
+}
+
+/// The approximate number of bytes of input that make up each [`Token`].
+///
+/// Most tokens are either short words or the single spaces between them, averaging closer to three
+/// bytes in long books. This rounds down, because allocating slightly too much is cheaper than
+/// growing once the estimate runs out.
+const BYTES_PER_TOKEN: usize = 2;
+
+/// A tokenized work, with the details of how it was tokenized.
+pub struct Tokenized {
+    /// The work itself.
+    pub tokens: TokenList,
+    /// How many lines were split for being longer than the maximum line length.
+    pub split_lines: usize,
+    /// The dialect of the work.
+    pub dialect: Dialect,
+    /// The malformed format codes that were dropped, if the options were lenient.
+    pub warnings: Vec<TokenizeWarning>,
+    /// The span of each token, if they were recorded.
+    pub spans: Vec<Span>,
+}
+
+/// Parse the lines of a string or file in the Stendhal format into an abstract syntax vector,
+/// also returning how many lines were split and which [`Dialect`] was detected.
+///
+/// The lines should already be split into [`Piece`]s no longer than the maximum line length.
+///
+/// `input_size` is the size of the input in bytes, if known, or zero. It is used to allocate
+/// enough space for the tokens up front, rather than growing repeatedly while parsing.
+///
+/// # Errors
+///
+/// See [`Stendhal::tokenize_reader_with_options`].
+fn tokenize_lines<S: AsRef<str>>(
+    iter: impl Iterator<Item = std::io::Result<Piece<S>>>,
+    input_size: usize,
+    options: &TokenizeOptions,
+    spanned: bool,
+) -> Result<Tokenized, TokenizeError> {
+    let mut tokens: Vec<Token> =
+        Vec::with_capacity((input_size / BYTES_PER_TOKEN).min(options.limits().max_tokens()));
+    let mut iter = iter.peekable();
+
+    let (metadata, mut pages) = Pages::start(&mut iter, &mut tokens, options.clone(), spanned)?;
+
+    for piece in iter {
+        pages.push(&mut tokens, &piece?)?;
+    }
+
+    Ok(Tokenized {
+        tokens: TokenList::new_from_boxed(metadata, tokens.into()),
+        split_lines: pages.split_lines,
+        dialect: pages.dialect,
+        warnings: pages.warnings,
+        spans: pages.spans.unwrap_or_default(),
+    })
+}
+
+/// Parses the pages of a work, one [`Piece`] at a time, after its frontmatter.
+struct Pages {
+    /// The options to parse with.
+    options: TokenizeOptions,
+    /// The dialect of the work.
+    dialect: Dialect,
+    /// Where the current line is, if it was split into pieces.
+    state: parse::LineState,
+    /// How many lines were split for being longer than the maximum line length.
+    split_lines: usize,
+    /// How many tokens have been parsed so far.
+    tokens: usize,
+    /// How many pages have started so far.
+    page: usize,
+    /// How many lines have started on the current page so far.
+    line: usize,
+    /// The malformed format codes that were dropped, if the options are lenient.
+    warnings: Vec<TokenizeWarning>,
+    /// The span of each token parsed so far, if they are being recorded.
+    spans: Option<Vec<Span>>,
+}
+
+impl Pages {
+    /// Parse the frontmatter from `iter`, returning it alongside a [`Pages`] to parse the rest,
+    /// which records the span of each token if `spanned`.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::IncompleteOrMissingFrontmatter`] if `iter` ends before the frontmatter
+    ///   parsing is finished
+    /// - [`TokenizeError::DialectMismatch`] if the frontmatter is not allowed by the [`Dialect`]
+    ///   set in `options`
+    /// - [`TokenizeError::Io`] if a piece from `iter` is an I/O error of some kind
+    fn start<S: AsRef<str>>(
+        iter: &mut Peekable<impl Iterator<Item = std::io::Result<Piece<S>>>>,
+        output: &mut Vec<Token>,
+        options: TokenizeOptions,
+        spanned: bool,
+    ) -> Result<(Box<[Metadata]>, Self), TokenizeError> {
+        let (metadata, dialect, lines) = if options.headless() {
+            // The first line starts a page, whether or not it is marked as one
+            if iter.peek().is_some_and(|piece| {
+                piece
+                    .as_ref()
+                    .is_ok_and(|piece| !piece.text.as_ref().starts_with("#- "))
+            }) {
+                output.push(Token::ThematicBreak);
+            }
+
+            (Box::default(), Dialect::Classic, 0)
+        } else {
+            parse::frontmatter(iter)?
+        };
+
+        if let Some(expected) = options.dialect() {
+            if !expected.accepts(dialect) {
+                return Err(TokenizeError::DialectMismatch {
+                    expected,
+                    detected: dialect,
+                });
+            }
         }
 
-        let mut iter = BufReader::new(input).lines();
-        let mut tokens: Vec<Token> = vec![];
+        // The frontmatter already consumed the separator before the first page
+        if dialect == Dialect::MsBookshelf {
+            output.push(Token::ThematicBreak);
+        }
 
-        let chunk: [&str; 3] = [next!(iter), next!(iter), next!(iter)];
-        let metadata = parse::frontmatter(&mut chunk.into_iter())?;
+        let pages = Self {
+            options,
+            dialect,
+            state: parse::LineState::at_line(lines),
+            split_lines: 0,
+            tokens: output.len(),
+            page: output
+                .iter()
+                .filter(|token| **token == Token::ThematicBreak)
+                .count(),
+            line: 0,
+            warnings: vec![],
+            // Tokens inserted before the first page are at its start
+            spans: spanned.then(|| {
+                let start = Position::new(lines + 1, 1);
+                vec![Span::new(start, start); output.len()]
+            }),
+        };
 
-        for line in iter {
-            parse::line(&mut tokens, &line?)?;
+        Ok((metadata, pages))
+    }
+
+    /// Parse the next piece of the work into `output`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Stendhal::tokenize_reader_with_options`].
+    fn push<S: AsRef<str>>(
+        &mut self,
+        output: &mut Vec<Token>,
+        piece: &Piece<S>,
+    ) -> Result<(), TokenizeError> {
+        let limits = self.options.limits();
+        let piece = Piece {
+            text: piece.text.as_ref(),
+            ends_line: piece.ends_line,
+        };
+        limits.check_piece(&piece)?;
+
+        let before = output.len();
+        if self.dialect == Dialect::MsBookshelf
+            && piece.ends_line
+            && !self.state.is_continuing()
+            && parse::is_page_separator(piece.text)
+        {
+            parse::page_separator(output, self.spans.as_mut(), piece.text, &mut self.state);
+            self.page += 1;
+            self.line = 0;
+        } else {
+            self.push_line(output, &piece)?;
         }
 
-        Ok(TokenList::new_from_boxed(metadata, tokens.into()))
+        self.tokens += output.len() - before;
+        limits.check_tokens(self.tokens)?;
+
+        Ok(())
+    }
+
+    /// Parse the next piece of a line into `output`, recording the malformed format codes that
+    /// were dropped.
+    ///
+    /// # Errors
+    ///
+    /// See [`parse::piece`].
+    fn push_line(
+        &mut self,
+        output: &mut Vec<Token>,
+        piece: &Piece<&str>,
+    ) -> Result<(), TokenizeError> {
+        let before = output.len();
+        let starts_line = !self.state.is_continuing();
+
+        if starts_line && !piece.ends_line {
+            self.split_lines += 1;
+        }
+        let recovered = parse::piece(
+            output,
+            self.spans.as_mut(),
+            piece,
+            &self.options,
+            &mut self.state,
+        )?;
+
+        if starts_line {
+            self.line += 1;
+        }
+        // Only the start of a line can start a page
+        if output.get(before) == Some(&Token::ThematicBreak) {
+            self.page += 1;
+            self.line = 1;
+        }
+
+        let (page, line) = (self.page.saturating_sub(1), self.line - 1);
+        self.warnings.extend(
+            recovered
+                .into_iter()
+                .map(|(position, error)| TokenizeWarning::new(page, line, position, error)),
+        );
+
+        Ok(())
+    }
+}
+
+impl Tokenize for Stendhal {
+    type Error = TokenizeError;
+
+    /// Parse a string in the Stendhal format into an abstract syntax vector.
+    ///
+    /// Uses the default [`TokenizeOptions`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Stendhal::tokenize_string_with_options`].
+    fn tokenize_string(input: &str) -> Result<TokenList, Self::Error> {
+        Self::tokenize_string_with_options(input, &TokenizeOptions::default())
+    }
+
+    /// Parse a file in the Stendhal format into an abstract syntax vector.
+    ///
+    /// Uses the default [`TokenizeOptions`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Stendhal::tokenize_reader_with_options`].
+    fn tokenize_reader(input: impl Read) -> Result<TokenList, Self::Error> {
+        Self::tokenize_reader_with_options(input, &TokenizeOptions::default())
+    }
+
+    /// Parse a file in the Stendhal format lazily, a line at a time.
+    ///
+    /// Uses the default [`TokenizeOptions`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Stendhal::token_iter_with_options`].
+    fn token_iter<'a>(input: impl BufRead + 'a) -> Result<TokenStream<'a, Self::Error>, Self::Error>
+    where
+        Self::Error: 'a,
+    {
+        Self::token_iter_with_options(input, &TokenizeOptions::default())
     }
 }
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration for [`super::Stendhal`].
+//!
+//! See [`TokenizeOptions`].
+
+use super::{Dialect, FilenamePattern};
+use crate::format::limits::Limits;
+
+/// Options that control how a Stendhal document is tokenized.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::{Limits, Stendhal, StendhalTokenizeOptions};
+///
+/// let options = StendhalTokenizeOptions::new().with_limits(Limits::default().with_max_tokens(3));
+/// let input = "title: crafty_novels
+/// author: RemasteredArch
+/// pages:
+/// #- Too many tokens";
+///
+/// assert!(Stendhal::tokenize_string_with_options(input, &options).is_err());
+/// ```
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[allow(clippy::struct_excessive_bools)] // Each is an independent option
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TokenizeOptions {
+    /// The resource limits to enforce on the input and output.
+    limits: Limits,
+    /// Whether to preserve format codes that Minecraft: Java Edition does not recognize as
+    /// [`Format::Unknown`] instead of returning an error.
+    ///
+    /// [`Format::Unknown`]: crate::syntax::minecraft::Format::Unknown
+    preserve_unknown_format_codes: bool,
+    /// Whether to accept uppercase format codes, like `"§L"`, as if they were lowercase.
+    accept_uppercase_format_codes: bool,
+    /// The dialect to require, or [`None`] to accept any.
+    dialect: Option<Dialect>,
+    /// Whether the input is only pages, without any frontmatter.
+    headless: bool,
+    /// Whether to drop malformed format codes instead of returning an error.
+    lenient: bool,
+    /// How to derive a missing title and author from the name of the file, or [`None`] to not.
+    filename_pattern: Option<FilenamePattern>,
+}
+
+impl TokenizeOptions {
+    /// Creates a new [`TokenizeOptions`] with the default options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            limits: Limits::new(),
+            preserve_unknown_format_codes: false,
+            accept_uppercase_format_codes: false,
+            dialect: None,
+            headless: false,
+            lenient: false,
+            filename_pattern: None,
+        }
+    }
+
+    /// Returns the resource limits to enforce on the input and output.
+    #[must_use]
+    pub const fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Sets the resource limits to enforce on the input and output.
+    #[must_use]
+    pub const fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns whether format codes that Minecraft: Java Edition does not recognize are preserved
+    /// as [`Format::Unknown`] instead of returning an error.
+    ///
+    /// [`Format::Unknown`]: crate::syntax::minecraft::Format::Unknown
+    #[must_use]
+    pub const fn preserve_unknown_format_codes(&self) -> bool {
+        self.preserve_unknown_format_codes
+    }
+
+    /// Sets whether format codes that Minecraft: Java Edition does not recognize are preserved as
+    /// [`Format::Unknown`] instead of returning an error.
+    ///
+    /// Modded clients can add their own format codes, which would otherwise fail to parse.
+    ///
+    /// [`Format::Unknown`]: crate::syntax::minecraft::Format::Unknown
+    #[must_use]
+    pub const fn with_preserve_unknown_format_codes(mut self, preserve: bool) -> Self {
+        self.preserve_unknown_format_codes = preserve;
+        self
+    }
+
+    /// Returns whether uppercase format codes, like `"§L"`, are accepted as if they were
+    /// lowercase.
+    #[must_use]
+    pub const fn accept_uppercase_format_codes(&self) -> bool {
+        self.accept_uppercase_format_codes
+    }
+
+    /// Sets whether uppercase format codes, like `"§L"`, are accepted as if they were lowercase.
+    ///
+    /// Minecraft: Java Edition only emits lowercase format codes, but some external tools do not.
+    /// See [`FormatCode::new_lenient`].
+    ///
+    /// [`FormatCode::new_lenient`]: crate::syntax::minecraft::FormatCode::new_lenient
+    #[must_use]
+    pub const fn with_accept_uppercase_format_codes(mut self, accept: bool) -> Self {
+        self.accept_uppercase_format_codes = accept;
+        self
+    }
+
+    /// Returns the [`Dialect`] to require, or [`None`] if any dialect is accepted.
+    #[must_use]
+    pub const fn dialect(&self) -> Option<Dialect> {
+        self.dialect
+    }
+
+    /// Sets the [`Dialect`] to require, or [`None`] to accept any dialect.
+    ///
+    /// Useful for checking that a work can be read by a specific version of Stendhal.
+    #[must_use]
+    pub const fn with_dialect(mut self, dialect: Option<Dialect>) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Returns whether the input is only pages, without any frontmatter.
+    #[must_use]
+    pub const fn headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Sets whether the input is only pages, without any frontmatter.
+    ///
+    /// Useful for the body of a book copied on its own, without the `"title: "`, `"author: "`, and
+    /// `"pages:"` lines. The work has no [`Metadata`][`crate::syntax::Metadata`], is in the
+    /// [`Dialect::Classic`] page syntax, and starts a page at its first line even if that line
+    /// doesn't start with `"#- "`.
+    #[must_use]
+    pub const fn with_headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Returns whether malformed format codes are dropped instead of returning an error.
+    #[must_use]
+    pub const fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Sets whether malformed format codes are dropped instead of returning an error.
+    ///
+    /// Unknown format codes and `'§'`s at the end of a line are dropped, like Minecraft hides
+    /// them. Use [`Stendhal::tokenize_string_lenient`] or [`Stendhal::tokenize_reader_lenient`]
+    /// to also get a [`TokenizeWarning`] for each of them. Other errors, like missing frontmatter
+    /// or exceeded limits, are still returned.
+    ///
+    /// [`Stendhal::tokenize_string_lenient`]: super::Stendhal::tokenize_string_lenient
+    /// [`Stendhal::tokenize_reader_lenient`]: super::Stendhal::tokenize_reader_lenient
+    /// [`TokenizeWarning`]: super::TokenizeWarning
+    #[must_use]
+    pub const fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Returns how a missing title and author are derived from the name of the file, or [`None`]
+    /// if they are not.
+    #[must_use]
+    pub const fn filename_pattern(&self) -> Option<&FilenamePattern> {
+        self.filename_pattern.as_ref()
+    }
+
+    /// Sets how a missing title and author are derived from the name of the file, or [`None`] to
+    /// not derive them.
+    ///
+    /// Only used when the path is known, like by [`Stendhal::tokenize_path_with_options`] and
+    /// [`Stendhal::tokenize_dir_with_options`]. Useful alongside [`Self::with_headless`], for
+    /// page files without any frontmatter.
+    ///
+    /// [`Stendhal::tokenize_path_with_options`]: super::Stendhal::tokenize_path_with_options
+    /// [`Stendhal::tokenize_dir_with_options`]: super::Stendhal::tokenize_dir_with_options
+    #[must_use]
+    pub fn with_filename_pattern(mut self, pattern: Option<FilenamePattern>) -> Self {
+        self.filename_pattern = pattern;
+        self
+    }
+}
@@ -17,73 +17,199 @@
 
 //! The actual, under the hood, line-by-line parsing for the [Stendhal][`super::Stendhal`] format.
 
-use super::TokenizeError;
-use crate::syntax::{minecraft::Format, ConversionError, Metadata, Token};
+use super::{Dialect, TokenizeError, TokenizeOptions};
+use crate::{
+    format::{
+        lines::Piece,
+        scan::{field, FormatLexer, Lexeme},
+    },
+    syntax::{
+        minecraft::{Format, FormatCode},
+        span::{Position, Span},
+        ConversionError, Metadata, Token,
+    },
+};
+use std::iter::Peekable;
 
-/// Parse a line in the Stendhal format into an abstract syntax vector.
-///
-/// If a line is empty, it is considered a paragraph break.
-///
-/// # Errors
-///
-/// - [`ConversionError::MissingFormatCode`] if `'§'` isn't followed by another character
-/// - [`ConversionError::NoSuchFormatCode`] if `'§'` isn't followed by a valid [`Format`] character
-pub fn line(output: &mut Vec<Token>, line: &str) -> Result<(), ConversionError> {
-    /// Flush the current word stack into a text node.
-    fn flush(output: &mut Vec<Token>, word_stack: &mut Vec<char>) {
-        if !word_stack.is_empty() {
-            output.push((word_stack).into());
+/// Where [`piece`] is within the input, and within a line of input that was split into pieces.
+#[derive(Debug, Default)]
+pub struct LineState {
+    /// The index of the current line within the input.
+    line: usize,
+    /// How many [`char`]s of the current line earlier pieces had.
+    column: usize,
+    /// Whether the line has already started in an earlier piece.
+    continuing: bool,
+    /// Whether or not the line has a formatting code yet to be reset.
+    trailing_formatting: bool,
+}
+
+impl LineState {
+    /// Creates a new [`LineState`] at the start of the line at index `line` within the input.
+    pub fn at_line(line: usize) -> Self {
+        Self {
+            line,
+            ..Self::default()
         }
     }
 
-    if line.is_empty() {
-        output.push(Token::ParagraphBreak);
-        return Ok(());
+    /// Whether the line has already started in an earlier piece.
+    pub const fn is_continuing(&self) -> bool {
+        self.continuing
     }
 
-    let line = start_of_page(output, line);
+    /// Returns the position of the `column`th [`char`] of the current line, counting from zero.
+    const fn position(&self, column: usize) -> Position {
+        Position::new(self.line + 1, column + 1)
+    }
 
-    // Builds a word out of consectutive characters
-    let mut word_stack: Vec<char> = vec![];
+    /// Moves on to the start of the next line.
+    fn next_line(&mut self) {
+        *self = Self::at_line(self.line + 1);
+    }
+}
 
-    // Whether or not this line has a formatting code yet to be reset
-    let mut trailing_formatting = false;
+/// Push the [`Token::ThematicBreak`] for a whole line that separates pages in the
+/// [`Dialect::MsBookshelf`] layout into `output`, and its span into `spans`.
+pub fn page_separator(
+    output: &mut Vec<Token>,
+    spans: Option<&mut Vec<Span>>,
+    line: &str,
+    state: &mut LineState,
+) {
+    output.push(Token::ThematicBreak);
+    if let Some(spans) = spans {
+        spans.push(Span::new(
+            state.position(0),
+            state.position(line.chars().count()),
+        ));
+    }
+    state.next_line();
+}
 
-    let mut iter = line.chars();
+/// Parse a piece of a line in the Stendhal format into an abstract syntax vector, continuing from
+/// `state`, and push the [`Span`] of each token into `spans`.
+///
+/// If a piece is a whole, empty line, it is considered a paragraph break. Only the first piece of
+/// a line can start a page or be a paragraph break, and only the last piece ends the line. A word
+/// that is split between pieces becomes two [`Token::Text`]s.
+///
+/// If `options` is [lenient][`TokenizeOptions::with_lenient`], format codes that would be errors
+/// are dropped instead, and returned with their positions.
+///
+/// # Errors
+///
+/// - [`TokenizeError::Conversion`] with:
+///     - [`ConversionError::MissingFormatCode`] if `'§'` isn't followed by another character
+///     - [`ConversionError::NoSuchFormatCode`] if `'§'` isn't followed by a valid [`Format`]
+///       character and `options` does not preserve unknown format codes, or by an uppercase one
+///       and `options` does not accept uppercase format codes
+pub fn piece(
+    output: &mut Vec<Token>,
+    mut spans: Option<&mut Vec<Span>>,
+    piece: &Piece<&str>,
+    options: &TokenizeOptions,
+    state: &mut LineState,
+) -> Result<Vec<(Position, ConversionError)>, TokenizeError> {
+    let mut line = piece.text;
+    let mut recovered = vec![];
 
-    while let Some(char) = iter.next() {
-        match char {
-            // Flush current word and insert a space
-            ' ' => {
-                flush(output, &mut word_stack);
-                output.push(Token::Space);
-            }
-            // Flush current word and insert new formatting code
-            '§' => {
-                flush(output, &mut word_stack);
+    // Columns count from zero here, and are converted to positions as needed
+    let mut push = |output: &mut Vec<Token>, token: Token, state: &LineState, start: usize| {
+        output.push(token);
+        if let Some(spans) = spans.as_deref_mut() {
+            spans.push(Span::new(
+                state.position(start),
+                state.position(state.column),
+            ));
+        }
+    };
 
-                let code: char = iter.next().ok_or(ConversionError::MissingFormatCode)?;
-                let code: Token = Token::Format(Format::try_from(code)?);
+    if !state.continuing {
+        if line.is_empty() && piece.ends_line {
+            push(output, Token::ParagraphBreak, state, 0);
+            state.next_line();
+            return Ok(recovered);
+        }
+
+        // A line that starts with `"#- "` starts a new page
+        if let Some(stripped) = line.strip_prefix("#- ") {
+            state.column = "#- ".len();
+            push(output, Token::ThematicBreak, state, 0);
+            line = stripped;
+        }
+    }
+
+    for lexeme in FormatLexer::new(line) {
+        let start = state.column;
+        state.column += match lexeme {
+            Lexeme::Word(word) => word.chars().count(),
+            Lexeme::Space | Lexeme::MissingCode => 1,
+            Lexeme::Code(_) => 2,
+        };
+        let error = |error: ConversionError| TokenizeError::Conversion {
+            position: state.position(start),
+            error,
+        };
+
+        match lexeme {
+            Lexeme::Word(word) => push(output, Token::Text(word.into()), state, start),
+            Lexeme::Space => push(output, Token::Space, state, start),
+            // A dangling `'§'` is dropped
+            Lexeme::MissingCode if options.lenient() => {
+                recovered.push((state.position(start), ConversionError::MissingFormatCode));
+            }
+            Lexeme::MissingCode => return Err(error(ConversionError::MissingFormatCode)),
+            Lexeme::Code(code) => {
+                let format_code = if options.accept_uppercase_format_codes() {
+                    FormatCode::new_lenient(code)
+                } else {
+                    FormatCode::new(code)
+                };
+                let format = match format_code {
+                    Ok(format_code) => format_code.format(),
+                    Err(_) if options.preserve_unknown_format_codes() => Format::Unknown(code),
+                    // Minecraft hides unknown format codes, so they are dropped
+                    Err(conversion) if options.lenient() => {
+                        recovered.push((state.position(start), conversion));
+                        continue;
+                    }
+                    Err(conversion) => return Err(error(conversion)),
+                };
 
-                trailing_formatting = !matches!(code, Token::Format(Format::Reset));
-                output.push(code);
+                state.trailing_formatting = !matches!(format, Format::Reset);
+                push(output, Token::Format(format), state, start);
             }
-            // Add a new character onto the current word
-            _ => word_stack.push(char),
         }
     }
 
-    flush(output, &mut word_stack);
+    if !piece.ends_line {
+        state.continuing = true;
+        return Ok(recovered);
+    }
 
-    if trailing_formatting {
-        output.push(Token::Format(Format::Reset));
+    if state.trailing_formatting {
+        push(output, Token::Format(Format::Reset), state, state.column);
     }
-    output.push(Token::LineBreak);
+    push(output, Token::LineBreak, state, state.column);
+    state.next_line();
 
-    Ok(())
+    Ok(recovered)
 }
 
-/// Parses the metadata about a work into the output.
+/// Parses the metadata about a work into the output, also returning its [`Dialect`] and how many
+/// lines of input it took up.
+///
+/// The frontmatter is a series of `"key: value"` fields, ending with `"pages:"`. `"title"` and
+/// `"author"` are required, and any other fields become [`Metadata`] as in
+/// [`Metadata::from_field`]. Some versions of Stendhal also write fields after `"pages:"`, which
+/// are captured up until the first page.
+///
+/// If the frontmatter instead ends with a [page separator][`is_page_separator`], the work is in
+/// the [`Dialect::MsBookshelf`] layout, and the separator is consumed as well.
+///
+/// Lines that were split into pieces are joined back together, except for fields after
+/// `"pages:"`, which must be whole lines.
 ///
 /// # Side effects
 ///
@@ -93,42 +219,88 @@ pub fn line(output: &mut Vec<Token>, line: &str) -> Result<(), ConversionError>
 /// # Errors
 ///
 /// - [`TokenizeError::IncompleteOrMissingFrontmatter`] if, before it finishes parsing the
-///   frontmatter, the iterator empties or a line does not have the expected field
-pub fn frontmatter<'s>(
-    iter: &mut impl Iterator<Item = &'s str>,
-) -> Result<Box<[Metadata]>, TokenizeError> {
-    /// Strip the prefix from the next line and return it or an error.
-    fn get_field<'s>(
-        iter: &mut impl Iterator<Item = &'s str>,
-        field: &str,
-    ) -> Result<&'s str, TokenizeError> {
-        iter.next()
-            .ok_or(TokenizeError::IncompleteOrMissingFrontmatter)?
-            .strip_prefix(field)
-            .ok_or(TokenizeError::IncompleteOrMissingFrontmatter)
-    }
-
+///   frontmatter, the iterator empties, a line is not a field, or the title or author is missing
+/// - [`TokenizeError::Io`] if a line from the iterator is an I/O error of some kind
+pub fn frontmatter<S: AsRef<str>>(
+    iter: &mut Peekable<impl Iterator<Item = std::io::Result<Piece<S>>>>,
+) -> Result<(Box<[Metadata]>, Dialect, usize), TokenizeError> {
     let mut output: Vec<Metadata> = vec![];
+    let mut bookshelf = false;
+    let mut lines = 0;
+
+    loop {
+        let line = whole_line(iter)?.ok_or(TokenizeError::IncompleteOrMissingFrontmatter)?;
+        lines += 1;
 
-    /// Parse a frontmatter field from `iter` and push the token to `output`, or return an error.
-    macro_rules! parse_field {
-        ($field:ident, $field_str:expr) => {
-            output.push(Metadata::$field(get_field(iter, $field_str)?.into()));
+        if line == "pages:" {
+            break;
+        }
+        if is_page_separator(&line) {
+            bookshelf = true;
+            break;
+        }
+
+        let (key, value) = field(&line).ok_or(TokenizeError::IncompleteOrMissingFrontmatter)?;
+        output.push(metadata(key, value));
+    }
+
+    // Fields after `"pages:"`, which can't be confused with the start of a page
+    while let Some(Ok(piece)) = iter.peek().filter(|_| !bookshelf) {
+        let Some((key, value)) = field(piece.text.as_ref()).filter(|_| piece.ends_line) else {
+            break;
         };
+        output.push(metadata(key, value));
+        iter.next();
+        lines += 1;
+    }
+
+    let has_title = output.iter().any(|data| matches!(data, Metadata::Title(_)));
+    let has_author = output
+        .iter()
+        .any(|data| matches!(data, Metadata::Author(_)));
+    if !has_title || !has_author {
+        return Err(TokenizeError::IncompleteOrMissingFrontmatter);
     }
 
-    parse_field!(Title, "title: ");
-    parse_field!(Author, "author: ");
-    get_field(iter, "pages:")?; // Should just be an empty string, just need to make sure it's there
+    let dialect = if bookshelf {
+        Dialect::MsBookshelf
+    } else {
+        Dialect::detect(&output)
+    };
+
+    Ok((output.into(), dialect, lines))
+}
+
+/// Whether a line separates pages in the [`Dialect::MsBookshelf`] layout, which is any line that
+/// starts and ends with `"---"`, ex. `"---"` or `"--- Page 2 ---"`.
+pub fn is_page_separator(line: &str) -> bool {
+    line.starts_with("---") && line.ends_with("---")
+}
+
+/// Returns the next line, joining the pieces of a line that was split, or [`None`] if the iterator
+/// empties first.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if a piece from the iterator is an I/O error of some kind
+fn whole_line<S: AsRef<str>>(
+    iter: &mut impl Iterator<Item = std::io::Result<Piece<S>>>,
+) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+
+    for piece in iter {
+        let piece = piece?;
+        line.push_str(piece.text.as_ref());
+
+        if piece.ends_line {
+            return Ok(Some(line));
+        }
+    }
 
-    Ok(output.into())
+    Ok(None)
 }
 
-/// If a line starts with `"#- "`, push a [`Token::ThematicBreak`] into the output.
-/// Returns the line without the `"#- "`.
-fn start_of_page<'s>(output: &mut Vec<Token>, line: &'s str) -> &'s str {
-    line.strip_prefix("#- ").map_or(line, |stripped| {
-        output.push(Token::ThematicBreak);
-        stripped
-    })
+/// Convert a frontmatter field into [`Metadata`].
+fn metadata(key: &str, value: &str) -> Metadata {
+    Metadata::from_field(key, value)
 }
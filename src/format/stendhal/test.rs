@@ -17,18 +17,36 @@
 
 //! Tests for parsing the [Stendhal][`super::Stendhal`] format.
 
-use super::parse;
-use crate::syntax::{Metadata, Token};
+use super::{parse, Stendhal, TokenizeError, TokenizeOptions};
+use crate::{
+    format::{
+        limits::{Limit, Limits},
+        lines::{BoundedLines, Piece},
+    },
+    syntax::{Metadata, Token},
+};
 
 type Result = std::result::Result<(), Box<dyn std::error::Error>>;
 
+/// Parse a whole line with [`parse::piece`], as if it were the first line of the input.
+fn parse_line(
+    output: &mut Vec<Token>,
+    line: &str,
+    options: &TokenizeOptions,
+) -> std::result::Result<(), TokenizeError> {
+    let state = &mut parse::LineState::default();
+    parse::piece(output, None, &Piece::whole(line), options, state).map(drop)
+}
+
 #[test]
 fn test_parse_frontmatter() -> Result {
     let mut lines = "title: crafty_novels
 author: RemasteredArch
 pages:
 #- The text of the book"
-        .lines();
+        .lines()
+        .map(|line| Ok::<_, std::io::Error>(Piece::whole(line)))
+        .peekable();
     let expected_line = "#- The text of the book";
     let expected_metadata: Box<[Metadata]> = [
         Metadata::Title("crafty_novels".into()),
@@ -36,12 +54,13 @@ pages:
     ]
     .into();
 
-    let metadata = parse::frontmatter(&mut lines)?;
+    let (metadata, _, _) = parse::frontmatter(&mut lines)?;
 
     assert_eq!(
         lines
             .next()
-            .expect("there should be a line after the frontmatter"),
+            .expect("there should be a line after the frontmatter")?
+            .text,
         expected_line
     );
     assert_eq!(metadata, expected_metadata);
@@ -49,14 +68,81 @@ pages:
     Ok(())
 }
 
+#[test]
+fn test_parse_extra_frontmatter() -> Result {
+    /// Insert a [`Metadata::Custom`] with the given key and value.
+    macro_rules! custom {
+        ($key:expr, $value:expr) => {
+            Metadata::Custom {
+                key: $key.into(),
+                value: $value.into(),
+            }
+        };
+    }
+
+    /// Compare the metadata from tokenizing a document with the expected metadata.
+    macro_rules! test {
+        ( $( $input:expr => $expects:expr );+ ; ) => {
+            $({
+                let tokens = Stendhal::tokenize_string_with_options($input, &TokenizeOptions::default())?;
+
+                assert_eq!(tokens.metadata_as_slice(), $expects);
+                assert_eq!(tokens.tokens_as_slice().first(), Some(&Token::ThematicBreak));
+            })+
+        };
+    }
+
+    let title = Metadata::Title("crafty_novels".into());
+    let author = Metadata::Author("RemasteredArch".into());
+
+    test!(
+        // Fields before `pages:`
+        "title: crafty_novels\nauthor: RemasteredArch\ndescription: A book\npages:\n#- Text"
+            => [title.clone(), author.clone(), Metadata::Description("A book".into())];
+        // Known fields on either side of `pages:`
+        "title: crafty_novels\nauthor: RemasteredArch\nlanguage: de\npages:\ndate: 2024-05-01\ngenerator: Stendhal\n#- Text"
+            => [
+                title.clone(),
+                author.clone(),
+                Metadata::Language("de".into()),
+                Metadata::Date("2024-05-01".into()),
+                Metadata::Generator("Stendhal".into()),
+            ];
+        // Fields after `pages:`, including an empty one
+        "title: crafty_novels\nauthor: RemasteredArch\npages:\ngeneration: 2\nsigned:\n#- Text"
+            => [title.clone(), author.clone(), custom!("generation", "2"), custom!("signed", "")];
+        // Fields out of order
+        "author: RemasteredArch\nstendhal-version: 1.3\ntitle: crafty_novels\npages:\n#- a: b"
+            => [author.clone(), custom!("stendhal-version", "1.3"), title.clone()];
+    );
+
+    // A page that looks like a field is still a page
+    let tokens = Stendhal::tokenize_string_with_options(
+        "title: crafty_novels\nauthor: RemasteredArch\npages:\n#- note: text",
+        &TokenizeOptions::default(),
+    )?;
+    assert_eq!(tokens.metadata_as_slice(), [title, author]);
+
+    // The title and author are required
+    assert!(matches!(
+        Stendhal::tokenize_string_with_options(
+            "title: crafty_novels\npages:\n#- Text",
+            &TokenizeOptions::default()
+        ),
+        Err(TokenizeError::IncompleteOrMissingFrontmatter)
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn test_line() -> Result {
-    /// Compare an an output from [`parse::line`] and the expected output.
+    /// Compare an an output from [`parse_line`] and the expected output.
     macro_rules! test {
         ( $( $input:expr => $expects:expr );+ ; ) => {
             $({
                 let mut output: Vec<Token> = vec![];
-                parse::line(&mut output, $input)?;
+                parse_line(&mut output, $input, &TokenizeOptions::default())?;
 
                 assert_eq!(output, $expects);
             })+
@@ -149,7 +235,762 @@ fn test_line() -> Result {
             text!("&"), Space,
             text!("&amp;</div>"), LineBreak,
         ];
+        "§e\u{2B50} emoji \u{1F600}" => [
+            color!(Yellow),
+            text!("\u{2B50}"), Space,
+            text!("emoji"), Space,
+            text!("\u{1F600}"),
+            format!(Reset), LineBreak,
+        ];
+        "naïve§lünd§r€" => [
+            text!("naïve"), format!(Bold), text!("ünd"), format!(Reset), text!("€"), LineBreak,
+        ];
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_headless() -> Result {
+    use Token::{LineBreak, Space, Text, ThematicBreak};
+
+    let expected = [
+        ThematicBreak,
+        Text("title:".into()),
+        Space,
+        Text("one".into()),
+        LineBreak,
+        ThematicBreak,
+        Text("two".into()),
+        LineBreak,
+    ];
+
+    // The first page doesn't need to be marked, and fields are only text
+    let tokens = Stendhal::tokenize_string_headless("title: one\n#- two")?;
+    assert!(tokens.metadata_as_slice().is_empty());
+    assert_eq!(tokens.tokens_as_slice(), expected);
+
+    let options = TokenizeOptions::new().with_headless(true);
+    let tokens = Stendhal::tokenize_reader_with_options(&b"#- title: one\n#- two"[..], &options)?;
+    assert!(tokens.metadata_as_slice().is_empty());
+    assert_eq!(tokens.tokens_as_slice(), expected);
+
+    assert!(Stendhal::tokenize_string_headless("")?
+        .tokens_as_slice()
+        .is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_filename_pattern() -> Result {
+    use super::{FilenamePattern, FilenamePatternError};
+    use crate::syntax::Metadata;
+
+    let pattern = |pattern: &str| pattern.parse::<FilenamePattern>();
+    let owned = |(title, author): (Option<&str>, Option<&str>)| {
+        (title.map(str::to_owned), author.map(str::to_owned))
+    };
+
+    // The title is as long as it can be, and the author as short
+    assert_eq!(
+        pattern("{title} - {author}")?.parse("A - B - RemasteredArch.stendhal"),
+        owned((Some("A - B"), Some("RemasteredArch")))
+    );
+    assert_eq!(
+        pattern("{author} - {title}")?.parse("RemasteredArch - A - B.txt"),
+        owned((Some("A - B"), Some("RemasteredArch")))
+    );
+    assert_eq!(
+        pattern("{title}")?.parse("dir/A - B.txt"),
+        owned((Some("A - B"), None))
+    );
+    assert_eq!(
+        pattern("{author} - {title}")?.parse(" - B"),
+        owned((Some("B"), None))
+    );
+    assert_eq!(pattern("{author} - {title}")?.parse("B"), (None, None));
+    // Directories, and extensions when the pattern has one
+    let nested = pattern("{author}/Book {title}.stendhal")?;
+    assert_eq!(
+        nested.parse("/books/RemasteredArch/Book 1.stendhal"),
+        owned((Some("1"), Some("RemasteredArch")))
+    );
+    assert_eq!(nested.parse("Book 1.stendhal"), (None, None));
+    assert_eq!(nested.parse("RemasteredArch/Book 1.txt"), (None, None));
+
+    assert_eq!(
+        pattern("{title"),
+        Err(FilenamePatternError::UnmatchedBrace('{'))
+    );
+    assert_eq!(
+        pattern("{name}"),
+        Err(FilenamePatternError::UnknownPlaceholder("name".into()))
+    );
+    assert_eq!(
+        pattern("{title}{author}"),
+        Err(FilenamePatternError::AdjacentPlaceholders)
+    );
+    assert_eq!(
+        pattern("{title}/{title}"),
+        Err(FilenamePatternError::RepeatedPlaceholder("title"))
+    );
+    assert_eq!(
+        pattern("{author}//{title}"),
+        Err(FilenamePatternError::EmptyComponent)
+    );
+    assert_eq!(pattern("book"), Err(FilenamePatternError::NoPlaceholders));
+
+    let dir = std::env::temp_dir().join(format!(
+        "crafty_novels_stendhal_filename_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir)?;
+    let bare = dir.join("RemasteredArch - crafty_novels.stendhal");
+    std::fs::write(&bare, "#- one")?;
+    let titled = dir.join("Someone - Something.stendhal");
+    std::fs::write(&titled, "title: Kept\nauthor: \npages:\n#- one")?;
+
+    let options = TokenizeOptions::new()
+        .with_headless(true)
+        .with_filename_pattern(Some(FilenamePattern::default()));
+    let bare_tokens = Stendhal::tokenize_path_with_options(&bare, &options);
+    let without_pattern =
+        Stendhal::tokenize_path_with_options(&bare, &options.clone().with_filename_pattern(None));
+    let titled_tokens =
+        Stendhal::tokenize_path_with_options(&titled, &options.with_headless(false));
+    std::fs::remove_dir_all(&dir)?;
+
+    assert_eq!(
+        bare_tokens?.metadata_as_slice(),
+        [
+            Metadata::Title("crafty_novels".into()),
+            Metadata::Author("RemasteredArch".into()),
+        ]
+    );
+    assert!(without_pattern?.metadata_as_slice().is_empty());
+    // Only the missing fields are filled in
+    let titled_tokens = titled_tokens?;
+    assert!(titled_tokens
+        .metadata_as_slice()
+        .contains(&Metadata::Title("Kept".into())));
+    assert!(!titled_tokens
+        .metadata_as_slice()
+        .contains(&Metadata::Title("Something".into())));
+
+    Ok(())
+}
+
+#[test]
+fn test_lenient() -> Result {
+    use crate::{
+        syntax::{minecraft::Format, span::Position, ConversionError},
+        Tokenize,
+    };
+    use Token::{Format as Code, LineBreak, Text, ThematicBreak};
+
+    let input = "title: Book
+author: RemasteredArch
+pages:
+#- §lone§z§r
+two§
+#- §Lthree";
+
+    // The strict tokenizer stops at the first problem
+    assert!(matches!(
+        Stendhal::tokenize_string(input),
+        Err(TokenizeError::Conversion {
+            error: ConversionError::NoSuchFormatCode('z'),
+            ..
+        })
+    ));
+
+    let (tokens, warnings) = Stendhal::tokenize_string_lenient(input, &TokenizeOptions::new())?;
+    assert_eq!(
+        tokens.tokens_as_slice(),
+        [
+            ThematicBreak,
+            Code(Format::Bold),
+            Text("one".into()),
+            Code(Format::Reset),
+            LineBreak,
+            Text("two".into()),
+            LineBreak,
+            ThematicBreak,
+            Text("three".into()),
+            LineBreak,
+        ]
     );
 
+    let warnings: Vec<_> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        [
+            "page 1, line 1: no such format code 'z'",
+            "page 1, line 2: expected a format code after '§'",
+            "page 2, line 1: no such format code 'L'",
+        ]
+    );
+
+    // Positions are in the input, rather than the book
+    let (_, warnings) = Stendhal::tokenize_string_lenient(input, &TokenizeOptions::new())?;
+    assert_eq!(warnings[1].position(), Position::new(5, 4));
+
+    // Other options still apply
+    let options = TokenizeOptions::new().with_accept_uppercase_format_codes(true);
+    let (_, warnings) = Stendhal::tokenize_reader_lenient(input.as_bytes(), &options)?;
+    assert_eq!(warnings.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_full_width_spaces() -> Result {
+    let mut output: Vec<Token> = vec![];
+    parse_line(
+        &mut output,
+        "\u{3000}吾輩は\u{3000}猫である",
+        &TokenizeOptions::default(),
+    )?;
+
+    // Full-width spaces are part of the text, not separators between words
+    assert_eq!(
+        output,
+        [
+            Token::Text("\u{3000}吾輩は\u{3000}猫である".into()),
+            Token::LineBreak
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_limits() {
+    /// Tokenize `$input` as both a string and a reader with `$limits`, expecting `$limit` to be
+    /// exceeded.
+    macro_rules! expect_limit {
+        ( $( $limits:expr, $input:expr => $limit:expr );+ ; ) => {
+            $({
+                let options = TokenizeOptions::new().with_limits($limits);
+
+                for result in [
+                    Stendhal::tokenize_string_with_options($input, &options),
+                    Stendhal::tokenize_reader_with_options($input.as_bytes(), &options),
+                ] {
+                    match result {
+                        Err(TokenizeError::LimitExceeded(limit)) => assert_eq!(limit, $limit),
+                        other => panic!("expected {:?}, received {other:?}", $limit),
+                    }
+                }
+            })+
+        };
+    }
+
+    let input = "title: crafty_novels
+author: RemasteredArch
+pages:
+#- §l§o§n§m§k§cformatting
+a short line";
+
+    expect_limit!(
+        Limits::none().with_max_input_size(32), input => Limit::InputSize(32);
+        Limits::none().with_max_line_length(12), input => Limit::LineLength(12);
+        Limits::none().with_max_tokens(8), input => Limit::Tokens(8);
+    );
+
+    let options = TokenizeOptions::new().with_limits(
+        Limits::none()
+            .with_max_input_size(input.len())
+            .with_max_line_length(31)
+            .with_max_tokens(16),
+    );
+    assert!(Stendhal::tokenize_string_with_options(input, &options).is_ok());
+    assert!(Stendhal::tokenize_reader_with_options(input.as_bytes(), &options).is_ok());
+}
+
+#[test]
+fn test_unknown_format_codes() -> Result {
+    use crate::syntax::minecraft::Format;
+
+    let input = "Modded §zcode";
+
+    let mut output: Vec<Token> = vec![];
+    parse_line(&mut output, input, &TokenizeOptions::default()).unwrap_err();
+
+    let mut output: Vec<Token> = vec![];
+    let options = TokenizeOptions::new().with_preserve_unknown_format_codes(true);
+    parse_line(&mut output, input, &options)?;
+
+    assert_eq!(
+        output,
+        [
+            Token::Text("Modded".into()),
+            Token::Space,
+            Token::Format(Format::Unknown('z')),
+            Token::Text("code".into()),
+            Token::Format(Format::Reset),
+            Token::LineBreak,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_uppercase_format_codes() -> Result {
+    use crate::syntax::minecraft::Format;
+
+    let input = "§LLoud §Zcode";
+
+    let mut output: Vec<Token> = vec![];
+    parse_line(&mut output, input, &TokenizeOptions::default()).unwrap_err();
+
+    let mut output: Vec<Token> = vec![];
+    let options = TokenizeOptions::new().with_accept_uppercase_format_codes(true);
+    parse_line(&mut output, input, &options).unwrap_err();
+
+    let mut output: Vec<Token> = vec![];
+    let options = options.with_preserve_unknown_format_codes(true);
+    parse_line(&mut output, input, &options)?;
+
+    assert_eq!(
+        output,
+        [
+            Token::Format(Format::Bold),
+            Token::Text("Loud".into()),
+            Token::Space,
+            Token::Format(Format::Unknown('Z')),
+            Token::Text("code".into()),
+            Token::Format(Format::Reset),
+            Token::LineBreak,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_dialects() -> Result {
+    use super::Dialect;
+
+    let classic = "title: crafty_novels
+author: RemasteredArch
+pages:
+#- Page one";
+    let extended = "title: crafty_novels
+author: RemasteredArch
+pages:
+generation: 0
+#- Page one";
+
+    assert_eq!(Stendhal::detect_dialect(classic)?, Dialect::Classic);
+    assert_eq!(Stendhal::detect_dialect(extended)?, Dialect::Extended);
+    assert!(Stendhal::detect_dialect("not stendhal").is_err());
+
+    let options = TokenizeOptions::new().with_dialect(Some(Dialect::Classic));
+    Stendhal::tokenize_string_with_options(classic, &options)?;
+    assert!(matches!(
+        Stendhal::tokenize_string_with_options(extended, &options),
+        Err(TokenizeError::DialectMismatch {
+            expected: Dialect::Classic,
+            detected: Dialect::Extended,
+        })
+    ));
+
+    let options = TokenizeOptions::new().with_dialect(Some(Dialect::Extended));
+    Stendhal::tokenize_string_with_options(classic, &options)?;
+    Stendhal::tokenize_reader_with_options(extended.as_bytes(), &options)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_bookshelf_dialect() -> Result {
+    use super::Dialect;
+    use crate::Tokenize;
+    use Token::{LineBreak, Space, Text, ThematicBreak};
+
+    let input = "title: crafty_novels
+author: RemasteredArch
+--- Page 1 ---
+Page one
+---
+Page two";
+
+    assert_eq!(Stendhal::detect_dialect(input)?, Dialect::MsBookshelf);
+
+    let tokens = Stendhal::tokenize_string(input)?;
+    assert_eq!(
+        tokens.metadata_as_slice(),
+        [
+            Metadata::Title("crafty_novels".into()),
+            Metadata::Author("RemasteredArch".into()),
+        ]
+    );
+    assert_eq!(
+        tokens.tokens_as_slice(),
+        [
+            ThematicBreak,
+            Text("Page".into()),
+            Space,
+            Text("one".into()),
+            LineBreak,
+            ThematicBreak,
+            Text("Page".into()),
+            Space,
+            Text("two".into()),
+            LineBreak,
+        ]
+    );
+    assert_eq!(Stendhal::tokenize_reader(input.as_bytes())?, tokens);
+
+    let options = TokenizeOptions::new().with_dialect(Some(Dialect::Extended));
+    assert!(matches!(
+        Stendhal::tokenize_string_with_options(input, &options),
+        Err(TokenizeError::DialectMismatch {
+            expected: Dialect::Extended,
+            detected: Dialect::MsBookshelf,
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_split_long_lines() -> Result {
+    use crate::syntax::minecraft::Format;
+
+    /// Insert a [`Piece`] with the given text.
+    macro_rules! piece {
+        ($text:expr, $ends_line:expr) => {
+            Piece {
+                text: String::from($text),
+                ends_line: $ends_line,
+            }
+        };
+    }
+
+    // Never splits a character or a format code
+    let pieces = BoundedLines::new("ab§lcdéé\r\n\nghi".as_bytes(), 4)
+        .collect::<std::io::Result<Vec<_>>>()?;
+    assert_eq!(
+        pieces,
+        [
+            piece!("ab", false),
+            piece!("§lc", false),
+            piece!("dé", false),
+            piece!("é", true),
+            piece!("", true),
+            piece!("ghi", true),
+        ]
+    );
+
+    let input = "title: a title longer than the limit
+author: RemasteredArch
+pages:
+#- §lalphabet soup
+";
+    let limits = Limits::new().with_max_line_length(8);
+    let expected = [
+        Token::ThematicBreak,
+        Token::Format(Format::Bold),
+        Token::Text("al".into()),
+        Token::Text("phabet".into()),
+        Token::Space,
+        Token::Text("s".into()),
+        Token::Text("oup".into()),
+        Token::Format(Format::Reset),
+        Token::LineBreak,
+    ];
+
+    let options = TokenizeOptions::new().with_limits(limits);
+    assert!(matches!(
+        Stendhal::tokenize_reader_with_options(input.as_bytes(), &options),
+        Err(TokenizeError::LimitExceeded(Limit::LineLength(8)))
+    ));
+
+    let options = TokenizeOptions::new().with_limits(limits.with_split_long_lines(true));
+    let tokenized = Stendhal::tokenize_reader_detailed(input.as_bytes(), &options, false)?;
+    let (tokens, split_lines) = (tokenized.tokens, tokenized.split_lines);
+    assert_eq!(tokens.tokens_as_slice(), expected);
+    assert_eq!(
+        tokens.metadata_as_slice()[0],
+        Metadata::Title("a title longer than the limit".into())
+    );
+    assert_eq!(split_lines, 1);
+    assert_eq!(
+        Stendhal::tokenize_string_with_options(input, &options)?,
+        tokens
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_suggest_fixes() -> Result {
+    /// Tokenize `input`, returning the suggested fixes as `(message, fixed input)`.
+    fn fixes(input: &str) -> Vec<(String, String)> {
+        let error =
+            Stendhal::tokenize_string_with_options(input, &TokenizeOptions::default()).unwrap_err();
+
+        Stendhal::suggest_fixes(input, &error)
+            .iter()
+            .map(|suggestion| (suggestion.message().to_owned(), suggestion.apply(input)))
+            .collect()
+    }
+
+    let frontmatter = "title: §iBook\nauthor: RemasteredArch\npages:\n";
+    assert_eq!(
+        fixes(&format!("{frontmatter}#- §Lbold")),
+        [
+            (
+                "did you mean `§l`?".into(),
+                format!("{frontmatter}#- §lbold")
+            ),
+            (
+                "remove the unknown format code `§L`".into(),
+                format!("{frontmatter}#- bold")
+            ),
+        ]
+    );
+    assert_eq!(
+        fixes(&format!("{frontmatter}#- §zoops")),
+        [(
+            "remove the unknown format code `§z`".into(),
+            format!("{frontmatter}#- oops")
+        )]
+    );
+    assert_eq!(
+        fixes(&format!("{frontmatter}#- dangling§\r\nnext")),
+        [(
+            "remove the `§` that isn't followed by a format code".into(),
+            format!("{frontmatter}#- dangling\r\nnext")
+        )]
+    );
+
+    let input = "title Book\nauthor: RemasteredArch\n#- page";
+    let suggestions =
+        Stendhal::suggest_fixes(input, &TokenizeError::IncompleteOrMissingFrontmatter);
+    assert_eq!(
+        suggestions
+            .iter()
+            .map(|suggestion| (suggestion.line(), suggestion.column(), suggestion.message()))
+            .collect::<Vec<_>>(),
+        [
+            (1, 6, "insert `:` after `title`"),
+            (
+                3,
+                1,
+                "insert `pages:` after the last field to end the frontmatter"
+            ),
+        ]
+    );
+    let fixed = suggestions
+        .iter()
+        .rev()
+        .fold(input.to_owned(), |input, suggestion| {
+            suggestion.apply(&input)
+        });
+    assert_eq!(
+        fixed,
+        "title: Book\nauthor: RemasteredArch\npages:\n#- page"
+    );
+    Stendhal::tokenize_string_with_options(&fixed, &TokenizeOptions::default())?;
+
+    assert_eq!(
+        fixes("title: Book"),
+        [
+            (
+                "insert an `author:` line".into(),
+                "title: Book\nauthor: ".into()
+            ),
+            (
+                "insert `pages:` after the last field to end the frontmatter".into(),
+                "title: Book\npages:".into()
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_token_iter() -> Result {
+    use crate::Tokenize;
+
+    let input = "title: crafty_novels
+author: RemasteredArch
+pages:
+description: A book
+#- Page §lone
+a line much longer than the limit
+
+#- Page two";
+
+    let stream = Stendhal::token_iter(input.as_bytes())?;
+    assert_eq!(
+        stream.metadata(),
+        Stendhal::tokenize_string(input)?.metadata_as_slice()
+    );
+    assert_eq!(
+        stream.collect_token_list()?,
+        Stendhal::tokenize_string(input)?
+    );
+
+    let bookshelf = "title: crafty_novels\nauthor: RemasteredArch\n---\nPage one\n---\nPage two";
+    assert_eq!(
+        Stendhal::token_iter(bookshelf.as_bytes())?.collect_token_list()?,
+        Stendhal::tokenize_string(bookshelf)?
+    );
+
+    let options = TokenizeOptions::new().with_limits(
+        Limits::none()
+            .with_max_line_length(12)
+            .with_split_long_lines(true),
+    );
+    assert_eq!(
+        Stendhal::token_iter_with_options(input.as_bytes(), &options)?.collect_token_list()?,
+        Stendhal::tokenize_string_with_options(input, &options)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_token_iter_errors() -> Result {
+    use crate::Tokenize;
+
+    // Tokens before the error are still parsed, and nothing after it
+    let input = "title: crafty_novels\nauthor: RemasteredArch\npages:\n#- One\n§z\n#- Two";
+    let tokens: Vec<_> = Stendhal::token_iter(input.as_bytes())?.collect();
+    assert_eq!(tokens.len(), 4);
+    assert!(matches!(tokens[3], Err(TokenizeError::Conversion { .. })));
+
+    let options = TokenizeOptions::new().with_limits(Limits::none().with_max_input_size(60));
+    let result = Stendhal::token_iter_with_options(input.as_bytes(), &options)?.last();
+    assert!(matches!(
+        result,
+        Some(Err(TokenizeError::LimitExceeded(Limit::InputSize(60))))
+    ));
+
+    assert!(matches!(
+        Stendhal::token_iter(b"title: crafty_novels".as_slice()),
+        Err(TokenizeError::IncompleteOrMissingFrontmatter)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_positions() -> Result {
+    use crate::syntax::span::{Position, Span};
+    use crate::Tokenize;
+
+    let span = |line, start, end| Span::new(Position::new(line, start), Position::new(line, end));
+
+    // Errors say where they happened, counting columns in characters
+    let input = "title: Book\nauthor: RemasteredArch\npages:\n#- One\n\n§l二 §x";
+    let error = Stendhal::tokenize_string(input).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "could not perform conversion at line 6, column 5: no such format code 'x'"
+    );
+
+    // Lines split for being too long still count as one line
+    let limits = Limits::default()
+        .with_max_line_length(4)
+        .with_split_long_lines(true);
+    let options = TokenizeOptions::new().with_limits(limits);
+    let input = "title: a\nauthor: b\npages:\n#- ab cd§";
+    assert!(matches!(
+        Stendhal::tokenize_string_with_options(input, &options),
+        Err(TokenizeError::Conversion { position, .. }) if position == Position::new(4, 9)
+    ));
+
+    let input = "title: Book\nauthor: RemasteredArch\npages:\n#- §lOne two\n\n--- x\n";
+    // `"--- x"` is only text in the classic layout
+    let (tokens, spans) = Stendhal::tokenize_string_spanned(input, &TokenizeOptions::new())?;
+    assert_eq!(tokens.tokens_as_slice().len(), spans.len());
+    assert_eq!(
+        spans,
+        [
+            span(4, 1, 4),
+            span(4, 4, 6),
+            span(4, 6, 9),
+            span(4, 9, 10),
+            span(4, 10, 13),
+            span(4, 13, 13),
+            span(4, 13, 13),
+            span(5, 1, 1),
+            span(6, 1, 4),
+            span(6, 4, 5),
+            span(6, 5, 6),
+            span(6, 6, 6),
+        ]
+    );
+
+    // Page separators in the Bookshelf layout, including the one consumed by the frontmatter
+    let input = "title: Book\nauthor: RemasteredArch\n--- Page 1 ---\nOne\n--- Page 2 ---";
+    let (_, spans) = Stendhal::tokenize_reader_spanned(input.as_bytes(), &TokenizeOptions::new())?;
+    assert_eq!(
+        spans,
+        [span(4, 1, 1), span(4, 1, 4), span(4, 4, 4), span(5, 1, 15),]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_tokenize_dir() -> Result {
+    use super::DirectoryError;
+    use crate::syntax::TokenList;
+
+    let dir =
+        std::env::temp_dir().join(format!("crafty_novels_stendhal_dir_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let write = |name: &str, text: &str| std::fs::write(dir.join(name), text);
+
+    write(
+        "b.stendhal",
+        "title: B\nauthor: RemasteredArch\npages:\n#- two",
+    )?;
+    write(
+        "a.STENDHAL",
+        "title: A\nauthor: RemasteredArch\npages:\n#- one",
+    )?;
+    write("notes.txt", "not a book")?;
+
+    let works = Stendhal::tokenize_dir(&dir);
+    let names = |works: &[(std::path::PathBuf, TokenList)]| {
+        works
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap_or_default().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let result = works.map(|works| names(&works));
+
+    // Every file is still tried after one fails
+    write("c.stendhal", "no frontmatter")?;
+    let failed = Stendhal::tokenize_dir(&dir);
+    std::fs::remove_dir_all(&dir)?;
+
+    assert_eq!(result?, ["a.STENDHAL", "b.stendhal"]);
+    let Err(DirectoryError::Files { works, errors }) = failed else {
+        panic!("expected the third file to fail");
+    };
+    assert_eq!(names(&works), ["a.STENDHAL", "b.stendhal"]);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].path().ends_with("c.stendhal"));
+    assert!(matches!(
+        errors[0].error(),
+        TokenizeError::IncompleteOrMissingFrontmatter
+    ));
+
+    assert!(matches!(
+        Stendhal::tokenize_dir(&dir),
+        Err(DirectoryError::Io(_))
+    ));
+
     Ok(())
 }
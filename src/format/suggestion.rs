@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fixes for invalid input, for people to read and for tools to apply.
+//!
+//! See [`Suggestion`].
+
+use std::{fmt::Display, ops::Range};
+
+/// A change to the input of an importer that would fix an error, like replacing `"§i"` with
+/// `"§o"`.
+///
+/// The change is a byte range of the input to replace, so that an editor can apply it without
+/// understanding the format, alongside its line and column for people to find it.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::import::Suggestion;
+///
+/// let input = "Italic:§i text";
+/// let suggestion = Suggestion::new(input, 7..10, "§o", "did you mean `§o`?");
+///
+/// assert_eq!(suggestion.apply(input), "Italic:§o text");
+/// assert_eq!(
+///     suggestion.to_string(),
+///     "line 1, column 8: did you mean `§o`?"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Suggestion {
+    /// A description of the change.
+    message: Box<str>,
+    /// The byte range of the input to replace.
+    range: Range<usize>,
+    /// The line that the range starts on, numbered from one.
+    line: usize,
+    /// The character that the range starts at within its line, numbered from one.
+    column: usize,
+    /// The text to replace the range with.
+    replacement: Box<str>,
+}
+
+impl Suggestion {
+    /// Creates a suggestion to replace `range` of `input` with `replacement`, described by
+    /// `message`.
+    ///
+    /// An empty `range` inserts `replacement`, and an empty `replacement` removes `range`.
+    ///
+    /// # Panics
+    ///
+    /// - If `range` does not start on a [`char`] boundary within `input`
+    #[must_use]
+    pub fn new(
+        input: &str,
+        range: Range<usize>,
+        replacement: impl Into<Box<str>>,
+        message: impl Into<Box<str>>,
+    ) -> Self {
+        let before = &input[..range.start];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        Self {
+            message: message.into(),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Returns a description of the change.
+    #[must_use]
+    pub const fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the byte range of the input to replace.
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the line that the change starts on, numbered from one.
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the character that the change starts at within its line, numbered from one.
+    #[must_use]
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the text to replace the range with.
+    #[must_use]
+    pub const fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    /// Returns a copy of `input` with the change applied.
+    ///
+    /// Each suggestion is made against the original input, so after applying one, the ranges of
+    /// the others may no longer line up.
+    ///
+    /// # Panics
+    ///
+    /// - If the range is not on [`char`] boundaries within `input`
+    #[must_use]
+    pub fn apply(&self, input: &str) -> String {
+        let mut output = input.to_owned();
+        output.replace_range(self.range(), &self.replacement);
+        output
+    }
+}
+
+impl Display for Suggestion {
+    /// Ex. `"line 4, column 8: did you mean `§o`?"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
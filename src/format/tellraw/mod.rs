@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parsing for the text shown by `tellraw` and `title` commands in datapack functions.
+//! See [`Tellraw`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     import::Tellraw,
+//!     syntax::{minecraft::{Color, Format}, Token},
+//!     Tokenize,
+//! };
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let input = r#"# The story so far
+//! tellraw @a {"text": "Chapter one", "color": "gold"}
+//! execute as @a[tag=reader] run tellraw @s ["It was ", {"text": "dark", "italic": true}]"#;
+//!
+//! let tokens = Tellraw::tokenize_string(input)?;
+//!
+//! assert_eq!(
+//!     tokens.tokens_as_slice(),
+//!     [
+//!         Token::Format(Format::Color(Color::Gold)),
+//!         Token::Text("Chapter".into()),
+//!         Token::Space,
+//!         Token::Text("one".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::LineBreak,
+//!         Token::Text("It".into()),
+//!         Token::Space,
+//!         Token::Text("was".into()),
+//!         Token::Space,
+//!         Token::Format(Format::Italic),
+//!         Token::Text("dark".into()),
+//!         Token::Format(Format::Reset),
+//!         Token::LineBreak,
+//!     ]
+//! );
+//! #
+//! #     Ok(())
+//! # }
+//! ```
+
+use crate::{
+    format::{
+        component,
+        limits::{tokenize_limited, Limits},
+    },
+    syntax::{Token, TokenList},
+    Tokenize,
+};
+pub use error::TokenizeError;
+use std::io::{BufRead, Read};
+
+mod error;
+mod parse;
+#[cfg(test)]
+mod test;
+
+/// Parses the text shown by `tellraw` and `title` commands in a datapack function (`.mcfunction`).
+///
+/// Datapacks often tell stories through chat, one command at a time. This archives that story as a
+/// document.
+///
+/// # Expected format
+///
+/// Each line is a command:
+/// - Empty lines, comments (`"# ..."`), and any other commands are ignored
+/// - `"tellraw <targets> <text>"` becomes one line of the document
+///     - Commands can start with `'/'`, and can be run through `"execute ... run "`
+/// - `"title <targets> title|subtitle|actionbar <text>"` also becomes one line of the document
+/// - If the text is empty, it becomes a [`Token::ParagraphBreak`] instead
+///
+/// `<text>` is a JSON text component, like `{"text": "Hi", "color": "gold"}`:
+/// - Named colors, decorations like `"bold": true`, `"extra"` children, and `'§'` format codes are
+///   all supported
+/// - Hexadecimal colors become the nearest [`Color`][`crate::syntax::minecraft::Color`]
+/// - Translated text and key binds are written as their keys
+/// - Other dynamic content, like scores and selectors, is dropped
+/// - Only strict JSON is supported, not the looser SNBT syntax of recent Minecraft versions
+///
+/// There is no metadata, so the [`TokenList`] has none.
+pub struct Tellraw;
+
+impl Tellraw {
+    /// Parse the lines of a string or file into an abstract syntax vector.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Json`] if the text of a command is not valid JSON
+    /// - [`TokenizeError::Io`] if the a line from `lines` is an I/O error of some kind
+    fn tokenize_lines<S: AsRef<str>>(
+        lines: impl Iterator<Item = std::io::Result<S>>,
+    ) -> Result<TokenList, TokenizeError> {
+        let mut tokens: Vec<Token> = vec![];
+
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            let Some(payload) = parse::payload(line.as_ref()) else {
+                continue;
+            };
+
+            let text = serde_json::from_str(payload).map_err(|source| TokenizeError::Json {
+                line: index + 1,
+                source,
+            })?;
+            let text = component::tokenize(&text);
+
+            if text.is_empty() {
+                tokens.push(Token::ParagraphBreak);
+            } else {
+                tokens.extend(text);
+                tokens.push(Token::LineBreak);
+            }
+        }
+
+        Ok(TokenList::new_from_boxed(Box::new([]), tokens.into()))
+    }
+
+    /// Parse a datapack function from a reader, like a [`std::fs::File`], into an abstract
+    /// syntax vector, according to `limits`.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Json`] if the text of a command is not valid JSON
+    /// - [`TokenizeError::LimitExceeded`] if the input or output exceeds `limits`
+    /// - [`TokenizeError::Io`] if it cannot read from `input`
+    pub fn tokenize_reader_with_limits(
+        input: impl Read,
+        limits: &Limits,
+    ) -> Result<TokenList, TokenizeError> {
+        tokenize_limited(input, limits, |input| Self::tokenize_lines(input.lines()))
+    }
+}
+
+impl Tokenize for Tellraw {
+    type Error = TokenizeError;
+
+    /// Parse a datapack function into an abstract syntax vector.
+    ///
+    /// # Errors
+    ///
+    /// - [`TokenizeError::Json`] if the text of a command is not valid JSON
+    fn tokenize_string(input: &str) -> Result<TokenList, Self::Error> {
+        Self::tokenize_lines(input.lines().map(Ok))
+    }
+
+    /// Parse a datapack function from a reader, like a [`std::fs::File`], into an abstract
+    /// syntax vector, with the default [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Tellraw::tokenize_reader_with_limits`].
+    fn tokenize_reader(input: impl Read) -> Result<TokenList, Self::Error> {
+        Self::tokenize_reader_with_limits(input, &Limits::default())
+    }
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Finding the JSON text in the commands of a `.mcfunction` file.
+
+/// Returns the JSON text that a command displays, if it is a `tellraw` command or a `title`
+/// command that displays text.
+///
+/// Commands can start with a `'/'`, and can be run through `execute ... run`.
+pub fn payload(line: &str) -> Option<&str> {
+    let mut command = line.trim();
+    command = command.strip_prefix('/').unwrap_or(command);
+
+    // `execute` can chain many subcommands, but only the last `run` is the actual command
+    if let Some(subcommands) = command.strip_prefix("execute ") {
+        command = subcommands.rsplit_once(" run ")?.1.trim_start();
+    }
+
+    let (name, rest) = command.split_once(char::is_whitespace)?;
+    let rest = skip_target(rest.trim_start())?;
+
+    match name {
+        "tellraw" => Some(rest),
+        "title" => {
+            let (kind, rest) = rest.split_once(char::is_whitespace)?;
+            matches!(kind, "title" | "subtitle" | "actionbar").then(|| rest.trim_start())
+        }
+        _ => None,
+    }
+}
+
+/// Returns `arguments` after the target selector or player name at its start, and any white space
+/// following it.
+///
+/// Handles selectors with arguments, like `@a[tag=reader,name="Some One"]`.
+fn skip_target(arguments: &str) -> Option<&str> {
+    let end = if arguments.starts_with('@') {
+        let mut depth = 0_usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut end = None;
+
+        for (index, char) in arguments.char_indices().skip(2) {
+            match char {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                _ if in_string => (),
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth = depth.checked_sub(1)?,
+                _ if depth == 0 && char.is_whitespace() => {
+                    end = Some(index);
+                    break;
+                }
+                _ => (),
+            }
+        }
+
+        end?
+    } else {
+        arguments.find(char::is_whitespace)?
+    };
+
+    Some(arguments[end..].trim_start())
+}
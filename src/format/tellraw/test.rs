@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for parsing [datapack functions][`super::Tellraw`].
+
+use super::{parse, Tellraw, TokenizeError};
+use crate::{
+    syntax::{
+        minecraft::{Color, Format},
+        Token,
+    },
+    Tokenize,
+};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+/// Insert a [`Token::Format`] with the given variant.
+macro_rules! format {
+    ($format:ident) => {
+        Token::Format(Format::$format)
+    };
+}
+
+/// Insert a [`Token::Format`] with the given color.
+macro_rules! color {
+    ($color:ident) => {
+        Token::Format(Format::Color(Color::$color))
+    };
+}
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+#[test]
+fn test_payload() {
+    for (line, expected) in [
+        (r#"tellraw @a "hi""#, Some(r#""hi""#)),
+        (
+            r#"/tellraw Steve   {"text":"hi"}"#,
+            Some(r#"{"text":"hi"}"#),
+        ),
+        (
+            r#"tellraw @a[tag=reader,name="Some One",scores={read=1..}] "hi""#,
+            Some(r#""hi""#),
+        ),
+        (
+            r#"execute as @a at @s run tellraw @s "hi""#,
+            Some(r#""hi""#),
+        ),
+        (r#"title @a subtitle "hi""#, Some(r#""hi""#)),
+        (r#"title @a actionbar "hi""#, Some(r#""hi""#)),
+        ("title @a times 10 70 20", None),
+        ("title @a clear", None),
+        (r#"say "hi""#, None),
+        (r#"# tellraw @a "hi""#, None),
+        ("", None),
+        ("tellraw @a[tag=unclosed", None),
+    ] {
+        assert_eq!(parse::payload(line), expected, "{line}");
+    }
+}
+
+#[test]
+fn test_components() -> Result {
+    /// Compare the tokens of a single `tellraw` command with the expected tokens.
+    macro_rules! test {
+        ( $( $input:expr => [ $( $expects:expr ),* $(,)? ] );+ ; ) => {
+            $(
+                assert_eq!(
+                    Tellraw::tokenize_string(&std::format!("tellraw @a {}", $input))?
+                        .tokens_as_slice(),
+                    [ $( $expects ),* ],
+                    "{}",
+                    $input
+                );
+            )+
+        };
+    }
+
+    use Token::{LineBreak, ParagraphBreak, Space};
+
+    test!(
+        r#""plain""# => [text!("plain"), LineBreak];
+        r#""""# => [ParagraphBreak];
+        r#"{"text": "big", "bold": true, "color": "dark_red"}"# => [
+            color!(DarkRed), format!(Bold), text!("big"), format!(Reset), LineBreak,
+        ];
+        // Children inherit, and can override, the formatting of their parent
+        r#"{"text": "a", "italic": true, "extra": ["b", {"text": "c", "italic": false}]}"# => [
+            format!(Italic), text!("ab"), format!(Reset), text!("c"), LineBreak,
+        ];
+        // Later elements of an array inherit the formatting of the first
+        r#"[{"text": "", "color": "gold"}, "one ", {"text": "two", "underlined": true}]"# => [
+            color!(Gold), text!("one"), Space,
+            format!(Underline), text!("two"), format!(Reset), LineBreak,
+        ];
+        // Hexadecimal colors become the nearest color
+        r##"{"text": "hex", "color": "#FFAA11"}"## => [
+            color!(Gold), text!("hex"), format!(Reset), LineBreak,
+        ];
+        // Format codes inside of text
+        r#"{"text": "§lloud§r quiet", "color": "red"}"# => [
+            color!(Red), format!(Bold), text!("loud"),
+            format!(Reset), Space, text!("quiet"), LineBreak,
+        ];
+        // Line breaks inside of text
+        r#""one\ntwo""# => [text!("one"), LineBreak, text!("two"), LineBreak];
+        // Keys stand in for translations, and dynamic content is dropped
+        r#"[{"translate": "item.minecraft.book"}, {"score": {"name": "@s", "objective": "x"}}]"# => [
+            text!("item.minecraft.book"), LineBreak,
+        ];
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_tellraw() -> Result {
+    let input = r#"# Chapter one
+tellraw @a {"text": "Title", "color": "gold"}
+
+say this is ignored
+tellraw @a ""
+title @a subtitle "The end""#;
+
+    let tokens = Tellraw::tokenize_string(input)?;
+    assert!(tokens.metadata_as_slice().is_empty());
+    assert_eq!(
+        tokens.tokens_as_slice(),
+        [
+            color!(Gold),
+            text!("Title"),
+            format!(Reset),
+            Token::LineBreak,
+            Token::ParagraphBreak,
+            text!("The"),
+            Token::Space,
+            text!("end"),
+            Token::LineBreak,
+        ]
+    );
+    assert_eq!(Tellraw::tokenize_reader(input.as_bytes())?, tokens);
+
+    assert!(matches!(
+        Tellraw::tokenize_string("say hi\ntellraw @a {not json}"),
+        Err(TokenizeError::Json { line: 2, .. })
+    ));
+
+    Ok(())
+}
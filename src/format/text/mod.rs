@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Exporting for plain text, wrapped like Minecraft: Java Edition displays it.
+//!
+//! See [`Text`] for more details.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use crafty_novels::{
+//!     export::Text,
+//!     syntax::{Metadata, Token, TokenList},
+//!     Export,
+//! };
+//!
+//! let input = TokenList::new_from_boxed(
+//!     Box::new([
+//!         Metadata::Title("crafty_novels".into()),
+//!         Metadata::Author("RemasteredArch".into()),
+//!     ]),
+//!     Box::new([
+//!         Token::ThematicBreak,
+//!         Token::Text("Page".into()),
+//!         Token::Space,
+//!         Token::Text("one".into()),
+//!         Token::LineBreak,
+//!         Token::ThematicBreak,
+//!         Token::Text("Page".into()),
+//!         Token::Space,
+//!         Token::Text("two".into()),
+//!         Token::LineBreak,
+//!     ]),
+//! );
+//!
+//! let expected = "\
+//! crafty_novels
+//! by RemasteredArch
+//!
+//! --- Page 1 ---
+//! Page one
+//! --- Page 2 ---
+//! Page two
+//! ";
+//!
+//! assert_eq!(Text::export_token_vector_to_string(input).as_ref(), expected);
+//! ```
+
+use crate::{
+    export::Capabilities,
+    syntax::{
+        align::Alignment,
+        minecraft::{
+            metrics::{char_width, PAGE_WIDTH},
+            Format,
+        },
+        Metadata, Token, TokenList,
+    },
+    writer::Utf8Writer,
+    Export, ExportWithOptions,
+};
+pub use options::ExportOptions;
+use std::{borrow::Cow, io::Write};
+
+mod options;
+#[cfg(test)]
+mod test;
+
+/// Exporting for plain text, reproducing the line breaks that Minecraft: Java Edition displays.
+///
+/// # Format
+///
+/// If present, the title and the author are written first, followed by an empty line:
+///
+/// ```text
+/// {title}
+/// by {author}
+///
+/// ```
+///
+/// Each page opens with a separator, `--- Page {number} ---`, counting from one. Then, each line
+/// of the page is wrapped to the width of a page in a written book, measured with the font metrics
+/// of the default font:
+///
+/// - Lines are broken at the last space that fits, and that space is dropped
+/// - Words that are wider than a whole line are broken at the last character that fits
+/// - Bold text is one pixel wider per character, just like in-game
+/// - Obfuscated text is scrambled if [`ExportOptions`] sets an
+///   [`Obfuscator`][`crate::syntax::minecraft::Obfuscator`]
+/// - All other formatting is dropped
+/// - Paragraph breaks are written as empty lines
+/// - Images are written as their alt text
+/// - Lines end with `"\n"`, unless [`ExportOptions`] sets another
+///   [`LineEnding`][`crate::format::line_ending::LineEnding`]
+///
+/// Characters outside of the ASCII range are measured with an approximate width, so wrapping
+/// non-Latin text may not exactly match the game.
+pub struct Text {}
+
+impl Text {
+    /// Parse a given abstract syntax vector into wrapped plain text with the given options, then
+    /// output that as a string.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_token_vector_to_string_with_options(
+        tokens: &TokenList,
+        options: &ExportOptions,
+    ) -> Box<str> {
+        let mut bytes: Vec<u8> = vec![];
+
+        Self::export_token_vector_to_writer_with_options(tokens, &mut bytes, options)
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/std/src/io/impls.rs#L433-L437
+            // https://github.com/rust-lang/rust/blob/1.80.1/library/alloc/src/vec/mod.rs#L2569-L2592
+            .expect(
+                "the `std::io::Write` implementations for `Vec<u8>` are infallible (as of 1.80.1)",
+            );
+
+        String::from_utf8(bytes)
+            .expect("`Utf8Writer` only writes UTF-8 encoded types")
+            .into_boxed_str()
+    }
+
+    /// Parse a given abstract syntax vector into wrapped plain text with the given options, then
+    /// output that into a writer, like a [`std::fs::File`].
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    pub fn export_token_vector_to_writer_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        let mut writer = Utf8Writer::new(output);
+        let line_ending = options.line_ending().as_str();
+
+        write_metadata(&mut writer, tokens.metadata_as_slice(), line_ending)?;
+
+        let mut page = Page::default();
+        for token in tokens.tokens_as_slice() {
+            match token {
+                Token::Text(text) => {
+                    let text = match options.obfuscation() {
+                        Some(obfuscator) if page.obfuscated => {
+                            Cow::Owned(obfuscator.obfuscate(text))
+                        }
+                        _ => Cow::Borrowed(&**text),
+                    };
+                    for char in text.chars() {
+                        page.push(char);
+                    }
+                }
+                Token::Image { alt, .. } => {
+                    for char in alt.chars() {
+                        page.push(char);
+                    }
+                }
+                Token::Space => page.push(' '),
+                Token::Format(Format::Bold) => page.bold = true,
+                Token::Format(Format::Obfuscated) => page.obfuscated = true,
+                Token::Format(Format::Reset) => {
+                    page.bold = false;
+                    page.obfuscated = false;
+                }
+                Token::Format(_) | Token::Role(_) => (),
+                Token::Align(alignment) => page.alignment = Some(*alignment),
+                Token::LineBreak => page.end_line(),
+                Token::ParagraphBreak => {
+                    if !page.line.is_empty() {
+                        page.end_line();
+                    }
+                    page.end_line();
+                }
+                Token::ThematicBreak => {
+                    page.write(&mut writer, line_ending)?;
+                    page.open = true;
+                }
+            }
+            if token.is_break() && *token != Token::Space {
+                page.alignment = None;
+            }
+        }
+        page.write(&mut writer, line_ending)?;
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl ExportWithOptions for Text {
+    type Options = ExportOptions;
+
+    /// See [`Text::export_token_vector_to_writer_with_options`].
+    fn export_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &Self::Options,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(tokens, output, options)
+    }
+}
+
+impl Export for Text {
+    fn capabilities() -> Capabilities {
+        Capabilities::PAGINATION | Capabilities::ALIGNMENT
+    }
+
+    /// Parse a given abstract syntax vector into wrapped plain text, then output that as a string.
+    fn export_token_vector_to_string(tokens: TokenList) -> Box<str> {
+        Self::export_token_vector_to_string_with_options(&tokens, &ExportOptions::default())
+    }
+
+    /// Parse a given abstract syntax vector into wrapped plain text, then output that into a
+    /// writer, like a [`std::fs::File`].
+    ///
+    /// Guaranteed to only write valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_token_vector_to_writer(
+        tokens: TokenList,
+        output: &mut impl Write,
+    ) -> std::io::Result<()> {
+        Self::export_token_vector_to_writer_with_options(&tokens, output, &ExportOptions::default())
+    }
+}
+
+/// Write the title and author, if present, followed by an empty line, ending each line with
+/// `line_ending`.
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot write into `output`
+fn write_metadata(
+    output: &mut Utf8Writer<impl Write>,
+    metadata: &[Metadata],
+    line_ending: &str,
+) -> std::io::Result<()> {
+    let mut wrote_any = false;
+
+    for data in metadata {
+        match data {
+            Metadata::Title(title) => write!(output, "{title}{line_ending}")?,
+            Metadata::Author(author) => write!(output, "by {author}{line_ending}")?,
+            _ => continue,
+        }
+        wrote_any = true;
+    }
+
+    if wrote_any {
+        output.write_str(line_ending)?;
+    }
+
+    Ok(())
+}
+
+/// The lines of the page currently being exported.
+#[derive(Debug, Default)]
+struct Page {
+    /// The number of the page, starting from one, once it has been written.
+    number: usize,
+    /// The finished, wrapped lines of the page.
+    lines: Vec<String>,
+    /// The characters of the line currently being wrapped, and their widths.
+    line: Vec<(char, u32)>,
+    /// The total width of [`Self::line`].
+    width: u32,
+    /// Whether or not the current text is bold.
+    bold: bool,
+    /// Whether or not the current text is obfuscated.
+    obfuscated: bool,
+    /// How the current line of the work is aligned, if at all, padding each line it wraps onto.
+    alignment: Option<Alignment>,
+    /// Whether or not a page has been started, even if it has no lines yet.
+    open: bool,
+}
+
+impl Page {
+    /// Push a character onto the current line, wrapping it if it doesn't fit.
+    fn push(&mut self, char: char) {
+        let width = char_width(char, self.bold);
+
+        if self.width + width > PAGE_WIDTH && !self.line.is_empty() {
+            // Breaking at a space consumes it
+            if char == ' ' {
+                self.end_line();
+                return;
+            }
+
+            match self.line.iter().rposition(|(char, _)| *char == ' ') {
+                // Carry the partial word over to the next line
+                Some(space) => {
+                    let carried = self.line.split_off(space + 1);
+                    self.line.pop();
+                    self.end_line();
+                    self.width = carried.iter().map(|(_, width)| width).sum();
+                    self.line = carried;
+                }
+                // The word is wider than a whole line
+                None => self.end_line(),
+            }
+        }
+
+        self.line.push((char, width));
+        self.width += width;
+    }
+
+    /// Finish the current line, padding it with spaces to its alignment.
+    fn end_line(&mut self) {
+        let mut line = String::new();
+        if let Some(alignment) = self.alignment.filter(|_| !self.line.is_empty()) {
+            let free = PAGE_WIDTH.saturating_sub(self.width) / char_width(' ', false);
+            let padding = alignment.offset(free.try_into().unwrap_or_default());
+            line.extend(std::iter::repeat_n(' ', padding));
+        }
+        line.extend(self.line.drain(..).map(|(char, _)| char));
+
+        self.lines.push(line);
+        self.width = 0;
+    }
+
+    /// Write the page into `output`, if one was started or has any text, ending each line with
+    /// `line_ending`.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn write(
+        &mut self,
+        output: &mut Utf8Writer<impl Write>,
+        line_ending: &str,
+    ) -> std::io::Result<()> {
+        if !self.line.is_empty() {
+            self.end_line();
+        }
+        if !self.open && self.lines.is_empty() {
+            return Ok(());
+        }
+
+        self.number += 1;
+        write!(output, "--- Page {} ---{line_ending}", self.number)?;
+        for line in self.lines.drain(..) {
+            write!(output, "{line}{line_ending}")?;
+        }
+
+        Ok(())
+    }
+}
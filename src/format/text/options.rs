@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Configuration for [`super::Text`].
+//!
+//! See [`ExportOptions`].
+
+use crate::{format::line_ending::LineEnding, syntax::minecraft::Obfuscator};
+
+/// Options that control how wrapped plain text is written.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{LineEnding, Text, TextExportOptions},
+///     syntax::{Token, TokenList},
+/// };
+///
+/// let input = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([Token::ThematicBreak, Token::Text("one".into())]),
+/// );
+/// let options = TextExportOptions::new().with_line_ending(LineEnding::CrLf);
+///
+/// assert_eq!(
+///     Text::export_token_vector_to_string_with_options(&input, &options).as_ref(),
+///     "--- Page 1 ---\r\none\r\n"
+/// );
+/// ```
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExportOptions {
+    /// What ends each line.
+    line_ending: LineEnding,
+    /// What replaces obfuscated text, if anything.
+    obfuscation: Option<Obfuscator>,
+}
+
+impl ExportOptions {
+    /// Creates a new [`ExportOptions`] with the default options.
+    ///
+    /// Lines end with [`LineEnding::Lf`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            line_ending: LineEnding::Lf,
+            obfuscation: None,
+        }
+    }
+
+    /// Returns what ends each line.
+    #[must_use]
+    pub const fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Sets what ends each line.
+    #[must_use]
+    pub const fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Returns what replaces obfuscated text, if anything.
+    #[must_use]
+    pub const fn obfuscation(&self) -> Option<&Obfuscator> {
+        self.obfuscation.as_ref()
+    }
+
+    /// Sets what replaces obfuscated text, or [`None`] to write it as it is.
+    ///
+    /// The scramble is wrapped like any other text, so use [`ObfuscatedLength::Keep`] to keep the
+    /// lines where they would be in-game.
+    ///
+    /// [`ObfuscatedLength::Keep`]: crate::syntax::minecraft::ObfuscatedLength::Keep
+    #[must_use]
+    pub fn with_obfuscation(mut self, obfuscation: Option<Obfuscator>) -> Self {
+        self.obfuscation = obfuscation;
+        self
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for exporting to the [text][`super::Text`] format.
+
+use super::{ExportOptions, Text};
+use crate::{
+    format::line_ending::LineEnding,
+    syntax::{
+        minecraft::{Format, ObfuscatedLength, Obfuscator},
+        Token, TokenList,
+    },
+    Export,
+};
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+/// Compare an output from [`Text::export_token_vector_to_string`], without metadata, and the
+/// expected output.
+macro_rules! test {
+    ( $( [ $( $token:expr ),* $(,)? ] => $expects:expr );+ ; ) => {
+        $(
+            assert_eq!(
+                Text::export_token_vector_to_string(TokenList::new_from_boxed(
+                    Box::new([]),
+                    Box::new([ $( $token ),* ]),
+                ))
+                .as_ref(),
+                $expects
+            );
+        )+
+    };
+}
+
+#[test]
+fn text_wrapping() {
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let word = "aaaaa";
+
+    test!(
+        // Broken at the last space that fits, which is dropped
+        [
+            ThematicBreak,
+            text!(word), Space, text!(word), Space, text!(word), Space, text!(word), LineBreak,
+        ] => "--- Page 1 ---\naaaaa aaaaa aaaaa\naaaaa\n";
+        // A space that doesn't fit is dropped
+        [
+            ThematicBreak,
+            text!("i".repeat(57)), Space, text!("next"), LineBreak,
+        ] => format!("--- Page 1 ---\n{}\nnext\n", "i".repeat(57));
+        // Words wider than a line are broken anywhere
+        [
+            ThematicBreak,
+            text!("i".repeat(60)), LineBreak,
+        ] => format!("--- Page 1 ---\n{}\niii\n", "i".repeat(57));
+        // Bold text is wider
+        [
+            ThematicBreak,
+            Token::Format(Format::Bold), text!("i".repeat(40)),
+            Token::Format(Format::Reset), text!("i"), LineBreak,
+        ] => format!("--- Page 1 ---\n{}\niii\n", "i".repeat(38));
+        // Paragraph breaks and empty pages
+        [
+            ThematicBreak,
+            text!("one"), LineBreak,
+            ParagraphBreak,
+            text!("two"), LineBreak,
+            ThematicBreak,
+            ThematicBreak,
+            text!("three"), LineBreak,
+        ] => "--- Page 1 ---\none\n\ntwo\n--- Page 2 ---\n--- Page 3 ---\nthree\n";
+    );
+}
+
+#[test]
+fn text_alignment() {
+    use crate::syntax::align::Alignment;
+    use Token::{LineBreak, ThematicBreak};
+
+    test!(
+        [
+            ThematicBreak,
+            Token::Align(Alignment::Center), text!("Title"), LineBreak,
+            text!("body"), LineBreak,
+        ] => format!("--- Page 1 ---\n{}Title\nbody\n", " ".repeat(11));
+    );
+}
+
+#[test]
+fn text_line_ending() {
+    use Token::{LineBreak, ParagraphBreak, ThematicBreak};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([]),
+        Box::new([
+            ThematicBreak,
+            text!("one"),
+            LineBreak,
+            ParagraphBreak,
+            text!("two"),
+            LineBreak,
+        ]),
+    );
+    let options = ExportOptions::new().with_line_ending(LineEnding::CrLf);
+
+    assert_eq!(
+        Text::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "--- Page 1 ---\r\none\r\n\r\ntwo\r\n"
+    );
+}
+
+#[test]
+fn text_export_stream_interrupted() {
+    use crate::syntax::stream::{StreamError, TokenStream};
+
+    let tokens = TokenList::new_from_boxed(
+        Box::new([]),
+        Box::new([Token::ThematicBreak, text!("one"), Token::LineBreak]),
+    );
+
+    // Pages aren't tracked by default, only the bytes that made it into the output
+    let mut buffer = [0; 8];
+    let Err(StreamError::Interrupted {
+        written_pages: 0,
+        written_tokens: 0,
+        written_bytes,
+        ..
+    }) = Text::export_token_iter_to_writer(
+        TokenStream::<()>::from(tokens),
+        &mut buffer.as_mut_slice(),
+    )
+    else {
+        panic!("expected the export to be interrupted");
+    };
+
+    assert_eq!(written_bytes, 8);
+    assert_eq!(&buffer, b"--- Page");
+}
+
+#[test]
+fn text_obfuscation() {
+    use Token::{LineBreak, Space, ThematicBreak};
+
+    let tokens = TokenList::from(vec![
+        ThematicBreak,
+        Token::Format(Format::Obfuscated),
+        text!("secret"),
+        Space,
+        text!("words"),
+        Token::Format(Format::Reset),
+        Space,
+        text!("open"),
+        LineBreak,
+    ]);
+
+    assert_eq!(
+        Text::export_token_vector_to_string(tokens.clone()).as_ref(),
+        "--- Page 1 ---\nsecret words open\n"
+    );
+
+    let obfuscator = Obfuscator::new("#").with_length(ObfuscatedLength::Fixed(3));
+    let options = ExportOptions::new().with_obfuscation(Some(obfuscator));
+    assert_eq!(
+        Text::export_token_vector_to_string_with_options(&tokens, &options).as_ref(),
+        "--- Page 1 ---\n### ### open\n"
+    );
+}
@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Detecting the encoding of text input, and decoding it to UTF-8.
+//!
+//! Text editors on Windows commonly save text with a byte order mark, or as UTF-16, neither of
+//! which the importers understand directly. See [`decode`] and [`Encoding`].
+
+#[cfg(test)]
+mod test;
+
+use std::{
+    char::REPLACEMENT_CHARACTER,
+    io::{self, BufRead, BufReader, Cursor, ErrorKind, Read},
+};
+
+/// The byte order mark of UTF-8 text.
+const UTF_8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+/// The byte order mark of little-endian UTF-16 text.
+const UTF_16_LE_BOM: [u8; 2] = [0xFF, 0xFE];
+/// The byte order mark of big-endian UTF-16 text.
+const UTF_16_BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+/// How much UTF-16 input to read at a time while decoding it.
+const CHUNK_SIZE: usize = 4096;
+
+/// An encoding of text input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// UTF-8, with or without a byte order mark.
+    #[default]
+    Utf8,
+    /// Little-endian UTF-16, as saved by Notepad as "Unicode".
+    Utf16Le,
+    /// Big-endian UTF-16, as saved by Notepad as "Unicode big endian".
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Detect the encoding of the text that starts with `bytes`, going by its byte order mark,
+    /// returning it and the length of the byte order mark.
+    ///
+    /// Text without a byte order mark is assumed to be UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::import::Encoding;
+    ///
+    /// assert_eq!(Encoding::detect(b"\xEF\xBB\xBFtext"), (Encoding::Utf8, 3));
+    /// assert_eq!(Encoding::detect(b"\xFF\xFEt\0"), (Encoding::Utf16Le, 2));
+    /// assert_eq!(Encoding::detect(b"text"), (Encoding::Utf8, 0));
+    /// ```
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> (Self, usize) {
+        if bytes.starts_with(&UTF_8_BOM) {
+            (Self::Utf8, UTF_8_BOM.len())
+        } else if bytes.starts_with(&UTF_16_LE_BOM) {
+            (Self::Utf16Le, UTF_16_LE_BOM.len())
+        } else if bytes.starts_with(&UTF_16_BE_BOM) {
+            (Self::Utf16Be, UTF_16_BE_BOM.len())
+        } else {
+            (Self::Utf8, 0)
+        }
+    }
+}
+
+/// Wrap `input` so that reading from it yields UTF-8 text, without a byte order mark.
+///
+/// The encoding is detected with [`Encoding::detect`]. UTF-16 is decoded as it is read, with
+/// unpaired surrogates replaced by [`REPLACEMENT_CHARACTER`].
+///
+/// # Errors
+///
+/// - [`std::io::Error`] if it cannot read from `input`
+pub fn decode<'a>(mut input: impl Read + 'a) -> io::Result<Box<dyn BufRead + 'a>> {
+    // Read as much as the longest byte order mark, however few bytes each read yields
+    let mut start = [0; UTF_8_BOM.len()];
+    let mut length = 0;
+    while length < start.len() {
+        match input.read(&mut start[length..]) {
+            Ok(0) => break,
+            Ok(read) => length += read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    let (encoding, bom_length) = Encoding::detect(&start[..length]);
+    let input = BufReader::new(Cursor::new(start[bom_length..length].to_vec()).chain(input));
+
+    Ok(match encoding {
+        Encoding::Utf8 => Box::new(input),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            Box::new(BufReader::new(Utf16Reader::new(input, encoding)))
+        }
+    })
+}
+
+/// Wraps a reader of UTF-16 text, yielding it as UTF-8 text.
+struct Utf16Reader<R: Read> {
+    /// The UTF-16 text.
+    input: R,
+    /// Whether the UTF-16 text is big-endian.
+    big_endian: bool,
+    /// Bytes of UTF-16 text that have been read but not yet decoded.
+    pending: Vec<u8>,
+    /// UTF-8 text that has been decoded but not yet read.
+    output: Vec<u8>,
+    /// How much of [`Self::output`] has been read.
+    position: usize,
+}
+
+impl<R: Read> Utf16Reader<R> {
+    /// Wrap `input`, which is UTF-16 text in `encoding`.
+    const fn new(input: R, encoding: Encoding) -> Self {
+        Self {
+            input,
+            big_endian: matches!(encoding, Encoding::Utf16Be),
+            pending: vec![],
+            output: vec![],
+            position: 0,
+        }
+    }
+
+    /// Decode the next chunk of input into [`Self::output`], returning `false` once there is no
+    /// input left.
+    ///
+    /// A trailing odd byte or high surrogate is kept in [`Self::pending`] until the rest of it is
+    /// read.
+    fn fill(&mut self) -> io::Result<bool> {
+        let mut chunk = [0; CHUNK_SIZE];
+        let read = self.input.read(&mut chunk)?;
+
+        self.output.clear();
+        self.position = 0;
+
+        if read == 0 {
+            if self.pending.is_empty() {
+                return Ok(false);
+            }
+
+            self.pending.clear();
+            self.push(REPLACEMENT_CHARACTER);
+            return Ok(true);
+        }
+
+        self.pending.extend_from_slice(&chunk[..read]);
+
+        let mut units: Vec<u16> = self
+            .pending
+            .chunks_exact(2)
+            .map(|pair| {
+                let pair = [pair[0], pair[1]];
+                if self.big_endian {
+                    u16::from_be_bytes(pair)
+                } else {
+                    u16::from_le_bytes(pair)
+                }
+            })
+            .collect();
+
+        if units
+            .last()
+            .is_some_and(|unit| (0xD800..0xDC00).contains(unit))
+        {
+            units.pop();
+        }
+        self.pending.drain(..units.len() * 2);
+
+        for character in char::decode_utf16(units) {
+            self.push(character.unwrap_or(REPLACEMENT_CHARACTER));
+        }
+
+        Ok(true)
+    }
+
+    /// Append `character` to [`Self::output`] as UTF-8.
+    fn push(&mut self, character: char) {
+        let mut buffer = [0; 4];
+        self.output
+            .extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+    }
+}
+
+impl<R: Read> Read for Utf16Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.output.len() {
+            if !self.fill()? {
+                return Ok(0);
+            }
+        }
+
+        let read = (&self.output[self.position..]).read(buf)?;
+        self.position += read;
+
+        Ok(read)
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{decode, Encoding};
+use crate::{import::Stendhal, Tokenize};
+use std::io::{self, Read};
+
+/// Yields the bytes of a slice one at a time, to split UTF-16 code units across reads.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((first, rest)) = self.0.split_first() else {
+            return Ok(0);
+        };
+        let Some(target) = buf.first_mut() else {
+            return Ok(0);
+        };
+
+        *target = *first;
+        self.0 = rest;
+        Ok(1)
+    }
+}
+
+/// Encode `text` as UTF-16 with a byte order mark.
+fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+    std::iter::once('\u{FEFF}')
+        .chain(text.chars())
+        .collect::<String>()
+        .encode_utf16()
+        .flat_map(|unit| {
+            if big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            }
+        })
+        .collect()
+}
+
+/// Read everything from `input` after decoding it.
+fn read(input: impl Read) -> io::Result<String> {
+    let mut string = String::new();
+    decode(input)?.read_to_string(&mut string)?;
+    Ok(string)
+}
+
+#[test]
+fn text_encoding_detect() {
+    assert_eq!(
+        Encoding::detect(b"\xEF\xBB\xBF\xC2\xA7"),
+        (Encoding::Utf8, 3)
+    );
+    assert_eq!(Encoding::detect(b"\xFF\xFE"), (Encoding::Utf16Le, 2));
+    assert_eq!(Encoding::detect(b"\xFE\xFF"), (Encoding::Utf16Be, 2));
+    assert_eq!(Encoding::detect(b"\xC2\xA7"), (Encoding::Utf8, 0));
+    assert_eq!(Encoding::detect(b""), (Encoding::Utf8, 0));
+}
+
+#[test]
+fn text_encoding_decode() -> io::Result<()> {
+    let text = "title: §lBook\n#- 𝄞 §c¶";
+
+    assert_eq!(read(text.as_bytes())?, text);
+    assert_eq!(
+        read([b"\xEF\xBB\xBF", text.as_bytes()].concat().as_slice())?,
+        text
+    );
+    assert_eq!(read(utf16(text, false).as_slice())?, text);
+    assert_eq!(read(utf16(text, true).as_slice())?, text);
+    assert_eq!(read(Trickle(&utf16(text, false)))?, text);
+
+    // An unpaired high surrogate and a trailing odd byte
+    assert_eq!(read(&b"\xFF\xFEa\0\x34\xD8b\0c"[..])?, "a\u{FFFD}b\u{FFFD}");
+
+    Ok(())
+}
+
+#[test]
+fn text_encoding_tokenize_path() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "title: Book\nauthor: Author\npages:\n#- §lhello";
+    let path = std::env::temp_dir().join(format!(
+        "crafty_novels_text_encoding_{}.stendhal",
+        std::process::id()
+    ));
+
+    std::fs::write(&path, utf16(text, false))?;
+    let tokens = Stendhal::tokenize_path(&path);
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(tokens?, Stendhal::tokenize_string(text)?);
+
+    Ok(())
+}
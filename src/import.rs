@@ -17,5 +17,27 @@
 
 //! Implementations of [`Tokenize`][`crate::Tokenize`].
 
+#[cfg(feature = "compression")]
+pub use crate::format::compression::{decompress, DecompressError};
+pub use crate::format::ghostwriter::Ghostwriter;
+pub use crate::format::ghostwriter::TokenizeError as GhostwriterTokenizeError;
+pub use crate::format::json_text::JsonText;
+pub use crate::format::json_text::TokenizeError as JsonTextTokenizeError;
+pub use crate::format::limits::{Limit, Limits};
+pub use crate::format::names::{InputFormat, UnknownFormatError};
+pub use crate::format::nbt::Nbt;
+pub use crate::format::nbt::TokenizeError as NbtTokenizeError;
+pub use crate::format::scan as util;
+pub use crate::format::stendhal::Dialect as StendhalDialect;
+pub use crate::format::stendhal::DirectoryError as StendhalDirectoryError;
+pub use crate::format::stendhal::FileError as StendhalFileError;
+pub use crate::format::stendhal::FilenamePattern as StendhalFilenamePattern;
+pub use crate::format::stendhal::FilenamePatternError as StendhalFilenamePatternError;
 pub use crate::format::stendhal::Stendhal;
 pub use crate::format::stendhal::TokenizeError as StendhalTokenizeError;
+pub use crate::format::stendhal::TokenizeOptions as StendhalTokenizeOptions;
+pub use crate::format::stendhal::TokenizeWarning as StendhalTokenizeWarning;
+pub use crate::format::suggestion::Suggestion;
+pub use crate::format::tellraw::Tellraw;
+pub use crate::format::tellraw::TokenizeError as TellrawTokenizeError;
+pub use crate::format::text_encoding::{decode, Encoding};
@@ -27,7 +27,12 @@
 //! Structs that implement [`Export`] take that [`TokenList`], convert it to their format, and
 //! write that to the output.
 //!
+//! For huge inputs, [`Tokenize::token_iter`] and [`Export::export_token_iter_to_writer`] pass
+//! tokens through one at a time instead, as a [`syntax::stream::TokenStream`].
+//!
 //! Built-in implementations can be found in [`import`] and [`export`].
+//! [`session::Session`] bundles them together with a single set of options, for applications that
+//! want to run the whole pipeline.
 //!
 //! # Examples
 //!
@@ -72,13 +77,22 @@
 
 #![warn(clippy::cargo, clippy::nursery, clippy::pedantic)]
 #![cfg_attr(debug_assertions, allow(clippy::missing_errors_doc))]
+// `serde_json` and `thiserror` depend on different major versions of `syn`
+#![allow(clippy::multiple_crate_versions)]
 
-use std::io::{Read, Write};
-use syntax::TokenList;
+use std::{
+    io::{BufRead, Read, Write},
+    path::Path,
+};
+use syntax::{
+    stream::{StreamError, TokenStream},
+    TokenList,
+};
 
 pub mod export;
 mod format;
 pub mod import;
+pub mod session;
 pub mod syntax;
 mod writer;
 
@@ -94,7 +108,20 @@ mod writer;
 /// As of Rust 1.80.1, `.write_all` is infallible for [`Vec<u8>`], and a UTF-8 wrapper over a
 /// [`std::io::BufWriter`] can render [`String::from_utf8`] infallible.
 pub trait Export {
+    /// Returns the features of a work that this format can represent.
+    ///
+    /// Anything else is dropped or approximated when exporting.
+    ///
+    /// Defaults to [`Capabilities::NONE`][`export::Capabilities::NONE`], so that an exporter
+    /// which doesn't say otherwise is assumed to lose every feature, rather than to silently drop
+    /// one it was assumed to keep.
+    #[must_use]
+    fn capabilities() -> export::Capabilities {
+        export::Capabilities::NONE
+    }
+
     /// Parse a given abstract syntax vector into a certain format, then output that as a string.
+    #[must_use]
     fn export_token_vector_to_string(tokens: TokenList) -> Box<str>;
 
     /// Parse a given abstract syntax vector into a certain format, writing the result into `output`.
@@ -106,6 +133,118 @@ pub trait Export {
         tokens: TokenList,
         output: &mut impl Write,
     ) -> std::io::Result<()>;
+
+    /// Export only the page at `index`, starting with the formatting carried over from the pages
+    /// before it, then output that as a string.
+    ///
+    /// The output is the same no matter which other pages are exported, so a server can convert
+    /// each page of a long work on demand. See [`TokenList::page`].
+    ///
+    /// # Errors
+    ///
+    /// - [`syntax::edit::PageIndexError`] if `index` is past the last page
+    fn export_page(
+        tokens: &TokenList,
+        index: usize,
+    ) -> Result<Box<str>, syntax::edit::PageIndexError> {
+        tokens.page(index).map(Self::export_token_vector_to_string)
+    }
+
+    /// Export a [`TokenStream`] as its tokens are parsed, writing the result into `output`.
+    ///
+    /// By default, the whole stream is collected into a [`TokenList`] first. Exporters that can
+    /// write each token as it comes, like [`export::PlainText`], override this to run in constant
+    /// memory, alongside [`Tokenize::token_iter`].
+    ///
+    /// # Errors
+    ///
+    /// - [`StreamError::Tokenize`] if a token of `tokens` fails to parse
+    /// - [`StreamError::Interrupted`] if it cannot write into `output`, with how much was written.
+    ///   By default, pages are not tracked, so only `written_bytes` is known and the export can
+    ///   only be retried from the start. Exporters that flush each page as it finishes, like
+    ///   [`export::PlainText`], also report the written pages, so that it can be resumed.
+    fn export_token_iter_to_writer<E>(
+        tokens: TokenStream<'_, E>,
+        output: &mut impl Write,
+    ) -> Result<(), StreamError<E>> {
+        let tokens = tokens.collect_token_list().map_err(StreamError::Tokenize)?;
+        let mut output = writer::CountingWriter {
+            inner: output,
+            count: 0,
+        };
+
+        Self::export_token_vector_to_writer(tokens, &mut output).map_err(|source| {
+            StreamError::Interrupted {
+                written_pages: 0,
+                written_tokens: 0,
+                written_bytes: output.count,
+                source,
+            }
+        })
+    }
+
+    /// Export each of `pages` into its own writer, as returned by `writer_for`.
+    ///
+    /// `writer_for` is given the index of each page, starting from zero, and is called just before
+    /// that page is exported, so only one writer needs to be open at a time. Useful for exporting
+    /// large works page by page, like one file per page, alongside [`TokenList::pages_iter`].
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into one of the writers
+    fn export_pages_to_writers<W: Write>(
+        pages: impl IntoIterator<Item = TokenList>,
+        mut writer_for: impl FnMut(usize) -> W,
+    ) -> std::io::Result<()> {
+        for (index, page) in pages.into_iter().enumerate() {
+            let mut output = writer_for(index);
+            Self::export_token_vector_to_writer(page, &mut output)?;
+            output.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Methods for exporting [`TokenList`]s with the options of a certain format.
+///
+/// Implemented by every exporter that has options, so that callers can configure the output
+/// without knowing the format ahead of time, rather than editing the output afterwards.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::{Html, HtmlExportOptions, TextDirection},
+///     syntax::TokenList,
+///     ExportWithOptions,
+/// };
+///
+/// let options = HtmlExportOptions::new()
+///     .with_language("ar")
+///     .with_direction(TextDirection::RightToLeft);
+///
+/// let mut output = vec![];
+/// Html::export_with_options(&TokenList::from(vec![]), &mut output, &options)?;
+///
+/// assert!(String::from_utf8_lossy(&output).contains(r#"<html lang="ar" dir="rtl">"#));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub trait ExportWithOptions: Export {
+    /// The options for this format, where [`Default`] writes the same output as [`Export`].
+    type Options: Default;
+
+    /// Parse a given abstract syntax vector into a certain format with `options`, writing the
+    /// result into `output`.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if it cannot write into `output`
+    fn export_with_options(
+        tokens: &TokenList,
+        output: &mut impl Write,
+        options: &Self::Options,
+    ) -> std::io::Result<()>;
 }
 
 /// Methods for importing documents into [`TokenList`]s.
@@ -134,4 +273,37 @@ pub trait Tokenize {
     ///
     /// Typical errors include I/O errors and incorrect, malformed, or misplaced syntax.
     fn tokenize_reader(input: impl Read) -> Result<TokenList, Self::Error>;
+
+    /// Parse the file at `path` into an abstract syntax vector.
+    ///
+    /// The file is decoded first, so UTF-8 with a byte order mark and UTF-16, as saved by Windows
+    /// editors, are read like any other UTF-8 file. See [`import::decode`].
+    ///
+    /// # Errors
+    ///
+    /// Typical errors include I/O errors and incorrect, malformed, or misplaced syntax.
+    fn tokenize_path(path: impl AsRef<Path>) -> Result<TokenList, Self::Error>
+    where
+        Self::Error: From<std::io::Error>,
+    {
+        Self::tokenize_reader(import::decode(std::fs::File::open(path)?)?)
+    }
+
+    /// Parse a file lazily, returning its metadata and an iterator that parses each token as it is
+    /// needed.
+    ///
+    /// By default, the whole file is parsed up front. Importers that can parse a little at a time,
+    /// like [`import::Stendhal`], override this so that huge inputs never need to be held in
+    /// memory, alongside [`Export::export_token_iter_to_writer`].
+    ///
+    /// # Errors
+    ///
+    /// Typical errors include I/O errors and incorrect, malformed, or misplaced syntax, either
+    /// while parsing the metadata here or while parsing a token later.
+    fn token_iter<'a>(input: impl BufRead + 'a) -> Result<TokenStream<'a, Self::Error>, Self::Error>
+    where
+        Self::Error: 'a,
+    {
+        Self::tokenize_reader(input).map(TokenStream::from)
+    }
 }
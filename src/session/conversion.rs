@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! A one-shot conversion between two formats, configured up front.
+//!
+//! See [`Conversion`].
+
+use super::{Session, SessionError};
+use crate::{
+    export::{HtmlExportOptions, OutputFormat},
+    import::{InputFormat, StendhalTokenizeOptions},
+};
+use std::io::{Read, Write};
+
+/// Marks a required field of a [`ConversionBuilder`] that has not been set yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Unset;
+
+/// Builds a [`Conversion`].
+///
+/// The input and output formats are tracked in the type, `I` and `O`, so
+/// [`ConversionBuilder::build`] is only available once both have been set.
+///
+/// ```compile_fail
+/// use crafty_novels::{import::InputFormat, session::Conversion};
+///
+/// // Missing the output format
+/// let conversion = Conversion::builder().from(InputFormat::Stendhal).build();
+/// ```
+#[must_use]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConversionBuilder<I, O> {
+    /// The format of the input, once set.
+    from: I,
+    /// The format of the output, once set.
+    to: O,
+    /// The options for [`InputFormat::Stendhal`].
+    stendhal_options: StendhalTokenizeOptions,
+    /// The options for [`OutputFormat::Html`].
+    html_options: HtmlExportOptions,
+}
+
+impl ConversionBuilder<Unset, Unset> {
+    /// Creates a new [`ConversionBuilder`] with the default options and no formats.
+    pub const fn new() -> Self {
+        Self {
+            from: Unset,
+            to: Unset,
+            stendhal_options: StendhalTokenizeOptions::new(),
+            html_options: HtmlExportOptions::new(),
+        }
+    }
+}
+
+impl<O> ConversionBuilder<Unset, O> {
+    /// Sets the format of the input.
+    pub fn from(self, from: InputFormat) -> ConversionBuilder<InputFormat, O> {
+        ConversionBuilder {
+            from,
+            to: self.to,
+            stendhal_options: self.stendhal_options,
+            html_options: self.html_options,
+        }
+    }
+}
+
+impl<I> ConversionBuilder<I, Unset> {
+    /// Sets the format of the output.
+    pub fn to(self, to: OutputFormat) -> ConversionBuilder<I, OutputFormat> {
+        ConversionBuilder {
+            from: self.from,
+            to,
+            stendhal_options: self.stendhal_options,
+            html_options: self.html_options,
+        }
+    }
+}
+
+impl<I, O> ConversionBuilder<I, O> {
+    /// Sets the options for [`InputFormat::Stendhal`].
+    pub fn with_stendhal_options(mut self, options: StendhalTokenizeOptions) -> Self {
+        self.stendhal_options = options;
+        self
+    }
+
+    /// Sets the options for [`OutputFormat::Html`].
+    pub fn with_html_options(mut self, options: HtmlExportOptions) -> Self {
+        self.html_options = options;
+        self
+    }
+}
+
+impl ConversionBuilder<InputFormat, OutputFormat> {
+    /// Creates the [`Conversion`].
+    #[must_use]
+    pub fn build(self) -> Conversion {
+        Conversion {
+            from: self.from,
+            to: self.to,
+            stendhal_options: self.stendhal_options,
+            html_options: self.html_options,
+        }
+    }
+}
+
+/// Converts works from one format to another, without any transforms.
+///
+/// Unlike a [`Session`], it does not collect diagnostics, so it can be shared between threads and
+/// reused freely.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{export::OutputFormat, import::InputFormat, session::Conversion};
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let conversion = Conversion::builder()
+///     .from(InputFormat::Stendhal)
+///     .to("txt".parse::<OutputFormat>()?)
+///     .build();
+///
+/// let input = "title: crafty_novels
+/// author: RemasteredArch
+/// pages:
+/// #- Page one";
+///
+/// assert_eq!(
+///     conversion.convert_str(input)?.as_ref(),
+///     "crafty_novels\nby RemasteredArch\n\n--- Page 1 ---\nPage one\n"
+/// );
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Conversion {
+    /// The format of the input.
+    from: InputFormat,
+    /// The format of the output.
+    to: OutputFormat,
+    /// The options for [`InputFormat::Stendhal`].
+    stendhal_options: StendhalTokenizeOptions,
+    /// The options for [`OutputFormat::Html`].
+    html_options: HtmlExportOptions,
+}
+
+impl Conversion {
+    /// Start building a [`Conversion`].
+    ///
+    /// See [`ConversionBuilder`].
+    pub const fn builder() -> ConversionBuilder<Unset, Unset> {
+        ConversionBuilder::new()
+    }
+
+    /// Returns the format of the input.
+    #[must_use]
+    pub const fn from(&self) -> InputFormat {
+        self.from
+    }
+
+    /// Returns the format of the output.
+    #[must_use]
+    pub const fn to(&self) -> OutputFormat {
+        self.to
+    }
+
+    /// Convert a string.
+    ///
+    /// # Errors
+    ///
+    /// - [`SessionError::Stendhal`] if [`InputFormat::Stendhal`] cannot tokenize `input`
+    /// - [`SessionError::Tellraw`] if [`InputFormat::Tellraw`] cannot tokenize `input`
+    pub fn convert_str(&self, input: &str) -> Result<Box<str>, SessionError> {
+        let mut session = self.session();
+        let tokens = session.import_str(self.from, input)?;
+
+        Ok(session.export_to_string(&tokens, self.to))
+    }
+
+    /// Convert from a reader into a writer, like [`std::fs::File`]s.
+    ///
+    /// # Errors
+    ///
+    /// - [`SessionError::Stendhal`] if [`InputFormat::Stendhal`] cannot tokenize `input`
+    /// - [`SessionError::Tellraw`] if [`InputFormat::Tellraw`] cannot tokenize `input`
+    /// - [`SessionError::Io`] if it cannot write into `output`
+    pub fn convert(&self, input: impl Read, output: &mut impl Write) -> Result<(), SessionError> {
+        let mut session = self.session();
+        let tokens = session.import(self.from, input)?;
+
+        session.export(&tokens, self.to, output)
+    }
+
+    /// Creates a [`Session`] with the same options.
+    fn session(&self) -> Session {
+        Session::new()
+            .with_stendhal_options(self.stendhal_options.clone())
+            .with_html_options(self.html_options.clone())
+    }
+}
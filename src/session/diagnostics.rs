@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! What a [`super::Session`] reports about its work.
+//!
+//! See [`Diagnostic`] and [`Stats`].
+
+use crate::{
+    format::suggestion::Suggestion,
+    syntax::{minecraft::Format, Token, TokenList},
+};
+use std::fmt::Display;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Something worth knowing about the work, like how it was interpreted.
+    Info,
+    /// Something was lost or changed, but the conversion succeeded.
+    Warning,
+    /// The conversion failed.
+    Error,
+}
+
+/// The step of a [`super::Session`] that produced a [`Diagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// [`super::Session::import`].
+    Import,
+    /// [`super::Session::transform`].
+    Transform,
+    /// [`super::Session::export`].
+    Export,
+}
+
+/// A message about something that happened during a [`super::Session`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// How serious the diagnostic is.
+    severity: Severity,
+    /// The step that produced the diagnostic.
+    stage: Stage,
+    /// A description of what happened.
+    message: Box<str>,
+    /// Changes to the input that would fix the problem, most likely first.
+    suggestions: Box<[Suggestion]>,
+}
+
+impl Diagnostic {
+    /// Creates a new [`Diagnostic`].
+    #[must_use]
+    pub fn new(severity: Severity, stage: Stage, message: impl Into<Box<str>>) -> Self {
+        Self {
+            severity,
+            stage,
+            message: message.into(),
+            suggestions: Box::new([]),
+        }
+    }
+
+    /// Attaches `suggestions` for fixing the problem, most likely first.
+    #[must_use]
+    pub fn with_suggestions(mut self, suggestions: impl IntoIterator<Item = Suggestion>) -> Self {
+        self.suggestions = suggestions.into_iter().collect();
+        self
+    }
+
+    /// Returns how serious the diagnostic is.
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the step that produced the diagnostic.
+    #[must_use]
+    pub const fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// Returns a description of what happened.
+    #[must_use]
+    pub const fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the changes to the input that would fix the problem, most likely first.
+    #[must_use]
+    pub const fn suggestions(&self) -> &[Suggestion] {
+        &self.suggestions
+    }
+}
+
+impl Display for Diagnostic {
+    /// Ex. `"warning (import): preserved 2 unknown format codes"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let stage = match self.stage {
+            Stage::Import => "import",
+            Stage::Transform => "transform",
+            Stage::Export => "export",
+        };
+
+        write!(f, "{severity} ({stage}): {}", self.message)
+    }
+}
+
+/// Running totals across every step of a [`super::Session`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// How many documents were successfully imported.
+    documents_imported: usize,
+    /// How many documents were successfully exported.
+    documents_exported: usize,
+    /// How many bytes of input were successfully imported.
+    bytes_imported: usize,
+    /// How many bytes of output were successfully exported.
+    bytes_exported: usize,
+    /// How many [`Token`]s were produced by importing.
+    tokens_imported: usize,
+    /// How many pages were imported, counted by [`Token::ThematicBreak`].
+    pages_imported: usize,
+}
+
+impl Stats {
+    /// Returns how many documents were successfully imported.
+    #[must_use]
+    pub const fn documents_imported(&self) -> usize {
+        self.documents_imported
+    }
+
+    /// Returns how many documents were successfully exported.
+    #[must_use]
+    pub const fn documents_exported(&self) -> usize {
+        self.documents_exported
+    }
+
+    /// Returns how many bytes of input were successfully imported.
+    #[must_use]
+    pub const fn bytes_imported(&self) -> usize {
+        self.bytes_imported
+    }
+
+    /// Returns how many bytes of output were successfully exported.
+    #[must_use]
+    pub const fn bytes_exported(&self) -> usize {
+        self.bytes_exported
+    }
+
+    /// Returns how many [`Token`]s were produced by importing.
+    #[must_use]
+    pub const fn tokens_imported(&self) -> usize {
+        self.tokens_imported
+    }
+
+    /// Returns how many pages were imported, counted by [`Token::ThematicBreak`].
+    #[must_use]
+    pub const fn pages_imported(&self) -> usize {
+        self.pages_imported
+    }
+
+    /// Record a successfully imported document.
+    pub(super) fn record_import(&mut self, bytes: usize, tokens: &TokenList) {
+        let tokens = tokens.tokens_as_slice();
+
+        self.documents_imported += 1;
+        self.bytes_imported += bytes;
+        self.tokens_imported += tokens.len();
+        self.pages_imported += tokens
+            .iter()
+            .filter(|token| matches!(token, Token::ThematicBreak))
+            .count();
+    }
+
+    /// Record a successfully exported document.
+    pub(super) const fn record_export(&mut self, bytes: usize) {
+        self.documents_exported += 1;
+        self.bytes_exported += bytes;
+    }
+}
+
+/// Count the [`Format::Unknown`]s in `tokens`.
+pub(super) fn unknown_format_codes(tokens: &TokenList) -> usize {
+    tokens
+        .tokens_as_slice()
+        .iter()
+        .filter(|token| matches!(token, Token::Format(Format::Unknown(_))))
+        .count()
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Error definitions for [`super::Session`].
+//!
+//! See [`SessionError`].
+
+use crate::format::{
+    ghostwriter::TokenizeError as GhostwriterTokenizeError,
+    json_text::TokenizeError as JsonTextTokenizeError, nbt::TokenizeError as NbtTokenizeError,
+    stendhal::TokenizeError as StendhalTokenizeError,
+    tellraw::TokenizeError as TellrawTokenizeError,
+};
+
+/// All the errors that could occur during a [`super::Session`].
+#[allow(clippy::module_name_repetitions)] // This will be exported outside of `error`
+#[derive(thiserror::Error, Debug)]
+pub enum SessionError {
+    /// Encountered when the [Stendhal][`crate::import::Stendhal`] importer fails.
+    #[error("could not import Stendhal document: {0}")]
+    Stendhal(#[from] StendhalTokenizeError),
+    /// Encountered when the [`Tellraw`][`crate::import::Tellraw`] importer fails.
+    #[error("could not import datapack function: {0}")]
+    Tellraw(#[from] TellrawTokenizeError),
+    /// Encountered when the [`Nbt`][`crate::import::Nbt`] importer fails.
+    #[error("could not import NBT data: {0}")]
+    Nbt(#[from] NbtTokenizeError),
+    /// Encountered when the [`JsonText`][`crate::import::JsonText`] importer fails.
+    #[error("could not import JSON text: {0}")]
+    JsonText(#[from] JsonTextTokenizeError),
+    /// Encountered when the [`Ghostwriter`][`crate::import::Ghostwriter`] importer fails.
+    #[error("could not import Ghostwriter export: {0}")]
+    Ghostwriter(#[from] GhostwriterTokenizeError),
+    /// Encountered when a gzip or zip input cannot be decompressed.
+    #[cfg(feature = "compression")]
+    #[error("could not decompress input: {0}")]
+    Decompress(#[from] crate::import::DecompressError),
+    /// Encoutered when an I/O action fails in some way.
+    #[error("could not perform I/O action: {0}")]
+    Io(#[from] std::io::Error),
+}
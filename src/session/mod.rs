@@ -0,0 +1,506 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! A high-level API for embedding the whole conversion pipeline.
+//!
+//! See [`Session`].
+
+use crate::{
+    export::{
+        Brf, Capabilities, Cp437, Html, HtmlExportOptions, OutputFormat, PlainText, Ssml, Text,
+    },
+    import::{
+        Ghostwriter, InputFormat, JsonText, Limits, Nbt, Stendhal, StendhalTokenizeOptions, Tellraw,
+    },
+    syntax::{
+        transform::{Builtin, Pipeline, Transform},
+        TokenList,
+    },
+    writer::CountingWriter,
+    Export,
+};
+pub use conversion::{Conversion, ConversionBuilder, Unset};
+pub use diagnostics::{Diagnostic, Severity, Stage, Stats};
+pub use error::SessionError;
+use std::io::{Read, Write};
+
+mod conversion;
+mod diagnostics;
+mod error;
+#[cfg(test)]
+mod test;
+
+/// Imports, transforms, and exports works with a single set of options, collecting
+/// [`Diagnostic`]s and [`Stats`] along the way.
+///
+/// Configure a [`Session`] once, then use it for as many works as needed.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::OutputFormat,
+///     import::InputFormat,
+///     session::Session,
+///     syntax::normalize::MetadataNormalizer,
+/// };
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let mut session = Session::new().with_transform(MetadataNormalizer::new());
+///
+/// let input = "title: §6crafty_novels
+/// author: RemasteredArch
+/// pages:
+/// #- Page one";
+///
+/// let tokens = session.import_str(InputFormat::Stendhal, input)?;
+/// let tokens = session.transform(&tokens);
+/// let html = session.export_to_string(&tokens, OutputFormat::Html);
+///
+/// assert!(html.contains("<title>crafty_novels</title>"));
+/// assert_eq!(session.stats().pages_imported(), 1);
+/// assert!(!session.has_errors());
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Session {
+    /// The options for [`InputFormat::Stendhal`].
+    stendhal_options: StendhalTokenizeOptions,
+    /// The limits for every other [`InputFormat`].
+    limits: Limits,
+    /// The options for [`OutputFormat::Html`].
+    html_options: HtmlExportOptions,
+    /// The zip entry for [`Self::import`] to read, if not the first file.
+    #[cfg(feature = "compression")]
+    archive_entry: Option<Box<str>>,
+    /// Whether [`Self::export`] gzip-compresses its output.
+    #[cfg(feature = "compression")]
+    compress_output: bool,
+    /// Applied by [`Self::transform`].
+    transforms: Pipeline,
+    /// Everything reported so far.
+    diagnostics: Vec<Diagnostic>,
+    /// Running totals so far.
+    stats: Stats,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    /// Creates a new [`Session`] with the default options and no transforms, other than
+    /// [`Builtin::DetectTitlePage`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stendhal_options: StendhalTokenizeOptions::new(),
+            limits: Limits::new(),
+            html_options: HtmlExportOptions::new(),
+            #[cfg(feature = "compression")]
+            archive_entry: None,
+            #[cfg(feature = "compression")]
+            compress_output: false,
+            transforms: Pipeline::new().with(Builtin::DetectTitlePage),
+            diagnostics: vec![],
+            stats: Stats::default(),
+        }
+    }
+
+    /// Sets the options for [`InputFormat::Stendhal`].
+    #[must_use]
+    pub fn with_stendhal_options(mut self, options: StendhalTokenizeOptions) -> Self {
+        self.stendhal_options = options;
+        self
+    }
+
+    /// Sets the options for [`OutputFormat::Html`].
+    ///
+    /// The language and direction are replaced by those of each work, where its metadata names
+    /// them, see [`HtmlExportOptions::with_work_language`].
+    #[must_use]
+    pub fn with_html_options(mut self, options: HtmlExportOptions) -> Self {
+        self.html_options = options;
+        self
+    }
+
+    /// Sets the resource limits for every importer.
+    ///
+    /// For [`InputFormat::Stendhal`], these replace the limits of the options set by
+    /// [`Self::with_stendhal_options`], and are replaced by any set after this.
+    #[must_use]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self.stendhal_options = self.stendhal_options.with_limits(limits);
+        self
+    }
+
+    /// Sets which entry [`Self::import`] reads from a zip archive, rather than its first file.
+    #[cfg(feature = "compression")]
+    #[must_use]
+    pub fn with_archive_entry(mut self, entry: impl Into<Box<str>>) -> Self {
+        self.archive_entry = Some(entry.into());
+        self
+    }
+
+    /// Sets whether [`Self::export`] gzip-compresses its output, which is disabled by default.
+    ///
+    /// [`Self::export_to_string`] is never compressed.
+    #[cfg(feature = "compression")]
+    #[must_use]
+    pub const fn with_compressed_output(mut self, compress: bool) -> Self {
+        self.compress_output = compress;
+        self
+    }
+
+    /// Replaces every transform with `pipeline`, including the default
+    /// [`Builtin::DetectTitlePage`].
+    ///
+    /// Use an empty [`Pipeline`] to opt out of title page detection, or one with a configured
+    /// [`TitlePageDetector`][`crate::syntax::title_page::TitlePageDetector`] to change it.
+    #[must_use]
+    pub fn with_pipeline(mut self, pipeline: Pipeline) -> Self {
+        self.transforms = pipeline;
+        self
+    }
+
+    /// Adds a transform, to be applied after any previously added transforms.
+    #[must_use]
+    pub fn with_transform(mut self, transform: impl Transform + Send + Sync + 'static) -> Self {
+        self.transforms.push(transform);
+        self
+    }
+
+    /// Returns everything reported so far, oldest first.
+    #[must_use]
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Removes and returns everything reported so far, oldest first.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Whether or not any [`Severity::Error`] has been reported.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity() == Severity::Error)
+    }
+
+    /// Returns the running totals so far.
+    #[must_use]
+    pub const fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Import a work from a string.
+    ///
+    /// If it fails, the [`Severity::Error`] it reports includes
+    /// [suggestions][`Diagnostic::suggestions`] for fixing `input`, where possible.
+    ///
+    /// # Errors
+    ///
+    /// - [`SessionError::Decompress`] if `input` is a gzip or zip input that cannot be
+    ///   decompressed
+    /// - [`SessionError::Io`] if it cannot read from `input`
+    /// - [`SessionError::Stendhal`] if [`InputFormat::Stendhal`] cannot tokenize `input`
+    /// - [`SessionError::Tellraw`] if [`InputFormat::Tellraw`] cannot tokenize `input`
+    /// - [`SessionError::Nbt`] if [`InputFormat::Nbt`] cannot tokenize `input`
+    /// - [`SessionError::JsonText`] if [`InputFormat::JsonText`] cannot tokenize `input`
+    /// - [`SessionError::Ghostwriter`] if [`InputFormat::Ghostwriter`] cannot tokenize `input`
+    pub fn import_str(
+        &mut self,
+        format: InputFormat,
+        input: &str,
+    ) -> Result<TokenList, SessionError> {
+        let result = self.import(format, input.as_bytes());
+
+        if let Err(SessionError::Stendhal(e)) = &result {
+            let suggestions = Stendhal::suggest_fixes(input, e);
+            if let Some(diagnostic) = self.diagnostics.pop() {
+                self.diagnostics
+                    .push(diagnostic.with_suggestions(suggestions));
+            }
+        }
+
+        result
+    }
+
+    /// Import a work from a reader, like a [`std::fs::File`].
+    ///
+    /// With the `compression` feature, gzip and zip inputs are decompressed first, see
+    /// [`crate::import::decompress`] and [`Self::with_archive_entry`]. Text inputs are then
+    /// decoded, so UTF-8 with a byte order mark and UTF-16 are read like any other UTF-8, see
+    /// [`crate::import::decode`].
+    ///
+    /// # Errors
+    ///
+    /// - [`SessionError::Decompress`] if `input` is a gzip or zip input that cannot be
+    ///   decompressed
+    /// - [`SessionError::Stendhal`] if [`InputFormat::Stendhal`] cannot tokenize `input`
+    /// - [`SessionError::Tellraw`] if [`InputFormat::Tellraw`] cannot tokenize `input`
+    /// - [`SessionError::Nbt`] if [`InputFormat::Nbt`] cannot tokenize `input`
+    /// - [`SessionError::JsonText`] if [`InputFormat::JsonText`] cannot tokenize `input`
+    /// - [`SessionError::Ghostwriter`] if [`InputFormat::Ghostwriter`] cannot tokenize `input`
+    pub fn import(
+        &mut self,
+        format: InputFormat,
+        input: impl Read,
+    ) -> Result<TokenList, SessionError> {
+        #[cfg(feature = "compression")]
+        let input = self.report(
+            Stage::Import,
+            crate::import::decompress(input, self.archive_entry.as_deref())
+                .map_err(SessionError::from),
+        )?;
+
+        let mut input = CountingReader {
+            inner: input,
+            count: 0,
+        };
+        // NBT is binary, everything else is text that might not be UTF-8
+        let mut reader: Box<dyn Read + '_> = match format {
+            InputFormat::Nbt => Box::new(&mut input),
+            _ => Box::new(self.report(
+                Stage::Import,
+                crate::import::decode(&mut input).map_err(SessionError::from),
+            )?),
+        };
+
+        let mut recovered = vec![];
+        let result = match format {
+            InputFormat::Stendhal => {
+                Stendhal::tokenize_reader_detailed(&mut reader, &self.stendhal_options, false)
+                    .map(|tokenized| {
+                        recovered = tokenized.warnings;
+                        (
+                            tokenized.tokens,
+                            tokenized.split_lines,
+                            Some(tokenized.dialect),
+                        )
+                    })
+                    .map_err(SessionError::from)
+            }
+            InputFormat::Tellraw => Tellraw::tokenize_reader_with_limits(&mut reader, &self.limits)
+                .map(|tokens| (tokens, 0, None))
+                .map_err(SessionError::from),
+            InputFormat::Nbt => Nbt::tokenize_reader_with_limits(&mut reader, &self.limits)
+                .map(|tokens| (tokens, 0, None))
+                .map_err(SessionError::from),
+            InputFormat::JsonText => {
+                JsonText::tokenize_reader_with_limits(&mut reader, &self.limits)
+                    .map(|tokens| (tokens, 0, None))
+                    .map_err(SessionError::from)
+            }
+            InputFormat::Ghostwriter => {
+                Ghostwriter::tokenize_reader_with_limits(&mut reader, &self.limits)
+                    .map(|tokens| (tokens, 0, None))
+                    .map_err(SessionError::from)
+            }
+        };
+
+        drop(reader);
+
+        let (tokens, split_lines, dialect) = self.report(Stage::Import, result)?;
+        self.stats.record_import(input.count, &tokens);
+
+        if let Some(dialect) = dialect {
+            self.info(
+                Stage::Import,
+                format!("detected the {dialect} dialect of Stendhal"),
+            );
+        }
+
+        if split_lines > 0 {
+            self.warn(
+                Stage::Import,
+                format!("split {split_lines} line(s) longer than the maximum line length"),
+            );
+        }
+
+        for warning in recovered {
+            self.warn(Stage::Import, format!("dropped a format code on {warning}"));
+        }
+
+        let unknown = diagnostics::unknown_format_codes(&tokens);
+        if unknown > 0 {
+            self.warn(
+                Stage::Import,
+                format!("preserved {unknown} unknown format code(s)"),
+            );
+        }
+
+        Ok(tokens)
+    }
+
+    /// Apply every transform, in the order they were added, starting with
+    /// [`Builtin::DetectTitlePage`] unless it was replaced with [`Self::with_pipeline`].
+    #[must_use]
+    pub fn transform(&mut self, tokens: &TokenList) -> TokenList {
+        self.transforms.apply(tokens.clone())
+    }
+
+    /// Export a work into a string.
+    ///
+    /// [`OutputFormat::Cp437`] is decoded back into Unicode, see [`Cp437::decode`].
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Writing into a `Vec<u8>` is infallible
+    pub fn export_to_string(&mut self, tokens: &TokenList, format: OutputFormat) -> Box<str> {
+        let mut bytes: Vec<u8> = vec![];
+
+        self.export_uncompressed(tokens, format, &mut bytes)
+            .expect("the `std::io::Write` implementations for `Vec<u8>` are infallible");
+
+        if format == OutputFormat::Cp437 {
+            return Cp437::decode(&bytes);
+        }
+
+        String::from_utf8(bytes)
+            .expect("every exporter but CP-437 only writes UTF-8")
+            .into_boxed_str()
+    }
+
+    /// Export a work into a writer, like a [`std::fs::File`].
+    ///
+    /// With [`Self::with_compressed_output`], the output is gzip-compressed, see
+    /// [`crate::export::GzipWriter`].
+    ///
+    /// # Errors
+    ///
+    /// - [`SessionError::Io`] if it cannot write into `output`
+    pub fn export(
+        &mut self,
+        tokens: &TokenList,
+        format: OutputFormat,
+        output: &mut impl Write,
+    ) -> Result<(), SessionError> {
+        #[cfg(feature = "compression")]
+        if self.compress_output {
+            let mut output = crate::export::GzipWriter::new(output);
+            self.export_uncompressed(tokens, format, &mut output)?;
+
+            return self.report(
+                Stage::Export,
+                output.finish().map(|_| ()).map_err(SessionError::from),
+            );
+        }
+
+        self.export_uncompressed(tokens, format, output)
+    }
+
+    /// Export a work into a writer, without compressing it.
+    fn export_uncompressed(
+        &mut self,
+        tokens: &TokenList,
+        format: OutputFormat,
+        output: impl Write,
+    ) -> Result<(), SessionError> {
+        let mut output = CountingWriter {
+            inner: output,
+            count: 0,
+        };
+
+        let dropped = Capabilities::used_by(tokens).missing_from(format.capabilities());
+        if !dropped.is_empty() {
+            self.warn(
+                Stage::Export,
+                format!("the {format} format will drop {dropped}"),
+            );
+        }
+
+        let result = match format {
+            OutputFormat::Html => {
+                let unknown = diagnostics::unknown_format_codes(tokens);
+                if unknown > 0 {
+                    self.warn(
+                        Stage::Export,
+                        format!("dropped {unknown} unknown format code(s)"),
+                    );
+                }
+
+                Html::export_token_vector_to_writer_with_options(
+                    tokens,
+                    &mut output,
+                    &self.html_options.clone().with_work_language(tokens),
+                )
+            }
+            OutputFormat::Text => Text::export_token_vector_to_writer(tokens.clone(), &mut output),
+            OutputFormat::Ssml => Ssml::export_token_vector_to_writer(tokens.clone(), &mut output),
+            OutputFormat::Brf => Brf::export_token_vector_to_writer(tokens.clone(), &mut output),
+            OutputFormat::PlainText => {
+                PlainText::export_token_vector_to_writer(tokens.clone(), &mut output)
+            }
+            OutputFormat::Cp437 => {
+                Cp437::export_token_vector_to_writer(tokens.clone(), &mut output)
+            }
+        };
+
+        self.report(Stage::Export, result.map_err(SessionError::from))?;
+        self.stats.record_export(output.count);
+
+        Ok(())
+    }
+
+    /// Record a [`Severity::Info`].
+    fn info(&mut self, stage: Stage, message: String) {
+        self.diagnostics
+            .push(Diagnostic::new(Severity::Info, stage, message));
+    }
+
+    /// Record a [`Severity::Warning`].
+    fn warn(&mut self, stage: Stage, message: String) {
+        self.diagnostics
+            .push(Diagnostic::new(Severity::Warning, stage, message));
+    }
+
+    /// Record `result` as a [`Severity::Error`] if it is an error, then pass it along.
+    fn report<T>(
+        &mut self,
+        stage: Stage,
+        result: Result<T, SessionError>,
+    ) -> Result<T, SessionError> {
+        if let Err(e) = &result {
+            self.diagnostics
+                .push(Diagnostic::new(Severity::Error, stage, e.to_string()));
+        }
+
+        result
+    }
+}
+
+/// Counts the bytes read through it.
+struct CountingReader<R> {
+    /// The reader to count.
+    inner: R,
+    /// How many bytes have been read so far.
+    count: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read;
+        Ok(read)
+    }
+}
@@ -0,0 +1,393 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for [`Session`].
+
+use super::{Session, SessionError, Severity, Stage};
+use crate::{
+    export::OutputFormat,
+    format::{ghostwriter, json_text, tellraw},
+    import::{InputFormat, Limit, Limits, StendhalTokenizeOptions},
+    syntax::{
+        title_page::TitlePageDetector,
+        transform::{Pipeline, StripColors},
+        Metadata, Token, TokenList,
+    },
+};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+const INPUT: &str = "title: crafty_novels
+author: RemasteredArch
+pages:
+#- Page §zone
+#- Page two";
+
+#[test]
+fn session_pipeline() -> Result {
+    let mut session = Session::new()
+        .with_stendhal_options(
+            StendhalTokenizeOptions::new().with_preserve_unknown_format_codes(true),
+        )
+        .with_transform(|tokens: TokenList| {
+            tokens.map_metadata(|metadata| match metadata {
+                Metadata::Author(_) => Metadata::Author("Jaxydog".into()),
+                other => other.clone(),
+            })
+        })
+        .with_transform(|mut tokens: TokenList| {
+            tokens.push_page([Token::Text("Appendix".into())]);
+            tokens
+        });
+
+    let tokens = session.import_str(InputFormat::Stendhal, INPUT)?;
+    let tokens = session.transform(&tokens);
+    let html = session.export_to_string(&tokens, OutputFormat::Html);
+
+    assert!(html.contains(r#"<meta name="author" content="Jaxydog" />"#));
+    assert!(html.ends_with("<hr />Appendix</article></body></html>"));
+
+    let stats = session.stats();
+    assert_eq!(stats.documents_imported(), 1);
+    assert_eq!(stats.documents_exported(), 1);
+    assert_eq!(stats.bytes_imported(), INPUT.len());
+    assert_eq!(stats.bytes_exported(), html.len());
+    assert_eq!(stats.pages_imported(), 2);
+
+    let stages: Vec<_> = session
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| (diagnostic.severity(), diagnostic.stage()))
+        .collect();
+    assert_eq!(
+        stages,
+        [
+            (Severity::Info, Stage::Import),
+            (Severity::Warning, Stage::Import),
+            (Severity::Warning, Stage::Export),
+        ]
+    );
+    assert!(!session.has_errors());
+
+    Ok(())
+}
+
+#[test]
+fn session_errors() {
+    let mut session = Session::new().with_limits(Limits::new().with_max_tokens(2));
+
+    assert!(matches!(
+        session.import_str(InputFormat::Stendhal, INPUT),
+        Err(SessionError::Stendhal(_))
+    ));
+    assert!(matches!(
+        session.import_str(InputFormat::Stendhal, "not stendhal"),
+        Err(SessionError::Stendhal(_))
+    ));
+
+    assert!(session.has_errors());
+    assert_eq!(
+        session.diagnostics()[0].suggestions()[0].message(),
+        "remove the unknown format code `§z`"
+    );
+    assert_eq!(
+        session.diagnostics()[1].suggestions()[0].message(),
+        "insert a `title:` line"
+    );
+    assert_eq!(session.take_diagnostics().len(), 2);
+    assert!(session.diagnostics().is_empty());
+    assert_eq!(session.stats().documents_imported(), 0);
+}
+
+#[test]
+fn session_lenient() -> Result {
+    let mut session =
+        Session::new().with_stendhal_options(StendhalTokenizeOptions::new().with_lenient(true));
+
+    session.import_str(InputFormat::Stendhal, INPUT)?;
+
+    assert!(!session.has_errors());
+    assert!(session.diagnostics().iter().any(|diagnostic| {
+        diagnostic.severity() == Severity::Warning
+            && diagnostic.message()
+                == "dropped a format code on page 1, line 1: no such format code 'z'"
+    }));
+
+    Ok(())
+}
+
+#[test]
+fn session_title_page() -> Result {
+    let input = "title: crafty_novels
+author: RemasteredArch
+pages:
+#- crafty_novels
+#- Page one";
+
+    let mut session = Session::new();
+    let tokens = session.import_str(InputFormat::Stendhal, input)?;
+    assert_eq!(
+        session.transform(&tokens).tokens_as_slice(),
+        [
+            Token::ThematicBreak,
+            Token::Text("Page".into()),
+            Token::Space,
+            Token::Text("one".into()),
+            Token::LineBreak,
+        ]
+    );
+
+    // Later transforms run after the title page is detected
+    let mut session = Session::new().with_transform(StripColors);
+    assert_eq!(session.transform(&tokens).page_count(), 1);
+
+    let mut session = Session::new().with_pipeline(Pipeline::new());
+    assert_eq!(session.transform(&tokens), tokens);
+
+    let mut session = Session::new()
+        .with_pipeline(Pipeline::new().with(TitlePageDetector::new().with_enabled(false)));
+    assert_eq!(session.transform(&tokens), tokens);
+
+    Ok(())
+}
+
+#[test]
+fn conversion() -> Result {
+    use super::Conversion;
+    use crate::export::{HtmlExportOptions, MetadataFormatCodes};
+
+    let conversion = Conversion::builder()
+        .to(OutputFormat::Html)
+        .with_html_options(
+            HtmlExportOptions::new().with_metadata_format_codes(MetadataFormatCodes::Render),
+        )
+        .from(InputFormat::Stendhal)
+        .with_stendhal_options(
+            StendhalTokenizeOptions::new().with_preserve_unknown_format_codes(true),
+        )
+        .build();
+    assert_eq!(conversion.from(), InputFormat::Stendhal);
+    assert_eq!(conversion.to(), OutputFormat::Html);
+
+    let mut output = vec![];
+    conversion.convert(INPUT.as_bytes(), &mut output)?;
+
+    assert_eq!(String::from_utf8(output)?, *conversion.convert_str(INPUT)?);
+    assert!(conversion
+        .convert_str(INPUT)?
+        .contains(r#"<h1 id="crafty-novels">crafty_novels</h1>"#));
+    assert!(matches!(
+        conversion.convert_str("not stendhal"),
+        Err(SessionError::Stendhal(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn session_stendhal_dialect() -> Result {
+    use crate::import::StendhalDialect;
+
+    let extended = "title: crafty_novels
+author: RemasteredArch
+description: A book
+pages:
+#- Page one";
+
+    let mut session = Session::new();
+    session.import_str(InputFormat::Stendhal, extended)?;
+    assert_eq!(
+        session.diagnostics()[0].to_string(),
+        "info (import): detected the extended dialect of Stendhal"
+    );
+
+    let mut session = Session::new().with_stendhal_options(
+        StendhalTokenizeOptions::new().with_dialect(Some(StendhalDialect::Classic)),
+    );
+    assert!(matches!(
+        session.import_str(InputFormat::Stendhal, extended),
+        Err(SessionError::Stendhal(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn session_capabilities() {
+    use crate::{
+        export::Capabilities,
+        syntax::{
+            minecraft::{Color, Format},
+            TokenList,
+        },
+    };
+
+    let plain = TokenList::from(vec![Token::Text("gold".into())]);
+    let colored = TokenList::from(vec![
+        Token::Format(Format::Color(Color::Gold)),
+        Token::Text("gold".into()),
+    ]);
+    let image = TokenList::from(vec![Token::Image {
+        src: "gold.png".into(),
+        alt: "gold".into(),
+    }]);
+
+    // Each format should behave as its capabilities declare
+    for format in OutputFormat::ALL.iter().copied() {
+        let capabilities = format.capabilities();
+
+        for (feature, tokens) in [
+            (Capabilities::COLOR, &colored),
+            (Capabilities::IMAGES, &image),
+        ] {
+            let mut session = Session::new();
+            let output = session.export_to_string(tokens, format);
+            let expected = session.export_to_string(&plain, format);
+
+            assert_eq!(
+                output == expected,
+                !capabilities.contains(feature),
+                "{format} {feature}"
+            );
+            assert_eq!(
+                session
+                    .diagnostics()
+                    .iter()
+                    .any(|diagnostic| diagnostic.message().contains(&format!("drop {feature}"))),
+                !capabilities.contains(feature),
+                "{format} {feature}"
+            );
+        }
+    }
+}
+
+#[test]
+fn session_limits() {
+    const TELLRAW: &str = r#"tellraw @a "one"
+tellraw @a "two"
+"#;
+    const JSON_TEXT: &str = r#""one" "two""#;
+    const GHOSTWRITER: &str = "title: crafty_novels\nauthor: RemasteredArch\n\n##PAGE##\none two\n";
+
+    let mut session =
+        Session::new().with_limits(Limits::new().with_max_input_size(TELLRAW.len() - 1));
+    assert!(matches!(
+        session.import_str(InputFormat::Tellraw, TELLRAW),
+        Err(SessionError::Tellraw(
+            tellraw::TokenizeError::LimitExceeded(Limit::InputSize(_))
+        ))
+    ));
+
+    let mut session = Session::new().with_limits(Limits::new().with_max_tokens(1));
+    assert!(matches!(
+        session.import_str(InputFormat::JsonText, JSON_TEXT),
+        Err(SessionError::JsonText(
+            json_text::TokenizeError::LimitExceeded(Limit::Tokens(1))
+        ))
+    ));
+    assert!(matches!(
+        session.import_str(InputFormat::Ghostwriter, GHOSTWRITER),
+        Err(SessionError::Ghostwriter(
+            ghostwriter::TokenizeError::LimitExceeded(Limit::Tokens(1))
+        ))
+    ));
+}
+
+#[test]
+fn session_import_bom() -> Result {
+    let input = "title: crafty_novels
+author: RemasteredArch
+pages:
+#- Page one";
+
+    let mut session = Session::new();
+    let expected = session.import_str(InputFormat::Stendhal, input)?;
+
+    let mut utf_8 = b"\xEF\xBB\xBF".to_vec();
+    utf_8.extend_from_slice(input.as_bytes());
+    assert_eq!(
+        session.import(InputFormat::Stendhal, utf_8.as_slice())?,
+        expected
+    );
+
+    let mut utf_16 = b"\xFF\xFE".to_vec();
+    utf_16.extend(input.encode_utf16().flat_map(u16::to_le_bytes));
+    assert_eq!(
+        session.import(InputFormat::Stendhal, utf_16.as_slice())?,
+        expected
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn session_import_gzip() -> Result {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let input = "title: crafty_novels
+author: RemasteredArch
+pages:
+#- Page one";
+
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(input.as_bytes())?;
+    let gzip = encoder.finish()?;
+
+    let mut session = Session::new();
+    let tokens = session.import(InputFormat::Stendhal, gzip.as_slice())?;
+
+    assert_eq!(tokens, session.import_str(InputFormat::Stendhal, input)?);
+    // Counts the decompressed bytes
+    assert_eq!(session.stats().bytes_imported(), input.len() * 2);
+
+    let mut session = Session::new().with_archive_entry("book.stendhal");
+    assert!(matches!(
+        session.import(InputFormat::Stendhal, b"PK\x03\x04".as_slice()),
+        Err(SessionError::Decompress(_))
+    ));
+    assert_eq!(session.diagnostics()[0].stage(), Stage::Import);
+
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn session_export_gzip() -> Result {
+    use std::io::Read;
+
+    let mut session = Session::new().with_compressed_output(true);
+    let tokens = session.import_str(
+        InputFormat::Stendhal,
+        "title: T\nauthor: A\npages:\n#- Page one",
+    )?;
+
+    let mut gzip = vec![];
+    session.export(&tokens, OutputFormat::PlainText, &mut gzip)?;
+
+    let mut text = String::new();
+    crate::import::decompress(gzip.as_slice(), None)?.read_to_string(&mut text)?;
+    assert_eq!(text, "Page one\n");
+    // Strings are never compressed
+    assert_eq!(
+        &*session.export_to_string(&tokens, OutputFormat::PlainText),
+        "Page one\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Aligning lines of text, and finding lines that were aligned by hand.
+//!
+//! See [`Alignment`] and [`CenterDetector`].
+
+use super::{Token, TokenList};
+use std::sync::Arc;
+
+/// How a line of text is aligned, see [`Token::Align`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    /// Centered between the edges of the page, like a title.
+    Center,
+}
+
+impl Alignment {
+    /// Returns the value of the CSS `text-align` property for this alignment.
+    #[must_use]
+    pub const fn css(self) -> &'static str {
+        match self {
+            Self::Center => "center",
+        }
+    }
+
+    /// Returns how much of the `free` space left on a line goes before its text.
+    #[must_use]
+    pub const fn offset(self, free: usize) -> usize {
+        match self {
+            Self::Center => free / 2,
+        }
+    }
+}
+
+/// Finds lines that were centered by hand, with about as many spaces on either side, and replaces
+/// their padding with a [`Token::Align`], so that exports that reflow the text keep them centered.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     align::{Alignment, CenterDetector},
+///     Token, TokenList,
+/// };
+///
+/// let tokens = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::Space,
+///         Token::Space,
+///         Token::Space,
+///         Token::Text("Title".into()),
+///         Token::Space,
+///         Token::Space,
+///         Token::LineBreak,
+///     ]),
+/// );
+///
+/// assert_eq!(
+///     CenterDetector::new().apply(&tokens).tokens_as_slice(),
+///     [
+///         Token::Align(Alignment::Center),
+///         Token::Text("Title".into()),
+///         Token::LineBreak,
+///     ]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CenterDetector {
+    /// The fewest spaces on either side of a centered line.
+    min_padding: usize,
+    /// How many more spaces one side of a centered line can have than the other.
+    tolerance: usize,
+}
+
+impl CenterDetector {
+    /// The default for [`Self::min_padding`].
+    pub const DEFAULT_MIN_PADDING: usize = 2;
+    /// The default for [`Self::tolerance`].
+    pub const DEFAULT_TOLERANCE: usize = 1;
+
+    /// Creates a new [`CenterDetector`] with the default options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            min_padding: Self::DEFAULT_MIN_PADDING,
+            tolerance: Self::DEFAULT_TOLERANCE,
+        }
+    }
+
+    /// Returns the fewest spaces on either side of a centered line.
+    #[must_use]
+    pub const fn min_padding(&self) -> usize {
+        self.min_padding
+    }
+
+    /// Sets the fewest spaces on either side of a centered line.
+    ///
+    /// Lines with less padding are more likely to be indented than centered.
+    #[must_use]
+    pub const fn with_min_padding(mut self, min_padding: usize) -> Self {
+        self.min_padding = min_padding;
+        self
+    }
+
+    /// Returns how many more spaces one side of a centered line can have than the other.
+    #[must_use]
+    pub const fn tolerance(&self) -> usize {
+        self.tolerance
+    }
+
+    /// Sets how many more spaces one side of a centered line can have than the other.
+    ///
+    /// Text with an odd number of spaces to spare can't be centered exactly.
+    #[must_use]
+    pub const fn with_tolerance(mut self, tolerance: usize) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Returns a copy of `tokens` with each centered line starting with a [`Token::Align`]
+    /// instead of its padding.
+    ///
+    /// Format codes within the padding are kept, and lines that are already aligned are left
+    /// as-is.
+    #[must_use]
+    pub fn apply(&self, tokens: &TokenList) -> TokenList {
+        let tokens_slice = tokens.tokens_as_slice();
+        let mut output: Vec<Token> = Vec::with_capacity(tokens_slice.len());
+
+        let mut rest = tokens_slice;
+        while !rest.is_empty() {
+            let end = rest
+                .iter()
+                .position(|token| {
+                    matches!(
+                        token,
+                        Token::LineBreak | Token::ParagraphBreak | Token::ThematicBreak
+                    )
+                })
+                .map_or(rest.len(), |index| index + 1);
+            let (line, after) = rest.split_at(end);
+            self.push_line(&mut output, line);
+            rest = after;
+        }
+
+        TokenList::new(tokens.metadata(), Arc::from(output))
+    }
+
+    /// Push `line`, which ends with its break, if any, into `output`, centered if it was padded
+    /// to be.
+    fn push_line(&self, output: &mut Vec<Token>, line: &[Token]) {
+        let is_content = |token: &Token| token.is_text() || token.is_image();
+        let (Some(first), Some(last)) = (
+            line.iter().position(is_content),
+            line.iter().rposition(is_content),
+        ) else {
+            output.extend_from_slice(line);
+            return;
+        };
+
+        let spaces = |tokens: &[Token]| {
+            tokens
+                .iter()
+                .filter(|token| **token == Token::Space)
+                .count()
+        };
+        let leading = spaces(&line[..first]);
+        let trailing = spaces(&line[last..]);
+
+        let centered = leading >= self.min_padding
+            && trailing >= self.min_padding
+            && leading.abs_diff(trailing) <= self.tolerance
+            && !line.iter().any(|token| matches!(token, Token::Align(_)));
+        if !centered {
+            output.extend_from_slice(line);
+            return;
+        }
+
+        output.push(Token::Align(Alignment::Center));
+        for (index, token) in line.iter().enumerate() {
+            if *token == Token::Space && (index < first || index > last) {
+                continue;
+            }
+            output.push(token.clone());
+        }
+    }
+}
+
+impl Default for CenterDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,299 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reporting how heavily a work is formatted.
+//!
+//! See [`Analysis`].
+
+use super::{minecraft::FormatState, Token};
+use std::{fmt::Display, ops::AddAssign};
+
+/// How many characters of some text have each kind of formatting.
+///
+/// Only the characters of [`Token::Text`] are counted, not white space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FormattingCounts {
+    /// Every character.
+    characters: usize,
+    /// Characters with any formatting at all.
+    formatted: usize,
+    /// Characters with a color.
+    colored: usize,
+    /// Obfuscated characters.
+    obfuscated: usize,
+    /// Bold characters.
+    bold: usize,
+    /// Struck through characters.
+    strikethrough: usize,
+    /// Underlined characters.
+    underline: usize,
+    /// Italic characters.
+    italic: usize,
+    /// [`Token::Format`]s, including [`Format::Reset`][`super::minecraft::Format::Reset`].
+    format_codes: usize,
+}
+
+impl FormattingCounts {
+    /// Returns how many characters there are.
+    #[must_use]
+    pub const fn characters(&self) -> usize {
+        self.characters
+    }
+
+    /// Returns how many characters have any formatting at all.
+    #[must_use]
+    pub const fn formatted(&self) -> usize {
+        self.formatted
+    }
+
+    /// Returns how many characters have a color.
+    #[must_use]
+    pub const fn colored(&self) -> usize {
+        self.colored
+    }
+
+    /// Returns how many characters are obfuscated.
+    #[must_use]
+    pub const fn obfuscated(&self) -> usize {
+        self.obfuscated
+    }
+
+    /// Returns how many characters are bold.
+    #[must_use]
+    pub const fn bold(&self) -> usize {
+        self.bold
+    }
+
+    /// Returns how many characters are struck through.
+    #[must_use]
+    pub const fn strikethrough(&self) -> usize {
+        self.strikethrough
+    }
+
+    /// Returns how many characters are underlined.
+    #[must_use]
+    pub const fn underline(&self) -> usize {
+        self.underline
+    }
+
+    /// Returns how many characters are italic.
+    #[must_use]
+    pub const fn italic(&self) -> usize {
+        self.italic
+    }
+
+    /// Returns how many format codes there are, including [`Format::Reset`][`super::minecraft::Format::Reset`].
+    #[must_use]
+    pub const fn format_codes(&self) -> usize {
+        self.format_codes
+    }
+
+    /// Returns the share of characters that have any formatting, from `0.0` to `1.0`.
+    ///
+    /// Text without any characters has a density of `0.0`.
+    #[must_use]
+    pub fn density(&self) -> f64 {
+        share(self.formatted, self.characters)
+    }
+
+    /// Count `count` characters of text with the formatting `state`.
+    const fn record_text(&mut self, count: usize, state: FormatState) {
+        self.characters += count;
+
+        if !state.is_plain() {
+            self.formatted += count;
+        }
+        if state.color().is_some() {
+            self.colored += count;
+        }
+        if state.obfuscated() {
+            self.obfuscated += count;
+        }
+        if state.bold() {
+            self.bold += count;
+        }
+        if state.strikethrough() {
+            self.strikethrough += count;
+        }
+        if state.underline() {
+            self.underline += count;
+        }
+        if state.italic() {
+            self.italic += count;
+        }
+    }
+}
+
+/// How heavily a work is formatted, overall and page by page.
+///
+/// Useful for authors to see where formatting is overused. Pages are separated by
+/// [`Token::ThematicBreak`], and, like in Minecraft, formatting does not carry over from one page to
+/// the next.
+///
+/// The [`Display`] implementation writes a human-readable report.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{minecraft::Format, Token, TokenList};
+///
+/// let tokens = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::ThematicBreak,
+///         Token::Text("plain".into()),
+///         Token::Format(Format::Italic),
+///         Token::Text("quiet".into()),
+///         Token::ThematicBreak,
+///         Token::Text("plain".into()),
+///     ]),
+/// );
+/// let analysis = tokens.analyze();
+///
+/// assert_eq!(analysis.total().characters(), 15);
+/// assert_eq!(analysis.total().italic(), 5);
+/// assert_eq!(analysis.pages()[0].density(), 0.5);
+/// assert_eq!(analysis.pages()[1].density(), 0.0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Analysis {
+    /// The counts for the whole work.
+    total: FormattingCounts,
+    /// The counts for each page, in order.
+    pages: Box<[FormattingCounts]>,
+}
+
+impl AddAssign for FormattingCounts {
+    /// Add the counts of `rhs` to these, like to total the counts of several works.
+    fn add_assign(&mut self, rhs: Self) {
+        self.characters += rhs.characters;
+        self.formatted += rhs.formatted;
+        self.colored += rhs.colored;
+        self.obfuscated += rhs.obfuscated;
+        self.bold += rhs.bold;
+        self.strikethrough += rhs.strikethrough;
+        self.underline += rhs.underline;
+        self.italic += rhs.italic;
+        self.format_codes += rhs.format_codes;
+    }
+}
+
+impl Analysis {
+    /// Analyze the formatting of `tokens`.
+    #[must_use]
+    pub fn new(tokens: &[Token]) -> Self {
+        let mut total = FormattingCounts::default();
+        let mut pages: Vec<FormattingCounts> = vec![];
+        let mut state = FormatState::new();
+
+        for token in tokens {
+            if *token == Token::ThematicBreak {
+                pages.push(FormattingCounts::default());
+                state = FormatState::new();
+                continue;
+            }
+
+            // Content before the first page break is its own page
+            if pages.is_empty() {
+                pages.push(FormattingCounts::default());
+            }
+            let Some(page) = pages.last_mut() else {
+                unreachable!("a page was just pushed");
+            };
+
+            match token {
+                Token::Text(text) => {
+                    let count = text.chars().count();
+                    total.record_text(count, state);
+                    page.record_text(count, state);
+                }
+                Token::Format(format) => {
+                    total.format_codes += 1;
+                    page.format_codes += 1;
+                    state.apply(*format);
+                }
+                _ => (),
+            }
+        }
+
+        Self {
+            total,
+            pages: pages.into(),
+        }
+    }
+
+    /// Returns the counts for the whole work.
+    #[must_use]
+    pub const fn total(&self) -> FormattingCounts {
+        self.total
+    }
+
+    /// Returns the counts for each page, in order.
+    #[must_use]
+    pub const fn pages(&self) -> &[FormattingCounts] {
+        &self.pages
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = &self.total;
+        writeln!(f, "Characters: {}", total.characters)?;
+
+        for (name, count) in [
+            ("Formatted", total.formatted),
+            ("Colored", total.colored),
+            ("Obfuscated", total.obfuscated),
+            ("Bold", total.bold),
+            ("Strikethrough", total.strikethrough),
+            ("Underline", total.underline),
+            ("Italic", total.italic),
+        ] {
+            writeln!(
+                f,
+                "{name}: {count} ({:.1}%)",
+                share(count, total.characters) * 100.0
+            )?;
+        }
+
+        writeln!(f, "Format codes: {}", total.format_codes)?;
+        write!(f, "Pages:")?;
+
+        for (index, page) in self.pages.iter().enumerate() {
+            write!(
+                f,
+                "\n  {}: {} characters, {:.1}% formatted, {} format codes",
+                index + 1,
+                page.characters,
+                page.density() * 100.0,
+                page.format_codes
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `part` as a share of `whole`, or `0.0` if `whole` is zero.
+#[allow(clippy::cast_precision_loss)] // Books are nowhere near long enough to lose precision
+fn share(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Editorial notes attached to ranges of a work, without altering its text.
+//!
+//! See [`Annotations`].
+
+use serde_json::{json, Value};
+use std::ops::Range;
+
+/// A note attached to a range of [`Token`][`super::Token`]s in a [`super::TokenList`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Annotation {
+    /// The index of the first token in the range.
+    start: usize,
+    /// The index after the last token in the range.
+    end: usize,
+    /// The text of the note.
+    note: Box<str>,
+}
+
+impl Annotation {
+    /// Creates a new [`Annotation`] on the tokens in `range`.
+    ///
+    /// A range that ends before it starts is treated as empty, which annotates the point between
+    /// two tokens.
+    #[must_use]
+    pub fn new(range: Range<usize>, note: impl Into<Box<str>>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end.max(range.start),
+            note: note.into(),
+        }
+    }
+
+    /// Returns the index of the first token in the range.
+    #[must_use]
+    pub const fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the index after the last token in the range.
+    #[must_use]
+    pub const fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the range of token indices that the note is attached to.
+    #[must_use]
+    pub const fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the text of the note.
+    #[must_use]
+    pub const fn note(&self) -> &str {
+        &self.note
+    }
+}
+
+/// Returned when [`Annotations::from_json`] cannot parse its input.
+#[allow(clippy::module_name_repetitions)] // This will be re-exported outside of this module
+#[derive(thiserror::Error, Debug)]
+pub enum AnnotationsError {
+    /// Encountered when the input is not valid JSON.
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Encountered when the input is not an array.
+    #[error("expected an array of annotations")]
+    NotAnArray,
+    /// Encountered when an entry is not an object with a `start`, `end`, and `note`, or ends before
+    /// it starts.
+    #[error("annotation {0} should be an object with a `start`, `end`, and `note`")]
+    InvalidAnnotation(usize),
+}
+
+/// A layer of [`Annotation`]s, kept alongside a [`super::TokenList`] rather than inside of it.
+///
+/// Annotations are ordered by where they start, then outermost first, which is the order they
+/// open in when rendered. Indices refer to [`super::TokenList::tokens_as_slice`], so any edit
+/// that moves tokens around should be followed by new annotations.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::annotations::{Annotation, Annotations};
+///
+/// let annotations = Annotations::new()
+///     .with(Annotation::new(2..4, "a later hand"))
+///     .with(Annotation::new(0..6, "smudged"));
+///
+/// assert_eq!(annotations.iter().next().map(Annotation::note), Some("smudged"));
+///
+/// let json = annotations.to_json();
+/// assert_eq!(
+///     json,
+///     r#"[{"end":6,"note":"smudged","start":0},{"end":4,"note":"a later hand","start":2}]"#
+/// );
+/// assert_eq!(Annotations::from_json(&json)?, annotations);
+/// # Ok::<(), crafty_novels::syntax::annotations::AnnotationsError>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Annotations {
+    /// Every annotation, sorted by [`Annotations::order`].
+    annotations: Vec<Annotation>,
+}
+
+impl Annotations {
+    /// Creates a new, empty [`Annotations`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            annotations: vec![],
+        }
+    }
+
+    /// Adds an [`Annotation`].
+    pub fn add(&mut self, annotation: Annotation) {
+        let index = self
+            .annotations
+            .partition_point(|other| Self::order(other) <= Self::order(&annotation));
+        self.annotations.insert(index, annotation);
+    }
+
+    /// Returns these annotations, with `annotation` added.
+    #[must_use]
+    pub fn with(mut self, annotation: Annotation) -> Self {
+        self.add(annotation);
+        self
+    }
+
+    /// Returns an iterator over every [`Annotation`], in the order they open.
+    pub fn iter(&self) -> std::slice::Iter<'_, Annotation> {
+        self.annotations.iter()
+    }
+
+    /// Returns the number of annotations.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    /// Whether or not there are no annotations.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    /// Returns the annotations whose range includes the token at `index`.
+    pub fn at(&self, index: usize) -> impl Iterator<Item = &Annotation> {
+        self.iter()
+            .filter(move |annotation| annotation.range().contains(&index))
+    }
+
+    /// Serialize the annotations into a JSON array of objects with a `start`, `end`, and `note`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        Value::Array(
+            self.iter()
+                .map(|annotation| {
+                    json!({
+                        "start": annotation.start(),
+                        "end": annotation.end(),
+                        "note": annotation.note(),
+                    })
+                })
+                .collect(),
+        )
+        .to_string()
+    }
+
+    /// Parse annotations from JSON, as written by [`Self::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// - [`AnnotationsError::Json`] if `json` is not valid JSON
+    /// - [`AnnotationsError::NotAnArray`] if `json` is not an array
+    /// - [`AnnotationsError::InvalidAnnotation`] if an entry is missing a field, has a field of the
+    ///   wrong type, or ends before it starts
+    pub fn from_json(json: &str) -> Result<Self, AnnotationsError> {
+        let Value::Array(entries) = serde_json::from_str(json)? else {
+            return Err(AnnotationsError::NotAnArray);
+        };
+
+        entries
+            .iter()
+            .enumerate()
+            .try_fold(Self::new(), |annotations, (index, entry)| {
+                let field = |key| entry.get(key).and_then(Value::as_u64);
+                let (Some(start), Some(end), Some(note)) = (
+                    field("start").and_then(|start| usize::try_from(start).ok()),
+                    field("end").and_then(|end| usize::try_from(end).ok()),
+                    entry.get("note").and_then(Value::as_str),
+                ) else {
+                    return Err(AnnotationsError::InvalidAnnotation(index));
+                };
+                if end < start {
+                    return Err(AnnotationsError::InvalidAnnotation(index));
+                }
+
+                Ok(annotations.with(Annotation::new(start..end, note)))
+            })
+    }
+
+    /// The key that annotations are sorted by: by start, then outermost first.
+    const fn order(annotation: &Annotation) -> (usize, std::cmp::Reverse<usize>) {
+        (annotation.start, std::cmp::Reverse(annotation.end))
+    }
+}
+
+impl<'a> IntoIterator for &'a Annotations {
+    type Item = &'a Annotation;
+    type IntoIter = std::slice::Iter<'a, Annotation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Composing formatted text in Rust, rather than by hand-writing [`Token`]s.
+//!
+//! See [`TextBuilder`].
+
+use super::{
+    minecraft::{Color, Format, FormatState},
+    Metadata, Token, TokenList,
+};
+
+/// Builds a [`TokenList`] one piece of text or formatting at a time.
+///
+/// Formatting applies to all of the text after it, until [`Self::reset`], like format codes in
+/// Minecraft. [`Self::build`] resets any formatting that is still active, so the result is always
+/// closed correctly.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     minecraft::{Color, Format},
+///     Metadata, TextBuilder, Token,
+/// };
+///
+/// let tokens = TextBuilder::new()
+///     .title("Lore")
+///     .text("Hello ")
+///     .color(Color::Red)
+///     .bold()
+///     .text("world")
+///     .reset()
+///     .build();
+///
+/// assert_eq!(tokens.metadata_as_slice(), [Metadata::Title("Lore".into())]);
+/// assert_eq!(
+///     tokens.tokens_as_slice(),
+///     [
+///         Token::Text("Hello".into()),
+///         Token::Space,
+///         Token::Format(Format::Color(Color::Red)),
+///         Token::Format(Format::Bold),
+///         Token::Text("world".into()),
+///         Token::Format(Format::Reset),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextBuilder {
+    /// The metadata added so far.
+    metadata: Vec<Metadata>,
+    /// The tokens added so far.
+    tokens: Vec<Token>,
+    /// The formatting that applies to the next text.
+    state: FormatState,
+}
+
+impl TextBuilder {
+    /// Creates a new, empty [`TextBuilder`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            metadata: vec![],
+            tokens: vec![],
+            state: FormatState::new(),
+        }
+    }
+
+    /// Adds `text`, splitting it into words, [`Token::Space`]s, and [`Token::LineBreak`]s.
+    ///
+    /// Format codes inside of `text` are not parsed, so `'§'` is kept as-is.
+    #[must_use]
+    pub fn text(mut self, text: &str) -> Self {
+        let mut word_start = 0;
+
+        for (index, char) in text.char_indices() {
+            let token = match char {
+                ' ' => Token::Space,
+                '\n' => Token::LineBreak,
+                _ => continue,
+            };
+
+            self.push_word(&text[word_start..index]);
+            self.tokens.push(token);
+            word_start = index + char.len_utf8();
+        }
+        self.push_word(&text[word_start..]);
+
+        self
+    }
+
+    /// Adds a [`Format`], which applies until [`Self::reset`].
+    #[must_use]
+    pub fn format(mut self, format: Format) -> Self {
+        self.state.apply(format);
+        self.tokens.push(Token::Format(format));
+        self
+    }
+
+    /// Colors the following text, replacing any previous color.
+    #[must_use]
+    pub fn color(self, color: Color) -> Self {
+        self.format(Format::Color(color))
+    }
+
+    /// Obfuscates the following text.
+    #[must_use]
+    pub fn obfuscated(self) -> Self {
+        self.format(Format::Obfuscated)
+    }
+
+    /// Makes the following text bold.
+    #[must_use]
+    pub fn bold(self) -> Self {
+        self.format(Format::Bold)
+    }
+
+    /// Strikes through the following text.
+    #[must_use]
+    pub fn strikethrough(self) -> Self {
+        self.format(Format::Strikethrough)
+    }
+
+    /// Underlines the following text.
+    #[must_use]
+    pub fn underline(self) -> Self {
+        self.format(Format::Underline)
+    }
+
+    /// Makes the following text italic.
+    #[must_use]
+    pub fn italic(self) -> Self {
+        self.format(Format::Italic)
+    }
+
+    /// Removes all formatting from the following text.
+    #[must_use]
+    pub fn reset(self) -> Self {
+        self.format(Format::Reset)
+    }
+
+    /// Adds a [`Token::LineBreak`].
+    #[must_use]
+    pub fn line_break(mut self) -> Self {
+        self.tokens.push(Token::LineBreak);
+        self
+    }
+
+    /// Adds a [`Token::ParagraphBreak`].
+    #[must_use]
+    pub fn paragraph_break(mut self) -> Self {
+        self.tokens.push(Token::ParagraphBreak);
+        self
+    }
+
+    /// Starts a new page, with a [`Token::ThematicBreak`].
+    #[must_use]
+    pub fn page(mut self) -> Self {
+        self.tokens.push(Token::ThematicBreak);
+        self
+    }
+
+    /// Adds a [`Token::Image`], described by `alt` in formats that cannot display it.
+    #[must_use]
+    pub fn image(mut self, src: &str, alt: &str) -> Self {
+        self.tokens.push(Token::Image {
+            src: src.into(),
+            alt: alt.into(),
+        });
+        self
+    }
+
+    /// Adds a [`Metadata`] entry.
+    #[must_use]
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata.push(metadata);
+        self
+    }
+
+    /// Adds a [`Metadata::Title`].
+    #[must_use]
+    pub fn title(self, title: impl Into<Box<str>>) -> Self {
+        self.metadata(Metadata::Title(title.into()))
+    }
+
+    /// Adds a [`Metadata::Author`].
+    #[must_use]
+    pub fn author(self, author: impl Into<Box<str>>) -> Self {
+        self.metadata(Metadata::Author(author.into()))
+    }
+
+    /// Finish building, resetting any formatting that is still active.
+    #[must_use]
+    pub fn build(mut self) -> TokenList {
+        if !self.state.is_plain() {
+            self.tokens.push(Token::Format(Format::Reset));
+        }
+
+        TokenList::new(self.metadata.into(), self.tokens.into())
+    }
+
+    /// Push `word` as a [`Token::Text`], unless it is empty.
+    fn push_word(&mut self, word: &str) {
+        if !word.is_empty() {
+            self.tokens.push(Token::Text(word.into()));
+        }
+    }
+}
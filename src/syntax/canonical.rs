@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! A canonical encoding of works, for hashing and deduplication.
+//!
+//! See [`TokenList::canonical_bytes`].
+
+use super::{
+    minecraft::{strip_format_codes, Format, FormatState},
+    provenance::sha256,
+    styled::{LINE_BREAK, THEMATIC_BREAK},
+    Metadata, Token, TokenList,
+};
+
+/// The start of every canonical encoding, which changes whenever the encoding does.
+const MAGIC: &[u8] = b"crafty_novels canonical 1\n";
+
+impl TokenList {
+    /// Returns a stable encoding of the work that is independent of how it was imported.
+    ///
+    /// Two works encode the same if they have the same title, author, and visible text with the
+    /// same formatting, even if the tokens that make them up differ. Namely:
+    ///
+    /// - Only the first title and author are included, with format codes and surrounding
+    ///   whitespace removed, and any other [`Metadata`] is left out, as it mostly records the
+    ///   tool that exported the work
+    /// - Format codes that don't change the formatting, like repeats, are ignored, as is
+    ///   formatting on line and page breaks
+    /// - Runs of text with the same formatting are joined, however they were split into tokens
+    /// - Formatting never carries over from one page into the next, as in Minecraft
+    /// - Trailing whitespace at the end of each page is ignored, as is whether the work starts
+    ///   with a [`Token::ThematicBreak`]
+    /// - Line breaks and paragraph breaks are the same
+    /// - Images are their alt text
+    ///
+    /// The encoding starts with a versioned header, so a future version that changes the encoding
+    /// will never collide with this one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::{import::Stendhal, syntax::TextBuilder, Tokenize};
+    ///
+    /// let imported = Stendhal::tokenize_string(
+    ///     "title: Book
+    /// author: RemasteredArch
+    /// pages:
+    /// #- §l§lBold§r words
+    /// ",
+    /// )?;
+    /// let built = TextBuilder::new()
+    ///     .title("Book")
+    ///     .author("RemasteredArch")
+    ///     .bold()
+    ///     .text("Bold")
+    ///     .reset()
+    ///     .text(" words")
+    ///     .build();
+    ///
+    /// assert_eq!(imported.canonical_bytes(), built.canonical_bytes());
+    /// assert_eq!(imported.content_hash(), built.content_hash());
+    /// # Ok::<(), crafty_novels::import::StendhalTokenizeError>(())
+    /// ```
+    #[must_use]
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut output = MAGIC.to_vec();
+
+        let first = |matches: fn(&Metadata) -> Option<&str>| {
+            self.metadata
+                .iter()
+                .find_map(matches)
+                .map(|value| strip_format_codes(value).trim().to_owned())
+                .unwrap_or_default()
+        };
+        push_field(
+            &mut output,
+            &first(|data| match data {
+                Metadata::Title(title) => Some(title),
+                _ => None,
+            }),
+        );
+        push_field(
+            &mut output,
+            &first(|data| match data {
+                Metadata::Author(author) => Some(author),
+                _ => None,
+            }),
+        );
+
+        for (state, text) in canonical_runs(&self.page_slices()) {
+            let color = state
+                .color()
+                .map_or(0, |color| char::from(Format::Color(color)) as u8);
+            output.push(color);
+            output.push(state.style().bits());
+            push_field(&mut output, &text);
+        }
+
+        output
+    }
+
+    /// Returns the SHA-256 digest of [`Self::canonical_bytes`], as 64 lowercase hexadecimal digits.
+    ///
+    /// Works with the same hash have the same title, author, and formatted text, so archives can
+    /// use it to find the same book exported by different players or tools.
+    #[must_use]
+    pub fn content_hash(&self) -> String {
+        sha256::hex_digest(&self.canonical_bytes())
+    }
+}
+
+/// Push `text` into `output`, prefixed with its length in bytes as a little-endian [`u64`].
+fn push_field(output: &mut Vec<u8>, text: &str) {
+    output.extend_from_slice(&(text.len() as u64).to_le_bytes());
+    output.extend_from_slice(text.as_bytes());
+}
+
+/// Resolve `pages` into runs of text that share the same formatting, following the rules of
+/// [`TokenList::canonical_bytes`].
+fn canonical_runs(pages: &[&[Token]]) -> Vec<(FormatState, String)> {
+    let mut runs: Vec<(FormatState, String)> = vec![];
+    let mut push = |state: FormatState, text: &str| match runs.last_mut() {
+        Some((last, run)) if *last == state => run.push_str(text),
+        _ if text.is_empty() => (),
+        _ => runs.push((state, text.to_owned())),
+    };
+
+    for (index, page) in pages.iter().enumerate() {
+        if index > 0 {
+            push(FormatState::new(), THEMATIC_BREAK.encode_utf8(&mut [0; 4]));
+        }
+
+        let end = page
+            .iter()
+            .rposition(|token| {
+                !token.is_white_space()
+                    && !matches!(token, Token::Format(_) | Token::Align(_) | Token::Role(_))
+            })
+            .map_or(0, |last| last + 1);
+
+        let mut state = FormatState::new();
+        for token in &page[..end] {
+            match token {
+                Token::Format(format) => state = state.with(*format),
+                Token::Align(_) | Token::Role(_) => (),
+                Token::Text(text) | Token::Image { alt: text, .. } => push(state, text),
+                Token::Space => push(state, " "),
+                Token::LineBreak | Token::ParagraphBreak | Token::ThematicBreak => {
+                    push(FormatState::new(), LINE_BREAK.encode_utf8(&mut [0; 4]));
+                }
+            }
+        }
+    }
+
+    runs
+}
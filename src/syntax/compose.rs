@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Joining several works into one, like the volumes of a series.
+//!
+//! See [`Compose`].
+
+use super::{
+    align::Alignment,
+    minecraft::{tokenize_formatted, Format, FormatState},
+    Metadata, Token, TokenList,
+};
+
+/// What to write between the volumes joined by a [`Compose`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Separator {
+    /// Start each volume on a new page, and nothing else.
+    #[default]
+    PageBreak,
+    /// Start each volume with a page of its own holding its [`Metadata::Title`], centered and
+    /// bold, if it has one.
+    TitlePage,
+}
+
+/// Joins several works into one, like the books of a novel that was too long for one book in
+/// Minecraft.
+///
+/// Each volume starts on a new page, after the [`Separator`], with the formatting left over from
+/// the volume before it reset. The metadata of the volumes is reconciled:
+///
+/// - The title is the one set with [`Self::with_title`], or else the first title of the volumes,
+///   and the other titles are left out
+/// - Every distinct [`Metadata::Author`] is kept, in the order they first appear
+/// - For any other field, only the first value of each key is kept
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     compose::{Compose, Separator},
+///     Metadata, Token, TokenList,
+/// };
+///
+/// let volume = |title: &str, text: &str| {
+///     let mut tokens = TokenList::new_from_boxed(
+///         Box::new([
+///             Metadata::Title(title.into()),
+///             Metadata::Author("RemasteredArch".into()),
+///         ]),
+///         Box::new([]),
+///     );
+///     tokens.push_page([Token::Text(text.into())]);
+///     tokens
+/// };
+///
+/// let series = Compose::new()
+///     .with_title(Some("Saga".into()))
+///     .apply(&[volume("Dawn", "one"), volume("Dusk", "two")]);
+///
+/// assert_eq!(
+///     series.metadata_as_slice(),
+///     [Metadata::Title("Saga".into()), Metadata::Author("RemasteredArch".into())]
+/// );
+/// assert_eq!(series.page_count(), 2);
+///
+/// let series = Compose::new()
+///     .with_separator(Separator::TitlePage)
+///     .apply(&[volume("Dawn", "one"), volume("Dusk", "two")]);
+/// assert_eq!(series.page_count(), 4);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Compose {
+    /// What to write between volumes.
+    separator: Separator,
+    /// The title of the whole work, if not that of the first volume.
+    title: Option<Box<str>>,
+}
+
+impl Compose {
+    /// Creates a new [`Compose`] that starts each volume on a new page and keeps the title of the
+    /// first volume.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            separator: Separator::PageBreak,
+            title: None,
+        }
+    }
+
+    /// Returns what is written between volumes.
+    #[must_use]
+    pub const fn separator(&self) -> Separator {
+        self.separator
+    }
+
+    /// Sets what is written between volumes.
+    #[must_use]
+    pub const fn with_separator(mut self, separator: Separator) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Returns the title of the whole work, if it isn't that of the first volume.
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Sets the title of the whole work, or [`None`] to keep the title of the first volume.
+    #[must_use]
+    pub fn with_title(mut self, title: Option<Box<str>>) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Joins `volumes` into one work, in order.
+    #[must_use]
+    pub fn apply(&self, volumes: &[TokenList]) -> TokenList {
+        let mut tokens: Vec<Token> = vec![];
+        let mut state = FormatState::new();
+
+        for (index, volume) in volumes.iter().enumerate() {
+            if !state.is_plain() {
+                tokens.push(Token::Format(Format::Reset));
+                state = FormatState::new();
+            }
+
+            if self.separator == Separator::TitlePage {
+                if let Some(title) = first_title(volume) {
+                    tokens.push(Token::ThematicBreak);
+                    tokens.push(Token::Align(Alignment::Center));
+                    tokens.push(Token::Format(Format::Bold));
+                    tokens.extend(tokenize_formatted(title));
+                    tokens.push(Token::Format(Format::Reset));
+                }
+            }
+
+            let volume_tokens = volume.tokens_as_slice();
+            let starts_page = volume_tokens.first() == Some(&Token::ThematicBreak);
+            if !starts_page && (index > 0 || !tokens.is_empty()) {
+                tokens.push(Token::ThematicBreak);
+            }
+
+            for token in volume_tokens {
+                if let Token::Format(format) = token {
+                    state.apply(*format);
+                }
+            }
+            tokens.extend_from_slice(volume_tokens);
+        }
+
+        TokenList::new(self.metadata(volumes).into(), tokens.into())
+    }
+
+    /// Reconciles the metadata of `volumes`, see [`Compose`].
+    fn metadata(&self, volumes: &[TokenList]) -> Vec<Metadata> {
+        let mut metadata: Vec<Metadata> = vec![];
+
+        let title = self
+            .title
+            .clone()
+            .or_else(|| volumes.iter().find_map(first_title).map(Into::into));
+        if let Some(title) = title {
+            metadata.push(Metadata::Title(title));
+        }
+
+        for data in volumes.iter().flat_map(TokenList::metadata_as_slice) {
+            let duplicate = match data {
+                Metadata::Title(_) => true,
+                Metadata::Author(_) => metadata.contains(data),
+                _ => metadata.iter().any(|kept| kept.key() == data.key()),
+            };
+
+            if !duplicate {
+                metadata.push(data.clone());
+            }
+        }
+
+        metadata
+    }
+}
+
+/// Returns the first [`Metadata::Title`] of `tokens`, if any.
+fn first_title(tokens: &TokenList) -> Option<&str> {
+    tokens
+        .metadata_as_slice()
+        .iter()
+        .find_map(|data| match data {
+            Metadata::Title(title) => Some(&**title),
+            _ => None,
+        })
+}
@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Statistics across a whole archive of works, for studying them as a dataset.
+//!
+//! See [`Corpus`].
+
+use super::{
+    analysis::FormattingCounts,
+    library::first,
+    minecraft::{Color, ColorValue, FormatState},
+    styled::{to_styled_spans, StyledSpan},
+    Metadata, Token, TokenList,
+};
+use serde_json::{json, Map, Value};
+use std::{collections::BTreeMap, fmt::Write as _};
+
+/// The statistics of one work in a [`Corpus`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkStats {
+    /// The name the work was added under, like the name of its file.
+    name: Box<str>,
+    /// The first [`Metadata::Title`] of the work, without format codes, or an empty string.
+    title: Box<str>,
+    /// The first [`Metadata::Author`] of the work, without format codes, or an empty string.
+    author: Box<str>,
+    /// The number of pages in the work.
+    pages: usize,
+    /// The number of words in the work.
+    words: usize,
+    /// How many characters have each kind of formatting.
+    formatting: FormattingCounts,
+    /// How many characters are written in each color.
+    colors: BTreeMap<Color, usize>,
+}
+
+impl WorkStats {
+    /// Collects the statistics of `tokens`, under `name`.
+    ///
+    /// Words are runs of characters between white space and breaks, however they were split
+    /// into [`Token::Text`]s by format codes. Like in [`super::Analysis`], formatting does not
+    /// carry over from one page to the next.
+    #[must_use]
+    pub fn new(name: impl Into<Box<str>>, tokens: &TokenList) -> Self {
+        let text: String = to_styled_spans(tokens.tokens_as_slice())
+            .iter()
+            .map(StyledSpan::text)
+            .collect();
+
+        Self {
+            name: name.into(),
+            title: first(tokens.metadata_as_slice(), |data| match data {
+                Metadata::Title(title) => Some(title),
+                _ => None,
+            }),
+            author: first(tokens.metadata_as_slice(), |data| match data {
+                Metadata::Author(author) => Some(author),
+                _ => None,
+            }),
+            pages: tokens.page_count(),
+            words: text.split_whitespace().count(),
+            formatting: tokens.analyze().total(),
+            colors: colors(tokens.tokens_as_slice()),
+        }
+    }
+
+    /// Returns the name the work was added under.
+    #[must_use]
+    pub const fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the title of the work, or an empty string if it has none.
+    #[must_use]
+    pub const fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the author of the work, or an empty string if it has none.
+    #[must_use]
+    pub const fn author(&self) -> &str {
+        &self.author
+    }
+
+    /// Returns the number of pages in the work.
+    #[must_use]
+    pub const fn pages(&self) -> usize {
+        self.pages
+    }
+
+    /// Returns the number of words in the work.
+    #[must_use]
+    pub const fn words(&self) -> usize {
+        self.words
+    }
+
+    /// Returns how many characters of the work have each kind of formatting.
+    #[must_use]
+    pub const fn formatting(&self) -> FormattingCounts {
+        self.formatting
+    }
+
+    /// Returns how many characters of the work are written in each color, leaving out colors
+    /// that aren't used.
+    #[must_use]
+    pub const fn colors(&self) -> &BTreeMap<Color, usize> {
+        &self.colors
+    }
+}
+
+/// Statistics collected from many works, which can be written as a CSV or JSON dataset.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{import::Stendhal, syntax::corpus::Corpus, Tokenize};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut corpus = Corpus::new();
+/// corpus.add(
+///     "lore.stendhal",
+///     &Stendhal::tokenize_string("title: Lore\nauthor: Sam\npages:\n#- Once §cupon a time")?,
+/// );
+///
+/// assert_eq!(corpus.words(), 4);
+/// assert_eq!(
+///     corpus.to_csv().lines().nth(1),
+///     Some("lore.stendhal,Lore,Sam,1,4,13,9,9,0,0,0,0,0,2,9"),
+/// );
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Corpus {
+    /// The statistics of each work, in the order they were added.
+    works: Vec<WorkStats>,
+}
+
+impl Corpus {
+    /// The columns of [`Self::to_csv`] before those for each color.
+    pub const CSV_COLUMNS: [&'static str; 14] = [
+        "name",
+        "title",
+        "author",
+        "pages",
+        "words",
+        "characters",
+        "formatted",
+        "colored",
+        "obfuscated",
+        "bold",
+        "strikethrough",
+        "underline",
+        "italic",
+        "format_codes",
+    ];
+
+    /// Creates a new, empty [`Corpus`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { works: vec![] }
+    }
+
+    /// Collects the statistics of `tokens` under `name`, like the name of the file it was read
+    /// from. See [`WorkStats::new`].
+    pub fn add(&mut self, name: impl Into<Box<str>>, tokens: &TokenList) {
+        self.works.push(WorkStats::new(name, tokens));
+    }
+
+    /// Returns the statistics of each work, in the order they were added.
+    #[must_use]
+    pub fn works(&self) -> &[WorkStats] {
+        &self.works
+    }
+
+    /// Returns the number of pages across every work.
+    #[must_use]
+    pub fn pages(&self) -> usize {
+        self.works.iter().map(WorkStats::pages).sum()
+    }
+
+    /// Returns the number of words across every work.
+    #[must_use]
+    pub fn words(&self) -> usize {
+        self.works.iter().map(WorkStats::words).sum()
+    }
+
+    /// Returns how many characters have each kind of formatting across every work.
+    #[must_use]
+    pub fn formatting(&self) -> FormattingCounts {
+        let mut total = FormattingCounts::default();
+        for work in &self.works {
+            total += work.formatting;
+        }
+
+        total
+    }
+
+    /// Returns how many characters are written in each color across every work, leaving out
+    /// colors that aren't used.
+    #[must_use]
+    pub fn colors(&self) -> BTreeMap<Color, usize> {
+        let mut total = BTreeMap::new();
+        for (color, count) in self.works.iter().flat_map(|work| &work.colors) {
+            *total.entry(*color).or_default() += count;
+        }
+
+        total
+    }
+
+    /// Returns the statistics as CSV, with a header row and a row for each work.
+    ///
+    /// The columns are [`Self::CSV_COLUMNS`], then `color_{name}` for each color used anywhere in
+    /// the corpus, like `color_dark_red`, in the order of [`Color::ALL`]. Fields are quoted as in
+    /// RFC 4180 where needed, and rows end with `"\n"`.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let colors: Vec<Color> = self.colors().into_keys().collect();
+        let mut output = Self::CSV_COLUMNS.join(",");
+        for color in &colors {
+            let _ = write!(output, ",color_{}", ColorValue::from(*color).name());
+        }
+        output.push('\n');
+
+        for work in &self.works {
+            let formatting = work.formatting;
+            let fields = [
+                csv_field(&work.name),
+                csv_field(&work.title),
+                csv_field(&work.author),
+            ];
+            let counts = [
+                work.pages,
+                work.words,
+                formatting.characters(),
+                formatting.formatted(),
+                formatting.colored(),
+                formatting.obfuscated(),
+                formatting.bold(),
+                formatting.strikethrough(),
+                formatting.underline(),
+                formatting.italic(),
+                formatting.format_codes(),
+            ]
+            .into_iter()
+            .chain(
+                colors
+                    .iter()
+                    .map(|color| work.colors.get(color).copied().unwrap_or_default()),
+            )
+            .map(|count| count.to_string());
+
+            output.push_str(
+                &fields
+                    .into_iter()
+                    .chain(counts)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Returns the statistics as JSON, with the `"works"` in the order they were added and the
+    /// `"totals"` across all of them.
+    ///
+    /// Each work has the fields of [`Self::CSV_COLUMNS`], and `"colors"`, an object of the number
+    /// of characters in each color that it uses, by name. The totals have the same fields as a
+    /// work, other than `"name"`, `"title"`, and `"author"`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let works: Vec<Value> = self
+            .works
+            .iter()
+            .map(|work| {
+                let mut value =
+                    json!({ "name": work.name, "title": work.title, "author": work.author });
+                if let Value::Object(object) = &mut value {
+                    object.extend(counts_json(
+                        work.pages,
+                        work.words,
+                        work.formatting,
+                        &work.colors,
+                    ));
+                }
+
+                value
+            })
+            .collect();
+        let totals = counts_json(
+            self.pages(),
+            self.words(),
+            self.formatting(),
+            &self.colors(),
+        );
+
+        json!({ "works": works, "totals": totals }).to_string()
+    }
+}
+
+/// Returns the numeric fields of a work or of the totals of a corpus as a JSON object, see
+/// [`Corpus::to_json`].
+fn counts_json(
+    pages: usize,
+    words: usize,
+    formatting: FormattingCounts,
+    colors: &BTreeMap<Color, usize>,
+) -> Map<String, Value> {
+    let colors: Map<String, Value> = colors
+        .iter()
+        .map(|(color, count)| (ColorValue::from(*color).name().to_string(), json!(count)))
+        .collect();
+    let value = json!({
+        "pages": pages,
+        "words": words,
+        "characters": formatting.characters(),
+        "formatted": formatting.formatted(),
+        "colored": formatting.colored(),
+        "obfuscated": formatting.obfuscated(),
+        "bold": formatting.bold(),
+        "strikethrough": formatting.strikethrough(),
+        "underline": formatting.underline(),
+        "italic": formatting.italic(),
+        "format_codes": formatting.format_codes(),
+        "colors": colors,
+    });
+
+    match value {
+        Value::Object(object) => object,
+        _ => unreachable!("built as an object"),
+    }
+}
+
+/// Counts the characters of text in each color in `tokens`, resetting the formatting at each
+/// page.
+fn colors(tokens: &[Token]) -> BTreeMap<Color, usize> {
+    let mut colors = BTreeMap::new();
+    let mut state = FormatState::new();
+
+    for token in tokens {
+        match token {
+            Token::ThematicBreak => state = FormatState::new(),
+            Token::Format(format) => state.apply(*format),
+            Token::Text(text) => {
+                if let Some(color) = state.color() {
+                    *colors.entry(color).or_default() += text.chars().count();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    colors
+}
+
+/// Returns `field` as a CSV field, quoted if it has a comma, a quote, or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Walking through tokens with lookahead.
+//!
+//! See [`TokenCursor`].
+
+use super::{Token, TokenList};
+
+/// An iterator over a slice of [`Token`]s that can look ahead without consuming anything.
+///
+/// Exporters and transforms that need to know what comes next, like whether a page is blank or
+/// whether a line break ends a paragraph, should use this rather than buffering tokens themselves.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{cursor::TokenCursor, Token};
+///
+/// let tokens = [Token::Text("one".into()), Token::Space, Token::LineBreak];
+/// let mut cursor = TokenCursor::new(&tokens);
+///
+/// assert_eq!(cursor.next(), Some(&Token::Text("one".into())));
+/// assert_eq!(cursor.peek(), Some(&Token::Space));
+/// assert_eq!(cursor.peek_nth(1), Some(&Token::LineBreak));
+/// assert_eq!(cursor.next_if(|token| token.is_text()), None);
+/// assert_eq!(cursor.remaining(), [Token::Space, Token::LineBreak]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TokenCursor<'a> {
+    /// Every token being walked through, including those already consumed.
+    tokens: &'a [Token],
+    /// The index of the next token.
+    position: usize,
+}
+
+impl<'a> TokenCursor<'a> {
+    /// Creates a new [`TokenCursor`] at the start of `tokens`.
+    #[must_use]
+    pub const fn new(tokens: &'a [Token]) -> Self {
+        Self {
+            tokens,
+            position: 0,
+        }
+    }
+
+    /// Returns the index of the next token, which is also how many have been consumed.
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the next token without consuming it.
+    #[must_use]
+    pub fn peek(&self) -> Option<&'a Token> {
+        self.peek_nth(0)
+    }
+
+    /// Returns the token `n` places after the next one without consuming anything, so that
+    /// `peek_nth(0)` is the same as [`Self::peek`].
+    #[must_use]
+    pub fn peek_nth(&self, n: usize) -> Option<&'a Token> {
+        self.tokens.get(self.position.saturating_add(n))
+    }
+
+    /// Returns the token before the next one, if any have been consumed.
+    #[must_use]
+    pub fn previous(&self) -> Option<&'a Token> {
+        self.position
+            .checked_sub(1)
+            .and_then(|index| self.tokens.get(index))
+    }
+
+    /// Returns every token that has not been consumed yet.
+    #[must_use]
+    pub fn remaining(&self) -> &'a [Token] {
+        &self.tokens[self.position..]
+    }
+
+    /// Consumes and returns the next token, but only if `predicate` returns `true` for it.
+    pub fn next_if(&mut self, predicate: impl FnOnce(&Token) -> bool) -> Option<&'a Token> {
+        let token = self.peek().filter(|token| predicate(token))?;
+        self.position += 1;
+        Some(token)
+    }
+
+    /// Consumes tokens for as long as `predicate` returns `true`, returning how many were
+    /// consumed.
+    pub fn advance_while(&mut self, mut predicate: impl FnMut(&Token) -> bool) -> usize {
+        let start = self.position;
+        while self.next_if(&mut predicate).is_some() {}
+        self.position - start
+    }
+}
+
+impl<'a> Iterator for TokenCursor<'a> {
+    type Item = &'a Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining().len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for TokenCursor<'_> {}
+
+impl TokenList {
+    /// Returns a [`TokenCursor`] at the start of the tokens of the work.
+    #[must_use]
+    pub fn cursor(&self) -> TokenCursor<'_> {
+        TokenCursor::new(&self.tokens)
+    }
+}
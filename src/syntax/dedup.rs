@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Finding near-duplicate works in a corpus, like copies of a book with a few typos fixed.
+//!
+//! See [`Deduplicator`].
+
+use super::{Token, TokenList};
+use std::collections::{BTreeSet, HashMap};
+
+/// The words of the visible text of a work, broken into overlapping runs ("shingles") of a fixed
+/// number of words, each reduced to a 64-bit hash.
+///
+/// Formatting, metadata, and letter case are ignored, so only changes to the words themselves make
+/// two works less similar.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Shingles {
+    /// The hash of every distinct shingle.
+    hashes: BTreeSet<u64>,
+}
+
+impl Shingles {
+    /// Collects the shingles of `size` words from the text of `tokens`.
+    ///
+    /// A work with fewer than `size` words has a single shingle of all of its words, and a `size`
+    /// of zero is treated as one.
+    #[must_use]
+    pub fn new(tokens: &TokenList, size: usize) -> Self {
+        let words = words(tokens.tokens_as_slice());
+        let size = size.clamp(1, words.len().max(1));
+
+        Self {
+            hashes: words
+                .windows(size)
+                .map(|shingle| fnv1a(shingle.join(" ").as_bytes()))
+                .collect(),
+        }
+    }
+
+    /// Returns how many distinct shingles there are.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Whether or not there are no shingles, which only happens for a work without any words.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the Jaccard similarity of the two sets of shingles, from zero for nothing in common
+    /// to one for identical sets.
+    ///
+    /// Two works without any words are identical.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Far more shingles than any book has
+    pub fn similarity(&self, other: &Self) -> f64 {
+        let shared = self.hashes.intersection(&other.hashes).count();
+        let total = self.len() + other.len() - shared;
+
+        if total == 0 {
+            return 1.0;
+        }
+        shared as f64 / total as f64
+    }
+}
+
+/// One work in a [`Cluster`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Member {
+    /// The index of the work in the corpus.
+    index: usize,
+    /// The similarity of the work to the first work in the cluster.
+    similarity: f64,
+}
+
+impl Member {
+    /// Returns the index of the work in the corpus.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the similarity of the work to the first work in the cluster, from zero to one.
+    ///
+    /// See [`Shingles::similarity`].
+    #[must_use]
+    pub const fn similarity(&self) -> f64 {
+        self.similarity
+    }
+}
+
+/// A group of works that are near-duplicates of each other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cluster {
+    /// Every work in the cluster, ordered by index.
+    members: Box<[Member]>,
+}
+
+impl Cluster {
+    /// Returns every work in the cluster, ordered by their index in the corpus.
+    ///
+    /// The first work represents the cluster, and has a similarity of one.
+    #[must_use]
+    pub const fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// Returns the lowest similarity of any work to the first work in the cluster.
+    #[must_use]
+    pub fn min_similarity(&self) -> f64 {
+        self.members
+            .iter()
+            .map(Member::similarity)
+            .fold(1.0, f64::min)
+    }
+}
+
+/// Groups near-duplicate works in a corpus.
+///
+/// Works with the same [`TokenList::content_hash`] are always grouped. Otherwise, two works are
+/// near-duplicates if the [similarity][`Shingles::similarity`] of their [`Shingles`] is at least
+/// the threshold, and clusters are formed from chains of near-duplicates. Every pair of works is
+/// compared, so this is best suited to corpora of up to a few thousand works.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{dedup::Deduplicator, TextBuilder, TokenList};
+///
+/// let book = |text: &str| TextBuilder::new().text(text).build();
+/// let corpus: Vec<TokenList> = vec![
+///     book("It was a dark and stormy night, and the rain fell in torrents"),
+///     book("Call me Ishmael"),
+///     book("It was a dark and stormy night, and the rain fell in torents"),
+/// ];
+///
+/// let clusters = Deduplicator::new().cluster(&corpus);
+/// assert_eq!(clusters.len(), 1);
+///
+/// let members: Vec<usize> = clusters[0].members().iter().map(|m| m.index()).collect();
+/// assert_eq!(members, [0, 2]);
+/// assert!(clusters[0].min_similarity() > 0.8);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Deduplicator {
+    /// The number of words in each shingle.
+    shingle_size: usize,
+    /// The lowest similarity at which two works are near-duplicates.
+    threshold: f64,
+}
+
+impl Deduplicator {
+    /// The default for [`Self::shingle_size`].
+    pub const DEFAULT_SHINGLE_SIZE: usize = 4;
+    /// The default for [`Self::threshold`].
+    pub const DEFAULT_THRESHOLD: f64 = 0.8;
+
+    /// Creates a new [`Deduplicator`] with the default shingle size and threshold.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            shingle_size: Self::DEFAULT_SHINGLE_SIZE,
+            threshold: Self::DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Returns the number of words in each shingle.
+    #[must_use]
+    pub const fn shingle_size(&self) -> usize {
+        self.shingle_size
+    }
+
+    /// Sets the number of words in each shingle.
+    ///
+    /// Smaller shingles are more forgiving of small edits, but also find more similarity between
+    /// unrelated works.
+    #[must_use]
+    pub const fn with_shingle_size(mut self, shingle_size: usize) -> Self {
+        self.shingle_size = shingle_size;
+        self
+    }
+
+    /// Returns the lowest similarity at which two works are near-duplicates.
+    #[must_use]
+    pub const fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Sets the lowest similarity at which two works are near-duplicates, from zero to one.
+    #[must_use]
+    pub const fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Returns the similarity of two works, from zero to one.
+    ///
+    /// See [`Shingles::similarity`].
+    #[must_use]
+    pub fn similarity(&self, a: &TokenList, b: &TokenList) -> f64 {
+        Shingles::new(a, self.shingle_size).similarity(&Shingles::new(b, self.shingle_size))
+    }
+
+    /// Group the near-duplicates in `corpus`, returning every cluster of more than one work,
+    /// ordered by the index of their first work.
+    #[must_use]
+    pub fn cluster(&self, corpus: &[TokenList]) -> Vec<Cluster> {
+        let hashes: Vec<String> = corpus.iter().map(TokenList::content_hash).collect();
+        let shingles: Vec<Shingles> = corpus
+            .iter()
+            .map(|tokens| Shingles::new(tokens, self.shingle_size))
+            .collect();
+        let similarity = |a: usize, b: usize| {
+            if hashes[a] == hashes[b] {
+                1.0
+            } else {
+                shingles[a].similarity(&shingles[b])
+            }
+        };
+
+        // Union-find, where each work points towards the first work of its cluster
+        let mut parents: Vec<usize> = (0..corpus.len()).collect();
+        for a in 0..corpus.len() {
+            for b in a + 1..corpus.len() {
+                if similarity(a, b) >= self.threshold {
+                    let (root_a, root_b) = (root(&mut parents, a), root(&mut parents, b));
+                    parents[root_a.max(root_b)] = root_a.min(root_b);
+                }
+            }
+        }
+
+        let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+        for index in 0..corpus.len() {
+            clusters
+                .entry(root(&mut parents, index))
+                .or_default()
+                .push(index);
+        }
+
+        let mut clusters: Vec<Cluster> = clusters
+            .into_iter()
+            .filter(|(_, indices)| indices.len() > 1)
+            .map(|(first, indices)| Cluster {
+                members: indices
+                    .into_iter()
+                    .map(|index| Member {
+                        index,
+                        similarity: similarity(first, index),
+                    })
+                    .collect(),
+            })
+            .collect();
+        clusters.sort_by_key(|cluster| cluster.members[0].index);
+
+        clusters
+    }
+}
+
+impl Default for Deduplicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the first work of the cluster that `index` belongs to, compressing the path to it.
+fn root(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+
+    let mut index = index;
+    while parents[index] != root {
+        index = std::mem::replace(&mut parents[index], root);
+    }
+
+    root
+}
+
+/// Returns the lowercase words of the visible text of `tokens`, without punctuation.
+fn words(tokens: &[Token]) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+
+    for token in tokens {
+        match token {
+            Token::Text(text) | Token::Image { alt: text, .. } => {
+                for char in text.chars() {
+                    if char.is_alphanumeric() {
+                        word.extend(char.to_lowercase());
+                    } else if !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                }
+            }
+            Token::Format(_) | Token::Align(_) | Token::Role(_) => (),
+            Token::Space | Token::LineBreak | Token::ParagraphBreak | Token::ThematicBreak => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Returns the 64-bit FNV-1a hash of `bytes`, which is stable across platforms and versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! The direction that the text of a work is written in, and how to find it from the work.
+//!
+//! See [`TextDirection`].
+
+use super::{Metadata, TokenList};
+
+/// The key of the [`Metadata::Custom`] entry that names the direction of a work, one of `"ltr"`,
+/// `"rtl"`, or `"auto"`.
+pub const DIRECTION_KEY: &str = "direction";
+
+/// The languages that are written right to left by default, as the primary subtag of a language
+/// tag.
+const RIGHT_TO_LEFT_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ji", "nqo", "ps", "sd", "syr", "ug", "ur", "yi",
+];
+
+/// The scripts that are written right to left, as the script subtag of a language tag.
+const RIGHT_TO_LEFT_SCRIPTS: &[&str] = &[
+    "adlm", "arab", "hebr", "mand", "nkoo", "rohg", "samr", "syrc", "thaa",
+];
+
+/// The direction that the text of a work is written in, as the `dir` attribute of an HTML
+/// document.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{direction::TextDirection, Metadata, TokenList};
+///
+/// let tokens = TokenList::new_from_boxed(
+///     Box::new([Metadata::Language("he-IL".into())]),
+///     Box::new([]),
+/// );
+///
+/// assert_eq!(TextDirection::for_work(&tokens), Some(TextDirection::RightToLeft));
+/// assert_eq!(TextDirection::for_language("az-Arab"), TextDirection::RightToLeft);
+/// assert_eq!(TextDirection::for_language("en"), TextDirection::LeftToRight);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Left to right, like English.
+    #[default]
+    LeftToRight,
+    /// Right to left, like Arabic or Hebrew.
+    RightToLeft,
+    /// Left to the reader to decide from the text itself.
+    Auto,
+}
+
+impl TextDirection {
+    /// Returns the value of the `dir` attribute for this direction.
+    #[must_use]
+    pub const fn attribute(self) -> &'static str {
+        match self {
+            Self::LeftToRight => "ltr",
+            Self::RightToLeft => "rtl",
+            Self::Auto => "auto",
+        }
+    }
+
+    /// Returns the direction with the `dir` attribute `value`, ignoring case, or [`None`] if it is
+    /// not one.
+    #[must_use]
+    pub fn from_attribute(value: &str) -> Option<Self> {
+        [Self::LeftToRight, Self::RightToLeft, Self::Auto]
+            .into_iter()
+            .find(|direction| value.trim().eq_ignore_ascii_case(direction.attribute()))
+    }
+
+    /// Returns the invisible mark that sets the direction of a line of plain text, or [`None`]
+    /// for [`Self::Auto`].
+    ///
+    /// Without one, a line takes the direction of its first letter, so a Hebrew line that starts
+    /// with a number or a Latin word would be laid out left to right, with its punctuation on the
+    /// wrong side and its brackets facing the wrong way.
+    #[must_use]
+    pub const fn mark(self) -> Option<char> {
+        match self {
+            Self::LeftToRight => Some('\u{200E}'),
+            Self::RightToLeft => Some('\u{200F}'),
+            Self::Auto => None,
+        }
+    }
+
+    /// Returns the direction of the language with the tag `tag`, like `"ar"` or `"uz-Arab"`.
+    ///
+    /// A script subtag takes precedence over the language, and unknown languages are
+    /// [`Self::LeftToRight`].
+    #[must_use]
+    pub fn for_language(tag: &str) -> Self {
+        let mut subtags = tag.trim().split(['-', '_']);
+        let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        let script = subtags
+            .find(|subtag| {
+                subtag.len() == 4 && subtag.chars().all(|char| char.is_ascii_alphabetic())
+            })
+            .map(str::to_ascii_lowercase);
+
+        let right_to_left = script.map_or_else(
+            || RIGHT_TO_LEFT_LANGUAGES.contains(&language.as_str()),
+            |script| RIGHT_TO_LEFT_SCRIPTS.contains(&script.as_str()),
+        );
+
+        if right_to_left {
+            Self::RightToLeft
+        } else {
+            Self::LeftToRight
+        }
+    }
+
+    /// Returns the direction of a work, from its [`Metadata::Custom`] entry named
+    /// [`DIRECTION_KEY`], or else from its [`Metadata::language`], or [`None`] if it has neither.
+    #[must_use]
+    pub fn for_work(tokens: &TokenList) -> Option<Self> {
+        let custom = |name: &str| {
+            tokens
+                .metadata_as_slice()
+                .iter()
+                .find_map(|data| match data {
+                    Metadata::Custom { key, value } if key.eq_ignore_ascii_case(name) => {
+                        Some(value.as_ref())
+                    }
+                    _ => None,
+                })
+        };
+
+        custom(DIRECTION_KEY)
+            .and_then(Self::from_attribute)
+            .or_else(|| {
+                tokens
+                    .metadata_as_slice()
+                    .iter()
+                    .find_map(Metadata::language)
+                    .map(Self::for_language)
+            })
+    }
+}
@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Structural editing of the pages of a work.
+//!
+//! Pages are separated by [`Token::ThematicBreak`], see [`TokenList::pages_iter`].
+
+use super::{minecraft::FormatState, Token, TokenList};
+use std::ops::{Bound, RangeBounds};
+use thiserror::Error;
+
+/// Returned when a page index is past the end of a work.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("page {index} is out of range for a work with {pages} pages")]
+pub struct PageIndexError {
+    /// The index that was out of range.
+    index: usize,
+    /// The number of pages in the work.
+    pages: usize,
+}
+
+impl PageIndexError {
+    /// Returns the index that was out of range.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the number of pages in the work.
+    #[must_use]
+    pub const fn pages(&self) -> usize {
+        self.pages
+    }
+}
+
+/// Page editing operations, for fixing up a work before exporting it.
+///
+/// Each keeps whether or not the work starts with a [`Token::ThematicBreak`], like Stendhal
+/// exports do.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{Token, TokenList};
+///
+/// let mut tokens = TokenList::new_from_boxed(Box::new([]), Box::new([]));
+/// tokens.push_page([Token::Text("two".into())]);
+/// tokens.push_page([Token::Text("one".into())]);
+/// tokens.push_page([Token::Text("scan error".into())]);
+///
+/// tokens.move_page(1, 0)?;
+/// tokens.remove_page(2)?;
+///
+/// let mut expected = TokenList::new_from_boxed(Box::new([]), Box::new([]));
+/// expected.push_page([Token::Text("one".into())]);
+/// expected.push_page([Token::Text("two".into())]);
+/// assert_eq!(tokens, expected);
+/// # Ok::<(), crafty_novels::syntax::edit::PageIndexError>(())
+/// ```
+impl TokenList {
+    /// Returns the number of pages in the work.
+    #[must_use]
+    pub fn page_count(&self) -> usize {
+        self.pages_iter().count()
+    }
+
+    /// Remove the page at `index`, returning it.
+    ///
+    /// # Errors
+    ///
+    /// - [`PageIndexError`] if `index` is not the index of a page
+    pub fn remove_page(&mut self, index: usize) -> Result<Self, PageIndexError> {
+        let mut pages = self.page_slices();
+        let page = checked(index, pages.len())?;
+        let removed = Self::new(self.metadata(), pages.remove(page).into());
+
+        *self = self.with_pages(&pages);
+        Ok(removed)
+    }
+
+    /// Move the page at `from` so that it ends up at index `to`.
+    ///
+    /// # Errors
+    ///
+    /// - [`PageIndexError`] if either `from` or `to` is not the index of a page
+    pub fn move_page(&mut self, from: usize, to: usize) -> Result<(), PageIndexError> {
+        let mut pages = self.page_slices();
+        let from = checked(from, pages.len())?;
+        let to = checked(to, pages.len())?;
+
+        let page = pages.remove(from);
+        pages.insert(to, page);
+
+        *self = self.with_pages(&pages);
+        Ok(())
+    }
+
+    /// Insert the tokens of `page` as a new page at `index`, shifting the pages after it.
+    ///
+    /// `index` may be the number of pages, to append the page. The [`Metadata`][`super::Metadata`]
+    /// of `page` is ignored, so it can be a page taken from another work with
+    /// [`Self::pages_iter`] or [`Self::remove_page`].
+    ///
+    /// # Errors
+    ///
+    /// - [`PageIndexError`] if `index` is greater than the number of pages
+    pub fn insert_page(&mut self, index: usize, page: &Self) -> Result<(), PageIndexError> {
+        let mut pages = self.page_slices();
+        let index = checked(index, pages.len() + 1)?;
+
+        pages.insert(index, page.tokens_as_slice());
+
+        *self = self.with_pages(&pages);
+        Ok(())
+    }
+
+    /// Returns a copy of the work with only the pages in `range`, to convert or preview part of a
+    /// long work.
+    ///
+    /// A range that starts after it ends, like `5..3`, results in a work without any pages.
+    ///
+    /// # Errors
+    ///
+    /// - [`PageIndexError`] if `range` starts or ends past the last page
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::syntax::{Token, TokenList};
+    ///
+    /// let mut tokens = TokenList::new_from_boxed(Box::new([]), Box::new([]));
+    /// for page in ["one", "two", "three"] {
+    ///     tokens.push_page([Token::Text(page.into())]);
+    /// }
+    ///
+    /// let excerpt = tokens.pages(1..)?;
+    /// assert_eq!(excerpt.page_count(), 2);
+    /// assert_eq!(
+    ///     excerpt.pages_iter().next().unwrap().tokens_as_slice(),
+    ///     [Token::Text("two".into())]
+    /// );
+    /// assert!(tokens.pages(2..4).is_err());
+    /// # Ok::<(), crafty_novels::syntax::edit::PageIndexError>(())
+    /// ```
+    pub fn pages(&self, range: impl RangeBounds<usize>) -> Result<Self, PageIndexError> {
+        let pages = self.page_slices();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => pages.len(),
+        };
+
+        // Like when slicing, both bounds may be the number of pages
+        if start > pages.len() {
+            return Err(PageIndexError {
+                index: start,
+                pages: pages.len(),
+            });
+        }
+        if end > pages.len() {
+            return Err(PageIndexError {
+                index: end - 1,
+                pages: pages.len(),
+            });
+        }
+
+        Ok(self.with_pages(pages.get(start..end).unwrap_or_default()))
+    }
+
+    /// Returns a copy of the work with only the page at `index`, starting with the formatting
+    /// carried over from the pages before it.
+    ///
+    /// Unlike [`Self::pages`], the page looks the same on its own as it does in the whole work, so
+    /// it can be exported on demand, like when a web reader lazy-loads each page.
+    ///
+    /// # Errors
+    ///
+    /// - [`PageIndexError`] if `index` is past the last page
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::syntax::{minecraft::Format, Token, TokenList};
+    ///
+    /// let mut tokens = TokenList::new_from_boxed(Box::new([]), Box::new([]));
+    /// tokens.push_page([Token::Format(Format::Bold), Token::Text("one".into())]);
+    /// tokens.push_page([Token::Text("two".into())]);
+    ///
+    /// assert_eq!(
+    ///     tokens.page(1)?.tokens_as_slice(),
+    ///     [
+    ///         Token::ThematicBreak,
+    ///         Token::Format(Format::Bold),
+    ///         Token::Text("two".into()),
+    ///     ]
+    /// );
+    /// # Ok::<(), crafty_novels::syntax::edit::PageIndexError>(())
+    /// ```
+    pub fn page(&self, index: usize) -> Result<Self, PageIndexError> {
+        let pages = self.page_slices();
+        let index = checked(index, pages.len())?;
+
+        let carried = pages[..index].iter().flat_map(|page| page.iter()).fold(
+            FormatState::new(),
+            |state, token| match token {
+                Token::Format(format) => state.with(*format),
+                _ => state,
+            },
+        );
+
+        let mut page: Vec<Token> = carried.formats().into_iter().map(Token::Format).collect();
+        page.extend_from_slice(pages[index]);
+
+        Ok(self.with_pages(&[&page]))
+    }
+
+    /// Returns the tokens of each page.
+    ///
+    /// See [`Self::pages_iter`].
+    pub(super) fn page_slices(&self) -> Vec<&[Token]> {
+        self.tokens
+            .split(|token| *token == Token::ThematicBreak)
+            .enumerate()
+            // Skip the empty "page" before a leading page break
+            .filter(|(index, page)| *index > 0 || !page.is_empty())
+            .map(|(_, page)| page)
+            .collect()
+    }
+
+    /// Returns a copy of the work's [`Metadata`][`super::Metadata`] with `pages` as its content.
+    pub(super) fn with_pages(&self, pages: &[&[Token]]) -> Self {
+        let leading_break = self.tokens.first() == Some(&Token::ThematicBreak);
+        let mut tokens = vec![];
+
+        for (index, page) in pages.iter().enumerate() {
+            if leading_break || index > 0 {
+                tokens.push(Token::ThematicBreak);
+            }
+            tokens.extend_from_slice(page);
+        }
+
+        Self::new(self.metadata(), tokens.into())
+    }
+}
+
+/// Returns `index` if it is less than `pages`.
+const fn checked(index: usize, pages: usize) -> Result<usize, PageIndexError> {
+    if index < pages {
+        Ok(index)
+    } else {
+        Err(PageIndexError { index, pages })
+    }
+}
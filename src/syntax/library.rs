@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Indexing a corpus of works by author, for sites that host a library of converted books.
+//!
+//! See [`Library`].
+
+use super::{minecraft::strip_format_codes, Metadata, TokenList};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+
+/// A summary of a work in a [`Library`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Work {
+    /// The first [`Metadata::Title`] of the work, with format codes and surrounding whitespace
+    /// removed, or an empty string if it has none.
+    title: Box<str>,
+    /// The [`TokenList::content_hash`] of the work.
+    hash: Box<str>,
+    /// The number of pages in the work.
+    pages: usize,
+}
+
+impl Work {
+    /// Summarizes `tokens`.
+    #[must_use]
+    pub fn new(tokens: &TokenList) -> Self {
+        Self {
+            title: first(tokens.metadata_as_slice(), |data| match data {
+                Metadata::Title(title) => Some(title),
+                _ => None,
+            }),
+            hash: tokens.content_hash().into_boxed_str(),
+            pages: tokens.page_count(),
+        }
+    }
+
+    /// Returns the title of the work, or an empty string if it has none.
+    #[must_use]
+    pub const fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the [`TokenList::content_hash`] of the work.
+    #[must_use]
+    pub const fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Returns the number of pages in the work.
+    #[must_use]
+    pub const fn pages(&self) -> usize {
+        self.pages
+    }
+}
+
+/// An index of works by their author.
+///
+/// Works are filed under their first [`Metadata::Author`], with format codes and surrounding
+/// whitespace removed, or under an empty name if they have none. Authors are sorted by name, and
+/// each author's works by title. A work is only indexed once, however many times it is added, as
+/// identified by its [`TokenList::content_hash`].
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{library::Library, TextBuilder};
+///
+/// let book = |title: &str, author: &str| {
+///     TextBuilder::new()
+///         .title(title)
+///         .author(author)
+///         .text(title)
+///         .build()
+/// };
+/// let corpus = [
+///     book("Second", "RemasteredArch"),
+///     book("First", "RemasteredArch"),
+///     book("Other", "NebSpacefarer"),
+///     book("First", "RemasteredArch"),
+/// ];
+///
+/// let library: Library = corpus.iter().collect();
+/// let titles: Vec<&str> = library
+///     .works_by("RemasteredArch")
+///     .iter()
+///     .map(|work| work.title())
+///     .collect();
+///
+/// assert_eq!(library.len(), 3);
+/// assert_eq!(titles, ["First", "Second"]);
+/// assert_eq!(
+///     library.authors().map(|(author, _)| author).collect::<Vec<_>>(),
+///     ["NebSpacefarer", "RemasteredArch"]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Library {
+    /// The works of each author, sorted by title.
+    authors: BTreeMap<Box<str>, Vec<Work>>,
+    /// The hash of every work in the library.
+    hashes: HashSet<Box<str>>,
+}
+
+impl Library {
+    /// Creates an empty library.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes `tokens`, returning whether it was added, or `false` if the library already has a
+    /// work with the same content hash.
+    pub fn add(&mut self, tokens: &TokenList) -> bool {
+        let work = Work::new(tokens);
+        if !self.hashes.insert(work.hash.clone()) {
+            return false;
+        }
+
+        let author = first(tokens.metadata_as_slice(), |data| match data {
+            Metadata::Author(author) => Some(author),
+            _ => None,
+        });
+        let works = self.authors.entry(author).or_default();
+        let index = works.partition_point(|other| *other <= work);
+        works.insert(index, work);
+
+        true
+    }
+
+    /// Indexes `tokens`, returning the library.
+    ///
+    /// See [`Self::add`].
+    #[must_use]
+    pub fn with(mut self, tokens: &TokenList) -> Self {
+        self.add(tokens);
+        self
+    }
+
+    /// Returns each author and their works, sorted by name.
+    pub fn authors(&self) -> impl Iterator<Item = (&str, &[Work])> {
+        self.authors
+            .iter()
+            .map(|(author, works)| (author.as_ref(), works.as_slice()))
+    }
+
+    /// Returns the works of `author`, sorted by title, which is empty if there are none.
+    #[must_use]
+    pub fn works_by(&self, author: &str) -> &[Work] {
+        self.authors.get(author).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the number of works in the library.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Whether or not the library has no works.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the library as a JSON object, in the form:
+    ///
+    /// ```json
+    /// {
+    ///   "authors": [
+    ///     {
+    ///       "name": "RemasteredArch",
+    ///       "works": [{ "title": "Book", "pages": 2, "hash": "..." }]
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let authors: Vec<Value> = self
+            .authors()
+            .map(|(author, works)| {
+                let works: Vec<Value> = works
+                    .iter()
+                    .map(|work| {
+                        json!({
+                            "title": work.title(),
+                            "pages": work.pages(),
+                            "hash": work.hash(),
+                        })
+                    })
+                    .collect();
+
+                json!({ "name": author, "works": works })
+            })
+            .collect();
+
+        json!({ "authors": authors }).to_string()
+    }
+}
+
+impl<'a> FromIterator<&'a TokenList> for Library {
+    fn from_iter<T: IntoIterator<Item = &'a TokenList>>(iter: T) -> Self {
+        let mut library = Self::new();
+        library.extend(iter);
+        library
+    }
+}
+
+impl<'a> Extend<&'a TokenList> for Library {
+    fn extend<T: IntoIterator<Item = &'a TokenList>>(&mut self, iter: T) {
+        for tokens in iter {
+            self.add(tokens);
+        }
+    }
+}
+
+/// Returns the first value in `metadata` that `matches`, with format codes and surrounding
+/// whitespace removed, or an empty string if there is none.
+pub(super) fn first(metadata: &[Metadata], matches: fn(&Metadata) -> Option<&str>) -> Box<str> {
+    metadata
+        .iter()
+        .find_map(matches)
+        .map(|value| strip_format_codes(value).trim().into())
+        .unwrap_or_default()
+}
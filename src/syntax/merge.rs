@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Three-way merging of edited copies of a work.
+//!
+//! See [`Merge`].
+
+use super::{Metadata, Token, TokenList};
+
+/// The text of the marker that starts a conflict, followed by our side.
+pub const OURS_MARKER: &str = "<<<<<<<";
+/// The text of the marker that separates our side of a conflict from their side.
+pub const SEPARATOR_MARKER: &str = "=======";
+/// The text of the marker that ends a conflict, after their side.
+pub const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// A region where both copies changed the original in different ways.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict<T> {
+    /// The region in the common ancestor.
+    base: Box<[T]>,
+    /// The region in our copy.
+    ours: Box<[T]>,
+    /// The region in their copy.
+    theirs: Box<[T]>,
+}
+
+impl<T> Conflict<T> {
+    /// Returns the region in the common ancestor.
+    #[must_use]
+    pub const fn base(&self) -> &[T] {
+        &self.base
+    }
+
+    /// Returns the region in our copy.
+    #[must_use]
+    pub const fn ours(&self) -> &[T] {
+        &self.ours
+    }
+
+    /// Returns the region in their copy.
+    #[must_use]
+    pub const fn theirs(&self) -> &[T] {
+        &self.theirs
+    }
+}
+
+/// A region of a merge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Chunk<T> {
+    /// A region that merged cleanly.
+    Resolved(Box<[T]>),
+    /// A region that both copies changed in different ways.
+    Conflict(Conflict<T>),
+}
+
+/// The result of merging two edited copies of a work with their common ancestor.
+///
+/// Tokens are merged a line at a time, much like `git merge`: a line is the [`Token`]s up to and
+/// including a [`Token::LineBreak`], [`Token::ParagraphBreak`], or [`Token::ThematicBreak`].
+/// [`Metadata`] is merged an entry at a time.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{Token, TokenList};
+///
+/// fn page(text: &str) -> TokenList {
+///     TokenList::new_from_boxed(
+///         Box::new([]),
+///         Box::new([Token::Text(text.into()), Token::LineBreak]),
+///     )
+/// }
+///
+/// let merge = TokenList::merge(&page("base"), &page("ours"), &page("base"));
+/// assert!(merge.is_clean());
+/// assert_eq!(merge.to_token_list(), page("ours"));
+///
+/// let merge = TokenList::merge(&page("base"), &page("ours"), &page("theirs"));
+/// assert_eq!(merge.conflicts().count(), 1);
+///
+/// let resolved = merge.resolve(|conflict| conflict.ours().into(), |conflict| conflict.theirs().into());
+/// assert_eq!(resolved, page("theirs"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Merge {
+    /// The merged [`Metadata`] entries.
+    metadata: Box<[Chunk<Metadata>]>,
+    /// The merged [`Token`]s.
+    tokens: Box<[Chunk<Token>]>,
+}
+
+impl Merge {
+    /// Merge `ours` and `theirs`, two edited copies of `base`.
+    #[must_use]
+    pub fn new(base: &TokenList, ours: &TokenList, theirs: &TokenList) -> Self {
+        let metadata = diff3(
+            base.metadata_as_slice(),
+            ours.metadata_as_slice(),
+            theirs.metadata_as_slice(),
+        )
+        .into();
+
+        let tokens = diff3(
+            &lines(base.tokens_as_slice()),
+            &lines(ours.tokens_as_slice()),
+            &lines(theirs.tokens_as_slice()),
+        )
+        .into_iter()
+        .map(|chunk| chunk.map(<[_]>::concat))
+        .collect();
+
+        Self { metadata, tokens }
+    }
+
+    /// Returns the regions of the merged [`Metadata`].
+    #[must_use]
+    pub const fn metadata(&self) -> &[Chunk<Metadata>] {
+        &self.metadata
+    }
+
+    /// Returns the regions of the merged [`Token`]s.
+    #[must_use]
+    pub const fn tokens(&self) -> &[Chunk<Token>] {
+        &self.tokens
+    }
+
+    /// Whether or not the merge completed without any conflicts.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.conflicts().next().is_none() && self.metadata_conflicts().next().is_none()
+    }
+
+    /// Returns the conflicting regions of the merged [`Token`]s.
+    pub fn conflicts(&self) -> impl Iterator<Item = &Conflict<Token>> {
+        conflicts(&self.tokens)
+    }
+
+    /// Returns the conflicting regions of the merged [`Metadata`].
+    pub fn metadata_conflicts(&self) -> impl Iterator<Item = &Conflict<Metadata>> {
+        conflicts(&self.metadata)
+    }
+
+    /// Converts the merge into a [`TokenList`], marking conflicts inline.
+    ///
+    /// Each conflict becomes a line containing [`OURS_MARKER`], our side, a line containing
+    /// [`SEPARATOR_MARKER`], their side, and a line containing [`THEIRS_MARKER`]. [`Metadata`]
+    /// cannot hold markers, so conflicting [`Metadata`] uses our side.
+    #[must_use]
+    pub fn to_token_list(&self) -> TokenList {
+        self.resolve(
+            |conflict| conflict.ours().into(),
+            |conflict| {
+                let mut tokens = marker(OURS_MARKER, "ours");
+                push_side(&mut tokens, conflict.ours());
+                tokens.extend(marker(SEPARATOR_MARKER, ""));
+                push_side(&mut tokens, conflict.theirs());
+                tokens.extend(marker(THEIRS_MARKER, "theirs"));
+                tokens
+            },
+        )
+    }
+
+    /// Converts the merge into a [`TokenList`], replacing each conflict with the output of the
+    /// given functions.
+    ///
+    /// This allows for conflicts to be resolved interactively.
+    #[must_use]
+    pub fn resolve(
+        &self,
+        mut resolve_metadata: impl FnMut(&Conflict<Metadata>) -> Vec<Metadata>,
+        mut resolve_tokens: impl FnMut(&Conflict<Token>) -> Vec<Token>,
+    ) -> TokenList {
+        TokenList::new_from_boxed(
+            flatten(&self.metadata, &mut resolve_metadata).into(),
+            flatten(&self.tokens, &mut resolve_tokens).into(),
+        )
+    }
+}
+
+impl<T> Chunk<T> {
+    /// Convert the regions of the chunk.
+    fn map<U>(self, mut f: impl FnMut(&[T]) -> Vec<U>) -> Chunk<U> {
+        match self {
+            Self::Resolved(resolved) => Chunk::Resolved(f(&resolved).into()),
+            Self::Conflict(Conflict { base, ours, theirs }) => Chunk::Conflict(Conflict {
+                base: f(&base).into(),
+                ours: f(&ours).into(),
+                theirs: f(&theirs).into(),
+            }),
+        }
+    }
+}
+
+/// Returns the conflicting regions of `chunks`.
+fn conflicts<T>(chunks: &[Chunk<T>]) -> impl Iterator<Item = &Conflict<T>> {
+    chunks.iter().filter_map(|chunk| match chunk {
+        Chunk::Resolved(_) => None,
+        Chunk::Conflict(conflict) => Some(conflict),
+    })
+}
+
+/// Join `chunks` back together, replacing conflicts with the output of `resolve`.
+fn flatten<T: Clone>(
+    chunks: &[Chunk<T>],
+    resolve: &mut impl FnMut(&Conflict<T>) -> Vec<T>,
+) -> Vec<T> {
+    let mut output = vec![];
+
+    for chunk in chunks {
+        match chunk {
+            Chunk::Resolved(resolved) => output.extend_from_slice(resolved),
+            Chunk::Conflict(conflict) => output.extend(resolve(conflict)),
+        }
+    }
+
+    output
+}
+
+/// Returns a line containing a conflict marker, optionally followed by a label.
+fn marker(marker: &str, label: &str) -> Vec<Token> {
+    let mut tokens = vec![Token::Text(marker.into())];
+
+    if !label.is_empty() {
+        tokens.extend([Token::Space, Token::Text(label.into())]);
+    }
+
+    tokens.push(Token::LineBreak);
+    tokens
+}
+
+/// Append one side of a conflict, making sure it ends in a line break so the next marker gets its
+/// own line.
+fn push_side(output: &mut Vec<Token>, side: &[Token]) {
+    output.extend_from_slice(side);
+
+    if side.last().is_some_and(|token| !is_line_end(token)) {
+        output.push(Token::LineBreak);
+    }
+}
+
+/// Whether or not a [`Token`] ends a line.
+const fn is_line_end(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::LineBreak | Token::ParagraphBreak | Token::ThematicBreak
+    )
+}
+
+/// Split `tokens` into lines, each ending in (and including) a break.
+fn lines(tokens: &[Token]) -> Vec<&[Token]> {
+    tokens.split_inclusive(is_line_end).collect()
+}
+
+/// Perform a three-way merge of `ours` and `theirs` against `base`.
+///
+/// Regions that only one side changed take that side's changes, and regions that both sides
+/// changed identically merge cleanly. Everything else is a conflict.
+fn diff3<T: Clone + PartialEq>(base: &[T], ours: &[T], theirs: &[T]) -> Vec<Chunk<T>> {
+    let ours_matches = matches(base, ours);
+    let theirs_matches = matches(base, theirs);
+
+    let mut chunks = vec![];
+    let (mut b, mut o, mut t) = (0, 0, 0);
+
+    loop {
+        // Skip over lines that neither side changed
+        let mut stable = 0;
+        while b + stable < base.len()
+            && ours_matches[b + stable] == Some(o + stable)
+            && theirs_matches[b + stable] == Some(t + stable)
+        {
+            stable += 1;
+        }
+
+        if stable > 0 {
+            chunks.push(Chunk::Resolved(base[b..b + stable].into()));
+            b += stable;
+            o += stable;
+            t += stable;
+        }
+
+        // Find the next line that both sides kept, or the end of the documents
+        let (next_b, next_o, next_t) = (b..base.len())
+            .find_map(|i| Some((i, ours_matches[i]?, theirs_matches[i]?)))
+            .unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        // Only reached at the end of all three documents
+        if (next_b, next_o, next_t) == (b, o, t) {
+            break;
+        }
+
+        let (base_region, ours_region, theirs_region) =
+            (&base[b..next_b], &ours[o..next_o], &theirs[t..next_t]);
+
+        let resolved = if ours_region == base_region || ours_region == theirs_region {
+            Some(theirs_region)
+        } else if theirs_region == base_region {
+            Some(ours_region)
+        } else {
+            None
+        };
+
+        chunks.push(resolved.map_or_else(
+            || {
+                Chunk::Conflict(Conflict {
+                    base: base_region.into(),
+                    ours: ours_region.into(),
+                    theirs: theirs_region.into(),
+                })
+            },
+            |resolved| Chunk::Resolved(resolved.into()),
+        ));
+
+        (b, o, t) = (next_b, next_o, next_t);
+    }
+
+    chunks
+}
+
+/// For each item of `base`, find the index of the matching item in `other`, if any, by the longest
+/// common subsequence.
+fn matches<T: PartialEq>(base: &[T], other: &[T]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+
+    // Trim the common prefix and suffix, which are usually most of the work
+    let prefix = base
+        .iter()
+        .zip(other)
+        .take_while(|(base, other)| base == other)
+        .count();
+    let suffix = base[prefix..]
+        .iter()
+        .rev()
+        .zip(other[prefix..].iter().rev())
+        .take_while(|(base, other)| base == other)
+        .count();
+
+    for (i, matched) in matches.iter_mut().enumerate().take(prefix) {
+        *matched = Some(i);
+    }
+    for i in 0..suffix {
+        matches[base.len() - 1 - i] = Some(other.len() - 1 - i);
+    }
+
+    let base_middle = &base[prefix..base.len() - suffix];
+    let other_middle = &other[prefix..other.len() - suffix];
+    let width = other_middle.len() + 1;
+
+    // lengths[i * width + j] is the length of the longest common subsequence of
+    // base_middle[i..] and other_middle[j..]
+    let mut lengths = vec![0_usize; (base_middle.len() + 1) * width];
+    for i in (0..base_middle.len()).rev() {
+        for j in (0..other_middle.len()).rev() {
+            lengths[i * width + j] = if base_middle[i] == other_middle[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < base_middle.len() && j < other_middle.len() {
+        if base_middle[i] == other_middle[j] {
+            matches[prefix + i] = Some(prefix + j);
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    matches
+}
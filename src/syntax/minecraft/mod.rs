@@ -20,77 +20,49 @@
 //!
 //! See [`Format`].
 
-use super::ConversionError;
-pub use color::{Color, ColorValue, Rgb};
-pub use format_code::FormatCode;
-use std::str::FromStr;
+use super::Token;
+use crate::format::scan::{FormatLexer, Lexeme};
+pub use crafty_novels_minecraft::{
+    metrics, obfuscation, strip_format_codes, Color, ColorLayer, ColorValue, Format, FormatCode,
+    FormatState, ObfuscatedLength, Obfuscator, Rgb, Style,
+};
 
-mod color;
-mod format_code;
-
-/// Represents the ways that Minecraft: Java Edition will format text.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
-pub enum Format {
-    Color(Color),
-    /// AKA "Magical Text Source", characters should rapidly swap between a set of characters.
-    Obfuscated,
-    Bold,
-    Strikethrough,
-    Underline,
-    Italic,
-    Reset,
-}
-
-impl From<FormatCode> for Format {
-    /// Look up a [`char`] against Minecraft: Java Edition's list of formatting codes.
-    ///
-    /// # Errors
-    ///
-    /// - [`ConversionError::NoSuchFormatCode`] if the [`FormatCode`] does not correspond to a
-    ///   variant of [`Format`]
-    fn from(code: FormatCode) -> Self {
-        code.format()
-    }
-}
-
-impl TryFrom<char> for Format {
-    type Error = ConversionError;
-
-    /// Match a format code to a [`Format`] variant.
-    ///
-    /// # Errors
-    ///
-    /// - [`ConversionError::NoSuchFormatCode`] if the [`FormatCode`] does not correspond to a
-    ///   variant of [`Format`]
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        Ok(FormatCode::try_from(value)?.format())
-    }
-}
-
-impl FromStr for Format {
-    type Err = ConversionError;
-
-    /// Get the character following the `'§'` in a Minecraft format code.
-    ///
-    /// Expects a two byte string that starts with `'§'`.
-    ///
-    /// Ex. The `'0'` in `"§0"`.
-    ///
-    /// # Errors
-    ///
-    /// - [`ConversionError::InvalidFormatCodeString`] if passed a string that is longer than two
-    ///   [`char`]s or does not start with `'§'`
-    /// - [`ConversionError::NoSuchFormatCode`] if the [`FormatCode`] does not correspond to a
-    ///   variant of [`Format`]
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let code = FormatCode::from_str(s)?;
-
-        Ok(Self::from(code))
-    }
-}
-
-impl From<Format> for char {
-    fn from(value: Format) -> Self {
-        Self::from(FormatCode::from(value))
-    }
+/// Split a single line of `text` containing format codes into [`Token`]s.
+///
+/// Produces only [`Token::Text`], [`Token::Space`], and [`Token::Format`]. Format codes that
+/// Minecraft: Java Edition does not recognize become [`Format::Unknown`], and a trailing `'§'`
+/// without a following character is dropped.
+///
+/// Useful for short strings like [metadata][`super::Metadata`] values, which can contain format
+/// codes but are not part of a document.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     minecraft::{tokenize_formatted, Color, Format},
+///     Token,
+/// };
+///
+/// assert_eq!(
+///     tokenize_formatted("§6Golden §lChronicle"),
+///     [
+///         Token::Format(Format::Color(Color::Gold)),
+///         Token::Text("Golden".into()),
+///         Token::Space,
+///         Token::Format(Format::Bold),
+///         Token::Text("Chronicle".into()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn tokenize_formatted(text: &str) -> Vec<Token> {
+    FormatLexer::new(text)
+        .filter_map(|lexeme| match lexeme {
+            Lexeme::Word(word) => Some(Token::Text(word.into())),
+            Lexeme::Space => Some(Token::Space),
+            Lexeme::Code(code) => Some(Token::Format(FormatCode::new_or_unknown(code).format())),
+            Lexeme::MissingCode => None,
+        })
+        .collect()
 }
@@ -22,11 +22,44 @@
 //!
 //! See [`TokenList`].
 
-pub use error::ConversionError;
-use std::sync::Arc;
+pub use analysis::Analysis;
+pub use annotations::{Annotation, Annotations};
+pub use builder::TextBuilder;
+pub use crafty_novels_minecraft::ConversionError;
+pub use cursor::TokenCursor;
+pub use edit::PageIndexError;
+pub use merge::Merge;
+use std::{
+    ops::{Add, AddAssign},
+    sync::Arc,
+};
+pub use styled::StyledSpan;
 
-mod error;
+pub mod align;
+pub mod analysis;
+pub mod annotations;
+pub mod builder;
+mod canonical;
+pub mod compose;
+pub mod corpus;
+pub mod cursor;
+pub mod dedup;
+pub mod direction;
+pub mod edit;
+pub mod library;
+pub mod merge;
 pub mod minecraft;
+pub mod normalize;
+pub mod provenance;
+pub mod role;
+pub mod span;
+pub mod stream;
+pub mod styled;
+#[cfg(test)]
+mod test;
+pub mod title_page;
+pub mod transform;
+pub mod typography;
 
 /// Represents and entire work in abstract syntax.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -76,12 +109,278 @@ impl TokenList {
     pub fn tokens(&self) -> Arc<[Token]> {
         self.tokens.clone()
     }
+
+    /// Resolve the tokens into runs of text that share the same formatting.
+    ///
+    /// See [`StyledSpan`].
+    #[must_use]
+    pub fn to_styled_spans(&self) -> Vec<StyledSpan> {
+        styled::to_styled_spans(&self.tokens)
+    }
+
+    /// Creates a new [`TokenList`] from runs of formatted text.
+    ///
+    /// See [`StyledSpan`].
+    #[must_use]
+    pub fn from_styled_spans(metadata: Arc<[Metadata]>, spans: &[StyledSpan]) -> Self {
+        Self::new(metadata, styled::from_styled_spans(spans).into())
+    }
+
+    /// Returns a copy of the work with `f` applied to each of its [`Metadata`] entries.
+    #[must_use]
+    pub fn map_metadata(&self, f: impl FnMut(&Metadata) -> Metadata) -> Self {
+        Self::new(self.metadata.iter().map(f).collect(), self.tokens.clone())
+    }
+
+    /// Report how heavily the work is formatted.
+    ///
+    /// See [`Analysis`].
+    #[must_use]
+    pub fn analyze(&self) -> Analysis {
+        Analysis::new(&self.tokens)
+    }
+
+    /// Merge `ours` and `theirs`, two edited copies of `base`.
+    ///
+    /// See [`Merge`].
+    #[must_use]
+    pub fn merge(base: &Self, ours: &Self, theirs: &Self) -> Merge {
+        Merge::new(base, ours, theirs)
+    }
+
+    /// Joins `volumes` into one work, each starting on a new page, keeping the title of the first.
+    ///
+    /// See [`compose::Compose`] to write a title page for each volume or choose the title.
+    #[must_use]
+    pub fn concat(volumes: &[Self]) -> Self {
+        compose::Compose::new().apply(volumes)
+    }
+
+    /// Iterate over the pages of the work, each as its own [`TokenList`] sharing the work's
+    /// [`Metadata`].
+    ///
+    /// Pages are separated by [`Token::ThematicBreak`], which is not included in the pages. Any
+    /// content before the first [`Token::ThematicBreak`] is its own page.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::syntax::{Token, TokenList};
+    ///
+    /// let mut tokens = TokenList::new_from_boxed(Box::new([]), Box::new([]));
+    /// tokens.push_page([Token::Text("one".into())]);
+    /// tokens.push_page([Token::Text("two".into())]);
+    ///
+    /// let pages: Vec<TokenList> = tokens.pages_iter().collect();
+    /// assert_eq!(pages.len(), 2);
+    /// assert_eq!(pages[1].tokens_as_slice(), [Token::Text("two".into())]);
+    /// ```
+    pub fn pages_iter(&self) -> impl Iterator<Item = Self> + '_ {
+        self.page_slices()
+            .into_iter()
+            .map(|page| Self::new(self.metadata.clone(), page.into()))
+    }
+
+    /// Returns a copy of the work cut down to at most `n` visible characters.
+    ///
+    /// Characters of [`Token::Text`] and [`Token::Space`] count towards `n`, breaks do not. If the
+    /// work is longer than `n`, text is only cut between characters, [`Token::Format`]s left
+    /// dangling at the end are dropped, and a [`Format::Reset`][`minecraft::Format::Reset`] is
+    /// appended if any formatting is still active, so the excerpt is always closed correctly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::syntax::{minecraft::Format, Token, TokenList};
+    ///
+    /// let tokens = TokenList::from(vec![
+    ///     Token::Format(Format::Bold),
+    ///     Token::Text("naïve".into()),
+    ///     Token::Space,
+    ///     Token::Text("reader".into()),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     tokens.truncate_chars(4).tokens_as_slice(),
+    ///     [
+    ///         Token::Format(Format::Bold),
+    ///         Token::Text("naïv".into()),
+    ///         Token::Format(Format::Reset),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn truncate_chars(&self, n: usize) -> Self {
+        let length: usize = self
+            .tokens
+            .iter()
+            .map(|token| match token {
+                Token::Text(text) => text.chars().count(),
+                Token::Space => 1,
+                _ => 0,
+            })
+            .sum();
+        if length <= n {
+            return self.clone();
+        }
+
+        let mut tokens = Vec::new();
+        let mut remaining = n;
+
+        for token in self.tokens.iter() {
+            if remaining == 0 {
+                break;
+            }
+
+            match token {
+                Token::Text(text) => {
+                    if let Some((end, _)) = text.char_indices().nth(remaining) {
+                        tokens.push(Token::Text(text[..end].into()));
+                        remaining = 0;
+                    } else {
+                        tokens.push(token.clone());
+                        remaining -= text.chars().count();
+                    }
+                }
+                Token::Space => {
+                    tokens.push(Token::Space);
+                    remaining -= 1;
+                }
+                _ => tokens.push(token.clone()),
+            }
+        }
+
+        while matches!(tokens.last(), Some(Token::Format(_))) {
+            tokens.pop();
+        }
+
+        let state = tokens
+            .iter()
+            .fold(minecraft::FormatState::new(), |state, token| match token {
+                Token::Format(format) => state.with(*format),
+                _ => state,
+            });
+        if !state.is_plain() {
+            tokens.push(Token::Format(minecraft::Format::Reset));
+        }
+
+        Self::new(self.metadata.clone(), tokens.into())
+    }
+
+    /// Returns the [`Token`]s as a [`Vec`], discarding the [`Metadata`].
+    ///
+    /// For code written against the old `Vec<Token>` API.
+    #[must_use]
+    pub fn into_vec(self) -> Vec<Token> {
+        self.tokens.to_vec()
+    }
+
+    /// Append a new page to the end of the work.
+    ///
+    /// Inserts the [`Token::ThematicBreak`] that separates pages, followed by `tokens`.
+    ///
+    /// Like [`Extend`], this copies the whole work, so prefer building a [`Vec`] of tokens first
+    /// when adding many pages.
+    pub fn push_page(&mut self, tokens: impl IntoIterator<Item = Token>) {
+        self.extend(std::iter::once(Token::ThematicBreak).chain(tokens));
+    }
+}
+
+impl From<Vec<Token>> for TokenList {
+    /// Creates a new [`TokenList`] without any [`Metadata`].
+    ///
+    /// For code written against the old `Vec<Token>` API.
+    fn from(tokens: Vec<Token>) -> Self {
+        Self::new(Arc::new([]), tokens.into())
+    }
+}
+
+impl From<TokenList> for Vec<Token> {
+    /// Discards the [`Metadata`], keeping only the [`Token`]s.
+    ///
+    /// See [`TokenList::into_vec`].
+    fn from(tokens: TokenList) -> Self {
+        tokens.into_vec()
+    }
+}
+
+impl Extend<Token> for TokenList {
+    /// Append `iter` to the end of the internal [`Token`] slice.
+    ///
+    /// Because the slice is shared, this copies all of the existing tokens into a new allocation
+    /// on every call, so extending a work many times over takes quadratic time. To build a work
+    /// piece by piece, collect its tokens into a [`Vec`] first, then create the [`TokenList`] once
+    /// with [`TokenList::from`] or [`TokenList::new_from_boxed`].
+    fn extend<I: IntoIterator<Item = Token>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let mut tokens = Vec::with_capacity(self.tokens.len() + iter.size_hint().0);
+        tokens.extend_from_slice(&self.tokens);
+        tokens.extend(iter);
+
+        self.tokens = tokens.into();
+    }
+}
+
+impl Extend<Metadata> for TokenList {
+    /// Append `iter` to the end of the internal [`Metadata`] slice, skipping any entries that are
+    /// already present.
+    ///
+    /// Like extending the tokens, this copies the existing slice on every call.
+    fn extend<I: IntoIterator<Item = Metadata>>(&mut self, iter: I) {
+        let mut metadata = self.metadata.to_vec();
+
+        for data in iter {
+            if !metadata.contains(&data) {
+                metadata.push(data);
+            }
+        }
+
+        self.metadata = metadata.into();
+    }
+}
+
+impl Add for TokenList {
+    type Output = Self;
+
+    /// Concatenate two works.
+    ///
+    /// The tokens of `rhs` are placed after the tokens of `self`. The metadata of `rhs` is
+    /// appended to the metadata of `self`, skipping any entries that are already present. Like
+    /// [`compose::Compose`], the work keeps the title and author of `self`: those of `rhs` are only
+    /// used where `self` has none.
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for TokenList {
+    /// Concatenate `rhs` onto the end of `self`.
+    ///
+    /// Copies the tokens of both works into a new allocation. See [`TokenList::add`].
+    fn add_assign(&mut self, rhs: Self) {
+        let has = |kind: fn(&Metadata) -> bool| self.metadata.iter().any(kind);
+        let has_title = has(|data| matches!(data, Metadata::Title(_)));
+        let has_author = has(|data| matches!(data, Metadata::Author(_)));
+
+        self.extend(
+            rhs.metadata
+                .iter()
+                .filter(|data| match data {
+                    Metadata::Title(_) => !has_title,
+                    Metadata::Author(_) => !has_author,
+                    _ => true,
+                })
+                .cloned(),
+        );
+        self.extend(rhs.tokens.iter().cloned());
+    }
 }
 
 /// A lexical token.
 ///
 /// Represents an abstract representation of the text, formatting, structure, etc. of a document.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Token {
     /// Represents a string of plain text in the document.
     Text(Box<str>),
@@ -97,6 +396,26 @@ pub enum Token {
     ///
     /// Typically used to represent page breaks or topic shifts.
     ThematicBreak,
+    /// Represents an image placed inline with the text, like map art or an item icon.
+    ///
+    /// Formats that cannot display images use `alt` in its place.
+    Image {
+        /// The location of the image, typically a URL or a relative path.
+        src: Box<str>,
+        /// A text description of the image.
+        alt: Box<str>,
+    },
+    /// A hidden node to align the rest of the line it starts, up to the next break.
+    ///
+    /// Formats that cannot align text ignore it, and fixed-width formats pad the line with spaces
+    /// instead. See [`align::CenterDetector`] to find lines that were centered by hand.
+    Align(align::Alignment),
+    /// A hidden node to give the text after it a meaning, like a heading, up to the next
+    /// [`minecraft::Format::Reset`].
+    ///
+    /// Formats that cannot represent meanings ignore it. See [`role::ColorRoles`] to replace
+    /// colors that stand for a role.
+    Role(role::Role),
 }
 
 impl Token {
@@ -120,6 +439,12 @@ impl Token {
     pub const fn is_text(&self) -> bool {
         matches!(*self, Self::Text(_))
     }
+
+    /// Whether or not a [`Token`] is [`Token::Image`].
+    #[must_use]
+    pub const fn is_image(&self) -> bool {
+        matches!(*self, Self::Image { .. })
+    }
 }
 
 impl From<&mut Vec<char>> for Token {
@@ -130,10 +455,121 @@ impl From<&mut Vec<char>> for Token {
 }
 
 /// Metadata about a literary work.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Metadata {
     /// A title of a literary work.
     Title(Box<str>),
     /// An author of a literary work.
     Author(Box<str>),
+    /// When a literary work was written or published, as written by its author, like
+    /// `"2024-05-01"`.
+    Date(Box<str>),
+    /// A short summary of a literary work.
+    Description(Box<str>),
+    /// The language of a literary work, as a language tag like `"de"` or `"fr-CA"`.
+    Language(Box<str>),
+    /// The program that wrote a literary work, like `"Stendhal 1.3.6"`.
+    Generator(Box<str>),
+    /// Any other named field that an importer found but does not otherwise understand.
+    Custom {
+        /// The name of the field.
+        key: Box<str>,
+        /// The value of the field.
+        value: Box<str>,
+    },
+}
+
+impl Metadata {
+    /// Build the [`Metadata`] for a `key: value` field, where `key` is one of the names returned
+    /// by [`Self::key`], ignoring case, or else becomes a [`Self::Custom`] field as it is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use crafty_novels::syntax::Metadata;
+    ///
+    /// assert_eq!(Metadata::from_field("Date", "2024"), Metadata::Date("2024".into()));
+    /// assert_eq!(
+    ///     Metadata::from_field("series", "Lore"),
+    ///     Metadata::Custom { key: "series".into(), value: "Lore".into() },
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_field(key: &str, value: &str) -> Self {
+        let value = value.into();
+
+        match key.to_ascii_lowercase().as_str() {
+            "title" => Self::Title(value),
+            "author" => Self::Author(value),
+            "date" => Self::Date(value),
+            "description" => Self::Description(value),
+            "language" => Self::Language(value),
+            "generator" => Self::Generator(value),
+            _ => Self::Custom {
+                key: key.into(),
+                value,
+            },
+        }
+    }
+
+    /// Returns the name of the field, like `"title"` for [`Self::Title`], or the key of a
+    /// [`Self::Custom`] field.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Title(_) => "title",
+            Self::Author(_) => "author",
+            Self::Date(_) => "date",
+            Self::Description(_) => "description",
+            Self::Language(_) => "language",
+            Self::Generator(_) => "generator",
+            Self::Custom { key, .. } => key,
+        }
+    }
+
+    /// Returns the value of the field.
+    #[must_use]
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Title(value)
+            | Self::Author(value)
+            | Self::Date(value)
+            | Self::Description(value)
+            | Self::Language(value)
+            | Self::Generator(value)
+            | Self::Custom { value, .. } => value,
+        }
+    }
+
+    /// Returns the language tag named by this field, if it is a [`Self::Language`] or a
+    /// [`Self::Custom`] field named [`typography::LANGUAGE_KEY`].
+    #[must_use]
+    pub fn language(&self) -> Option<&str> {
+        match self {
+            Self::Language(language) => Some(language.trim()),
+            Self::Custom { key, value } if key.eq_ignore_ascii_case(typography::LANGUAGE_KEY) => {
+                Some(value.trim())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of this field with its value replaced by `value`.
+    #[must_use]
+    pub fn with_value(&self, value: impl Into<Box<str>>) -> Self {
+        let value = value.into();
+
+        match self {
+            Self::Title(_) => Self::Title(value),
+            Self::Author(_) => Self::Author(value),
+            Self::Date(_) => Self::Date(value),
+            Self::Description(_) => Self::Description(value),
+            Self::Language(_) => Self::Language(value),
+            Self::Generator(_) => Self::Generator(value),
+            Self::Custom { key, .. } => Self::Custom {
+                key: key.clone(),
+                value,
+            },
+        }
+    }
 }
@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Cleaning up the [`Metadata`] of a work.
+//!
+//! See [`MetadataNormalizer`].
+
+use super::{minecraft::strip_format_codes, Metadata, TokenList};
+
+/// Words that are not capitalized in English titles, unless they start or end the title.
+const ENGLISH_MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "onto", "or", "over", "so", "the", "to", "up", "via", "with", "yet",
+];
+
+/// Returns whether `char` separates words.
+///
+/// Full-width spaces (`'\u{3000}'`) are not, as they are part of the text of Japanese and Chinese
+/// works, like the indent at the start of a paragraph, rather than a gap between words.
+const fn is_separator(char: char) -> bool {
+    char.is_whitespace() && char != '\u{3000}'
+}
+
+/// The language rules to follow when title-casing text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TitleCaseLocale {
+    /// Capitalize the first letter of every word.
+    #[default]
+    Generic,
+    /// Capitalize the first letter of every word, except for minor words like "of" and "the" that
+    /// don't start or end the title.
+    English,
+    /// Capitalize the first letter of every word, treating "ij" as a single letter (`"IJsland"`).
+    Dutch,
+    /// Capitalize the first letter of every word, with dotted and dotless i ("İstanbul").
+    Turkish,
+}
+
+impl TitleCaseLocale {
+    /// Title-case `text` according to the rules of the locale.
+    ///
+    /// Only the first letter of each word is changed, so acronyms and names like `"McDonald"` are
+    /// kept intact.
+    #[must_use]
+    pub fn title_case(self, text: &str) -> String {
+        let word_count = text
+            .split(is_separator)
+            .filter(|word| !word.is_empty())
+            .count();
+        let mut output = String::with_capacity(text.len());
+        let mut word_index = 0;
+        let mut rest = text;
+
+        while !rest.is_empty() {
+            let word_end = rest.find(is_separator).unwrap_or(rest.len());
+            let (word, after) = rest.split_at(word_end);
+
+            if !word.is_empty() {
+                let is_edge = word_index == 0 || word_index + 1 == word_count;
+                self.push_word(&mut output, word, is_edge);
+                word_index += 1;
+            }
+
+            let space_end = after
+                .find(|char: char| !is_separator(char))
+                .unwrap_or(after.len());
+            output.push_str(&after[..space_end]);
+            rest = &after[space_end..];
+        }
+
+        output
+    }
+
+    /// Push a title-cased `word` onto `output`.
+    ///
+    /// `is_edge` is whether the word starts or ends the title.
+    fn push_word(self, output: &mut String, word: &str, is_edge: bool) {
+        if self == Self::English && !is_edge && ENGLISH_MINOR_WORDS.contains(&word) {
+            output.push_str(word);
+            return;
+        }
+
+        // Skip leading punctuation, like quotation marks
+        let Some(start) = word.find(char::is_alphabetic) else {
+            output.push_str(word);
+            return;
+        };
+        let (prefix, word) = word.split_at(start);
+        output.push_str(prefix);
+
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else {
+            return;
+        };
+
+        match (self, first) {
+            (Self::Turkish, 'i') => output.push('İ'),
+            (Self::Turkish, 'ı') => output.push('I'),
+            (Self::Dutch, 'i') if chars.as_str().starts_with('j') => {
+                output.push_str("IJ");
+                chars.next();
+            }
+            _ => output.extend(first.to_uppercase()),
+        }
+
+        output.push_str(chars.as_str());
+    }
+}
+
+/// Cleans up the values of [`Metadata`].
+///
+/// Raw exports frequently contain stray whitespace and format codes in fields like the title,
+/// which look wrong outside of Minecraft.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     normalize::{MetadataNormalizer, TitleCaseLocale},
+///     Metadata, TokenList,
+/// };
+///
+/// let tokens = TokenList::new_from_boxed(
+///     Box::new([
+///         Metadata::Title("  §6the   lord of §lthe  rings ".into()),
+///         Metadata::Author(" RemasteredArch".into()),
+///     ]),
+///     Box::new([]),
+/// );
+///
+/// let normalizer = MetadataNormalizer::new().with_title_case(Some(TitleCaseLocale::English));
+///
+/// assert_eq!(
+///     normalizer.apply(&tokens).metadata_as_slice(),
+///     [
+///         Metadata::Title("The Lord of the Rings".into()),
+///         Metadata::Author("RemasteredArch".into()),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)] // Each is an independent step
+pub struct MetadataNormalizer {
+    /// Whether to remove format codes.
+    strip_format_codes: bool,
+    /// Whether to remove leading and trailing whitespace.
+    trim: bool,
+    /// Whether to replace runs of whitespace with a single space.
+    collapse_whitespace: bool,
+    /// How to title-case titles, if at all.
+    title_case: Option<TitleCaseLocale>,
+}
+
+impl MetadataNormalizer {
+    /// Creates a new [`MetadataNormalizer`] that strips format codes, trims whitespace, and
+    /// collapses whitespace, but does not title-case titles.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            strip_format_codes: true,
+            trim: true,
+            collapse_whitespace: true,
+            title_case: None,
+        }
+    }
+
+    /// Sets whether to remove format codes.
+    #[must_use]
+    pub const fn with_strip_format_codes(mut self, strip_format_codes: bool) -> Self {
+        self.strip_format_codes = strip_format_codes;
+        self
+    }
+
+    /// Sets whether to remove leading and trailing whitespace.
+    #[must_use]
+    pub const fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Sets whether to replace runs of whitespace with a single space.
+    ///
+    /// Full-width spaces between other characters are kept as they are.
+    #[must_use]
+    pub const fn with_collapse_whitespace(mut self, collapse_whitespace: bool) -> Self {
+        self.collapse_whitespace = collapse_whitespace;
+        self
+    }
+
+    /// Sets how to title-case titles, if at all.
+    #[must_use]
+    pub const fn with_title_case(mut self, title_case: Option<TitleCaseLocale>) -> Self {
+        self.title_case = title_case;
+        self
+    }
+
+    /// Normalize a single [`Metadata`] entry.
+    #[must_use]
+    pub fn normalize(&self, metadata: &Metadata) -> Metadata {
+        match metadata {
+            Metadata::Title(title) => {
+                let title = self.normalize_str(title);
+                Metadata::Title(
+                    match self.title_case {
+                        Some(locale) => locale.title_case(&title),
+                        None => title,
+                    }
+                    .into(),
+                )
+            }
+            other => other.with_value(self.normalize_str(other.value())),
+        }
+    }
+
+    /// Returns a copy of `tokens` with all of its [`Metadata`] normalized.
+    #[must_use]
+    pub fn apply(&self, tokens: &TokenList) -> TokenList {
+        tokens.map_metadata(|metadata| self.normalize(metadata))
+    }
+
+    /// Apply the steps that are common to all [`Metadata`].
+    fn normalize_str(self, value: &str) -> String {
+        let mut value = if self.strip_format_codes {
+            strip_format_codes(value)
+        } else {
+            value.to_owned()
+        };
+
+        if self.collapse_whitespace {
+            let trailing = value.ends_with(is_separator);
+            let mut collapsed = if value.starts_with(is_separator) {
+                String::from(" ")
+            } else {
+                String::new()
+            };
+
+            let words: Vec<_> = value
+                .split(is_separator)
+                .filter(|word| !word.is_empty())
+                .collect();
+            collapsed.push_str(&words.join(" "));
+
+            if trailing && collapsed != " " {
+                collapsed.push(' ');
+            }
+            value = collapsed;
+        }
+
+        if self.trim {
+            value = value.trim().to_owned();
+        }
+
+        value
+    }
+}
+
+impl Default for MetadataNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Recording where a converted work came from.
+//!
+//! See [`Provenance`].
+
+use super::{Metadata, TokenList};
+use serde_json::json;
+
+pub mod sha256;
+
+/// The prefix of the keys of the [`Metadata::Custom`] entries written by [`Provenance::apply`].
+pub const KEY_PREFIX: &str = "provenance-";
+
+/// Where a converted work came from, so that archived conversions can be traced back to their
+/// inputs.
+///
+/// Records the SHA-256 digest of the input, the version of crafty_novels, and optionally the name
+/// of the source file and a description of the options used. It can be written into the work as
+/// [`Metadata::Custom`] entries, which the HTML exporter writes as `<meta>` tags, or as a JSON
+/// sidecar file.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     export::Html,
+///     import::Stendhal,
+///     syntax::provenance::Provenance,
+///     Export, Tokenize,
+/// };
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let input = "title: crafty_novels
+/// author: RemasteredArch
+/// pages:
+/// #- Page one";
+///
+/// let provenance = Provenance::new(input.as_bytes()).with_source(Some("book.stendhal".into()));
+/// let tokens = provenance.apply(&Stendhal::tokenize_string(input)?);
+///
+/// let html = Html::export_token_vector_to_string(tokens);
+/// assert!(html.contains(r#"<meta name="provenance-source" content="book.stendhal" />"#));
+/// assert!(html.contains(&format!(
+///     r#"<meta name="provenance-sha256" content="{}" />"#,
+///     provenance.sha256()
+/// )));
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Provenance {
+    /// The name of the source file, if any.
+    source: Option<Box<str>>,
+    /// The SHA-256 digest of the input, as lowercase hexadecimal.
+    sha256: Box<str>,
+    /// The version of crafty_novels that converted the work.
+    converter: Box<str>,
+    /// A description of the options used, if any.
+    options: Option<Box<str>>,
+}
+
+impl Provenance {
+    /// Creates a new [`Provenance`] for `input`, the raw bytes of the work before it was imported.
+    #[must_use]
+    pub fn new(input: &[u8]) -> Self {
+        Self {
+            source: None,
+            sha256: sha256::hex_digest(input).into(),
+            converter: concat!("crafty_novels ", env!("CARGO_PKG_VERSION")).into(),
+            options: None,
+        }
+    }
+
+    /// Sets the name of the source file, if any.
+    #[must_use]
+    pub fn with_source(mut self, source: Option<Box<str>>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets the description of the options used, if any, ex. `"stendhal to html"`.
+    #[must_use]
+    pub fn with_options(mut self, options: Option<Box<str>>) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns the name of the source file, if any.
+    #[must_use]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns the SHA-256 digest of the input, as lowercase hexadecimal.
+    #[must_use]
+    pub const fn sha256(&self) -> &str {
+        &self.sha256
+    }
+
+    /// Returns the name and version of the converter, ex. `"crafty_novels 0.1.0"`.
+    #[must_use]
+    pub const fn converter(&self) -> &str {
+        &self.converter
+    }
+
+    /// Returns the description of the options used, if any.
+    #[must_use]
+    pub fn options(&self) -> Option<&str> {
+        self.options.as_deref()
+    }
+
+    /// Returns the provenance as [`Metadata::Custom`] entries, with keys starting with
+    /// [`KEY_PREFIX`].
+    #[must_use]
+    pub fn to_metadata(&self) -> Vec<Metadata> {
+        [
+            ("source", self.source()),
+            ("sha256", Some(self.sha256())),
+            ("converter", Some(self.converter())),
+            ("options", self.options()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| {
+            value.map(|value| Metadata::Custom {
+                key: format!("{KEY_PREFIX}{key}").into(),
+                value: value.into(),
+            })
+        })
+        .collect()
+    }
+
+    /// Returns a copy of `tokens` with the provenance added to the end of its [`Metadata`].
+    #[must_use]
+    pub fn apply(&self, tokens: &TokenList) -> TokenList {
+        let mut tokens = tokens.clone();
+        tokens.extend(self.to_metadata());
+        tokens
+    }
+
+    /// Returns the provenance as a JSON object, for writing into a sidecar file next to the
+    /// output.
+    ///
+    /// Missing fields are written as `null`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        json!({
+            "source": self.source(),
+            "sha256": self.sha256(),
+            "converter": self.converter(),
+            "options": self.options(),
+        })
+        .to_string()
+    }
+}
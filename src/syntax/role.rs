@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Giving text a meaning, like a heading, rather than just a look.
+//!
+//! See [`Role`] and [`ColorRoles`].
+
+use super::{
+    minecraft::{Color, Format},
+    Token, TokenList,
+};
+
+/// What a run of text means, see [`Token::Role`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Role {
+    /// The most important heading.
+    Heading1,
+    /// A second-level heading.
+    Heading2,
+    /// A third-level heading.
+    Heading3,
+    /// A fourth-level heading.
+    Heading4,
+    /// A fifth-level heading.
+    Heading5,
+    /// The least important heading.
+    Heading6,
+    /// Important text, like a warning.
+    Strong,
+    /// Stressed text.
+    Emphasis,
+    /// Side comments and fine print.
+    Small,
+    /// Highlighted text.
+    Mark,
+    /// The title of a work.
+    Cite,
+    /// A short quotation.
+    Quote,
+}
+
+/// A mapping from [`Color`]s to the [`Role`]s that they stand for.
+///
+/// Many authors use colors with a consistent meaning, like gold for headings or gray for asides.
+/// [`Self::apply`] replaces each mapped color with a [`Token::Role`], so that exports that can
+/// represent roles write the meaning instead of the color.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     minecraft::{Color, Format},
+///     role::{ColorRoles, Role},
+///     Token, TokenList,
+/// };
+///
+/// let tokens = TokenList::from(vec![
+///     Token::Format(Format::Color(Color::Gold)),
+///     Token::Text("Chapter".into()),
+///     Token::Format(Format::Reset),
+///     Token::Format(Format::Color(Color::Red)),
+///     Token::Text("red".into()),
+/// ]);
+///
+/// let roles = ColorRoles::new().with(Color::Gold, Role::Heading2);
+/// assert_eq!(
+///     roles.apply(&tokens).tokens_as_slice(),
+///     [
+///         Token::Role(Role::Heading2),
+///         Token::Text("Chapter".into()),
+///         Token::Format(Format::Reset),
+///         Token::Format(Format::Color(Color::Red)),
+///         Token::Text("red".into()),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ColorRoles {
+    /// The role that each mapped color stands for.
+    roles: Vec<(Color, Role)>,
+}
+
+impl ColorRoles {
+    /// Creates a new [`ColorRoles`] without any mappings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { roles: vec![] }
+    }
+
+    /// Maps a [`Color`] to a [`Role`], replacing any previous mapping.
+    #[must_use]
+    pub fn with(mut self, color: Color, role: Role) -> Self {
+        self.roles.retain(|(existing, _)| *existing != color);
+        self.roles.push((color, role));
+        self
+    }
+
+    /// Returns the [`Role`] that a [`Color`] stands for, if it is mapped.
+    #[must_use]
+    pub fn get(&self, color: Color) -> Option<Role> {
+        self.roles
+            .iter()
+            .find(|(existing, _)| *existing == color)
+            .map(|(_, role)| *role)
+    }
+
+    /// Returns a copy of `tokens` with each [`Format::Color`] of a mapped color replaced by a
+    /// [`Token::Role`].
+    #[must_use]
+    pub fn apply(&self, tokens: &TokenList) -> TokenList {
+        let replaced = tokens
+            .tokens_as_slice()
+            .iter()
+            .map(|token| match token {
+                Token::Format(Format::Color(color)) => {
+                    self.get(*color).map_or_else(|| token.clone(), Token::Role)
+                }
+                _ => token.clone(),
+            })
+            .collect();
+
+        TokenList::new(tokens.metadata(), replaced)
+    }
+}
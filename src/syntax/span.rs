@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Where in the input of an importer something was found.
+//!
+//! See [`Position`] and [`Span`].
+
+use std::fmt::Display;
+
+/// A position in the input of an importer, numbering lines and columns from one, like a text
+/// editor does.
+///
+/// Columns count [`char`]s, not bytes, so `"§"` is one column wide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// The line number, starting from one.
+    line: usize,
+    /// The column number, starting from one.
+    column: usize,
+}
+
+impl Position {
+    /// Creates a new [`Position`] from a line and column number, both starting from one.
+    #[must_use]
+    pub const fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+
+    /// Returns the line number, starting from one.
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number, starting from one.
+    #[must_use]
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl Display for Position {
+    /// Ex. `"line 4, column 7"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// The part of the input of an importer that a [`Token`][`super::Token`] was parsed from.
+///
+/// Tokens that the importer inserted, like the [`Token::LineBreak`][`super::Token::LineBreak`] at
+/// the end of each line, have an empty span where they were inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    /// The position of the first character.
+    start: Position,
+    /// The position just after the last character.
+    end: Position,
+}
+
+impl Span {
+    /// Creates a new [`Span`] from the position of its first character, and the position just
+    /// after its last.
+    #[must_use]
+    pub const fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the position of the first character.
+    #[must_use]
+    pub const fn start(&self) -> Position {
+        self.start
+    }
+
+    /// Returns the position just after the last character.
+    #[must_use]
+    pub const fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Returns whether the span contains no characters.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.start.line == self.end.line && self.start.column == self.end.column
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tokens that are parsed lazily, one at a time.
+//!
+//! See [`TokenStream`].
+
+use super::{Metadata, Token, TokenList};
+
+/// The [`Metadata`] of a work, followed by its [`Token`]s as they are parsed.
+///
+/// Returned by [`Tokenize::token_iter`][`crate::Tokenize::token_iter`] and accepted by
+/// [`Export::export_token_iter_to_writer`][`crate::Export::export_token_iter_to_writer`], so that
+/// huge works can be converted without ever holding all of their tokens in memory.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{stream::TokenStream, Metadata, Token};
+///
+/// let stream = TokenStream::new(
+///     Box::new([Metadata::Title("crafty_novels".into())]),
+///     [Token::Text("one".into()), Token::LineBreak].into_iter().map(Ok::<_, ()>),
+/// );
+///
+/// assert_eq!(stream.metadata(), [Metadata::Title("crafty_novels".into())]);
+/// assert_eq!(stream.count(), 2);
+/// ```
+pub struct TokenStream<'a, E> {
+    /// Meta information about the work, which is always parsed up front.
+    metadata: Box<[Metadata]>,
+    /// The tokens of the work, or the error that stopped parsing them.
+    tokens: Box<dyn Iterator<Item = Result<Token, E>> + 'a>,
+}
+
+impl<'a, E> TokenStream<'a, E> {
+    /// Creates a new [`TokenStream`].
+    #[must_use]
+    pub fn new(
+        metadata: Box<[Metadata]>,
+        tokens: impl Iterator<Item = Result<Token, E>> + 'a,
+    ) -> Self {
+        Self {
+            metadata,
+            tokens: Box::new(tokens),
+        }
+    }
+
+    /// Returns a shared reference to the [`Metadata`] of the work.
+    #[must_use]
+    pub fn metadata(&self) -> &[Metadata] {
+        &self.metadata
+    }
+
+    /// Parse the rest of the tokens, collecting them into a [`TokenList`].
+    ///
+    /// # Errors
+    ///
+    /// - `E` if any token fails to parse
+    pub fn collect_token_list(self) -> Result<TokenList, E> {
+        let tokens = self.tokens.collect::<Result<Vec<Token>, E>>()?;
+
+        Ok(TokenList::new_from_boxed(self.metadata, tokens.into()))
+    }
+}
+
+impl<'a, E: 'a> From<TokenList> for TokenStream<'a, E> {
+    /// Creates a [`TokenStream`] over the tokens of a [`TokenList`], which is already fully
+    /// parsed.
+    fn from(value: TokenList) -> Self {
+        Self::new(
+            value.metadata_as_slice().into(),
+            value.into_vec().into_iter().map(Ok),
+        )
+    }
+}
+
+impl<E> Iterator for TokenStream<'_, E> {
+    type Item = Result<Token, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokens.next()
+    }
+}
+
+/// All the errors that could occur while exporting a [`TokenStream`].
+#[derive(thiserror::Error, Debug)]
+pub enum StreamError<E> {
+    /// Encountered when a token of the stream fails to parse.
+    #[error("could not tokenize input: {0}")]
+    Tokenize(E),
+    /// Encoutered when an I/O action fails in some way.
+    #[error("could not perform I/O action: {0}")]
+    Io(#[from] std::io::Error),
+    /// Encountered when writing fails partway through the work, like when the disk is full.
+    ///
+    /// From an exporter that flushes each page as it finishes, like
+    /// [`PlainText`][`crate::export::PlainText`], everything up to the end of the written pages
+    /// is in the output, followed by part of the next page. To resume, cut the output down to
+    /// `written_bytes`, then export the rest of the stream, skipping the first `written_tokens`
+    /// tokens.
+    ///
+    /// Other exporters don't track pages, so `written_pages` and `written_tokens` are zero,
+    /// `written_bytes` is everything written before the failure, and the export can only be
+    /// retried from the start.
+    #[error("could not write page {}: {source}", written_pages + 1)]
+    Interrupted {
+        /// How many pages were written in full.
+        written_pages: usize,
+        /// How many tokens of the stream were consumed by the written pages, including the page
+        /// break after the last of them.
+        written_tokens: usize,
+        /// How many bytes of output the written pages take up, or, if no pages are tracked, how
+        /// many bytes were written.
+        written_bytes: usize,
+        /// What went wrong while writing.
+        source: std::io::Error,
+    },
+}
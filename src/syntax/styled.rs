@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runs of text with resolved formatting.
+//!
+//! See [`StyledSpan`].
+
+use super::{
+    minecraft::{Format, FormatState},
+    Token,
+};
+
+/// The character that represents a [`Token::LineBreak`] or [`Token::ParagraphBreak`] in a
+/// [`StyledSpan`].
+pub const LINE_BREAK: char = '\n';
+/// The character that represents a [`Token::ThematicBreak`] in a [`StyledSpan`] (form feed).
+pub const THEMATIC_BREAK: char = '\u{c}';
+
+/// A run of text that shares the same formatting.
+///
+/// An alternative to the stream of stateful [`Token::Format`]s in a
+/// [`TokenList`][`super::TokenList`], for consumers that would rather not track formatting
+/// themselves.
+///
+/// Structure is represented inside of the text:
+///
+/// - [`Token::Space`] is `' '`
+/// - [`Token::LineBreak`] and [`Token::ParagraphBreak`] are [`LINE_BREAK`] (`'\n'`)
+///     - When converting back into [`Token`]s, a [`LINE_BREAK`] at the start of a line becomes a
+///       [`Token::ParagraphBreak`]
+/// - [`Token::ThematicBreak`] is [`THEMATIC_BREAK`] (`'\u{c}'`)
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     minecraft::{Format, FormatState},
+///     StyledSpan, Token, TokenList,
+/// };
+///
+/// let tokens = TokenList::new_from_boxed(
+///     Box::new([]),
+///     Box::new([
+///         Token::Text("Italic:".into()),
+///         Token::Format(Format::Italic),
+///         Token::Space,
+///         Token::Text("text".into()),
+///         Token::LineBreak,
+///     ]),
+/// );
+///
+/// assert_eq!(
+///     tokens.to_styled_spans(),
+///     [
+///         StyledSpan::new("Italic:", FormatState::new()),
+///         StyledSpan::new(" text\n", FormatState::new().with(Format::Italic)),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StyledSpan {
+    /// The text of the span.
+    text: Box<str>,
+    /// The formatting that applies to all of [`Self::text`].
+    format: FormatState,
+}
+
+impl StyledSpan {
+    /// Creates a new [`StyledSpan`].
+    #[must_use]
+    pub fn new(text: impl Into<Box<str>>, format: FormatState) -> Self {
+        Self {
+            text: text.into(),
+            format,
+        }
+    }
+
+    /// Returns the text of the span.
+    #[must_use]
+    pub const fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the formatting that applies to all of the text of the span.
+    #[must_use]
+    pub const fn format(&self) -> FormatState {
+        self.format
+    }
+}
+
+/// Resolve a stream of [`Token`]s into runs of text that share the same formatting.
+///
+/// Adjacent tokens with the same formatting are merged into a single [`StyledSpan`]. A
+/// [`Format::Unknown`] does not affect the formatting, and is dropped.
+#[must_use]
+pub fn to_styled_spans(tokens: &[Token]) -> Vec<StyledSpan> {
+    let mut spans: Vec<StyledSpan> = vec![];
+    let mut state = FormatState::new();
+    let mut text = String::new();
+
+    for token in tokens {
+        let next_char = match token {
+            Token::Format(format) => {
+                let next_state = state.with(*format);
+
+                if next_state != state && !text.is_empty() {
+                    spans.push(StyledSpan::new(std::mem::take(&mut text), state));
+                }
+
+                state = next_state;
+                continue;
+            }
+            Token::Text(s) | Token::Image { alt: s, .. } => {
+                text.push_str(s);
+                continue;
+            }
+            Token::Align(_) | Token::Role(_) => continue,
+            Token::Space => ' ',
+            Token::LineBreak | Token::ParagraphBreak => LINE_BREAK,
+            Token::ThematicBreak => THEMATIC_BREAK,
+        };
+
+        text.push(next_char);
+    }
+
+    if !text.is_empty() {
+        spans.push(StyledSpan::new(text, state));
+    }
+
+    spans
+}
+
+/// Convert runs of formatted text back into a stream of [`Token`]s.
+///
+/// Only emits [`Format::Reset`] when formatting needs to be removed, and otherwise only emits the
+/// [`Format`]s that are added between spans.
+#[must_use]
+pub fn from_styled_spans(spans: &[StyledSpan]) -> Vec<Token> {
+    /// Flush the current word into a text node.
+    fn flush(output: &mut Vec<Token>, word: &mut String) {
+        if !word.is_empty() {
+            output.push(Token::Text(std::mem::take(word).into_boxed_str()));
+        }
+    }
+
+    let mut output: Vec<Token> = vec![];
+    let mut state = FormatState::new();
+    // Whether or not the output is at the start of a line, where a line break is a paragraph break
+    let mut line_start = true;
+
+    for span in spans {
+        let next_state = span.format();
+
+        if state != next_state {
+            let added = if state.is_subset_of(&next_state) {
+                let existing = state.formats();
+                next_state
+                    .formats()
+                    .into_iter()
+                    .filter(|format| !existing.contains(format))
+                    .collect()
+            } else {
+                output.push(Token::Format(Format::Reset));
+                next_state.formats()
+            };
+
+            output.extend(added.into_iter().map(Token::Format));
+            state = next_state;
+        }
+
+        let mut word = String::new();
+        for char in span.text().chars() {
+            let token = match char {
+                ' ' => Token::Space,
+                LINE_BREAK if line_start => Token::ParagraphBreak,
+                LINE_BREAK => Token::LineBreak,
+                THEMATIC_BREAK => Token::ThematicBreak,
+                _ => {
+                    word.push(char);
+                    line_start = false;
+                    continue;
+                }
+            };
+
+            flush(&mut output, &mut word);
+            line_start = matches!(token, Token::LineBreak | Token::ParagraphBreak);
+            output.push(token);
+        }
+        flush(&mut output, &mut word);
+    }
+
+    output
+}
@@ -0,0 +1,1527 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for [`TokenList`].
+
+use super::{Metadata, Token, TokenList};
+
+/// Insert a [`Token::Text`] with the given string.
+macro_rules! text {
+    ($text:expr) => {
+        Token::Text($text.into())
+    };
+}
+
+/// Build a [`TokenList`] out of a [`Metadata`] array and a [`Token`] array.
+macro_rules! token_list {
+    ( [ $( $metadata:expr ),* $(,)? ], [ $( $token:expr ),* $(,)? ] ) => {
+        TokenList::new_from_boxed(Box::new([ $( $metadata ),* ]), Box::new([ $( $token ),* ]))
+    };
+}
+
+#[test]
+fn extend_tokens() {
+    let mut list = token_list!([], [text!("one"), Token::Space]);
+    list.extend([text!("two"), Token::LineBreak]);
+
+    assert_eq!(
+        list,
+        token_list!(
+            [],
+            [text!("one"), Token::Space, text!("two"), Token::LineBreak]
+        )
+    );
+}
+
+#[test]
+fn push_page() {
+    let mut list = token_list!([], [Token::ThematicBreak, text!("first")]);
+    list.push_page([text!("second")]);
+    list.push_page([]);
+
+    assert_eq!(
+        list,
+        token_list!(
+            [],
+            [
+                Token::ThematicBreak,
+                text!("first"),
+                Token::ThematicBreak,
+                text!("second"),
+                Token::ThematicBreak,
+            ]
+        )
+    );
+}
+
+#[test]
+fn add_token_lists() {
+    let first = token_list!(
+        [
+            Metadata::Title("crafty_novels".into()),
+            Metadata::Author("RemasteredArch".into()),
+        ],
+        [Token::ThematicBreak, text!("first")]
+    );
+    let second = token_list!(
+        [
+            Metadata::Title("crafty_novels, part two".into()),
+            Metadata::Author("Someone else".into()),
+            Metadata::Date("2024-05-01".into()),
+        ],
+        [Token::ThematicBreak, text!("second")]
+    );
+
+    // Only the title and author of the first work are kept
+    let expects = token_list!(
+        [
+            Metadata::Title("crafty_novels".into()),
+            Metadata::Author("RemasteredArch".into()),
+            Metadata::Date("2024-05-01".into()),
+        ],
+        [
+            Token::ThematicBreak,
+            text!("first"),
+            Token::ThematicBreak,
+            text!("second"),
+        ]
+    );
+
+    assert_eq!(first.clone() + second.clone(), expects);
+
+    let mut sum = first;
+    sum += second.clone();
+    assert_eq!(sum, expects);
+
+    // Unless it has none
+    let untitled = token_list!([], [text!("untitled")]);
+    assert_eq!(
+        (untitled + second.clone()).metadata_as_slice(),
+        second.metadata_as_slice()
+    );
+}
+
+#[test]
+fn styled_spans_round_trip() {
+    use super::{
+        minecraft::{Color, Format, FormatState},
+        StyledSpan,
+    };
+
+    let list = token_list!(
+        [],
+        [
+            Token::ParagraphBreak,
+            Token::ThematicBreak,
+            Token::LineBreak,
+            text!("Some"),
+            Token::Space,
+            Token::Format(Format::Color(Color::Red)),
+            text!("RED"),
+            Token::Format(Format::Bold),
+            Token::Space,
+            text!("text"),
+            Token::Format(Format::Reset),
+            Token::LineBreak,
+            Token::ParagraphBreak,
+            Token::Format(Format::Italic),
+            text!("italic"),
+            Token::Format(Format::Reset),
+            Token::LineBreak,
+        ]
+    );
+    let red = FormatState::new().with(Format::Color(Color::Red));
+
+    let spans = list.to_styled_spans();
+    assert_eq!(
+        spans,
+        [
+            StyledSpan::new("\n\u{c}\nSome ", FormatState::new()),
+            StyledSpan::new("RED", red),
+            StyledSpan::new(" text", red.with(Format::Bold)),
+            StyledSpan::new("\n\n", FormatState::new()),
+            StyledSpan::new("italic", FormatState::new().with(Format::Italic)),
+            StyledSpan::new("\n", FormatState::new()),
+        ]
+    );
+
+    assert_eq!(TokenList::from_styled_spans(list.metadata(), &spans), list);
+}
+
+#[test]
+fn merge() {
+    use super::merge::{OURS_MARKER, SEPARATOR_MARKER, THEIRS_MARKER};
+
+    let base = token_list!(
+        [Metadata::Title("crafty_novels".into())],
+        [
+            text!("one"),
+            Token::LineBreak,
+            text!("two"),
+            Token::LineBreak,
+            text!("three"),
+            Token::ThematicBreak,
+        ]
+    );
+    let ours = token_list!(
+        [Metadata::Title("crafty_novels".into())],
+        [
+            text!("one"),
+            Token::Space,
+            text!("edited"),
+            Token::LineBreak,
+            text!("two"),
+            Token::LineBreak,
+            text!("three"),
+            Token::ThematicBreak,
+        ]
+    );
+    let theirs = token_list!(
+        [Metadata::Title("crafty_novels, revised".into())],
+        [
+            text!("one"),
+            Token::LineBreak,
+            text!("two"),
+            Token::LineBreak,
+            text!("three"),
+            Token::ThematicBreak,
+            text!("four"),
+        ]
+    );
+
+    // Changes to different lines merge cleanly
+    let merge = TokenList::merge(&base, &ours, &theirs);
+    assert!(merge.is_clean());
+    assert_eq!(
+        merge.to_token_list(),
+        token_list!(
+            [Metadata::Title("crafty_novels, revised".into())],
+            [
+                text!("one"),
+                Token::Space,
+                text!("edited"),
+                Token::LineBreak,
+                text!("two"),
+                Token::LineBreak,
+                text!("three"),
+                Token::ThematicBreak,
+                text!("four"),
+            ]
+        )
+    );
+
+    // Different changes to the same line conflict
+    let theirs = token_list!(
+        [Metadata::Title("crafty_novels".into())],
+        [
+            text!("uno"),
+            Token::LineBreak,
+            text!("two"),
+            Token::LineBreak,
+            text!("three"),
+            Token::ThematicBreak,
+        ]
+    );
+    let merge = TokenList::merge(&base, &ours, &theirs);
+    assert_eq!(merge.conflicts().count(), 1);
+    assert_eq!(merge.metadata_conflicts().count(), 0);
+    assert_eq!(
+        merge.to_token_list(),
+        token_list!(
+            [Metadata::Title("crafty_novels".into())],
+            [
+                text!(OURS_MARKER),
+                Token::Space,
+                text!("ours"),
+                Token::LineBreak,
+                text!("one"),
+                Token::Space,
+                text!("edited"),
+                Token::LineBreak,
+                text!(SEPARATOR_MARKER),
+                Token::LineBreak,
+                text!("uno"),
+                Token::LineBreak,
+                text!(THEIRS_MARKER),
+                Token::Space,
+                text!("theirs"),
+                Token::LineBreak,
+                text!("two"),
+                Token::LineBreak,
+                text!("three"),
+                Token::ThematicBreak,
+            ]
+        )
+    );
+}
+
+#[test]
+fn title_case_locales() {
+    use super::normalize::TitleCaseLocale;
+
+    for (locale, input, expects) in [
+        (
+            TitleCaseLocale::Generic,
+            "the lord of the rings",
+            "The Lord Of The Rings",
+        ),
+        (
+            TitleCaseLocale::English,
+            "the lord of the rings",
+            "The Lord of the Rings",
+        ),
+        (TitleCaseLocale::English, "what it is for", "What It Is For"),
+        (
+            TitleCaseLocale::English,
+            "\"quoted\"  NBT",
+            "\"Quoted\"  NBT",
+        ),
+        (
+            TitleCaseLocale::Dutch,
+            "reis naar ijsland",
+            "Reis Naar IJsland",
+        ),
+        (TitleCaseLocale::Turkish, "istanbul ılık", "İstanbul Ilık"),
+    ] {
+        assert_eq!(locale.title_case(input), expects);
+    }
+}
+
+#[test]
+fn normalize_full_width_spaces() {
+    use super::{normalize::MetadataNormalizer, Metadata};
+
+    let normalizer = MetadataNormalizer::new();
+
+    assert_eq!(
+        normalizer.normalize(&Metadata::Title(" 吾輩は\u{3000}猫 \t である ".into())),
+        Metadata::Title("吾輩は\u{3000}猫 である".into())
+    );
+}
+
+#[test]
+fn title_page() {
+    use super::title_page::TitlePageDetector;
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let title_page = [
+        ThematicBreak,
+        Space,
+        Space,
+        text!("§6Golden"),
+        Space,
+        text!("chronicle"),
+        ParagraphBreak,
+        text!("by"),
+        Space,
+        text!("RemasteredArch"),
+        LineBreak,
+        text!("Book"),
+        Space,
+        text!("One"),
+    ];
+    let with_title = |title: &str, tokens: Vec<Token>| {
+        TokenList::new_from_boxed(Box::new([Metadata::Title(title.into())]), tokens.into())
+    };
+    let list = with_title(
+        "Golden Chronicle",
+        [&title_page[..], &[ThematicBreak, text!("Once")]].concat(),
+    );
+
+    let detector = TitlePageDetector::new();
+    let detected = detector.detect(&list).expect("should find the title page");
+    assert_eq!(detected.title(), "Golden chronicle");
+    assert_eq!(detected.author(), Some("RemasteredArch"));
+    assert_eq!(detected.subtitle().as_deref(), Some("Book One"));
+
+    assert_eq!(
+        detector.apply(&list),
+        token_list!(
+            [
+                Metadata::Title("Golden Chronicle".into()),
+                Metadata::Author("RemasteredArch".into()),
+                Metadata::Custom {
+                    key: "subtitle".into(),
+                    value: "Book One".into()
+                },
+            ],
+            [ThematicBreak, text!("Once")]
+        )
+    );
+
+    // Opted out
+    let disabled = detector.with_enabled(false);
+    assert_eq!(disabled.detect(&list), None);
+    assert_eq!(disabled.apply(&list), list);
+
+    // The only page
+    assert_eq!(
+        detector.detect(&with_title("Golden Chronicle", title_page.to_vec())),
+        None
+    );
+
+    // Doesn't contain the title
+    let other_title = with_title("Silver Saga", [&title_page[..], &[ThematicBreak]].concat());
+    assert_eq!(detector.detect(&other_title), None);
+
+    // Too long to be a title page
+    let prose = with_title(
+        "Golden Chronicle",
+        [
+            &title_page[..],
+            &[
+                LineBreak,
+                text!("It was a dark and stormy night, and the rain fell in torrents"),
+                LineBreak,
+                text!("except at occasional intervals, when it was checked by a violent gust"),
+                LineBreak,
+                text!("of wind which swept up the streets, rattling along the housetops"),
+                LineBreak,
+                text!("and fiercely agitating the scanty flame of the lamps"),
+                ThematicBreak,
+            ],
+        ]
+        .concat(),
+    );
+    assert_eq!(detector.detect(&prose), None);
+}
+
+#[test]
+fn analysis() {
+    use super::{
+        analysis::FormattingCounts,
+        minecraft::{Color, Format},
+    };
+    use Token::{Space, ThematicBreak};
+
+    let list = token_list!(
+        [],
+        [
+            text!("lead"),
+            ThematicBreak,
+            Token::Format(Format::Color(Color::Gold)),
+            Token::Format(Format::Bold),
+            text!("loud"),
+            Space,
+            Token::Format(Format::Reset),
+            text!("calm"),
+            ThematicBreak,
+            Token::Format(Format::Italic),
+            text!("whisper"),
+            ThematicBreak,
+            text!("plain"),
+        ]
+    );
+    let analysis = list.analyze();
+
+    let total = analysis.total();
+    assert_eq!(total.characters(), 24);
+    assert_eq!(total.formatted(), 11);
+    assert_eq!(total.colored(), 4);
+    assert_eq!(total.bold(), 4);
+    assert_eq!(total.italic(), 7);
+    assert_eq!(total.underline(), 0);
+    assert_eq!(total.format_codes(), 4);
+
+    let densities: Vec<f64> = analysis
+        .pages()
+        .iter()
+        .map(FormattingCounts::density)
+        .collect();
+    assert_eq!(densities, [0.0, 0.5, 1.0, 0.0]);
+
+    assert!(analysis
+        .to_string()
+        .ends_with("\n  2: 8 characters, 50.0% formatted, 3 format codes\n  3: 7 characters, 100.0% formatted, 1 format codes\n  4: 5 characters, 0.0% formatted, 0 format codes"));
+    assert!(analysis
+        .to_string()
+        .starts_with("Characters: 24\nFormatted: 11 (45.8%)\n"));
+}
+
+#[test]
+fn pages_iter() {
+    use Token::ThematicBreak;
+
+    let list = token_list!(
+        [Metadata::Title("crafty_novels".into())],
+        [
+            text!("lead"),
+            ThematicBreak,
+            text!("one"),
+            ThematicBreak,
+            ThematicBreak,
+            text!("three"),
+        ]
+    );
+    let pages: Vec<TokenList> = list.pages_iter().collect();
+
+    assert_eq!(
+        pages,
+        [
+            token_list!([Metadata::Title("crafty_novels".into())], [text!("lead")]),
+            token_list!([Metadata::Title("crafty_novels".into())], [text!("one")]),
+            token_list!([Metadata::Title("crafty_novels".into())], []),
+            token_list!([Metadata::Title("crafty_novels".into())], [text!("three")]),
+        ]
+    );
+
+    let list = token_list!([], [ThematicBreak, text!("only")]);
+    assert_eq!(list.pages_iter().count(), 1);
+    assert_eq!(token_list!([], []).pages_iter().count(), 0);
+}
+
+#[test]
+fn edit_pages() {
+    use std::ops::Bound;
+    use Token::ThematicBreak;
+
+    let mut list = token_list!(
+        [Metadata::Title("crafty_novels".into())],
+        [
+            ThematicBreak,
+            text!("one"),
+            ThematicBreak,
+            text!("three"),
+            ThematicBreak,
+            text!("two"),
+        ]
+    );
+    assert_eq!(list.page_count(), 3);
+
+    list.move_page(2, 1).unwrap();
+    assert_eq!(
+        list.tokens_as_slice(),
+        [
+            ThematicBreak,
+            text!("one"),
+            ThematicBreak,
+            text!("two"),
+            ThematicBreak,
+            text!("three"),
+        ]
+    );
+
+    let removed = list.remove_page(0).unwrap();
+    assert_eq!(
+        removed,
+        token_list!([Metadata::Title("crafty_novels".into())], [text!("one")])
+    );
+    assert_eq!(
+        list.tokens_as_slice(),
+        [ThematicBreak, text!("two"), ThematicBreak, text!("three")]
+    );
+
+    // Pages from other works keep the metadata of this one
+    let other = token_list!([], [text!("four"), ThematicBreak, text!("zero")]);
+    let zero = other.pages_iter().nth(1).unwrap();
+    list.insert_page(2, &other).unwrap();
+    list.insert_page(0, &zero).unwrap();
+    assert_eq!(
+        list,
+        token_list!(
+            [Metadata::Title("crafty_novels".into())],
+            [
+                ThematicBreak,
+                text!("zero"),
+                ThematicBreak,
+                text!("two"),
+                ThematicBreak,
+                text!("three"),
+                ThematicBreak,
+                text!("four"),
+                ThematicBreak,
+                text!("zero"),
+            ]
+        )
+    );
+
+    let error = list.remove_page(5).unwrap_err();
+    assert_eq!((error.index(), error.pages()), (5, 5));
+    let error = list.move_page(0, 5).unwrap_err();
+    assert_eq!((error.index(), error.pages()), (5, 5));
+    let error = list.insert_page(6, &zero).unwrap_err();
+    assert_eq!((error.index(), error.pages()), (6, 6));
+
+    assert_eq!(
+        list.pages(1..=2).unwrap(),
+        token_list!(
+            [Metadata::Title("crafty_novels".into())],
+            [ThematicBreak, text!("two"), ThematicBreak, text!("three")]
+        )
+    );
+    assert_eq!(list.pages(5..).unwrap().page_count(), 0);
+    let reversed = (Bound::Included(3), Bound::Excluded(1));
+    assert_eq!(list.pages(reversed).unwrap().page_count(), 0);
+    let error = list.pages(2..7).unwrap_err();
+    assert_eq!((error.index(), error.pages()), (6, 5));
+
+    // Works without a leading page break don't gain one
+    let mut list = token_list!([], [text!("a"), ThematicBreak, text!("b")]);
+    list.move_page(0, 1).unwrap();
+    assert_eq!(
+        list.tokens_as_slice(),
+        [text!("b"), ThematicBreak, text!("a")]
+    );
+}
+
+#[test]
+fn vec_compatibility() {
+    let tokens = vec![text!("one"), Token::Space, text!("two")];
+    let list = TokenList::from(tokens.clone());
+
+    assert!(list.metadata_as_slice().is_empty());
+    assert_eq!(list.clone().into_vec(), tokens);
+    assert_eq!(Vec::from(list), tokens);
+}
+
+#[test]
+fn tokenize_formatted_spans() {
+    use super::minecraft::{tokenize_formatted, Format};
+
+    assert_eq!(
+        tokenize_formatted("naïve§lünd §r€§"),
+        [
+            text!("naïve"),
+            Token::Format(Format::Bold),
+            text!("ünd"),
+            Token::Space,
+            Token::Format(Format::Reset),
+            text!("€"),
+        ]
+    );
+    assert_eq!(
+        tokenize_formatted("§z"),
+        [Token::Format(Format::Unknown('z'))]
+    );
+    assert_eq!(tokenize_formatted(""), []);
+}
+
+#[test]
+fn styles() {
+    use super::minecraft::{Color, Format, FormatState, Style};
+
+    let mut style = Style::BOLD | Style::UNDERLINE;
+    style |= Style::ITALIC;
+    style.remove(Style::UNDERLINE);
+
+    assert_eq!(style, Style::BOLD | Style::ITALIC);
+    assert_eq!(style & Style::ITALIC, Style::ITALIC);
+    assert_eq!(
+        !style,
+        Style::OBFUSCATED | Style::STRIKETHROUGH | Style::UNDERLINE
+    );
+    assert!(Style::ALL.contains(style));
+    assert!(Style::NONE.is_empty());
+    assert_eq!(Style::from_format(Format::Color(Color::Red)), None);
+
+    let state = FormatState::new()
+        .with(Format::Italic)
+        .with(Format::Color(Color::Red))
+        .with(Format::Bold);
+    assert_eq!(state, FormatState::from_parts(Some(Color::Red), style));
+    assert_eq!(state.style(), style);
+    assert_eq!(
+        state.formats(),
+        [Format::Color(Color::Red), Format::Bold, Format::Italic]
+    );
+    assert!(FormatState::new().with(Format::Bold).is_subset_of(&state));
+    assert!(!state.is_subset_of(&FormatState::new().with(Format::Bold)));
+}
+
+#[test]
+fn truncate_chars() {
+    use super::minecraft::Format;
+    use Token::{LineBreak, Space};
+
+    let tokens = token_list!(
+        [Metadata::Title("Title".into())],
+        [
+            text!("one"),
+            Space,
+            Token::Format(Format::Italic),
+            text!("über"),
+            Token::Format(Format::Reset),
+            LineBreak,
+            Token::Format(Format::Bold),
+            text!("three"),
+        ]
+    );
+
+    assert_eq!(tokens.truncate_chars(usize::MAX), tokens);
+    assert_eq!(tokens.truncate_chars(13), tokens);
+    assert_eq!(tokens.truncate_chars(0).tokens_as_slice(), []);
+    assert_eq!(
+        tokens.truncate_chars(0).metadata_as_slice(),
+        tokens.metadata_as_slice()
+    );
+    // Formatting that would only apply after the cut is dropped
+    assert_eq!(
+        tokens.truncate_chars(4).tokens_as_slice(),
+        [text!("one"), Space]
+    );
+    // Multi-byte characters are never split, and open formatting is closed
+    assert_eq!(
+        tokens.truncate_chars(6).tokens_as_slice(),
+        [
+            text!("one"),
+            Space,
+            Token::Format(Format::Italic),
+            text!("üb"),
+            Token::Format(Format::Reset),
+        ]
+    );
+    assert_eq!(
+        tokens.truncate_chars(9).tokens_as_slice(),
+        [
+            text!("one"),
+            Space,
+            Token::Format(Format::Italic),
+            text!("über"),
+            Token::Format(Format::Reset),
+            LineBreak,
+            Token::Format(Format::Bold),
+            text!("t"),
+            Token::Format(Format::Reset),
+        ]
+    );
+}
+
+#[test]
+fn text_builder() {
+    use super::{
+        minecraft::{Color, Format},
+        TextBuilder,
+    };
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = TextBuilder::new()
+        .title("crafty_novels")
+        .author("RemasteredArch")
+        .page()
+        .italic()
+        .text("two  words\n")
+        .paragraph_break()
+        .page()
+        .color(Color::Gold)
+        .text("§ kept")
+        .line_break()
+        .image("icon.png", "an icon")
+        .build();
+
+    assert_eq!(
+        tokens,
+        token_list!(
+            [
+                Metadata::Title("crafty_novels".into()),
+                Metadata::Author("RemasteredArch".into()),
+            ],
+            [
+                ThematicBreak,
+                Token::Format(Format::Italic),
+                text!("two"),
+                Space,
+                Space,
+                text!("words"),
+                LineBreak,
+                ParagraphBreak,
+                ThematicBreak,
+                Token::Format(Format::Color(Color::Gold)),
+                text!("§"),
+                Space,
+                text!("kept"),
+                LineBreak,
+                Token::Image {
+                    src: "icon.png".into(),
+                    alt: "an icon".into(),
+                },
+                Token::Format(Format::Reset),
+            ]
+        )
+    );
+
+    assert_eq!(TextBuilder::new().build(), token_list!([], []));
+    assert_eq!(
+        TextBuilder::new().bold().reset().build(),
+        token_list!(
+            [],
+            [Token::Format(Format::Bold), Token::Format(Format::Reset)]
+        )
+    );
+}
+
+#[test]
+fn provenance() {
+    use super::provenance::Provenance;
+
+    // Test vectors from FIPS 180-4
+    assert_eq!(
+        Provenance::new(b"").sha256(),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        Provenance::new(b"abc").sha256(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        Provenance::new(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq").sha256(),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+
+    let provenance = Provenance::new(b"abc").with_options(Some("stendhal to html".into()));
+    let tokens = provenance.apply(&token_list!([Metadata::Title("Title".into())], []));
+    assert_eq!(
+        tokens.metadata_as_slice(),
+        [
+            Metadata::Title("Title".into()),
+            Metadata::Custom {
+                key: "provenance-sha256".into(),
+                value: provenance.sha256().into(),
+            },
+            Metadata::Custom {
+                key: "provenance-converter".into(),
+                value: provenance.converter().into(),
+            },
+            Metadata::Custom {
+                key: "provenance-options".into(),
+                value: "stendhal to html".into(),
+            },
+        ]
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&provenance.to_json()).unwrap();
+    assert_eq!(json["source"], serde_json::Value::Null);
+    assert_eq!(json["sha256"], provenance.sha256());
+    assert_eq!(json["options"], "stendhal to html");
+}
+
+#[test]
+fn token_cursor() {
+    use Token::{LineBreak, Space};
+
+    let tokens = token_list!([], [text!("one"), Space, Space, text!("two"), LineBreak]);
+    let mut cursor = tokens.cursor();
+
+    assert_eq!(cursor.len(), 5);
+    assert_eq!(cursor.previous(), None);
+    assert_eq!(cursor.peek_nth(3), Some(&text!("two")));
+    assert_eq!(cursor.peek_nth(5), None);
+
+    assert_eq!(cursor.next(), Some(&text!("one")));
+    assert_eq!(cursor.next_if(Token::is_text), None);
+    assert_eq!(cursor.advance_while(|token| *token == Space), 2);
+    assert_eq!(cursor.position(), 3);
+    assert_eq!(cursor.previous(), Some(&Space));
+    assert_eq!(cursor.next_if(Token::is_text), Some(&text!("two")));
+    assert_eq!(cursor.remaining(), [LineBreak]);
+
+    assert_eq!(cursor.next(), Some(&LineBreak));
+    assert_eq!(cursor.next(), None);
+    assert_eq!(cursor.peek(), None);
+    assert!(cursor.remaining().is_empty());
+}
+
+#[test]
+fn annotations() {
+    use super::annotations::{Annotation, Annotations, AnnotationsError};
+
+    let annotations = Annotations::new()
+        .with(Annotation::new(4..6, "inner"))
+        .with(Annotation::new(8..8, "empty"))
+        .with(Annotation::new(4..9, "outer"));
+
+    let notes: Vec<_> = annotations.iter().map(Annotation::note).collect();
+    assert_eq!(notes, ["outer", "inner", "empty"]);
+    assert_eq!(annotations.iter().nth(2).map(Annotation::range), Some(8..8));
+
+    let notes: Vec<_> = annotations.at(5).map(Annotation::note).collect();
+    assert_eq!(notes, ["outer", "inner"]);
+    assert_eq!(annotations.at(8).count(), 1);
+
+    assert_eq!(
+        Annotations::from_json(&annotations.to_json()).ok(),
+        Some(annotations)
+    );
+
+    assert!(matches!(
+        Annotations::from_json("[{"),
+        Err(AnnotationsError::Json(_))
+    ));
+    assert!(matches!(
+        Annotations::from_json("{}"),
+        Err(AnnotationsError::NotAnArray)
+    ));
+    assert!(matches!(
+        Annotations::from_json(r#"[{"start":0,"end":1,"note":""},{"start":2,"end":1,"note":""}]"#),
+        Err(AnnotationsError::InvalidAnnotation(1))
+    ));
+    assert!(matches!(
+        Annotations::from_json(r#"[{"start":0,"end":1}]"#),
+        Err(AnnotationsError::InvalidAnnotation(0))
+    ));
+}
+
+#[test]
+fn canonical_bytes() {
+    use super::minecraft::{Color, Format};
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = token_list!(
+        [
+            Metadata::Title("§6Book ".into()),
+            Metadata::Author("RemasteredArch".into()),
+            Metadata::Custom {
+                key: "exported-by".into(),
+                value: "Stendhal".into(),
+            },
+        ],
+        [
+            ThematicBreak,
+            Token::Format(Format::Color(Color::Gold)),
+            text!("gold"),
+            Token::Format(Format::Reset),
+            LineBreak,
+            ThematicBreak,
+            text!("two"),
+            Space,
+            LineBreak,
+        ]
+    );
+    let equivalent = token_list!(
+        [
+            Metadata::Title("Book".into()),
+            Metadata::Author("RemasteredArch".into()),
+        ],
+        [
+            Token::Format(Format::Color(Color::Gold)),
+            Token::Format(Format::Color(Color::Gold)),
+            text!("go"),
+            text!("ld"),
+            ThematicBreak,
+            text!("two"),
+            ParagraphBreak,
+            Token::Format(Format::Bold),
+        ]
+    );
+    assert_eq!(tokens.canonical_bytes(), equivalent.canonical_bytes());
+    assert_eq!(tokens.content_hash(), equivalent.content_hash());
+    assert_eq!(tokens.content_hash().len(), 64);
+
+    // Formatting doesn't carry over between pages
+    let carried = token_list!(
+        [
+            Metadata::Title("Book".into()),
+            Metadata::Author("RemasteredArch".into()),
+        ],
+        [
+            Token::Format(Format::Color(Color::Gold)),
+            text!("gold"),
+            ThematicBreak,
+            Token::Format(Format::Color(Color::Gold)),
+            text!("two"),
+        ]
+    );
+    assert_ne!(tokens.content_hash(), carried.content_hash());
+
+    let retitled = tokens.map_metadata(|metadata| match metadata {
+        Metadata::Title(_) => Metadata::Title("Other".into()),
+        other => other.clone(),
+    });
+    assert_ne!(tokens.content_hash(), retitled.content_hash());
+}
+
+#[test]
+fn dedup() {
+    use super::{
+        dedup::{Deduplicator, Shingles},
+        TextBuilder,
+    };
+
+    let book = |text: &str| TextBuilder::new().text(text).build();
+    let corpus = [
+        book("one two three four five"),
+        book("something else entirely"),
+        TextBuilder::new()
+            .bold()
+            .text("One two")
+            .reset()
+            .text(" three four five")
+            .build(),
+        book("one two three four six"),
+        book(""),
+        book(""),
+    ];
+
+    assert_eq!(Shingles::new(&corpus[0], 2).len(), 4);
+    assert_eq!(Shingles::new(&corpus[1], 4).len(), 1);
+    assert!(Shingles::new(&corpus[4], 4).is_empty());
+
+    let deduplicator = Deduplicator::new().with_shingle_size(2);
+    assert!((deduplicator.similarity(&corpus[0], &corpus[3]) - 0.6).abs() < f64::EPSILON);
+
+    let clusters = deduplicator.with_threshold(0.5).cluster(&corpus);
+    let members: Vec<Vec<(usize, f64)>> = clusters
+        .iter()
+        .map(|cluster| {
+            cluster
+                .members()
+                .iter()
+                .map(|member| (member.index(), member.similarity()))
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        members,
+        [vec![(0, 1.0), (2, 1.0), (3, 0.6)], vec![(4, 1.0), (5, 1.0)]]
+    );
+    assert!((clusters[0].min_similarity() - 0.6).abs() < f64::EPSILON);
+}
+
+#[test]
+fn library() {
+    use super::{library::Library, TextBuilder};
+
+    let book = TextBuilder::new()
+        .title(" Book ")
+        .author("§4RemasteredArch")
+        .text("one")
+        .build();
+    let mut library = Library::new();
+
+    assert!(library.is_empty());
+    assert!(library.add(&book));
+    assert!(!library.add(&book));
+    assert_eq!(library.len(), 1);
+    assert!(library.works_by("Nobody").is_empty());
+    assert_eq!(
+        library.to_json(),
+        format!(
+            r#"{{"authors":[{{"name":"RemasteredArch","works":[{{"hash":"{}","pages":1,"title":"Book"}}]}}]}}"#,
+            book.content_hash()
+        )
+    );
+}
+
+#[test]
+fn edit_page() {
+    use super::minecraft::{Color, Format};
+    use Token::ThematicBreak;
+
+    // A single page starts with the formatting carried over from the pages before it
+    let formatted = token_list!(
+        [],
+        [
+            ThematicBreak,
+            Token::Format(Format::Color(Color::Gold)),
+            text!("a"),
+            ThematicBreak,
+            Token::Format(Format::Italic),
+            text!("b"),
+            ThematicBreak,
+            text!("c"),
+        ]
+    );
+    assert_eq!(
+        formatted.page(2).unwrap().tokens_as_slice(),
+        [
+            ThematicBreak,
+            Token::Format(Format::Color(Color::Gold)),
+            Token::Format(Format::Italic),
+            text!("c"),
+        ]
+    );
+    assert_eq!(
+        formatted.page(0).unwrap().tokens_as_slice(),
+        &formatted.tokens_as_slice()[..3]
+    );
+    let error = formatted.page(3).unwrap_err();
+    assert_eq!((error.index(), error.pages()), (3, 3));
+}
+
+#[test]
+fn typography() {
+    use super::{
+        minecraft::{Color, Format},
+        typography::Typography,
+    };
+
+    for (typography, input, expects) in [
+        (
+            Typography::ENGLISH,
+            r#""It's 'fine' -- really" - she said"#,
+            "“It’s ‘fine’ — really” — she said",
+        ),
+        (
+            Typography::GERMAN,
+            r#""Ich sag's: 'nein'""#,
+            "„Ich sag’s: ‚nein‘“",
+        ),
+        (
+            Typography::FRENCH,
+            r#""Oui", dit-il"#,
+            "«\u{202F}Oui\u{202F}», dit-il",
+        ),
+        (Typography::SWISS, r#"("Grüezi")"#, "(«Grüezi»)"),
+    ] {
+        assert_eq!(typography.convert(input), expects);
+    }
+
+    assert_eq!(Typography::for_language("DE_ch"), Some(Typography::SWISS));
+    assert_eq!(
+        Typography::for_language("de-Latn-AT"),
+        Some(Typography::GERMAN)
+    );
+    assert_eq!(Typography::for_language("ja"), None);
+
+    // Formatting doesn't change which way a quote faces, but a new line starts fresh
+    let tokens = token_list!(
+        [],
+        [
+            text!("\"Red"),
+            Token::Format(Format::Color(Color::Red)),
+            text!("\""),
+            Token::LineBreak,
+            text!("\"again"),
+        ]
+    );
+    assert_eq!(
+        Typography::ENGLISH.apply(&tokens).tokens_as_slice(),
+        [
+            text!("“Red"),
+            Token::Format(Format::Color(Color::Red)),
+            text!("”"),
+            Token::LineBreak,
+            text!("“again"),
+        ]
+    );
+}
+
+#[test]
+fn text_direction() {
+    use super::direction::TextDirection;
+
+    for (tag, expects) in [
+        ("ar", TextDirection::RightToLeft),
+        ("HE-il", TextDirection::RightToLeft),
+        ("fa_IR", TextDirection::RightToLeft),
+        ("uz-Arab", TextDirection::RightToLeft),
+        ("sd-Deva-IN", TextDirection::LeftToRight),
+        ("en-US", TextDirection::LeftToRight),
+        ("", TextDirection::LeftToRight),
+    ] {
+        assert_eq!(TextDirection::for_language(tag), expects, "{tag}");
+    }
+
+    assert_eq!(
+        TextDirection::from_attribute(" RTL "),
+        Some(TextDirection::RightToLeft)
+    );
+    assert_eq!(TextDirection::from_attribute("sideways"), None);
+}
+
+#[test]
+fn center_detector() {
+    use super::{
+        align::{Alignment, CenterDetector},
+        minecraft::Format,
+    };
+    use Token::{LineBreak, Space};
+
+    let detect = |tokens: Vec<Token>| {
+        CenterDetector::new()
+            .apply(&TokenList::new_from_boxed(
+                Box::new([]),
+                tokens.into_boxed_slice(),
+            ))
+            .tokens_as_slice()
+            .to_vec()
+    };
+
+    // Padding is dropped, format codes within it are kept
+    assert_eq!(
+        detect(vec![
+            Space,
+            Space,
+            Token::Format(Format::Bold),
+            text!("A"),
+            Space,
+            text!("Title"),
+            Space,
+            Space,
+            Space,
+            LineBreak,
+            text!("body"),
+        ]),
+        [
+            Token::Align(Alignment::Center),
+            Token::Format(Format::Bold),
+            text!("A"),
+            Space,
+            text!("Title"),
+            LineBreak,
+            text!("body"),
+        ]
+    );
+
+    // Indented, too lopsided, and already aligned lines are left alone
+    for line in [
+        vec![Space, Space, text!("indent"), LineBreak],
+        vec![Space, Space, Space, Space, text!("left"), Space, Space],
+        vec![
+            Token::Align(Alignment::Center),
+            Space,
+            Space,
+            text!("x"),
+            Space,
+            Space,
+        ],
+    ] {
+        assert_eq!(detect(line.clone()), line);
+    }
+}
+
+#[test]
+fn color_roles() {
+    use super::{
+        minecraft::{Color, Format},
+        role::{ColorRoles, Role},
+        transform::Pipeline,
+    };
+
+    let roles = ColorRoles::new()
+        .with(Color::Gold, Role::Heading1)
+        .with(Color::Gray, Role::Small)
+        // Replaces the earlier mapping
+        .with(Color::Gold, Role::Heading2);
+    assert_eq!(roles.get(Color::Gold), Some(Role::Heading2));
+    assert_eq!(roles.get(Color::Red), None);
+
+    let tokens = token_list!(
+        [Metadata::Title("crafty_novels".into())],
+        [
+            Token::Format(Format::Color(Color::Gold)),
+            text!("Title"),
+            Token::Format(Format::Reset),
+            Token::Format(Format::Color(Color::Red)),
+            Token::Format(Format::Bold),
+            text!("red"),
+            Token::Format(Format::Color(Color::Gray)),
+            text!("aside"),
+        ]
+    );
+
+    let transformed = Pipeline::new().with(roles).apply(tokens);
+    assert_eq!(
+        transformed.metadata_as_slice(),
+        [Metadata::Title("crafty_novels".into())]
+    );
+    assert_eq!(
+        transformed.tokens_as_slice(),
+        [
+            Token::Role(Role::Heading2),
+            text!("Title"),
+            Token::Format(Format::Reset),
+            Token::Format(Format::Color(Color::Red)),
+            Token::Format(Format::Bold),
+            text!("red"),
+            Token::Role(Role::Small),
+            text!("aside"),
+        ]
+    );
+}
+
+#[test]
+fn metadata_fields() {
+    use super::{typography::LANGUAGE_KEY, Metadata};
+
+    let fields = [
+        ("Title", Metadata::Title("Lore".into())),
+        ("author", Metadata::Author("Lore".into())),
+        ("DATE", Metadata::Date("Lore".into())),
+        ("description", Metadata::Description("Lore".into())),
+        ("language", Metadata::Language("Lore".into())),
+        ("generator", Metadata::Generator("Lore".into())),
+        (
+            "Series",
+            Metadata::Custom {
+                key: "Series".into(),
+                value: "Lore".into(),
+            },
+        ),
+    ];
+    for (key, expected) in fields {
+        let data = Metadata::from_field(key, "Lore");
+        assert_eq!(data, expected);
+        assert!(data.key().eq_ignore_ascii_case(key));
+        assert_eq!(data.value(), "Lore");
+        assert_eq!(data.with_value("Saga").value(), "Saga");
+    }
+
+    assert_eq!(Metadata::Language(" de ".into()).language(), Some("de"));
+    let custom = Metadata::Custom {
+        key: LANGUAGE_KEY.into(),
+        value: "fr".into(),
+    };
+    assert_eq!(custom.language(), Some("fr"));
+    assert_eq!(Metadata::Title("de".into()).language(), None);
+}
+
+#[test]
+fn compose_volumes() {
+    use super::{
+        align::Alignment,
+        compose::{Compose, Separator},
+        minecraft::Format,
+        Metadata, Token, TokenList,
+    };
+    use std::sync::Arc;
+    use Token::{Align, Text, ThematicBreak};
+
+    let one = TokenList::new(
+        Arc::new([
+            Metadata::Title("One".into()),
+            Metadata::Author("A".into()),
+            Metadata::Language("en".into()),
+        ]),
+        Arc::new([
+            ThematicBreak,
+            Token::Format(Format::Italic),
+            Text("a".into()),
+        ]),
+    );
+    // Headless, without a page break of its own
+    let two = TokenList::new(
+        Arc::new([
+            Metadata::Title("Two".into()),
+            Metadata::Author("B".into()),
+            Metadata::Author("A".into()),
+            Metadata::Language("de".into()),
+        ]),
+        Arc::new([Text("b".into())]),
+    );
+
+    let joined = TokenList::concat(&[one.clone(), two.clone()]);
+    assert_eq!(
+        joined.metadata_as_slice(),
+        [
+            Metadata::Title("One".into()),
+            Metadata::Author("A".into()),
+            Metadata::Language("en".into()),
+            Metadata::Author("B".into()),
+        ]
+    );
+    // The italics of the first volume don't carry over into the second
+    assert_eq!(
+        joined.tokens_as_slice(),
+        [
+            ThematicBreak,
+            Token::Format(Format::Italic),
+            Text("a".into()),
+            Token::Format(Format::Reset),
+            ThematicBreak,
+            Text("b".into()),
+        ]
+    );
+
+    let joined = Compose::new()
+        .with_separator(Separator::TitlePage)
+        .apply(&[two, one]);
+    assert_eq!(
+        joined.tokens_as_slice(),
+        [
+            ThematicBreak,
+            Align(Alignment::Center),
+            Token::Format(Format::Bold),
+            Text("Two".into()),
+            Token::Format(Format::Reset),
+            ThematicBreak,
+            Text("b".into()),
+            ThematicBreak,
+            Align(Alignment::Center),
+            Token::Format(Format::Bold),
+            Text("One".into()),
+            Token::Format(Format::Reset),
+            ThematicBreak,
+            Token::Format(Format::Italic),
+            Text("a".into()),
+        ]
+    );
+
+    assert_eq!(
+        TokenList::concat(&[]),
+        TokenList::new(Arc::new([]), Arc::new([]))
+    );
+}
+
+#[test]
+fn corpus_statistics() {
+    use super::{
+        corpus::Corpus,
+        minecraft::{Color, Format},
+        Metadata, Token, TokenList,
+    };
+    use std::sync::Arc;
+    use Token::{Space, Text, ThematicBreak};
+
+    let mut corpus = Corpus::new();
+    corpus.add(
+        "one",
+        &TokenList::new(
+            Arc::new([
+                Metadata::Title("§6A, \"B\"".into()),
+                Metadata::Author("Sam".into()),
+            ]),
+            Arc::new([
+                ThematicBreak,
+                Token::Format(Format::Color(Color::Red)),
+                Text("red".into()),
+                Token::Format(Format::Bold),
+                Text("der".into()),
+                Space,
+                Text("word".into()),
+                // Colors don't carry over into the next page
+                ThematicBreak,
+                Text("plain".into()),
+            ]),
+        ),
+    );
+    corpus.add(
+        "two",
+        &TokenList::new(
+            Arc::new([]),
+            Arc::new([
+                Token::Format(Format::Color(Color::Blue)),
+                Text("blue".into()),
+            ]),
+        ),
+    );
+
+    assert_eq!(corpus.works()[0].words(), 3);
+    assert_eq!(corpus.pages(), 3);
+    assert_eq!(corpus.words(), 4);
+    assert_eq!(corpus.formatting().characters(), 19);
+    assert_eq!(
+        corpus.colors().into_iter().collect::<Vec<_>>(),
+        [(Color::Blue, 4), (Color::Red, 10),]
+    );
+
+    assert_eq!(
+        corpus.to_csv(),
+        concat!(
+            "name,title,author,pages,words,characters,formatted,colored,obfuscated,bold,",
+            "strikethrough,underline,italic,format_codes,color_blue,color_red\n",
+            "one,\"A, \"\"B\"\"\",Sam,2,3,15,10,10,0,7,0,0,0,2,0,10\n",
+            "two,,,1,1,4,4,4,0,0,0,0,0,1,4,0\n",
+        )
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&corpus.to_json()).expect("valid JSON");
+    assert_eq!(json["works"][0]["title"], "A, \"B\"");
+    assert_eq!(json["works"][1]["colors"]["blue"], 4);
+    assert_eq!(json["totals"]["words"], 4);
+    assert_eq!(json["totals"]["colors"]["red"], 10);
+}
+
+#[test]
+fn transform_pipeline() {
+    use super::{
+        minecraft::{Color, Format},
+        transform::{
+            Builtin, CollapseEmptyPages, NormalizeWhitespace, Pipeline, StripColors, Transform,
+        },
+    };
+    use Token::{LineBreak, ParagraphBreak, Space, ThematicBreak};
+
+    let tokens = token_list!(
+        [Metadata::Title(" §6Title ".into())],
+        [
+            ThematicBreak,
+            Space,
+            Space,
+            Token::Format(Format::Color(Color::Gold)),
+            text!("indented"),
+            Space,
+            Token::Format(Format::Bold),
+            Space,
+            text!("word"),
+            Space,
+            LineBreak,
+            Space,
+            LineBreak,
+            ParagraphBreak,
+            ParagraphBreak,
+            text!("end"),
+            ThematicBreak,
+            Token::Format(Format::Reset),
+            Space,
+            ThematicBreak,
+            Token::Image {
+                src: "map.png".into(),
+                alt: "a map".into(),
+            },
+        ]
+    );
+
+    assert_eq!(
+        NormalizeWhitespace
+            .transform(tokens.clone())
+            .tokens_as_slice(),
+        [
+            ThematicBreak,
+            Space,
+            Space,
+            Token::Format(Format::Color(Color::Gold)),
+            text!("indented"),
+            Space,
+            Token::Format(Format::Bold),
+            text!("word"),
+            LineBreak,
+            LineBreak,
+            ParagraphBreak,
+            text!("end"),
+            ThematicBreak,
+            Token::Format(Format::Reset),
+            ThematicBreak,
+            Token::Image {
+                src: "map.png".into(),
+                alt: "a map".into(),
+            },
+        ]
+    );
+
+    let pipeline = Pipeline::new()
+        .with(StripColors)
+        .with(CollapseEmptyPages)
+        .with(|tokens: TokenList| tokens.truncate_chars(8));
+    assert_eq!(pipeline.len(), 3);
+    assert_eq!(
+        pipeline.apply(tokens.clone()).tokens_as_slice(),
+        [ThematicBreak, Space, Space, text!("indent")]
+    );
+
+    let pipeline: Pipeline = [Builtin::NormalizeMetadata, Builtin::CollapseEmptyPages]
+        .into_iter()
+        .collect();
+    let transformed = pipeline.apply(tokens);
+    assert_eq!(
+        transformed.metadata_as_slice(),
+        [Metadata::Title("Title".into())]
+    );
+    assert_eq!(transformed.page_count(), 2);
+
+    assert_eq!("Strip-Colors".parse::<Builtin>(), Ok(Builtin::StripColors));
+    assert_eq!("glitter".parse::<Builtin>().unwrap_err().name(), "glitter");
+}
@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Recognizing the decorative title page at the start of many works.
+//!
+//! See [`TitlePageDetector`].
+
+use super::{minecraft::strip_format_codes, Metadata, Token, TokenList};
+
+/// The key of the [`Metadata::Custom`] entry that holds the rest of a title page.
+pub const SUBTITLE_KEY: &str = "subtitle";
+
+/// A title page that was found by a [`TitlePageDetector`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TitlePage {
+    /// The plain text of each non-empty line, trimmed.
+    lines: Box<[Box<str>]>,
+    /// The index into [`Self::lines`] of the line containing the title.
+    title: usize,
+    /// The index into [`Self::lines`] of the line naming the author, if any.
+    author: Option<usize>,
+}
+
+impl TitlePage {
+    /// Returns the plain text of each non-empty line, trimmed.
+    #[must_use]
+    pub const fn lines(&self) -> &[Box<str>] {
+        &self.lines
+    }
+
+    /// Returns the line containing the title.
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.lines[self.title]
+    }
+
+    /// Returns the name on the line that credits the author, like `"by RemasteredArch"`, if any.
+    #[must_use]
+    pub fn author(&self) -> Option<&str> {
+        self.author.and_then(|index| author_of(&self.lines[index]))
+    }
+
+    /// Returns the lines that are neither the title nor the author, joined by spaces, if any.
+    #[must_use]
+    pub fn subtitle(&self) -> Option<String> {
+        let rest: Vec<&str> = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != self.title && Some(*index) != self.author)
+            .map(|(_, line)| line.as_ref())
+            .collect();
+
+        (!rest.is_empty()).then(|| rest.join(" "))
+    }
+}
+
+/// Detects a decorative title page at the start of a work, and turns it into [`Metadata`].
+///
+/// The first page is treated as a title page if:
+///
+/// - It is not the only page
+/// - It has at least one, and at most [`Self::with_max_lines`], non-empty lines
+/// - Most of those lines are short (see [`Self::with_max_line_length`]) or centered with leading
+///   spaces
+/// - One of those lines contains the title, ignoring case and format codes
+///   - If the work has no title, the first line is taken to be the title
+///
+/// When applied, the title page is removed, and:
+///
+/// - The title line becomes the [`Metadata::Title`], if the work doesn't have one
+/// - A line like `"by RemasteredArch"` becomes the [`Metadata::Author`], if the work doesn't have
+///   one
+/// - Every other line is joined into a [`Metadata::Custom`] with the key [`SUBTITLE_KEY`]
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::{
+///     import::Stendhal,
+///     syntax::{title_page::TitlePageDetector, Metadata, Token},
+///     Tokenize,
+/// };
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let input = "title: Golden Chronicle
+/// author: RemasteredArch
+/// pages:
+/// #-     §6§lGolden Chronicle
+///
+///     Book One
+/// #- Once upon a time...";
+///
+/// let tokens = TitlePageDetector::new().apply(&Stendhal::tokenize_string(input)?);
+///
+/// assert_eq!(
+///     tokens.metadata_as_slice().last(),
+///     Some(&Metadata::Custom {
+///         key: "subtitle".into(),
+///         value: "Book One".into()
+///     })
+/// );
+/// assert_eq!(tokens.tokens_as_slice()[0], Token::ThematicBreak);
+/// assert_eq!(tokens.tokens_as_slice()[1], Token::Text("Once".into()));
+/// #
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TitlePageDetector {
+    /// Whether to detect title pages at all.
+    enabled: bool,
+    /// The most non-empty lines that a title page can have.
+    max_lines: usize,
+    /// The most characters that a line can have to be considered short.
+    max_line_length: usize,
+}
+
+impl TitlePageDetector {
+    /// Creates a new [`TitlePageDetector`] that allows up to eight lines, of up to 24 characters
+    /// each.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            enabled: true,
+            max_lines: 8,
+            max_line_length: 24,
+        }
+    }
+
+    /// Sets whether to detect title pages at all.
+    ///
+    /// If `false`, [`Self::detect`] never finds a title page, and [`Self::apply`] always returns
+    /// the work unchanged.
+    #[must_use]
+    pub const fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Sets the most non-empty lines that a title page can have.
+    #[must_use]
+    pub const fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    /// Sets the most characters that a line can have to be considered short.
+    #[must_use]
+    pub const fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Find the title page of a work, if it has one.
+    #[must_use]
+    pub fn detect(&self, tokens: &TokenList) -> Option<TitlePage> {
+        self.detect_with_end(tokens)
+            .map(|(title_page, _)| title_page)
+    }
+
+    /// Returns a copy of `tokens` with its title page turned into [`Metadata`], if it has one.
+    #[must_use]
+    pub fn apply(&self, tokens: &TokenList) -> TokenList {
+        let Some((title_page, end)) = self.detect_with_end(tokens) else {
+            return tokens.clone();
+        };
+
+        let mut metadata = tokens.metadata_as_slice().to_vec();
+
+        if !metadata.iter().any(|m| matches!(m, Metadata::Title(_))) {
+            metadata.push(Metadata::Title(title_page.title().into()));
+        }
+        if let Some(author) = title_page.author() {
+            if !metadata.iter().any(|m| matches!(m, Metadata::Author(_))) {
+                metadata.push(Metadata::Author(author.into()));
+            }
+        }
+        if let Some(subtitle) = title_page.subtitle() {
+            metadata.push(Metadata::Custom {
+                key: SUBTITLE_KEY.into(),
+                value: subtitle.into(),
+            });
+        }
+
+        TokenList::new(metadata.into(), tokens.tokens_as_slice()[end..].into())
+    }
+
+    /// Find the title page of a work, along with the index of the first token after it.
+    fn detect_with_end(&self, tokens: &TokenList) -> Option<(TitlePage, usize)> {
+        if !self.enabled {
+            return None;
+        }
+
+        let slice = tokens.tokens_as_slice();
+        let page = slice.strip_prefix(&[Token::ThematicBreak])?;
+        // The title page must not be the only page
+        let end = 1 + page.iter().position(|t| *t == Token::ThematicBreak)?;
+
+        let mut lines: Vec<Box<str>> = vec![];
+        let mut short_or_centered = 0;
+        for line in page[..end - 1].split(|t| matches!(t, Token::LineBreak | Token::ParagraphBreak))
+        {
+            let text = plain_text(line);
+            let trimmed = text.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if trimmed.chars().count() <= self.max_line_length || text.starts_with(' ') {
+                short_or_centered += 1;
+            }
+            lines.push(trimmed.into());
+        }
+
+        if lines.is_empty() || lines.len() > self.max_lines || short_or_centered * 2 <= lines.len()
+        {
+            return None;
+        }
+
+        let title = match tokens.metadata_as_slice().iter().find_map(|m| match m {
+            Metadata::Title(title) => Some(comparable(title)),
+            _ => None,
+        }) {
+            Some(title) => lines
+                .iter()
+                .position(|line| comparable(line).contains(&title))?,
+            None => 0,
+        };
+        let author = lines
+            .iter()
+            .enumerate()
+            .position(|(index, line)| index != title && author_of(line).is_some());
+
+        Some((
+            TitlePage {
+                lines: lines.into(),
+                title,
+                author,
+            },
+            end,
+        ))
+    }
+}
+
+impl Default for TitlePageDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the text of `tokens`, without any formatting.
+fn plain_text(tokens: &[Token]) -> String {
+    let mut text = String::new();
+
+    for token in tokens {
+        match token {
+            Token::Text(s) => text.push_str(&strip_format_codes(s)),
+            Token::Space => text.push(' '),
+            _ => (),
+        }
+    }
+
+    text
+}
+
+/// Normalize `text` for comparison, ignoring case, format codes, and repeated whitespace.
+fn comparable(text: &str) -> String {
+    strip_format_codes(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Returns the name in a line that credits an author, like `"by RemasteredArch"`.
+fn author_of(line: &str) -> Option<&str> {
+    let (by, name) = line.split_once(' ')?;
+    let name = name.trim();
+
+    (by.eq_ignore_ascii_case("by") && !name.is_empty()).then_some(name)
+}
@@ -0,0 +1,396 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Passes that rewrite a work between importing and exporting it, chained into a [`Pipeline`].
+//!
+//! See [`Transform`].
+
+use super::{
+    minecraft::Format, normalize::MetadataNormalizer, role::ColorRoles,
+    title_page::TitlePageDetector, typography::Typography, Token, TokenList,
+};
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// A pass that rewrites a work, like stripping its colors or cleaning up its whitespace.
+///
+/// Implemented for any `Fn(TokenList) -> TokenList`, so a closure can be used as a one-off pass.
+pub trait Transform {
+    /// Returns `tokens`, rewritten.
+    fn transform(&self, tokens: TokenList) -> TokenList;
+}
+
+impl<F: Fn(TokenList) -> TokenList> Transform for F {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        self(tokens)
+    }
+}
+
+impl Transform for MetadataNormalizer {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        self.apply(&tokens)
+    }
+}
+
+impl Transform for Typography {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        self.apply(&tokens)
+    }
+}
+
+impl Transform for TitlePageDetector {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        self.apply(&tokens)
+    }
+}
+
+impl Transform for ColorRoles {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        self.apply(&tokens)
+    }
+}
+
+/// Runs [`Transform`]s one after another, each on the output of the one before it.
+///
+/// Every pass must be [`Send`] and [`Sync`], so that a pipeline, and the
+/// [`Session`][`crate::session::Session`] holding one, can be shared between threads.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     minecraft::{Color, Format},
+///     transform::{NormalizeWhitespace, Pipeline, StripColors},
+///     Token, TokenList,
+/// };
+///
+/// let tokens = TokenList::from(vec![
+///     Token::Format(Format::Color(Color::Red)),
+///     Token::Text("red".into()),
+///     Token::Space,
+///     Token::Space,
+///     Token::Text("text".into()),
+///     Token::Space,
+/// ]);
+///
+/// let pipeline = Pipeline::new()
+///     .with(StripColors)
+///     .with(NormalizeWhitespace);
+///
+/// assert_eq!(
+///     pipeline.apply(tokens).tokens_as_slice(),
+///     [
+///         Token::Text("red".into()),
+///         Token::Space,
+///         Token::Text("text".into()),
+///     ]
+/// );
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    /// The passes to run, in order.
+    transforms: Vec<Box<dyn Transform + Send + Sync>>,
+}
+
+impl Pipeline {
+    /// Creates a new [`Pipeline`] that leaves works unchanged.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { transforms: vec![] }
+    }
+
+    /// Returns the pipeline with `transform` run after its other passes.
+    #[must_use]
+    pub fn with(mut self, transform: impl Transform + Send + Sync + 'static) -> Self {
+        self.push(transform);
+        self
+    }
+
+    /// Run `transform` after the other passes.
+    pub fn push(&mut self, transform: impl Transform + Send + Sync + 'static) {
+        self.transforms.push(Box::new(transform));
+    }
+
+    /// Returns the number of passes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.transforms.len()
+    }
+
+    /// Returns whether there are no passes, so works are left unchanged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// Returns `tokens` after running every pass on it, in order.
+    #[must_use]
+    pub fn apply(&self, tokens: TokenList) -> TokenList {
+        self.transforms
+            .iter()
+            .fold(tokens, |tokens, transform| transform.transform(tokens))
+    }
+}
+
+impl Transform for Pipeline {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        self.apply(tokens)
+    }
+}
+
+impl std::fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("transforms", &self.transforms.len())
+            .finish()
+    }
+}
+
+impl FromIterator<Builtin> for Pipeline {
+    fn from_iter<T: IntoIterator<Item = Builtin>>(iter: T) -> Self {
+        let mut pipeline = Self::new();
+        for builtin in iter {
+            pipeline.push(builtin);
+        }
+        pipeline
+    }
+}
+
+/// Removes every [`Format::Color`], leaving the text in the default color.
+///
+/// Other formatting is kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StripColors;
+
+impl Transform for StripColors {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        let stripped = tokens
+            .tokens_as_slice()
+            .iter()
+            .filter(|token| !matches!(token, Token::Format(Format::Color(_))))
+            .cloned()
+            .collect();
+
+        TokenList::new(tokens.metadata(), stripped)
+    }
+}
+
+/// Cleans up stray [`Token::Space`]s and [`Token::ParagraphBreak`]s.
+///
+/// - Runs of spaces between words collapse into one
+/// - Spaces at the end of a line are removed, as are lines of nothing but spaces
+/// - Spaces at the start of a line are kept, as they are often used to indent or center text
+/// - Runs of paragraph breaks collapse into one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NormalizeWhitespace;
+
+impl Transform for NormalizeWhitespace {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        let mut output = Vec::with_capacity(tokens.tokens_as_slice().len());
+        // The index in `output` where the current line starts
+        let mut line_start = 0;
+        // Whether the current line has had any text or images yet
+        let mut has_content = false;
+        // Where to insert a space if more text follows on this line
+        let mut pending_space = None;
+
+        for token in tokens.tokens_as_slice() {
+            match token {
+                Token::Space if has_content => {
+                    pending_space.get_or_insert(output.len());
+                }
+                Token::Space | Token::Format(_) | Token::Align(_) | Token::Role(_) => {
+                    output.push(token.clone());
+                }
+                Token::Text(_) | Token::Image { .. } => {
+                    if let Some(index) = pending_space.take() {
+                        output.insert(index, Token::Space);
+                    }
+                    has_content = true;
+                    output.push(token.clone());
+                }
+                Token::LineBreak | Token::ParagraphBreak | Token::ThematicBreak => {
+                    if !has_content {
+                        remove_spaces(&mut output, line_start);
+                    }
+
+                    if !(*token == Token::ParagraphBreak
+                        && output.last() == Some(&Token::ParagraphBreak))
+                    {
+                        output.push(token.clone());
+                    }
+
+                    line_start = output.len();
+                    has_content = false;
+                    pending_space = None;
+                }
+            }
+        }
+
+        if !has_content {
+            remove_spaces(&mut output, line_start);
+        }
+
+        TokenList::new(tokens.metadata(), output.into())
+    }
+}
+
+/// Remove every [`Token::Space`] in `tokens` from `start` onward.
+fn remove_spaces(tokens: &mut Vec<Token>, start: usize) {
+    let rest: Vec<_> = tokens
+        .drain(start..)
+        .filter(|token| *token != Token::Space)
+        .collect();
+    tokens.extend(rest);
+}
+
+/// Removes pages without any [`Token::Text`] or [`Token::Image`], like the blank pages left
+/// between chapters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CollapseEmptyPages;
+
+impl Transform for CollapseEmptyPages {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        let pages: Vec<_> = tokens
+            .page_slices()
+            .into_iter()
+            .filter(|page| page.iter().any(|token| token.is_text() || token.is_image()))
+            .collect();
+
+        tokens.with_pages(&pages)
+    }
+}
+
+/// Returned when parsing the name of a [`Builtin`] transform that does not exist.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown transform '{name}', expected one of: {expected}")]
+pub struct UnknownTransformError {
+    /// The name that was not recognized.
+    name: Box<str>,
+    /// The supported names, separated by commas.
+    expected: Box<str>,
+}
+
+impl UnknownTransformError {
+    /// Returns the name that was not recognized.
+    #[must_use]
+    pub const fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The built-in [`Transform`]s, by name, so they can be chosen at runtime, like from the command
+/// line.
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::transform::{Builtin, Pipeline};
+///
+/// let pipeline: Pipeline = "strip-colors,collapse-empty-pages"
+///     .split(',')
+///     .map(str::parse::<Builtin>)
+///     .collect::<Result<_, _>>()?;
+///
+/// assert_eq!(pipeline.len(), 2);
+/// assert!("sparkle".parse::<Builtin>().is_err());
+/// # Ok::<(), crafty_novels::syntax::transform::UnknownTransformError>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Builtin {
+    /// [`StripColors`].
+    StripColors,
+    /// [`NormalizeWhitespace`].
+    NormalizeWhitespace,
+    /// [`CollapseEmptyPages`].
+    CollapseEmptyPages,
+    /// The default [`MetadataNormalizer`].
+    NormalizeMetadata,
+    /// [`Typography::apply_for_work`], using the typography of the work's language.
+    Typography,
+    /// The default [`TitlePageDetector`].
+    DetectTitlePage,
+}
+
+impl Builtin {
+    /// Every built-in transform.
+    pub const ALL: &'static [Self] = &[
+        Self::StripColors,
+        Self::NormalizeWhitespace,
+        Self::CollapseEmptyPages,
+        Self::NormalizeMetadata,
+        Self::Typography,
+        Self::DetectTitlePage,
+    ];
+
+    /// Returns the name of the transform, which is also its [`Display`] representation.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::StripColors => "strip-colors",
+            Self::NormalizeWhitespace => "normalize-whitespace",
+            Self::CollapseEmptyPages => "collapse-empty-pages",
+            Self::NormalizeMetadata => "normalize-metadata",
+            Self::Typography => "typography",
+            Self::DetectTitlePage => "detect-title-page",
+        }
+    }
+}
+
+impl Transform for Builtin {
+    fn transform(&self, tokens: TokenList) -> TokenList {
+        match self {
+            Self::StripColors => StripColors.transform(tokens),
+            Self::NormalizeWhitespace => NormalizeWhitespace.transform(tokens),
+            Self::CollapseEmptyPages => CollapseEmptyPages.transform(tokens),
+            Self::NormalizeMetadata => MetadataNormalizer::new().transform(tokens),
+            Self::Typography => Typography::apply_for_work(&tokens),
+            Self::DetectTitlePage => TitlePageDetector::new().transform(tokens),
+        }
+    }
+}
+
+impl Display for Builtin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Builtin {
+    type Err = UnknownTransformError;
+
+    /// Parse a transform from its name, ignoring ASCII case.
+    ///
+    /// # Errors
+    ///
+    /// - [`UnknownTransformError`] if `s` does not name a built-in transform
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|builtin| builtin.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownTransformError {
+                name: s.into(),
+                expected: Self::ALL
+                    .iter()
+                    .map(|builtin| builtin.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .into(),
+            })
+    }
+}
@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Converting straight quotes and dashes into the typography of a language.
+//!
+//! See [`Typography`].
+
+use super::{Metadata, Token, TokenList};
+use std::sync::Arc;
+
+/// The key of the field that names the language of a work, like `"de"` or `"fr-CA"`.
+///
+/// Importers turn it into [`Metadata::Language`], but a [`Metadata::Custom`] entry with this key
+/// is also understood.
+pub const LANGUAGE_KEY: &str = "language";
+
+/// The quotation marks and dashes of a language, to replace the straight quotes (`"` and `'`) and
+/// hyphens (`--`, or a lone `-` between spaces) that are all that most keyboards can type.
+///
+/// A straight quote opens a quotation at the start of a line, or after whitespace, an opening
+/// bracket, a dash, or another opening quotation mark, and closes one otherwise. A `'` between two
+/// letters is an apostrophe (`’`) instead, whatever the language.
+///
+/// The quotation marks of each language are constants, like [`Self::GERMAN`], and can be looked up
+/// from a language tag with [`Self::for_language`], or from the [`Metadata::Language`] of a work with
+/// [`Self::for_work`]. Other styles can be built with [`Self::new`].
+///
+/// # Examples
+///
+/// ```rust
+/// use crafty_novels::syntax::{
+///     typography::Typography,
+///     Metadata, Token, TokenList,
+/// };
+///
+/// let tokens = TokenList::new_from_boxed(
+///     Box::new([Metadata::Language("de-DE".into())]),
+///     Box::new([
+///         Token::Text("\"Halt!\"".into()),
+///         Token::Space,
+///         Token::Text("--".into()),
+///         Token::Space,
+///         Token::Text("sagte".into()),
+///     ]),
+/// );
+///
+/// assert_eq!(
+///     Typography::apply_for_work(&tokens).tokens_as_slice(),
+///     [
+///         Token::Text("„Halt!“".into()),
+///         Token::Space,
+///         Token::Text("–".into()),
+///         Token::Space,
+///         Token::Text("sagte".into()),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Typography {
+    /// The marks that open and close a quotation.
+    double_quotes: [char; 2],
+    /// The marks that open and close a quotation inside of another.
+    single_quotes: [char; 2],
+    /// The dash that separates parts of a sentence.
+    dash: char,
+    /// The space to write inside of quotation marks, if any.
+    quote_spacing: Option<char>,
+}
+
+impl Typography {
+    /// “English” and ‘English’ quotation marks, with an em dash (`—`).
+    pub const ENGLISH: Self = Self::new(['“', '”'], ['‘', '’'], '—');
+
+    /// „German“ and ‚German‘ quotation marks, with an en dash (`–`).
+    pub const GERMAN: Self = Self::new(['„', '“'], ['‚', '‘'], '–');
+
+    /// «Swiss» and ‹Swiss› guillemets, as in Swiss German, with an en dash (`–`).
+    pub const SWISS: Self = Self::new(['«', '»'], ['‹', '›'], '–');
+
+    /// « French » and ‹ French › guillemets, with a narrow no-break space inside of them, and an en
+    /// dash (`–`).
+    pub const FRENCH: Self =
+        Self::new(['«', '»'], ['‹', '›'], '–').with_quote_spacing(Some('\u{202F}'));
+
+    /// Creates a new [`Typography`] that opens and closes quotations with `double_quotes`,
+    /// quotations inside of quotations with `single_quotes`, and separates parts of a sentence
+    /// with `dash`.
+    #[must_use]
+    pub const fn new(double_quotes: [char; 2], single_quotes: [char; 2], dash: char) -> Self {
+        Self {
+            double_quotes,
+            single_quotes,
+            dash,
+            quote_spacing: None,
+        }
+    }
+
+    /// Sets the space to write inside of quotation marks, like the narrow no-break space of
+    /// French, if any.
+    #[must_use]
+    pub const fn with_quote_spacing(mut self, quote_spacing: Option<char>) -> Self {
+        self.quote_spacing = quote_spacing;
+        self
+    }
+
+    /// Returns the typography of the language named by `tag`, like `"de"` or `"fr-CA"`, ignoring
+    /// ASCII case, or [`None`] if it is not known.
+    ///
+    /// Only the language and region are considered, so `"de-CH"` is [`Self::SWISS`] and any other
+    /// German is [`Self::GERMAN`].
+    #[must_use]
+    pub fn for_language(tag: &str) -> Option<Self> {
+        let mut subtags = tag.split(['-', '_']).map(str::to_ascii_lowercase);
+        let language = subtags.next()?;
+        let region = subtags.find(|subtag| subtag.len() == 2);
+
+        match (language.as_str(), region.as_deref()) {
+            ("en", _) => Some(Self::ENGLISH),
+            ("de", Some("ch" | "li")) => Some(Self::SWISS),
+            ("de", _) => Some(Self::GERMAN),
+            ("fr", _) => Some(Self::FRENCH),
+            _ => None,
+        }
+    }
+
+    /// Returns the typography of the language of `tokens`, from its first [`Metadata::language`],
+    /// or [`None`] if it has none or the language is not known.
+    #[must_use]
+    pub fn for_work(tokens: &TokenList) -> Option<Self> {
+        tokens
+            .metadata_as_slice()
+            .iter()
+            .find_map(Metadata::language)
+            .and_then(Self::for_language)
+    }
+
+    /// Returns a copy of `tokens` in the typography of its language, see [`Self::for_work`], or
+    /// an unchanged copy if its language is not known.
+    #[must_use]
+    pub fn apply_for_work(tokens: &TokenList) -> TokenList {
+        Self::for_work(tokens).map_or_else(|| tokens.clone(), |typography| typography.apply(tokens))
+    }
+
+    /// Returns a copy of `tokens` with its straight quotes and hyphens converted.
+    ///
+    /// Quotes are matched across [`Token::Format`]s, so formatting doesn't change which way they
+    /// face, but each line starts fresh.
+    #[must_use]
+    pub fn apply(&self, tokens: &TokenList) -> TokenList {
+        let mut previous = None;
+        let converted: Arc<[Token]> = tokens
+            .tokens_as_slice()
+            .iter()
+            .map(|token| match token {
+                Token::Text(text) => Token::Text(self.convert_after(text, &mut previous).into()),
+                Token::Space => {
+                    previous = Some(' ');
+                    Token::Space
+                }
+                Token::Format(_) | Token::Align(_) | Token::Role(_) => token.clone(),
+                _ => {
+                    previous = None;
+                    token.clone()
+                }
+            })
+            .collect();
+
+        TokenList::new(tokens.metadata(), converted)
+    }
+
+    /// Returns `text` with its straight quotes and hyphens converted, as if it started a line.
+    #[must_use]
+    pub fn convert(&self, text: &str) -> String {
+        self.convert_after(text, &mut None)
+    }
+
+    /// Returns `text` with its straight quotes and hyphens converted, where `previous` is the
+    /// character before it, or [`None`] at the start of a line, and is left as the last character
+    /// of the output.
+    fn convert_after(&self, text: &str, previous: &mut Option<char>) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            let next = chars.peek().copied();
+
+            match char {
+                '\'' if previous.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric) =>
+                {
+                    output.push('’');
+                }
+                '"' => self.push_quote(&mut output, self.double_quotes, *previous),
+                '\'' => self.push_quote(&mut output, self.single_quotes, *previous),
+                '-' if next == Some('-') => {
+                    chars.next();
+                    output.push(self.dash);
+                }
+                '-' if previous.is_some_and(char::is_whitespace)
+                    && next.is_none_or(char::is_whitespace) =>
+                {
+                    output.push(self.dash);
+                }
+                _ => output.push(char),
+            }
+
+            *previous = output.chars().next_back();
+        }
+
+        output
+    }
+
+    /// Push the mark from `quotes` that opens a quotation after `previous`, or the one that closes
+    /// it, onto `output`.
+    fn push_quote(&self, output: &mut String, quotes: [char; 2], previous: Option<char>) {
+        let [open, close] = quotes;
+        let opens = previous.is_none_or(|previous| {
+            previous.is_whitespace()
+                || matches!(previous, '(' | '[' | '{' | '—' | '–')
+                || previous == self.double_quotes[0]
+                || previous == self.single_quotes[0]
+        });
+
+        if opens {
+            output.push(open);
+            output.extend(self.quote_spacing);
+        } else {
+            output.extend(self.quote_spacing);
+            output.push(close);
+        }
+    }
+}
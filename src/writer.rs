@@ -17,7 +17,7 @@
 
 //! Implements a UTF-8 safe writer wrapper.
 //!
-//! See [`Utf8Writer`].
+//! See [`Utf8Writer`] and [`CountingWriter`].
 
 #![allow(clippy::module_name_repetitions)]
 #![allow(dead_code)]
@@ -85,4 +85,29 @@ impl<W: Write> Utf8Writer<W> {
     pub fn flush(&mut self) -> Result<()> {
         self.0.flush()
     }
+
+    /// Returns a shared reference to the `output`, not including buffered writes.
+    pub fn get_ref(&self) -> &W {
+        self.0.get_ref()
+    }
+}
+
+/// Counts the bytes written through it.
+pub struct CountingWriter<W> {
+    /// The writer to count.
+    pub inner: W,
+    /// How many bytes have been written so far.
+    pub count: usize,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
 }
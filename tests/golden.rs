@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//
+// Copyright © 2024 RemasteredArch
+//
+// This file is part of crafty_novels.
+//
+// crafty_novels is free software: you can redistribute it and/or modify it under the terms of the
+// GNU Affero General Public License as published by the Free Software Foundation, either version
+// 3 of the License, or (at your option) any later version.
+//
+// crafty_novels is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+// without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See
+// the GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License along with
+// crafty_novels. If not, see <https://www.gnu.org/licenses/>.
+
+//! Golden tests, converting every Stendhal document in `tests/fixtures/stendhal` and comparing the
+//! output against the stored result next to it.
+//!
+//! The fixtures are written by hand in the layout that Stendhal exports use, so they pin down the
+//! output for the inputs the parser is expected to handle, but are not captured from the mod
+//! itself. See the `README.md` in the fixtures directory.
+//!
+//! To accept changes to the output, run with `UPDATE_GOLDEN=1` and review the diff of the fixtures
+//! directory.
+
+use crafty_novels::{export::Html, import::Stendhal, Export, Tokenize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+/// The directory containing the Stendhal fixtures.
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/stendhal")
+}
+
+/// Returns every `.stendhal` file in the fixtures directory, sorted by name.
+fn inputs() -> std::io::Result<Vec<PathBuf>> {
+    let mut inputs: Vec<PathBuf> = fs::read_dir(fixtures())?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.extension().is_some_and(|ext| ext == "stendhal")
+            })
+        })
+        .collect::<std::io::Result<_>>()?;
+
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Compare `actual` with the contents of `expected_path`, or overwrite it if `UPDATE_GOLDEN` is
+/// set.
+fn compare(expected_path: &Path, actual: &str) -> Result {
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(expected_path, actual)?;
+        return Ok(());
+    }
+
+    let expected = fs::read_to_string(expected_path).map_err(|e| {
+        format!(
+            "could not read {}, run with `UPDATE_GOLDEN=1` to create it: {e}",
+            expected_path.display()
+        )
+    })?;
+
+    assert!(
+        expected == actual,
+        "output for {} changed, run with `UPDATE_GOLDEN=1` to accept the new output",
+        expected_path.display()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn stendhal_to_html() -> Result {
+    let inputs = inputs()?;
+    assert!(!inputs.is_empty(), "there should be fixtures to test");
+
+    for input in inputs {
+        let tokens = Stendhal::tokenize_reader(fs::File::open(&input)?)
+            .map_err(|e| format!("could not tokenize {}: {e}", input.display()))?;
+
+        // The string and reader paths should always agree
+        assert_eq!(
+            Stendhal::tokenize_string(&fs::read_to_string(&input)?)?,
+            tokens,
+            "{}",
+            input.display()
+        );
+
+        let html = Html::export_token_vector_to_string(tokens);
+        compare(&input.with_extension("html"), &html)?;
+    }
+
+    Ok(())
+}